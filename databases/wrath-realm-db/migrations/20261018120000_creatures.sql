@@ -0,0 +1,42 @@
+CREATE TABLE `creature_template` (
+  `entry` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'Creature prototype ID.',
+  `name` varchar(100) NOT NULL DEFAULT '' COMMENT 'Creature name.',
+  `subname` varchar(100) NOT NULL DEFAULT '' COMMENT 'Title shown below the name.',
+  `display_id` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'A display model identifier (See CreatureDisplayInfo.dbc).',
+  `min_level` tinyint(3) unsigned NOT NULL DEFAULT '1' COMMENT 'Lowest level a spawn can have.',
+  `max_level` tinyint(3) unsigned NOT NULL DEFAULT '1' COMMENT 'Highest level a spawn can have.',
+  `min_health` int(10) unsigned NOT NULL DEFAULT '1' COMMENT 'Health at min_level.',
+  `max_health` int(10) unsigned NOT NULL DEFAULT '1' COMMENT 'Health at max_level.',
+  `faction` smallint(5) unsigned NOT NULL DEFAULT '0' COMMENT 'Faction template (See FactionTemplate.dbc).',
+  `npc_flags` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'Gossip, vendor, trainer, etc.',
+  `unit_flags` int(10) unsigned NOT NULL DEFAULT '0',
+  `scale` float NOT NULL DEFAULT '1',
+  `creature_type` tinyint(3) unsigned NOT NULL DEFAULT '0' COMMENT 'Beast, Humanoid, etc.',
+  PRIMARY KEY (`entry`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8;
+
+CREATE TABLE `creature` (
+  `guid` int(10) unsigned NOT NULL AUTO_INCREMENT COMMENT 'Unique spawn identifier.',
+  `entry` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'Creature prototype (See creature_template.entry).',
+  `map` smallint(5) unsigned NOT NULL DEFAULT '0' COMMENT 'The map ID (See Map.dbc)',
+  `position_x` float NOT NULL DEFAULT '0',
+  `position_y` float NOT NULL DEFAULT '0',
+  `position_z` float NOT NULL DEFAULT '0',
+  `orientation` float NOT NULL DEFAULT '0',
+  CONSTRAINT `FK_CREATURE_CREATURE_TEMPLATE` FOREIGN KEY (`entry`) REFERENCES `creature_template` (`entry`) ON DELETE CASCADE ON UPDATE RESTRICT,
+  PRIMARY KEY (`guid`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8;
+
+INSERT INTO `creature_template` (`entry`, `name`, `subname`, `display_id`, `min_level`, `max_level`, `min_health`, `max_health`, `faction`, `npc_flags`, `unit_flags`, `scale`, `creature_type`) VALUES
+(6,'Kobold Vermin','',607,1,2,42,55,32,0,0,1,7),
+(197,'Marshal McBride','',3258,20,20,1200,1200,12,2,0,1,7),
+(299,'Young Wolf','',903,1,2,42,55,32,0,0,1,1),
+(823,'Deputy Willem','',2105,18,18,900,900,12,2,0,1,7);
+
+INSERT INTO `creature` (`entry`, `map`, `position_x`, `position_y`, `position_z`, `orientation`) VALUES
+(197,0,-8902.59,-162.606,81.9395,1.95477),
+(823,0,-8933.54,-136.523,83.3372,2.1293),
+(299,0,-8960.24,-73.6283,88.3271,3.9114),
+(299,0,-8982.33,-96.1121,85.6527,0.8132),
+(6,0,-8773.29,-109.734,84.7014,2.7012),
+(6,0,-8791.05,-138.473,83.0322,5.2198);
@@ -0,0 +1,19 @@
+ALTER TABLE `characters`
+  ADD `dungeon_difficulty` tinyint(3) unsigned NOT NULL DEFAULT '0',
+  ADD `raid_difficulty` tinyint(3) unsigned NOT NULL DEFAULT '0';
+
+CREATE TABLE `instance` (
+  `id` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'The instance ID, also used as characters.instance_id.',
+  `map` smallint(5) unsigned NOT NULL DEFAULT '0' COMMENT 'The map ID (See Map.dbc)',
+  `difficulty` tinyint(3) unsigned NOT NULL DEFAULT '0',
+  `reset_time` bigint(20) unsigned NOT NULL DEFAULT '0' COMMENT 'Unix timestamp at which this instance resets.',
+  PRIMARY KEY (`id`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8;
+
+CREATE TABLE `character_instance` (
+  `character_id` int(10) unsigned NOT NULL DEFAULT '0',
+  `instance_id` int(10) unsigned NOT NULL DEFAULT '0',
+  CONSTRAINT `FK_CHARACTER_INSTANCE_CHARACTER` FOREIGN KEY (`character_id`) REFERENCES `characters` (`id`) ON DELETE CASCADE ON UPDATE RESTRICT,
+  CONSTRAINT `FK_CHARACTER_INSTANCE_INSTANCE` FOREIGN KEY (`instance_id`) REFERENCES `instance` (`id`) ON DELETE CASCADE ON UPDATE RESTRICT,
+  PRIMARY KEY (`character_id`, `instance_id`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8;
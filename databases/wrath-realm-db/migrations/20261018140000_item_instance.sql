@@ -0,0 +1,22 @@
+CREATE TABLE `item_instance` (
+  `guid` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'Realm-wide unique item identifier.',
+  `entry` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'Item prototype (See item_template.id).',
+  `owner_guid` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'The character owning this item.',
+  `container_guid` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'The bag holding this item, 0 if the character holds it directly.',
+  `slot` tinyint(3) unsigned NOT NULL DEFAULT '0' COMMENT 'Slot index inside the container.',
+  `stack_count` int(10) unsigned NOT NULL DEFAULT '1',
+  `durability` int(10) unsigned NOT NULL DEFAULT '0',
+  `enchantments` varchar(255) NOT NULL DEFAULT '' COMMENT 'Space separated enchantment IDs, one per enchantment slot.',
+  `flags` int(10) unsigned NOT NULL DEFAULT '0',
+  `creator_guid` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'The character that crafted this item.',
+  `charges` varchar(255) NOT NULL DEFAULT '' COMMENT 'Space separated charges, one per item spell.',
+  KEY `IDX_ITEM_INSTANCE_OWNER` (`owner_guid`),
+  CONSTRAINT `FK_ITEM_INSTANCE_CHARACTER` FOREIGN KEY (`owner_guid`) REFERENCES `characters` (`id`) ON DELETE CASCADE ON UPDATE RESTRICT,
+  PRIMARY KEY (`guid`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8;
+
+INSERT INTO `item_instance` (`guid`, `entry`, `owner_guid`, `slot`, `durability`, `enchantments`)
+SELECT ROW_NUMBER() OVER (ORDER BY `ce`.`character_id`, `ce`.`slot_id`), `ce`.`item`, `ce`.`character_id`, `ce`.`slot_id`, COALESCE(`it`.`MaxDurability`, 0), COALESCE(CAST(`ce`.`enchant` AS CHAR), '')
+FROM `character_equipment` `ce` LEFT JOIN `item_template` `it` ON `ce`.`item` = `it`.`id`;
+
+DROP TABLE `character_equipment`;
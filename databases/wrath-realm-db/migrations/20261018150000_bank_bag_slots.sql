@@ -0,0 +1,2 @@
+ALTER TABLE `characters`
+  ADD `bank_bag_slots` tinyint(3) unsigned NOT NULL DEFAULT '0' COMMENT 'Number of purchased bank bag slots.';
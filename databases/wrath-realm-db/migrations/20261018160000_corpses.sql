@@ -0,0 +1,20 @@
+CREATE TABLE `character_corpse` (
+  `character_id` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'The character that left this corpse behind.',
+  `map` smallint(5) unsigned NOT NULL DEFAULT '0' COMMENT 'The map ID (See Map.dbc)',
+  `instance_id` int(10) unsigned NOT NULL DEFAULT '0',
+  `position_x` float NOT NULL DEFAULT '0',
+  `position_y` float NOT NULL DEFAULT '0',
+  `position_z` float NOT NULL DEFAULT '0',
+  `orientation` float NOT NULL DEFAULT '0',
+  `display_id` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'The model of the character (See CreatureDisplayInfo.dbc).',
+  KEY `IDX_CHARACTER_CORPSE_MAP` (`map`, `instance_id`),
+  CONSTRAINT `FK_CHARACTER_CORPSE_CHARACTER` FOREIGN KEY (`character_id`) REFERENCES `characters` (`id`) ON DELETE CASCADE ON UPDATE RESTRICT,
+  PRIMARY KEY (`character_id`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8;
+
+CREATE TABLE `graveyard_zone` (
+  `id` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'The graveyard (See WorldSafeLocs.dbc).',
+  `ghost_zone` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'Characters dying in this zone (See AreaTable.dbc) release to this graveyard.',
+  `faction` smallint(5) unsigned NOT NULL DEFAULT '0' COMMENT '469 for Alliance only, 67 for Horde only, 0 for both.',
+  PRIMARY KEY (`id`, `ghost_zone`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8;
@@ -0,0 +1,77 @@
+CREATE TABLE `player_levelstats` (
+  `race` tinyint(3) unsigned NOT NULL DEFAULT '0',
+  `class` tinyint(3) unsigned NOT NULL DEFAULT '0',
+  `level` tinyint(3) unsigned NOT NULL DEFAULT '0',
+  `strength` smallint(5) unsigned NOT NULL DEFAULT '0',
+  `agility` smallint(5) unsigned NOT NULL DEFAULT '0',
+  `stamina` smallint(5) unsigned NOT NULL DEFAULT '0',
+  `intellect` smallint(5) unsigned NOT NULL DEFAULT '0',
+  `spirit` smallint(5) unsigned NOT NULL DEFAULT '0',
+  PRIMARY KEY (`race`, `class`, `level`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8;
+
+CREATE TABLE `player_classlevelstats` (
+  `class` tinyint(3) unsigned NOT NULL DEFAULT '0',
+  `level` tinyint(3) unsigned NOT NULL DEFAULT '0',
+  `base_health` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'Health before stamina is added.',
+  `base_mana` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'Mana before intellect is added, 0 for classes without mana.',
+  PRIMARY KEY (`class`, `level`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8;
+
+/* Base stats are the race base (ChrRaces) plus a class bonus at level 1, growing linearly per class after that.
+   This is an approximation of the retail tables, which can be imported over these rows. */
+INSERT INTO `player_levelstats` (`race`, `class`, `level`, `strength`, `agility`, `stamina`, `intellect`, `spirit`)
+WITH RECURSIVE `levels` (`level`) AS (SELECT 1 UNION ALL SELECT `level` + 1 FROM `levels` WHERE `level` < 80)
+SELECT `p`.`race`, `p`.`class`, `l`.`level`,
+  ROUND(`r`.`str` + `c`.`str` + `c`.`str_growth` * (`l`.`level` - 1)),
+  ROUND(`r`.`agi` + `c`.`agi` + `c`.`agi_growth` * (`l`.`level` - 1)),
+  ROUND(`r`.`sta` + `c`.`sta` + `c`.`sta_growth` * (`l`.`level` - 1)),
+  ROUND(`r`.`inte` + `c`.`inte` + `c`.`inte_growth` * (`l`.`level` - 1)),
+  ROUND(`r`.`spi` + `c`.`spi` + `c`.`spi_growth` * (`l`.`level` - 1))
+FROM `playercreateinfo` `p`
+CROSS JOIN `levels` `l`
+INNER JOIN (
+  SELECT 1 AS `race`, 20 AS `str`, 20 AS `agi`, 20 AS `sta`, 20 AS `inte`, 20 AS `spi` UNION ALL
+  SELECT 2, 23, 17, 22, 17, 23 UNION ALL
+  SELECT 3, 25, 16, 23, 19, 19 UNION ALL
+  SELECT 4, 17, 25, 19, 20, 20 UNION ALL
+  SELECT 5, 19, 18, 21, 18, 25 UNION ALL
+  SELECT 6, 25, 15, 22, 16, 22 UNION ALL
+  SELECT 7, 15, 23, 19, 24, 20 UNION ALL
+  SELECT 8, 21, 22, 21, 16, 21 UNION ALL
+  SELECT 10, 17, 22, 18, 24, 20 UNION ALL
+  SELECT 11, 21, 17, 19, 21, 22
+) `r` ON `r`.`race` = `p`.`race`
+INNER JOIN (
+  SELECT 1 AS `class`, 3 AS `str`, 0 AS `agi`, 2 AS `sta`, 0 AS `inte`, 0 AS `spi`,
+    1.9 AS `str_growth`, 1.2 AS `agi_growth`, 1.75 AS `sta_growth`, 0.2 AS `inte_growth`, 0.5 AS `spi_growth` UNION ALL
+  SELECT 2, 2, 0, 2, 0, 1, 1.6, 0.8, 1.5, 0.9, 1.0 UNION ALL
+  SELECT 3, 0, 3, 1, 0, 1, 0.6, 1.9, 1.4, 0.9, 1.0 UNION ALL
+  SELECT 4, 1, 3, 1, 0, 0, 1.0, 2.0, 1.1, 0.3, 0.6 UNION ALL
+  SELECT 5, 0, 0, 0, 2, 3, 0.3, 0.4, 0.6, 1.7, 1.8 UNION ALL
+  SELECT 6, 3, 0, 2, 0, 0, 1.9, 1.2, 1.75, 0.2, 0.5 UNION ALL
+  SELECT 7, 1, 0, 1, 1, 2, 1.1, 0.6, 1.3, 1.2, 1.4 UNION ALL
+  SELECT 8, 0, 0, 0, 3, 2, 0.2, 0.3, 0.5, 1.9, 1.7 UNION ALL
+  SELECT 9, 0, 0, 1, 2, 2, 0.5, 0.6, 0.9, 1.6, 1.7 UNION ALL
+  SELECT 11, 1, 0, 0, 2, 2, 0.8, 0.7, 0.9, 1.4, 1.5
+) `c` ON `c`.`class` = `p`.`class`;
+
+/* Health grows quadratically and mana linearly between the retail level 1 and level 80 values */
+INSERT INTO `player_classlevelstats` (`class`, `level`, `base_health`, `base_mana`)
+WITH RECURSIVE `levels` (`level`) AS (SELECT 1 UNION ALL SELECT `level` + 1 FROM `levels` WHERE `level` < 80)
+SELECT `c`.`class`, `l`.`level`,
+  ROUND(`c`.`health_1` + (`c`.`health_80` - `c`.`health_1`) * POW((`l`.`level` - 1) / 79, 2)),
+  ROUND(`c`.`mana_1` + (`c`.`mana_80` - `c`.`mana_1`) * (`l`.`level` - 1) / 79)
+FROM `levels` `l`
+CROSS JOIN (
+  SELECT 1 AS `class`, 20 AS `health_1`, 8121 AS `health_80`, 0 AS `mana_1`, 0 AS `mana_80` UNION ALL
+  SELECT 2, 28, 6934, 60, 4394 UNION ALL
+  SELECT 3, 46, 7324, 65, 5046 UNION ALL
+  SELECT 4, 45, 7604, 0, 0 UNION ALL
+  SELECT 5, 52, 6960, 123, 3863 UNION ALL
+  SELECT 6, 20, 8121, 0, 0 UNION ALL
+  SELECT 7, 40, 6939, 55, 4396 UNION ALL
+  SELECT 8, 32, 6963, 100, 3268 UNION ALL
+  SELECT 9, 23, 7164, 90, 3856 UNION ALL
+  SELECT 11, 44, 7417, 50, 3496
+) `c`;
@@ -0,0 +1,38 @@
+ALTER TABLE `characters`
+  ADD `xp` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'Experience gathered towards the next level.',
+  ADD `rest_bonus` float NOT NULL DEFAULT '0' COMMENT 'Rested experience that is still left.',
+  ADD `logout_time` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'Unix time of the last save, rested experience builds up from here while logged out.',
+  ADD `logout_resting` tinyint(3) unsigned NOT NULL DEFAULT '0' COMMENT 'Whether the character logged out in an inn or city.',
+  ADD `explored_zones` blob NULL COMMENT 'Explored area bits, as little endian 32 bit words.';
+
+CREATE TABLE `player_xp_for_level` (
+  `level` tinyint(3) unsigned NOT NULL DEFAULT '0',
+  `xp_for_next_level` int(10) unsigned NOT NULL DEFAULT '0',
+  PRIMARY KEY (`level`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8;
+
+INSERT INTO `player_xp_for_level` (`level`, `xp_for_next_level`) VALUES
+(1, 400), (2, 900), (3, 1400), (4, 2100), (5, 2800), (6, 3600), (7, 4500), (8, 5400), (9, 6500), (10, 7600),
+(11, 8700), (12, 9800), (13, 11000), (14, 12300), (15, 13600), (16, 15000), (17, 16400), (18, 17800), (19, 19300), (20, 20800),
+(21, 22400), (22, 24000), (23, 25500), (24, 27200), (25, 28900), (26, 30500), (27, 32200), (28, 33900), (29, 36300), (30, 38800),
+(31, 41600), (32, 44600), (33, 48000), (34, 51400), (35, 55000), (36, 58700), (37, 62400), (38, 66200), (39, 70200), (40, 74300),
+(41, 78500), (42, 82800), (43, 87100), (44, 91600), (45, 96300), (46, 101000), (47, 105800), (48, 110700), (49, 115700), (50, 120900),
+(51, 126100), (52, 131500), (53, 137000), (54, 142500), (55, 148200), (56, 154000), (57, 159900), (58, 165800), (59, 172000), (60, 290000),
+(61, 317000), (62, 349000), (63, 386000), (64, 428000), (65, 475000), (66, 527000), (67, 585000), (68, 648000), (69, 717000), (70, 1523800),
+(71, 1539600), (72, 1555700), (73, 1571800), (74, 1587900), (75, 1604200), (76, 1620700), (77, 1637400), (78, 1653900), (79, 1670800);
+
+CREATE TABLE `exploration_basexp` (
+  `level` tinyint(3) unsigned NOT NULL DEFAULT '0' COMMENT 'Exploration level of the area.',
+  `base_xp` int(10) unsigned NOT NULL DEFAULT '0',
+  PRIMARY KEY (`level`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8;
+
+INSERT INTO `exploration_basexp` (`level`, `base_xp`) VALUES
+(0, 0), (1, 5), (2, 15), (3, 25), (4, 35), (5, 45), (6, 55), (7, 65), (8, 70), (9, 80), (10, 85),
+(11, 90), (12, 90), (13, 90), (14, 100), (15, 105), (16, 115), (17, 125), (18, 135), (19, 145), (20, 155),
+(21, 165), (22, 175), (23, 185), (24, 195), (25, 200), (26, 210), (27, 220), (28, 230), (29, 240), (30, 245),
+(31, 250), (32, 255), (33, 265), (34, 270), (35, 275), (36, 280), (37, 285), (38, 285), (39, 300), (40, 315),
+(41, 330), (42, 345), (43, 360), (44, 375), (45, 390), (46, 405), (47, 420), (48, 440), (49, 455), (50, 470),
+(51, 490), (52, 510), (53, 530), (54, 540), (55, 560), (56, 580), (57, 600), (58, 620), (59, 640), (60, 660),
+(61, 970), (62, 1000), (63, 1050), (64, 1080), (65, 1100), (66, 1130), (67, 1160), (68, 1200), (69, 1230), (70, 1300),
+(71, 1330), (72, 1370), (73, 1410), (74, 1440), (75, 1470), (76, 1510), (77, 1530), (78, 1600), (79, 1630), (80, 1670);
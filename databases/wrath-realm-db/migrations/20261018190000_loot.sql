@@ -0,0 +1,46 @@
+ALTER TABLE `creature_template`
+  ADD `loot_id` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'Loot dropped on death (See creature_loot_template.entry), 0 for none.',
+  ADD `min_gold` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'Least money dropped on death, in copper.',
+  ADD `max_gold` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'Most money dropped on death, in copper.';
+
+CREATE TABLE `creature_loot_template` (
+  `entry` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'See creature_template.loot_id.',
+  `item` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'See item_template.id.',
+  `chance` float NOT NULL DEFAULT '100' COMMENT 'Percent chance to drop. Within a group, 0 means an equal share of whatever the other entries leave.',
+  `quest_required` tinyint(3) unsigned NOT NULL DEFAULT '0' COMMENT 'Only visible to characters that need the item for a quest.',
+  `group_id` tinyint(3) unsigned NOT NULL DEFAULT '0' COMMENT 'At most one item of a group drops, 0 means no group.',
+  `min_count` tinyint(3) unsigned NOT NULL DEFAULT '1',
+  `max_count` tinyint(3) unsigned NOT NULL DEFAULT '1',
+  PRIMARY KEY (`entry`, `item`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8;
+
+CREATE TABLE `gameobject_loot_template` (
+  `entry` int(10) unsigned NOT NULL DEFAULT '0',
+  `item` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'See item_template.id.',
+  `chance` float NOT NULL DEFAULT '100' COMMENT 'Percent chance to drop. Within a group, 0 means an equal share of whatever the other entries leave.',
+  `quest_required` tinyint(3) unsigned NOT NULL DEFAULT '0' COMMENT 'Only visible to characters that need the item for a quest.',
+  `group_id` tinyint(3) unsigned NOT NULL DEFAULT '0' COMMENT 'At most one item of a group drops, 0 means no group.',
+  `min_count` tinyint(3) unsigned NOT NULL DEFAULT '1',
+  `max_count` tinyint(3) unsigned NOT NULL DEFAULT '1',
+  PRIMARY KEY (`entry`, `item`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8;
+
+CREATE TABLE `item_loot_template` (
+  `entry` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'The container item (See item_template.id).',
+  `item` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'See item_template.id.',
+  `chance` float NOT NULL DEFAULT '100' COMMENT 'Percent chance to drop. Within a group, 0 means an equal share of whatever the other entries leave.',
+  `quest_required` tinyint(3) unsigned NOT NULL DEFAULT '0' COMMENT 'Only visible to characters that need the item for a quest.',
+  `group_id` tinyint(3) unsigned NOT NULL DEFAULT '0' COMMENT 'At most one item of a group drops, 0 means no group.',
+  `min_count` tinyint(3) unsigned NOT NULL DEFAULT '1',
+  `max_count` tinyint(3) unsigned NOT NULL DEFAULT '1',
+  PRIMARY KEY (`entry`, `item`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8;
+
+UPDATE `creature_template` SET `loot_id` = 6, `min_gold` = 1, `max_gold` = 5 WHERE `entry` = 6;
+UPDATE `creature_template` SET `loot_id` = 299 WHERE `entry` = 299;
+
+INSERT INTO `creature_loot_template` (`entry`, `item`, `chance`, `quest_required`, `group_id`, `min_count`, `max_count`) VALUES
+(6, 772, 30, 0, 0, 1, 1),
+(6, 2589, 20, 0, 0, 1, 2),
+(299, 750, 40, 0, 0, 1, 1),
+(299, 4865, 60, 0, 0, 1, 1);
@@ -0,0 +1,114 @@
+CREATE TABLE `quest_template` (
+  `id` int(10) unsigned NOT NULL DEFAULT '0',
+  `quest_level` smallint(6) NOT NULL DEFAULT '1' COMMENT 'Level of the quest, -1 scales with the character.',
+  `min_level` tinyint(3) unsigned NOT NULL DEFAULT '0' COMMENT 'Lowest level to accept the quest.',
+  `zone_or_sort` smallint(6) NOT NULL DEFAULT '0' COMMENT 'The zone the quest is listed under (See AreaTable.dbc), or the negative QuestSort.dbc id.',
+  `flags` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'Daily, sharable, etc.',
+  `required_races` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'Bits of the races that may take the quest, 0 for all.',
+  `required_classes` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'Bits of the classes that may take the quest, 0 for all.',
+  `prev_quest_id` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'Has to be completed first, 0 for none.',
+  `suggested_players` tinyint(3) unsigned NOT NULL DEFAULT '0',
+  `reward_xp` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'Experience at the quest level, less for characters far above it.',
+  `reward_money` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'In copper.',
+  `title` text NOT NULL,
+  `details` text NOT NULL COMMENT 'Shown when the quest is offered.',
+  `objectives` text NOT NULL COMMENT 'Summary in the quest log.',
+  `offer_reward_text` text NOT NULL COMMENT 'Shown when the quest is turned in.',
+  `request_items_text` text NOT NULL COMMENT 'Shown when talking to the ender before the quest is complete.',
+  `end_text` text NOT NULL,
+  PRIMARY KEY (`id`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8;
+
+CREATE TABLE `quest_objective` (
+  `quest` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'See quest_template.id.',
+  `objective_index` tinyint(3) unsigned NOT NULL DEFAULT '0' COMMENT 'Kills have up to 4 counters, items up to 6 slots.',
+  `objective_type` tinyint(3) unsigned NOT NULL DEFAULT '0' COMMENT '0 for killing creatures, 1 for collecting items.',
+  `target` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'See creature_template.entry or item_template.id.',
+  `count` smallint(5) unsigned NOT NULL DEFAULT '1',
+  PRIMARY KEY (`quest`, `objective_type`, `objective_index`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8;
+
+CREATE TABLE `quest_reward_item` (
+  `quest` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'See quest_template.id.',
+  `item` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'See item_template.id.',
+  `count` smallint(5) unsigned NOT NULL DEFAULT '1',
+  `choice` tinyint(3) unsigned NOT NULL DEFAULT '0' COMMENT 'The character picks one of the choice items, and gets all others.',
+  PRIMARY KEY (`quest`, `item`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8;
+
+CREATE TABLE `quest_reward_faction` (
+  `quest` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'See quest_template.id.',
+  `faction` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'See Faction.dbc.',
+  `value` int(11) NOT NULL DEFAULT '0' COMMENT 'Reputation gained, negative to lose it.',
+  PRIMARY KEY (`quest`, `faction`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8;
+
+CREATE TABLE `creature_queststarter` (
+  `id` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'See creature_template.entry.',
+  `quest` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'See quest_template.id.',
+  PRIMARY KEY (`id`, `quest`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8;
+
+CREATE TABLE `creature_questender` (
+  `id` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'See creature_template.entry.',
+  `quest` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'See quest_template.id.',
+  PRIMARY KEY (`id`, `quest`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8;
+
+CREATE TABLE `areatrigger_involvedrelation` (
+  `id` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'Exploring this area trigger completes the quest (See AreaTrigger.dbc).',
+  `quest` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'See quest_template.id.',
+  PRIMARY KEY (`id`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8;
+
+CREATE TABLE `character_queststatus` (
+  `character_id` int(10) unsigned NOT NULL DEFAULT '0',
+  `quest` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'See quest_template.id.',
+  `slot` tinyint(3) unsigned NOT NULL DEFAULT '0' COMMENT 'Position in the quest log.',
+  `status` tinyint(3) unsigned NOT NULL DEFAULT '0' COMMENT '0 incomplete, 1 complete, 2 failed.',
+  `explored` tinyint(3) unsigned NOT NULL DEFAULT '0',
+  `kill_count_1` smallint(5) unsigned NOT NULL DEFAULT '0',
+  `kill_count_2` smallint(5) unsigned NOT NULL DEFAULT '0',
+  `kill_count_3` smallint(5) unsigned NOT NULL DEFAULT '0',
+  `kill_count_4` smallint(5) unsigned NOT NULL DEFAULT '0',
+  CONSTRAINT `FK_CHARACTER_QUESTSTATUS_CHARACTER` FOREIGN KEY (`character_id`) REFERENCES `characters` (`id`) ON DELETE CASCADE ON UPDATE RESTRICT,
+  PRIMARY KEY (`character_id`, `quest`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8;
+
+CREATE TABLE `character_queststatus_rewarded` (
+  `character_id` int(10) unsigned NOT NULL DEFAULT '0',
+  `quest` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'See quest_template.id.',
+  `reward_time` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'Unix time of the turn in, daily quests can be done again after the next reset.',
+  CONSTRAINT `FK_CHARACTER_QUESTSTATUS_REWARDED_CHARACTER` FOREIGN KEY (`character_id`) REFERENCES `characters` (`id`) ON DELETE CASCADE ON UPDATE RESTRICT,
+  PRIMARY KEY (`character_id`, `quest`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8;
+
+CREATE TABLE `character_reputation` (
+  `character_id` int(10) unsigned NOT NULL DEFAULT '0',
+  `faction` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'See Faction.dbc.',
+  `standing` int(11) NOT NULL DEFAULT '0' COMMENT 'Reputation gained on top of the base reputation.',
+  CONSTRAINT `FK_CHARACTER_REPUTATION_CHARACTER` FOREIGN KEY (`character_id`) REFERENCES `characters` (`id`) ON DELETE CASCADE ON UPDATE RESTRICT,
+  PRIMARY KEY (`character_id`, `faction`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8;
+
+INSERT INTO `quest_template` (`id`, `quest_level`, `min_level`, `zone_or_sort`, `flags`, `required_races`, `required_classes`, `prev_quest_id`, `suggested_players`, `reward_xp`, `reward_money`, `title`, `details`, `objectives`, `offer_reward_text`, `request_items_text`, `end_text`) VALUES
+(7, 2, 1, 9, 0, 0, 0, 0, 0, 170, 35, 'Kobold Camp Cleanup', 'Your first task is one of cleansing. A clan of kobolds have infested the woods to the north. Go there and fight the Kobold Vermin you find. Reduce their numbers so that we may one day drive them from Northshire.', 'Kill 10 Kobold Vermin, then return to Marshal McBride.', 'You have done well, $N. The kobolds will think twice before they return.', 'How fares your hunt, $N?', ''),
+(33, 2, 1, 9, 0, 0, 0, 0, 0, 170, 50, 'Wolves Across the Border', 'The wolves of Northshire have grown bold. Bring me their meat, and we will see that the Abbey is well fed.', 'Bring 8 pieces of Tough Wolf Meat to Deputy Willem.', 'Ah, good. This will keep the Abbey fed for a while.', 'Have you brought the meat, $N?', '');
+
+INSERT INTO `quest_objective` (`quest`, `objective_index`, `objective_type`, `target`, `count`) VALUES
+(7, 0, 0, 6, 10),
+(33, 0, 1, 750, 8);
+
+INSERT INTO `quest_reward_faction` (`quest`, `faction`, `value`) VALUES
+(7, 72, 250),
+(33, 72, 250);
+
+INSERT INTO `creature_queststarter` (`id`, `quest`) VALUES
+(197, 7),
+(823, 33);
+
+INSERT INTO `creature_questender` (`id`, `quest`) VALUES
+(197, 7),
+(823, 33);
+
+UPDATE `creature_loot_template` SET `quest_required` = 1 WHERE `entry` = 299 AND `item` = 750;
@@ -0,0 +1,81 @@
+ALTER TABLE `creature_template`
+  ADD `gossip_menu_id` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'Menu shown when talking to the creature (See gossip_menu.entry), 0 for none.';
+
+CREATE TABLE `npc_text` (
+  `id` int(10) unsigned NOT NULL DEFAULT '0',
+  `text` text NOT NULL COMMENT 'Greeting at the top of a gossip menu.',
+  PRIMARY KEY (`id`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8;
+
+CREATE TABLE `gossip_menu` (
+  `entry` int(10) unsigned NOT NULL DEFAULT '0',
+  `text_id` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'See npc_text.id.',
+  PRIMARY KEY (`entry`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8;
+
+CREATE TABLE `gossip_menu_option` (
+  `menu_id` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'See gossip_menu.entry.',
+  `option_id` tinyint(3) unsigned NOT NULL DEFAULT '0' COMMENT 'Position of the option in the menu.',
+  `option_icon` tinyint(3) unsigned NOT NULL DEFAULT '0' COMMENT 'Chat bubble, vendor bag, trainer book, etc.',
+  `option_text` varchar(255) NOT NULL DEFAULT '',
+  `option_type` tinyint(3) unsigned NOT NULL DEFAULT '0' COMMENT 'What picking the option does: 0 opens another menu, 1 vendor, 2 banker, 3 spirit healer.',
+  `action_menu_id` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'Menu opened by options of type 0 (See gossip_menu.entry).',
+  PRIMARY KEY (`menu_id`, `option_id`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8;
+
+CREATE TABLE `npc_vendor` (
+  `entry` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'See creature_template.entry.',
+  `slot` tinyint(3) unsigned NOT NULL DEFAULT '0' COMMENT 'Position of the item in the vendor window.',
+  `item` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'See item_template.id.',
+  `max_count` tinyint(3) unsigned NOT NULL DEFAULT '0' COMMENT 'Limited supply of the item, 0 for unlimited.',
+  `restock_time` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'Seconds until a sold out supply is back to max_count.',
+  PRIMARY KEY (`entry`, `item`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8;
+
+INSERT INTO `creature_template` (`entry`, `name`, `subname`, `display_id`, `min_level`, `max_level`, `min_health`, `max_health`, `faction`, `npc_flags`, `unit_flags`, `scale`, `creature_type`, `gossip_menu_id`) VALUES
+(152,'Brother Danil','General Supplies',3255,5,5,166,166,12,129,0,1,7,1),
+(1213,'Godric Rothgar','Armorer & Weaponsmith',3256,5,5,166,166,12,128,0,1,7,0);
+
+INSERT INTO `creature` (`entry`, `map`, `position_x`, `position_y`, `position_z`, `orientation`) VALUES
+(152,0,-8901.59,-112.716,81.8481,4.9043),
+(1213,0,-8898.23,-119.838,81.8321,5.2534);
+
+INSERT INTO `npc_text` (`id`, `text`) VALUES
+(1, 'Welcome to Northshire Abbey, $N. If you are in need of food or drink, you have come to the right place.'),
+(2, 'The brothers of the Abbey have tended to these lands for generations. Lately the kobolds and wolves have made that hard work.');
+
+INSERT INTO `gossip_menu` (`entry`, `text_id`) VALUES
+(1, 1),
+(2, 2);
+
+INSERT INTO `gossip_menu_option` (`menu_id`, `option_id`, `option_icon`, `option_text`, `option_type`, `action_menu_id`) VALUES
+(1, 0, 1, 'Let me browse your goods.', 1, 0),
+(1, 1, 0, 'Tell me about the Abbey.', 0, 2);
+
+INSERT INTO `npc_vendor` (`entry`, `slot`, `item`, `max_count`, `restock_time`) VALUES
+(152, 0, 159, 0, 0),
+(152, 1, 4540, 0, 0),
+(152, 2, 2070, 0, 0),
+(152, 3, 4496, 2, 3600),
+(1213, 0, 2488, 0, 0),
+(1213, 1, 2489, 0, 0),
+(1213, 2, 2490, 0, 0),
+(1213, 3, 2491, 0, 0),
+(1213, 4, 2492, 0, 0),
+(1213, 5, 2493, 0, 0),
+(1213, 6, 2494, 0, 0),
+(1213, 7, 2495, 0, 0);
+
+INSERT INTO `item_template` (`id`, `class`, `subclass`, `name`, `displayid`, `Quality`, `BuyPrice`, `SellPrice`, `inventory_type`, `ItemLevel`, `RequiredLevel`, `stackable`, `ContainerSlots`, `dmg_min1`, `dmg_max1`, `delay`, `spellid_1`, `Material`, `sheath`, `MaxDurability`) VALUES
+(159, 0, 5, 'Refreshing Spring Water', 18084, 1, 25, 1, 0, 5, 1, 20, 0, 0, 0, 0, 430, -1, 0, 0),
+(4540, 0, 5, 'Tough Hunk of Bread', 6399, 1, 25, 1, 0, 5, 1, 20, 0, 0, 0, 0, 433, -1, 0, 0),
+(2070, 0, 5, 'Darnassian Bleu', 6353, 1, 25, 1, 0, 5, 1, 20, 0, 0, 0, 0, 433, -1, 0, 0),
+(4496, 1, 0, 'Small Brown Pouch', 1183, 1, 400, 100, 18, 5, 0, 1, 6, 0, 0, 0, 0, 8, 0, 0),
+(2488, 2, 7, 'Gladius', 8494, 1, 96, 19, 13, 5, 1, 1, 0, 3, 7, 2600, 0, 1, 3, 25),
+(2489, 2, 8, 'Two-Handed Sword', 8495, 1, 121, 24, 17, 5, 1, 1, 0, 8, 12, 3300, 0, 1, 1, 30),
+(2490, 2, 0, 'Tomahawk', 8496, 1, 96, 19, 13, 5, 1, 1, 0, 3, 6, 2300, 0, 1, 3, 25),
+(2491, 2, 1, 'Large Axe', 8497, 1, 121, 24, 17, 5, 1, 1, 0, 8, 13, 3300, 0, 1, 1, 30),
+(2492, 2, 4, 'Cudgel', 8498, 1, 96, 19, 13, 5, 1, 1, 0, 3, 6, 2300, 0, 2, 3, 25),
+(2493, 2, 5, 'Wooden Mallet', 8499, 1, 121, 24, 17, 5, 1, 1, 0, 9, 14, 3500, 0, 2, 1, 30),
+(2494, 2, 15, 'Stiletto', 8500, 1, 96, 19, 13, 5, 1, 1, 0, 2, 5, 1600, 0, 1, 3, 20),
+(2495, 2, 10, 'Walking Stick', 8501, 1, 121, 24, 17, 5, 1, 1, 0, 7, 11, 3000, 0, 2, 2, 30);
@@ -0,0 +1,5 @@
+ALTER TABLE `characters`
+  ADD `money` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'In copper.';
+
+/* The weapon vendor of Northshire Abbey repairs as well */
+UPDATE `creature_template` SET `npc_flags` = 4224 WHERE `entry` = 1213;
@@ -0,0 +1,55 @@
+CREATE TABLE `character_spell` (
+  `character_id` int(10) unsigned NOT NULL DEFAULT '0',
+  `spell` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'See Spell.dbc.',
+  CONSTRAINT `FK_CHARACTER_SPELL_CHARACTER` FOREIGN KEY (`character_id`) REFERENCES `characters` (`id`) ON DELETE CASCADE ON UPDATE RESTRICT,
+  PRIMARY KEY (`character_id`, `spell`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8;
+
+CREATE TABLE `character_skill` (
+  `character_id` int(10) unsigned NOT NULL DEFAULT '0',
+  `skill` smallint(5) unsigned NOT NULL DEFAULT '0' COMMENT 'See SkillLine.dbc.',
+  `value` smallint(5) unsigned NOT NULL DEFAULT '0',
+  `max_value` smallint(5) unsigned NOT NULL DEFAULT '0',
+  CONSTRAINT `FK_CHARACTER_SKILL_CHARACTER` FOREIGN KEY (`character_id`) REFERENCES `characters` (`id`) ON DELETE CASCADE ON UPDATE RESTRICT,
+  PRIMARY KEY (`character_id`, `skill`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8;
+
+ALTER TABLE `gossip_menu_option`
+  MODIFY `option_type` tinyint(3) unsigned NOT NULL DEFAULT '0' COMMENT 'What picking the option does: 0 opens another menu, 1 vendor, 2 banker, 3 spirit healer, 4 trainer.';
+
+ALTER TABLE `creature_template`
+  ADD `trainer_class` tinyint(3) unsigned NOT NULL DEFAULT '0' COMMENT 'Only characters of this class (See ChrClasses.dbc) can train here, 0 for everyone.';
+
+CREATE TABLE `npc_trainer` (
+  `entry` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'See creature_template.entry.',
+  `spell` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'See Spell.dbc.',
+  `cost` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'In copper.',
+  `required_level` tinyint(3) unsigned NOT NULL DEFAULT '0',
+  `required_skill` smallint(5) unsigned NOT NULL DEFAULT '0' COMMENT 'See SkillLine.dbc, 0 for none.',
+  `required_skill_value` smallint(5) unsigned NOT NULL DEFAULT '0',
+  `required_spell` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'Has to be known first, usually the previous rank. 0 for none.',
+  PRIMARY KEY (`entry`, `spell`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8;
+
+INSERT INTO `creature_template` (`entry`, `name`, `subname`, `display_id`, `min_level`, `max_level`, `min_health`, `max_health`, `faction`, `npc_flags`, `unit_flags`, `scale`, `creature_type`, `gossip_menu_id`, `trainer_class`) VALUES
+(911,'Llane Beshere','Warrior Trainer',1428,10,10,413,413,12,49,0,1,7,3,1);
+
+INSERT INTO `creature` (`entry`, `map`, `position_x`, `position_y`, `position_z`, `orientation`) VALUES
+(911,0,-8918.23,-208.466,82.1243,3.3857);
+
+INSERT INTO `npc_text` (`id`, `text`) VALUES
+(3, 'Hello, $C. Ready for some training?');
+
+INSERT INTO `gossip_menu` (`entry`, `text_id`) VALUES
+(3, 3);
+
+INSERT INTO `gossip_menu_option` (`menu_id`, `option_id`, `option_icon`, `option_text`, `option_type`, `action_menu_id`) VALUES
+(3, 0, 3, 'I require training.', 4, 0);
+
+INSERT INTO `npc_trainer` (`entry`, `spell`, `cost`, `required_level`, `required_skill`, `required_skill_value`, `required_spell`) VALUES
+(911, 100, 10, 4, 0, 0, 0),
+(911, 772, 10, 4, 0, 0, 0),
+(911, 6343, 100, 6, 0, 0, 0),
+(911, 284, 100, 8, 0, 0, 78),
+(911, 1715, 100, 8, 0, 0, 0),
+(911, 2687, 600, 10, 0, 0, 0);
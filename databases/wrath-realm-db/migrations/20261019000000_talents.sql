@@ -0,0 +1,32 @@
+ALTER TABLE `characters`
+  ADD `talent_spec_count` tinyint(3) unsigned NOT NULL DEFAULT '1' COMMENT '2 once dual specialization is bought.',
+  ADD `active_talent_spec` tinyint(3) unsigned NOT NULL DEFAULT '0',
+  ADD `talent_reset_cost` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'Paid for the last talent reset, in copper. The next one costs more.',
+  ADD `talent_reset_time` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'Unix time of the last talent reset.';
+
+CREATE TABLE `character_talent` (
+  `character_id` int(10) unsigned NOT NULL DEFAULT '0',
+  `spec` tinyint(3) unsigned NOT NULL DEFAULT '0',
+  `talent` int(10) unsigned NOT NULL DEFAULT '0' COMMENT 'See Talent.dbc.',
+  `talent_rank` tinyint(3) unsigned NOT NULL DEFAULT '0' COMMENT 'Counting from 0 for the first rank.',
+  CONSTRAINT `FK_CHARACTER_TALENT_CHARACTER` FOREIGN KEY (`character_id`) REFERENCES `characters` (`id`) ON DELETE CASCADE ON UPDATE RESTRICT,
+  PRIMARY KEY (`character_id`, `spec`, `talent`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8;
+
+CREATE TABLE `character_action` (
+  `character_id` int(10) unsigned NOT NULL DEFAULT '0',
+  `spec` tinyint(3) unsigned NOT NULL DEFAULT '0' COMMENT 'Every talent specialization has action bars of its own.',
+  `button` tinyint(3) unsigned NOT NULL DEFAULT '0',
+  `action` smallint(5) unsigned NOT NULL DEFAULT '0' COMMENT 'Spell, item or macro, depending on the action type.',
+  `action_type` tinyint(3) unsigned NOT NULL DEFAULT '0',
+  `misc` tinyint(3) unsigned NOT NULL DEFAULT '0',
+  CONSTRAINT `FK_CHARACTER_ACTION_CHARACTER` FOREIGN KEY (`character_id`) REFERENCES `characters` (`id`) ON DELETE CASCADE ON UPDATE RESTRICT,
+  PRIMARY KEY (`character_id`, `spec`, `button`)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8;
+
+ALTER TABLE `gossip_menu_option`
+  MODIFY `option_type` tinyint(3) unsigned NOT NULL DEFAULT '0' COMMENT 'What picking the option does: 0 opens another menu, 1 vendor, 2 banker, 3 spirit healer, 4 trainer, 5 talent reset, 6 dual specialization.';
+
+INSERT INTO `gossip_menu_option` (`menu_id`, `option_id`, `option_icon`, `option_text`, `option_type`, `action_menu_id`) VALUES
+(3, 1, 0, 'I wish to unlearn my talents.', 5, 0),
+(3, 2, 0, 'Purchase a Dual Talent Specialization.', 6, 0);
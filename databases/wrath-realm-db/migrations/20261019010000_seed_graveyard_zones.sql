@@ -0,0 +1,9 @@
+/* Graveyards of the starting zones. Locations in WorldSafeLocs.dbc without a link here are not used for releasing spirits */
+
+INSERT INTO `graveyard_zone` (`id`, `ghost_zone`, `faction`) VALUES
+(4, 12, 469),   /* Elwynn Forest, Goldshire */
+(100, 1, 469),  /* Dun Morogh, Kharanos */
+(93, 141, 469), /* Teldrassil, Dolanaar */
+(10, 14, 67),   /* Durotar, Razor Hill */
+(89, 215, 67),  /* Mulgore, Bloodhoof Village */
+(34, 85, 67);   /* Tirisfal Glades, Brill */
@@ -0,0 +1,2 @@
+/* Skills used to be saved with placeholder values of 299/300. Characters without skill rows get their real starting skills on their next login */
+DELETE FROM `character_skill` WHERE `value` = 299 AND `max_value` = 300;
@@ -31,6 +31,19 @@ pub struct DBCharacter {
     pub tutorial_data: Vec<u8>,
     pub playtime_total: u32,
     pub playtime_level: u32,
+    pub dungeon_difficulty: u8,
+    pub raid_difficulty: u8,
+    pub bank_bag_slots: u8,
+    pub xp: u32,
+    pub rest_bonus: f32,
+    pub logout_time: u32,
+    pub logout_resting: u8,
+    pub explored_zones: Option<Vec<u8>>,
+    pub money: u32,
+    pub talent_spec_count: u8,
+    pub active_talent_spec: u8,
+    pub talent_reset_cost: u32,
+    pub talent_reset_time: u32,
 }
 
 pub struct DBCharacterCreateParameters {
@@ -53,6 +66,35 @@ pub struct DBCharacterCreateParameters {
     pub o: f32,
 }
 
+pub struct DBCharacterUpdateParameters {
+    pub character_id: u32,
+    pub zone: u16,
+    pub level: u8,
+    pub map: u16,
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub o: f32,
+    pub instance_id: u32,
+    pub tutorial_data: Vec<u8>,
+    pub playtime_total: u32,
+    pub playtime_level: u32,
+    pub dungeon_difficulty: u8,
+    pub raid_difficulty: u8,
+    pub bank_bag_slots: u8,
+    pub player_flags: u32,
+    pub xp: u32,
+    pub rest_bonus: f32,
+    pub logout_time: u32,
+    pub logout_resting: u8,
+    pub explored_zones: Vec<u8>,
+    pub money: u32,
+    pub talent_spec_count: u8,
+    pub active_talent_spec: u8,
+    pub talent_reset_cost: u32,
+    pub talent_reset_time: u32,
+}
+
 impl super::RealmDatabase {
     pub async fn get_characters_for_account(&self, account_id: u32) -> Result<Vec<DBCharacter>> {
         let res = sqlx::query_as!(DBCharacter, "SELECT * FROM characters WHERE account_id = ?", account_id)
@@ -129,4 +171,48 @@ impl super::RealmDatabase {
 
         Ok(res.rows_affected() > 0)
     }
+
+    pub async fn update_character(&self, params: &DBCharacterUpdateParameters) -> Result<()> {
+        sqlx::query!(
+            "UPDATE characters SET zone = ?, level = ?, map = ?, x = ?, y = ?, z = ?, o = ?, instance_id = ?, tutorial_data = ?, playtime_total = ?, playtime_level = ?, dungeon_difficulty = ?, raid_difficulty = ?, bank_bag_slots = ?, player_flags = ?, xp = ?, rest_bonus = ?, logout_time = ?, logout_resting = ?, explored_zones = ?, money = ?, talent_spec_count = ?, active_talent_spec = ?, talent_reset_cost = ?, talent_reset_time = ? WHERE id = ?",
+            params.zone,
+            params.level,
+            params.map,
+            params.x,
+            params.y,
+            params.z,
+            params.o,
+            params.instance_id,
+            params.tutorial_data,
+            params.playtime_total,
+            params.playtime_level,
+            params.dungeon_difficulty,
+            params.raid_difficulty,
+            params.bank_bag_slots,
+            params.player_flags,
+            params.xp,
+            params.rest_bonus,
+            params.logout_time,
+            params.logout_resting,
+            params.explored_zones,
+            params.money,
+            params.talent_spec_count,
+            params.active_talent_spec,
+            params.talent_reset_cost,
+            params.talent_reset_time,
+            params.character_id,
+        )
+        .execute(&self.connection_pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_highest_character_instance_id(&self) -> Result<u32> {
+        let res = sqlx::query!("SELECT MAX(instance_id) AS max_instance_id FROM characters")
+            .fetch_one(&self.connection_pool)
+            .await?;
+
+        Ok(res.max_instance_id.unwrap_or(0))
+    }
 }
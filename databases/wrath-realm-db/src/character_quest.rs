@@ -0,0 +1,125 @@
+use anyhow::Result;
+use sqlx::{MySql, QueryBuilder};
+
+pub struct DBCharacterQuestStatus {
+    pub character_id: u32,
+    pub quest: u32,
+    pub slot: u8,
+    pub status: u8,
+    pub explored: u8,
+    pub kill_count_1: u16,
+    pub kill_count_2: u16,
+    pub kill_count_3: u16,
+    pub kill_count_4: u16,
+}
+
+pub struct DBCharacterRewardedQuest {
+    pub character_id: u32,
+    pub quest: u32,
+    pub reward_time: u32,
+}
+
+pub struct DBCharacterReputation {
+    pub character_id: u32,
+    pub faction: u32,
+    pub standing: i32,
+}
+
+impl super::RealmDatabase {
+    pub async fn get_character_quest_statuses(&self, character_id: u32) -> Result<Vec<DBCharacterQuestStatus>> {
+        let res = sqlx::query_as!(
+            DBCharacterQuestStatus,
+            "SELECT * FROM character_queststatus WHERE character_id = ?",
+            character_id
+        )
+        .fetch_all(&self.connection_pool)
+        .await?;
+
+        Ok(res)
+    }
+
+    pub async fn get_character_rewarded_quests(&self, character_id: u32) -> Result<Vec<DBCharacterRewardedQuest>> {
+        let res = sqlx::query_as!(
+            DBCharacterRewardedQuest,
+            "SELECT * FROM character_queststatus_rewarded WHERE character_id = ?",
+            character_id
+        )
+        .fetch_all(&self.connection_pool)
+        .await?;
+
+        Ok(res)
+    }
+
+    pub async fn get_character_reputations(&self, character_id: u32) -> Result<Vec<DBCharacterReputation>> {
+        let res = sqlx::query_as!(
+            DBCharacterReputation,
+            "SELECT * FROM character_reputation WHERE character_id = ?",
+            character_id
+        )
+        .fetch_all(&self.connection_pool)
+        .await?;
+
+        Ok(res)
+    }
+
+    //Replaces the quest log, turned in quests and reputation of this character with the given ones
+    pub async fn replace_character_quests(
+        &self,
+        character_id: u32,
+        statuses: Vec<DBCharacterQuestStatus>,
+        rewarded: Vec<DBCharacterRewardedQuest>,
+        reputations: Vec<DBCharacterReputation>,
+    ) -> Result<()> {
+        let mut transaction = self.connection_pool.begin().await?;
+
+        sqlx::query!("DELETE FROM character_queststatus WHERE character_id = ?", character_id)
+            .execute(&mut *transaction)
+            .await?;
+        sqlx::query!("DELETE FROM character_queststatus_rewarded WHERE character_id = ?", character_id)
+            .execute(&mut *transaction)
+            .await?;
+        sqlx::query!("DELETE FROM character_reputation WHERE character_id = ?", character_id)
+            .execute(&mut *transaction)
+            .await?;
+
+        if !statuses.is_empty() {
+            let mut query_builder: QueryBuilder<MySql> = QueryBuilder::new(
+                "INSERT INTO character_queststatus (character_id, quest, slot, status, explored, kill_count_1, kill_count_2, kill_count_3, kill_count_4) ",
+            );
+            query_builder.push_values(statuses, |mut b, status| {
+                b.push_bind(status.character_id)
+                    .push_bind(status.quest)
+                    .push_bind(status.slot)
+                    .push_bind(status.status)
+                    .push_bind(status.explored)
+                    .push_bind(status.kill_count_1)
+                    .push_bind(status.kill_count_2)
+                    .push_bind(status.kill_count_3)
+                    .push_bind(status.kill_count_4);
+            });
+            query_builder.build().execute(&mut *transaction).await?;
+        }
+
+        if !rewarded.is_empty() {
+            let mut query_builder: QueryBuilder<MySql> =
+                QueryBuilder::new("INSERT INTO character_queststatus_rewarded (character_id, quest, reward_time) ");
+            query_builder.push_values(rewarded, |mut b, quest| {
+                b.push_bind(quest.character_id).push_bind(quest.quest).push_bind(quest.reward_time);
+            });
+            query_builder.build().execute(&mut *transaction).await?;
+        }
+
+        if !reputations.is_empty() {
+            let mut query_builder: QueryBuilder<MySql> = QueryBuilder::new("INSERT INTO character_reputation (character_id, faction, standing) ");
+            query_builder.push_values(reputations, |mut b, reputation| {
+                b.push_bind(reputation.character_id)
+                    .push_bind(reputation.faction)
+                    .push_bind(reputation.standing);
+            });
+            query_builder.build().execute(&mut *transaction).await?;
+        }
+
+        transaction.commit().await?;
+        Ok(())
+    }
+}
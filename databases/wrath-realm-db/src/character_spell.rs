@@ -0,0 +1,66 @@
+use anyhow::Result;
+use sqlx::{MySql, QueryBuilder};
+
+pub struct DBCharacterSpell {
+    pub character_id: u32,
+    pub spell: u32,
+}
+
+pub struct DBCharacterSkill {
+    pub character_id: u32,
+    pub skill: u16,
+    pub value: u16,
+    pub max_value: u16,
+}
+
+impl super::RealmDatabase {
+    pub async fn get_character_spells(&self, character_id: u32) -> Result<Vec<DBCharacterSpell>> {
+        let res = sqlx::query_as!(DBCharacterSpell, "SELECT * FROM character_spell WHERE character_id = ?", character_id)
+            .fetch_all(&self.connection_pool)
+            .await?;
+
+        Ok(res)
+    }
+
+    pub async fn get_character_skills(&self, character_id: u32) -> Result<Vec<DBCharacterSkill>> {
+        let res = sqlx::query_as!(DBCharacterSkill, "SELECT * FROM character_skill WHERE character_id = ?", character_id)
+            .fetch_all(&self.connection_pool)
+            .await?;
+
+        Ok(res)
+    }
+
+    //Replaces the known spells and skills of this character with the given ones
+    pub async fn replace_character_spells(&self, character_id: u32, spells: Vec<DBCharacterSpell>, skills: Vec<DBCharacterSkill>) -> Result<()> {
+        let mut transaction = self.connection_pool.begin().await?;
+
+        sqlx::query!("DELETE FROM character_spell WHERE character_id = ?", character_id)
+            .execute(&mut *transaction)
+            .await?;
+        sqlx::query!("DELETE FROM character_skill WHERE character_id = ?", character_id)
+            .execute(&mut *transaction)
+            .await?;
+
+        if !spells.is_empty() {
+            let mut query_builder: QueryBuilder<MySql> = QueryBuilder::new("INSERT INTO character_spell (character_id, spell) ");
+            query_builder.push_values(spells, |mut b, spell| {
+                b.push_bind(spell.character_id).push_bind(spell.spell);
+            });
+            query_builder.build().execute(&mut *transaction).await?;
+        }
+
+        if !skills.is_empty() {
+            let mut query_builder: QueryBuilder<MySql> = QueryBuilder::new("INSERT INTO character_skill (character_id, skill, value, max_value) ");
+            query_builder.push_values(skills, |mut b, skill| {
+                b.push_bind(skill.character_id)
+                    .push_bind(skill.skill)
+                    .push_bind(skill.value)
+                    .push_bind(skill.max_value);
+            });
+            query_builder.build().execute(&mut *transaction).await?;
+        }
+
+        transaction.commit().await?;
+        Ok(())
+    }
+}
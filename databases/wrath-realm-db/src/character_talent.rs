@@ -0,0 +1,76 @@
+use anyhow::Result;
+use sqlx::{MySql, QueryBuilder};
+
+pub struct DBCharacterTalent {
+    pub character_id: u32,
+    pub spec: u8,
+    pub talent: u32,
+    pub talent_rank: u8,
+}
+
+pub struct DBCharacterAction {
+    pub character_id: u32,
+    pub spec: u8,
+    pub button: u8,
+    pub action: u16,
+    pub action_type: u8,
+    pub misc: u8,
+}
+
+impl super::RealmDatabase {
+    pub async fn get_character_talents(&self, character_id: u32) -> Result<Vec<DBCharacterTalent>> {
+        let res = sqlx::query_as!(DBCharacterTalent, "SELECT * FROM character_talent WHERE character_id = ?", character_id)
+            .fetch_all(&self.connection_pool)
+            .await?;
+
+        Ok(res)
+    }
+
+    pub async fn get_character_actions(&self, character_id: u32) -> Result<Vec<DBCharacterAction>> {
+        let res = sqlx::query_as!(DBCharacterAction, "SELECT * FROM character_action WHERE character_id = ?", character_id)
+            .fetch_all(&self.connection_pool)
+            .await?;
+
+        Ok(res)
+    }
+
+    //Replaces the talents and action bars of every talent spec of this character with the given ones
+    pub async fn replace_character_talents(&self, character_id: u32, talents: Vec<DBCharacterTalent>, actions: Vec<DBCharacterAction>) -> Result<()> {
+        let mut transaction = self.connection_pool.begin().await?;
+
+        sqlx::query!("DELETE FROM character_talent WHERE character_id = ?", character_id)
+            .execute(&mut *transaction)
+            .await?;
+        sqlx::query!("DELETE FROM character_action WHERE character_id = ?", character_id)
+            .execute(&mut *transaction)
+            .await?;
+
+        if !talents.is_empty() {
+            let mut query_builder: QueryBuilder<MySql> = QueryBuilder::new("INSERT INTO character_talent (character_id, spec, talent, talent_rank) ");
+            query_builder.push_values(talents, |mut b, talent| {
+                b.push_bind(talent.character_id)
+                    .push_bind(talent.spec)
+                    .push_bind(talent.talent)
+                    .push_bind(talent.talent_rank);
+            });
+            query_builder.build().execute(&mut *transaction).await?;
+        }
+
+        if !actions.is_empty() {
+            let mut query_builder: QueryBuilder<MySql> =
+                QueryBuilder::new("INSERT INTO character_action (character_id, spec, button, action, action_type, misc) ");
+            query_builder.push_values(actions, |mut b, action| {
+                b.push_bind(action.character_id)
+                    .push_bind(action.spec)
+                    .push_bind(action.button)
+                    .push_bind(action.action)
+                    .push_bind(action.action_type)
+                    .push_bind(action.misc);
+            });
+            query_builder.build().execute(&mut *transaction).await?;
+        }
+
+        transaction.commit().await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,76 @@
+use anyhow::Result;
+
+pub struct DBCorpse {
+    pub character_id: u32,
+    pub map: u16,
+    pub instance_id: u32,
+    pub position_x: f32,
+    pub position_y: f32,
+    pub position_z: f32,
+    pub orientation: f32,
+    pub display_id: u32,
+}
+
+pub struct DBGraveyardZone {
+    pub id: u32,
+    pub ghost_zone: u32,
+    pub faction: u16,
+}
+
+impl super::RealmDatabase {
+    pub async fn get_corpses_on_map(&self, map: u16, instance_id: u32) -> Result<Vec<DBCorpse>> {
+        let res = sqlx::query_as!(
+            DBCorpse,
+            "SELECT * FROM character_corpse WHERE map = ? AND instance_id = ?",
+            map,
+            instance_id
+        )
+        .fetch_all(&self.connection_pool)
+        .await?;
+
+        Ok(res)
+    }
+
+    pub async fn get_character_corpse(&self, character_id: u32) -> Result<Option<DBCorpse>> {
+        let res = sqlx::query_as!(DBCorpse, "SELECT * FROM character_corpse WHERE character_id = ?", character_id)
+            .fetch_optional(&self.connection_pool)
+            .await?;
+
+        Ok(res)
+    }
+
+    //A character only ever has one corpse, dying again replaces it
+    pub async fn save_character_corpse(&self, corpse: &DBCorpse) -> Result<()> {
+        sqlx::query!(
+            "REPLACE INTO character_corpse (character_id, map, instance_id, position_x, position_y, position_z, orientation, display_id) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+            corpse.character_id,
+            corpse.map,
+            corpse.instance_id,
+            corpse.position_x,
+            corpse.position_y,
+            corpse.position_z,
+            corpse.orientation,
+            corpse.display_id
+        )
+        .execute(&self.connection_pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn delete_character_corpse(&self, character_id: u32) -> Result<()> {
+        sqlx::query!("DELETE FROM character_corpse WHERE character_id = ?", character_id)
+            .execute(&self.connection_pool)
+            .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_all_graveyard_zones(&self) -> Result<Vec<DBGraveyardZone>> {
+        let res = sqlx::query_as!(DBGraveyardZone, "SELECT * FROM graveyard_zone")
+            .fetch_all(&self.connection_pool)
+            .await?;
+
+        Ok(res)
+    }
+}
@@ -0,0 +1,58 @@
+use anyhow::Result;
+
+pub struct DBCreatureTemplate {
+    pub entry: u32,
+    pub name: String,
+    pub subname: String,
+    pub display_id: u32,
+    pub min_level: u8,
+    pub max_level: u8,
+    pub min_health: u32,
+    pub max_health: u32,
+    pub faction: u16,
+    pub npc_flags: u32,
+    pub unit_flags: u32,
+    pub scale: f32,
+    pub creature_type: u8,
+    pub loot_id: u32,
+    pub min_gold: u32,
+    pub max_gold: u32,
+    pub gossip_menu_id: u32,
+    pub trainer_class: u8,
+}
+
+pub struct DBCreatureSpawn {
+    pub guid: u32,
+    pub entry: u32,
+    pub map: u16,
+    pub position_x: f32,
+    pub position_y: f32,
+    pub position_z: f32,
+    pub orientation: f32,
+}
+
+impl super::RealmDatabase {
+    pub async fn get_creature_template(&self, entry: u32) -> Result<DBCreatureTemplate> {
+        let res = sqlx::query_as!(DBCreatureTemplate, "SELECT * FROM creature_template WHERE entry = ?", entry)
+            .fetch_one(&self.connection_pool)
+            .await?;
+
+        Ok(res)
+    }
+
+    pub async fn get_highest_creature_guid(&self) -> Result<u32> {
+        let res = sqlx::query!("SELECT MAX(guid) AS max_guid FROM creature")
+            .fetch_one(&self.connection_pool)
+            .await?;
+
+        Ok(res.max_guid.unwrap_or(0))
+    }
+
+    pub async fn get_creature_spawns_on_map(&self, map: u16) -> Result<Vec<DBCreatureSpawn>> {
+        let res = sqlx::query_as!(DBCreatureSpawn, "SELECT * FROM creature WHERE map = ?", map)
+            .fetch_all(&self.connection_pool)
+            .await?;
+
+        Ok(res)
+    }
+}
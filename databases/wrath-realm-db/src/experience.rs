@@ -0,0 +1,29 @@
+use anyhow::Result;
+
+pub struct DBPlayerXpForLevel {
+    pub level: u8,
+    pub xp_for_next_level: u32,
+}
+
+pub struct DBExplorationBaseXp {
+    pub level: u8,
+    pub base_xp: u32,
+}
+
+impl super::RealmDatabase {
+    pub async fn get_all_player_xp_for_level(&self) -> Result<Vec<DBPlayerXpForLevel>> {
+        let res = sqlx::query_as!(DBPlayerXpForLevel, "SELECT * FROM player_xp_for_level")
+            .fetch_all(&self.connection_pool)
+            .await?;
+
+        Ok(res)
+    }
+
+    pub async fn get_all_exploration_base_xp(&self) -> Result<Vec<DBExplorationBaseXp>> {
+        let res = sqlx::query_as!(DBExplorationBaseXp, "SELECT * FROM exploration_basexp")
+            .fetch_all(&self.connection_pool)
+            .await?;
+
+        Ok(res)
+    }
+}
@@ -0,0 +1,46 @@
+use anyhow::Result;
+
+pub struct DBNpcText {
+    pub id: u32,
+    pub text: String,
+}
+
+pub struct DBGossipMenu {
+    pub entry: u32,
+    pub text_id: u32,
+}
+
+pub struct DBGossipMenuOption {
+    pub menu_id: u32,
+    pub option_id: u8,
+    pub option_icon: u8,
+    pub option_text: String,
+    pub option_type: u8,
+    pub action_menu_id: u32,
+}
+
+impl super::RealmDatabase {
+    pub async fn get_all_npc_texts(&self) -> Result<Vec<DBNpcText>> {
+        let res = sqlx::query_as!(DBNpcText, "SELECT * FROM npc_text")
+            .fetch_all(&self.connection_pool)
+            .await?;
+
+        Ok(res)
+    }
+
+    pub async fn get_all_gossip_menus(&self) -> Result<Vec<DBGossipMenu>> {
+        let res = sqlx::query_as!(DBGossipMenu, "SELECT * FROM gossip_menu")
+            .fetch_all(&self.connection_pool)
+            .await?;
+
+        Ok(res)
+    }
+
+    pub async fn get_all_gossip_menu_options(&self) -> Result<Vec<DBGossipMenuOption>> {
+        let res = sqlx::query_as!(DBGossipMenuOption, "SELECT * FROM gossip_menu_option ORDER BY menu_id, option_id")
+            .fetch_all(&self.connection_pool)
+            .await?;
+
+        Ok(res)
+    }
+}
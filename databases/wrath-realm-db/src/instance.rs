@@ -0,0 +1,65 @@
+use anyhow::Result;
+
+pub struct DBInstance {
+    pub id: u32,
+    pub map: u16,
+    pub difficulty: u8,
+    pub reset_time: u64,
+}
+
+impl super::RealmDatabase {
+    pub async fn get_highest_instance_id(&self) -> Result<u32> {
+        let res = sqlx::query!("SELECT MAX(id) AS max_id FROM instance")
+            .fetch_one(&self.connection_pool)
+            .await?;
+
+        Ok(res.max_id.unwrap_or(0))
+    }
+
+    pub async fn create_instance(&self, instance: &DBInstance) -> Result<()> {
+        sqlx::query!(
+            "INSERT INTO instance (id, map, difficulty, reset_time) VALUES (?, ?, ?, ?)",
+            instance.id,
+            instance.map,
+            instance.difficulty,
+            instance.reset_time
+        )
+        .execute(&self.connection_pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn bind_character_to_instance(&self, character_id: u32, instance_id: u32) -> Result<()> {
+        sqlx::query!(
+            "INSERT IGNORE INTO character_instance (character_id, instance_id) VALUES (?, ?)",
+            character_id,
+            instance_id
+        )
+        .execute(&self.connection_pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_character_instances(&self, character_id: u32) -> Result<Vec<DBInstance>> {
+        let res = sqlx::query_as!(
+            DBInstance,
+            "SELECT instance.id, instance.map, instance.difficulty, instance.reset_time FROM character_instance INNER JOIN instance ON character_instance.instance_id = instance.id WHERE character_instance.character_id = ?",
+            character_id
+        )
+        .fetch_all(&self.connection_pool)
+        .await?;
+
+        Ok(res)
+    }
+
+    //Binds to deleted instances are removed along with them
+    pub async fn delete_expired_instances(&self, current_time: u64) -> Result<u64> {
+        let res = sqlx::query!("DELETE FROM instance WHERE reset_time <= ?", current_time)
+            .execute(&self.connection_pool)
+            .await?;
+
+        Ok(res.rows_affected())
+    }
+}
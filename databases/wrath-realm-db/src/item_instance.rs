@@ -1,18 +1,119 @@
 use anyhow::Result;
+use sqlx::{MySql, QueryBuilder};
 
 pub struct DBItemInstance {
-    pub character_id: u32,
-    pub slot_id: u8,
-    pub item: u32,
-    pub enchant: Option<u32>,
+    pub guid: u32,
+    pub entry: u32,
+    pub owner_guid: u32,
+    pub container_guid: u32,
+    pub slot: u8,
+    pub stack_count: u32,
+    pub durability: u32,
+    pub enchantments: String,
+    pub flags: u32,
+    pub creator_guid: u32,
+    pub charges: String,
+}
+
+#[derive(Debug)]
+pub struct DBCharacterEquipmentDisplayInfo {
+    pub slot: u8,
+    pub enchantments: String,
+    pub inventory_type: Option<u8>,
+    pub displayid: Option<u32>,
 }
 
 impl super::RealmDatabase {
-    pub async fn get_all_character_equipment(&self, character_id: u32) -> Result<Vec<DBItemInstance>> {
-        let res = sqlx::query_as!(DBItemInstance, "SELECT * FROM character_equipment WHERE character_id = ?", character_id)
+    pub async fn get_highest_item_guid(&self) -> Result<u32> {
+        let res = sqlx::query!("SELECT MAX(guid) AS max_guid FROM item_instance")
+            .fetch_one(&self.connection_pool)
+            .await?;
+
+        Ok(res.max_guid.unwrap_or(0))
+    }
+
+    pub async fn get_all_character_items(&self, character_id: u32) -> Result<Vec<DBItemInstance>> {
+        let res = sqlx::query_as!(DBItemInstance, "SELECT * FROM item_instance WHERE owner_guid = ?", character_id)
             .fetch_all(&self.connection_pool)
             .await?;
 
         Ok(res)
     }
+
+    pub async fn get_all_character_equipment_display_info(&self, character_id: u32) -> Result<Vec<DBCharacterEquipmentDisplayInfo>> {
+        let res = sqlx::query_as!(
+            DBCharacterEquipmentDisplayInfo,
+            "SELECT item_instance.slot, item_instance.enchantments, item_template.inventory_type, item_template.displayid FROM item_instance LEFT JOIN item_template ON item_instance.entry = item_template.id WHERE item_instance.owner_guid = ? AND item_instance.container_guid = 0",
+            character_id
+        )
+        .fetch_all(&self.connection_pool)
+        .await?;
+
+        Ok(res)
+    }
+
+    pub async fn create_items(&self, items: impl IntoIterator<Item = DBItemInstance>) -> Result<()> {
+        let mut items = items.into_iter().peekable();
+        if items.peek().is_none() {
+            return Ok(());
+        }
+
+        let mut query_builder = Self::build_item_insert_query(items);
+        query_builder.build().execute(&self.connection_pool).await?;
+        Ok(())
+    }
+
+    //Replaces all stored items of this character with the given items, except for the rows in kept_item_guids
+    pub async fn replace_character_items(
+        &self,
+        character_id: u32,
+        items: impl IntoIterator<Item = DBItemInstance>,
+        kept_item_guids: &[u32],
+    ) -> Result<()> {
+        let mut transaction = self.connection_pool.begin().await?;
+
+        let mut delete_query: QueryBuilder<MySql> = QueryBuilder::new("DELETE FROM item_instance WHERE owner_guid = ");
+        delete_query.push_bind(character_id);
+        if !kept_item_guids.is_empty() {
+            delete_query.push(" AND guid NOT IN (");
+            let mut separated = delete_query.separated(", ");
+            for guid in kept_item_guids {
+                separated.push_bind(*guid);
+            }
+            separated.push_unseparated(")");
+        }
+        delete_query.build().execute(&mut *transaction).await?;
+
+        let mut items = items.into_iter().peekable();
+        if items.peek().is_some() {
+            let mut query_builder = Self::build_item_insert_query(items);
+            query_builder.build().execute(&mut *transaction).await?;
+        }
+
+        transaction.commit().await?;
+        Ok(())
+    }
+
+    //Have to use slightly more complicated query builder syntax to bulk-insert.
+    //Bulk insert is vastly faster than for-looping each item and "regular" inserting the items
+    //one by one.
+    fn build_item_insert_query<'a>(items: impl Iterator<Item = DBItemInstance>) -> QueryBuilder<'a, MySql> {
+        let mut query_builder: QueryBuilder<MySql> = QueryBuilder::new(
+            "INSERT INTO item_instance (guid, entry, owner_guid, container_guid, slot, stack_count, durability, enchantments, flags, creator_guid, charges) ",
+        );
+        query_builder.push_values(items, |mut b, item| {
+            b.push_bind(item.guid)
+                .push_bind(item.entry)
+                .push_bind(item.owner_guid)
+                .push_bind(item.container_guid)
+                .push_bind(item.slot)
+                .push_bind(item.stack_count)
+                .push_bind(item.durability)
+                .push_bind(item.enchantments)
+                .push_bind(item.flags)
+                .push_bind(item.creator_guid)
+                .push_bind(item.charges);
+        });
+        query_builder
+    }
 }
@@ -80,6 +80,7 @@ pub struct DBItemContainerLootInfo {
     pub money_loot_max: u32,
 }
 
+#[derive(Default)]
 pub struct DBItemTemplate {
     pub id: u32,
     pub class: u8,
@@ -144,16 +145,14 @@ pub struct DBItemTemplate {
     pub extra_flags: u8,
 }
 
-impl super::RealmDatabase {
-    pub async fn get_item_template(&self, item_id: u32) -> Result<DBItemTemplate> {
-        let res = sqlx::query!("SELECT * FROM item_template WHERE id = ?", item_id,)
-            .fetch_one(&self.connection_pool)
-            .await?;
-
-        let item = DBItemTemplate {
+//The record types generated by query! are anonymous, so the conversion is shared through a macro
+macro_rules! item_template_from_record {
+    ($res:ident) => {{
+        let res = $res;
+        DBItemTemplate {
             id: res.id,
             class: res.class,
-            subclass: res.class,
+            subclass: res.subclass,
             name: res.name,
             displayid: res.displayid,
             quality: res.Quality,
@@ -201,10 +200,32 @@ impl super::RealmDatabase {
             max_count: res.maxcount,
             stackable: res.stackable,
             container_slots: res.ContainerSlots,
-            granted_stats: vec![],
+            granted_stats: [
+                (res.stat_type1, res.stat_value1),
+                (res.stat_type2, res.stat_value2),
+                (res.stat_type3, res.stat_value3),
+                (res.stat_type4, res.stat_value4),
+                (res.stat_type5, res.stat_value5),
+                (res.stat_type6, res.stat_value6),
+                (res.stat_type7, res.stat_value7),
+                (res.stat_type8, res.stat_value8),
+                (res.stat_type9, res.stat_value9),
+                (res.stat_type10, res.stat_value10),
+            ]
+            .into_iter()
+            .take(res.StatsCount as usize)
+            .map(|(stat_type, stat_value)| DBItemStat {
+                stat_type,
+                stat_value: stat_value as u16,
+            })
+            .collect(),
             scaling_stat_distribution: res.ScalingStatDistribution,
             scaling_stat_value: res.ScalingStatValue,
-            damage: vec![],
+            damage: [(res.dmg_min1, res.dmg_max1, res.dmg_type1), (res.dmg_min2, res.dmg_max2, res.dmg_type2)]
+                .into_iter()
+                .filter(|(_, max, _)| *max > 0.0)
+                .map(|(min, max, damage_type)| DBItemDamage { min, max, damage_type })
+                .collect(),
             granted_armor: match res.armor {
                 0 => None,
                 v => Some(v),
@@ -236,7 +257,68 @@ impl super::RealmDatabase {
                 v => Some(v),
             },
             ranged_mod_range: res.RangedModRange,
-            spell_procs: vec![],
+            spell_procs: [
+                (
+                    res.spellid_1,
+                    res.spelltrigger_1,
+                    res.spellcharges_1,
+                    res.spellppmRate_1,
+                    res.spellcooldown_1,
+                    res.spellcategory_1,
+                    res.spellcategorycooldown_1,
+                ),
+                (
+                    res.spellid_2,
+                    res.spelltrigger_2,
+                    res.spellcharges_2,
+                    res.spellppmRate_2,
+                    res.spellcooldown_2,
+                    res.spellcategory_2,
+                    res.spellcategorycooldown_2,
+                ),
+                (
+                    res.spellid_3,
+                    res.spelltrigger_3,
+                    res.spellcharges_3,
+                    res.spellppmRate_3,
+                    res.spellcooldown_3,
+                    res.spellcategory_3,
+                    res.spellcategorycooldown_3,
+                ),
+                (
+                    res.spellid_4,
+                    res.spelltrigger_4,
+                    res.spellcharges_4,
+                    res.spellppmRate_4,
+                    res.spellcooldown_4,
+                    res.spellcategory_4,
+                    res.spellcategorycooldown_4,
+                ),
+                (
+                    res.spellid_5,
+                    res.spelltrigger_5,
+                    res.spellcharges_5,
+                    res.spellppmRate_5,
+                    res.spellcooldown_5,
+                    res.spellcategory_5,
+                    res.spellcategorycooldown_5,
+                ),
+            ]
+            .into_iter()
+            .map(
+                |(spell_id, trigger_type, charges, procs_per_minute, cooldown, category, category_cooldown)| DBItemSpellProc {
+                    spell_id,
+                    trigger_type,
+                    //Negative charges mean the item is consumed when the charges run out, the client wants the raw value
+                    charges: charges as u16,
+                    procs_per_minute,
+                    //-1 means "use the spell's own cooldown", which maps onto the u32::MAX default
+                    cooldown: cooldown as u32,
+                    category,
+                    category_cooldown: category_cooldown as u32,
+                },
+            )
+            .collect(),
             bonding: res.bonding,
             description: res.description,
             readable_info: match res.PageText {
@@ -284,7 +366,18 @@ impl super::RealmDatabase {
                 0 => None,
                 v => Some(v),
             },
-            sockets: vec![],
+            sockets: [
+                (res.socketColor_1, res.socketContent_1),
+                (res.socketColor_2, res.socketContent_2),
+                (res.socketColor_3, res.socketContent_3),
+            ]
+            .into_iter()
+            .filter(|(color, _)| *color != 0)
+            .map(|(color, content)| DBItemSocketInfo {
+                color: color as u8,
+                content: content as u32,
+            })
+            .collect(),
             socket_bonus: match res.socketBonus {
                 0 => None,
                 v => Some(v),
@@ -308,10 +401,22 @@ impl super::RealmDatabase {
                 }),
             },
             extra_flags: res.ExtraFlags,
-        };
+        }
+    }};
+}
+
+impl super::RealmDatabase {
+    pub async fn get_item_template(&self, item_id: u32) -> Result<DBItemTemplate> {
+        let res = sqlx::query!("SELECT * FROM item_template WHERE id = ?", item_id,)
+            .fetch_one(&self.connection_pool)
+            .await?;
+
+        Ok(item_template_from_record!(res))
+    }
 
-        //TODO: fill vecs granted_stats, damage, spell_procs and sockets
+    pub async fn get_all_item_templates(&self) -> Result<Vec<DBItemTemplate>> {
+        let res = sqlx::query!("SELECT * FROM item_template").fetch_all(&self.connection_pool).await?;
 
-        Ok(item)
+        Ok(res.into_iter().map(|r| item_template_from_record!(r)).collect())
     }
 }
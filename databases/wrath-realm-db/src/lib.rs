@@ -5,10 +5,21 @@ pub mod areatrigger_restedzone;
 pub mod areatrigger_teleport;
 pub mod character;
 pub mod character_account_data;
-pub mod character_equipment;
+pub mod character_quest;
+pub mod character_spell;
+pub mod character_talent;
+pub mod corpse;
+pub mod creature;
+pub mod experience;
+pub mod gossip;
+pub mod instance;
 pub mod item_instance;
 pub mod item_template;
+pub mod loot;
 pub mod player_create_info;
+pub mod quest;
+pub mod trainer;
+pub mod vendor;
 
 pub struct RealmDatabase {
     connection_pool: sqlx::MySqlPool,
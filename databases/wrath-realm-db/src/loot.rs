@@ -0,0 +1,37 @@
+use anyhow::Result;
+
+pub struct DBLootTemplate {
+    pub entry: u32,
+    pub item: u32,
+    pub chance: f32,
+    pub quest_required: u8,
+    pub group_id: u8,
+    pub min_count: u8,
+    pub max_count: u8,
+}
+
+impl super::RealmDatabase {
+    pub async fn get_all_creature_loot_templates(&self) -> Result<Vec<DBLootTemplate>> {
+        let res = sqlx::query_as!(DBLootTemplate, "SELECT * FROM creature_loot_template")
+            .fetch_all(&self.connection_pool)
+            .await?;
+
+        Ok(res)
+    }
+
+    pub async fn get_all_gameobject_loot_templates(&self) -> Result<Vec<DBLootTemplate>> {
+        let res = sqlx::query_as!(DBLootTemplate, "SELECT * FROM gameobject_loot_template")
+            .fetch_all(&self.connection_pool)
+            .await?;
+
+        Ok(res)
+    }
+
+    pub async fn get_all_item_loot_templates(&self) -> Result<Vec<DBLootTemplate>> {
+        let res = sqlx::query_as!(DBLootTemplate, "SELECT * FROM item_loot_template")
+            .fetch_all(&self.connection_pool)
+            .await?;
+
+        Ok(res)
+    }
+}
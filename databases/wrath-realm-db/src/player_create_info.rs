@@ -11,6 +11,24 @@ pub struct DBPlayerCreateInfo {
     pub orientation: f32,
 }
 
+pub struct DBPlayerLevelStats {
+    pub race: u8,
+    pub class: u8,
+    pub level: u8,
+    pub strength: u16,
+    pub agility: u16,
+    pub stamina: u16,
+    pub intellect: u16,
+    pub spirit: u16,
+}
+
+pub struct DBPlayerClassLevelStats {
+    pub class: u8,
+    pub level: u8,
+    pub base_health: u32,
+    pub base_mana: u32,
+}
+
 impl super::RealmDatabase {
     pub async fn get_player_create_info(&self, race: u8, class: u8) -> Result<DBPlayerCreateInfo> {
         let res = sqlx::query_as!(
@@ -24,4 +42,20 @@ impl super::RealmDatabase {
 
         Ok(res)
     }
+
+    pub async fn get_all_player_level_stats(&self) -> Result<Vec<DBPlayerLevelStats>> {
+        let res = sqlx::query_as!(DBPlayerLevelStats, "SELECT * FROM player_levelstats")
+            .fetch_all(&self.connection_pool)
+            .await?;
+
+        Ok(res)
+    }
+
+    pub async fn get_all_player_class_level_stats(&self) -> Result<Vec<DBPlayerClassLevelStats>> {
+        let res = sqlx::query_as!(DBPlayerClassLevelStats, "SELECT * FROM player_classlevelstats")
+            .fetch_all(&self.connection_pool)
+            .await?;
+
+        Ok(res)
+    }
 }
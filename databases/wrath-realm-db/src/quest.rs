@@ -0,0 +1,109 @@
+use anyhow::Result;
+
+pub struct DBQuestTemplate {
+    pub id: u32,
+    pub quest_level: i16,
+    pub min_level: u8,
+    pub zone_or_sort: i16,
+    pub flags: u32,
+    pub required_races: u32,
+    pub required_classes: u32,
+    pub prev_quest_id: u32,
+    pub suggested_players: u8,
+    pub reward_xp: u32,
+    pub reward_money: u32,
+    pub title: String,
+    pub details: String,
+    pub objectives: String,
+    pub offer_reward_text: String,
+    pub request_items_text: String,
+    pub end_text: String,
+}
+
+pub struct DBQuestObjective {
+    pub quest: u32,
+    pub objective_index: u8,
+    pub objective_type: u8,
+    pub target: u32,
+    pub count: u16,
+}
+
+pub struct DBQuestRewardItem {
+    pub quest: u32,
+    pub item: u32,
+    pub count: u16,
+    pub choice: u8,
+}
+
+pub struct DBQuestRewardFaction {
+    pub quest: u32,
+    pub faction: u32,
+    pub value: i32,
+}
+
+//Ties a creature or area trigger to a quest
+pub struct DBQuestRelation {
+    pub id: u32,
+    pub quest: u32,
+}
+
+impl super::RealmDatabase {
+    pub async fn get_all_quest_templates(&self) -> Result<Vec<DBQuestTemplate>> {
+        let res = sqlx::query_as!(DBQuestTemplate, "SELECT * FROM quest_template")
+            .fetch_all(&self.connection_pool)
+            .await?;
+
+        Ok(res)
+    }
+
+    pub async fn get_all_quest_objectives(&self) -> Result<Vec<DBQuestObjective>> {
+        let res = sqlx::query_as!(
+            DBQuestObjective,
+            "SELECT * FROM quest_objective ORDER BY quest, objective_type, objective_index"
+        )
+        .fetch_all(&self.connection_pool)
+        .await?;
+
+        Ok(res)
+    }
+
+    pub async fn get_all_quest_reward_items(&self) -> Result<Vec<DBQuestRewardItem>> {
+        let res = sqlx::query_as!(DBQuestRewardItem, "SELECT * FROM quest_reward_item")
+            .fetch_all(&self.connection_pool)
+            .await?;
+
+        Ok(res)
+    }
+
+    pub async fn get_all_quest_reward_factions(&self) -> Result<Vec<DBQuestRewardFaction>> {
+        let res = sqlx::query_as!(DBQuestRewardFaction, "SELECT * FROM quest_reward_faction")
+            .fetch_all(&self.connection_pool)
+            .await?;
+
+        Ok(res)
+    }
+
+    pub async fn get_all_creature_quest_starters(&self) -> Result<Vec<DBQuestRelation>> {
+        let res = sqlx::query_as!(DBQuestRelation, "SELECT * FROM creature_queststarter")
+            .fetch_all(&self.connection_pool)
+            .await?;
+
+        Ok(res)
+    }
+
+    pub async fn get_all_creature_quest_enders(&self) -> Result<Vec<DBQuestRelation>> {
+        let res = sqlx::query_as!(DBQuestRelation, "SELECT * FROM creature_questender")
+            .fetch_all(&self.connection_pool)
+            .await?;
+
+        Ok(res)
+    }
+
+    pub async fn get_all_areatrigger_quest_relations(&self) -> Result<Vec<DBQuestRelation>> {
+        let res = sqlx::query_as!(DBQuestRelation, "SELECT * FROM areatrigger_involvedrelation")
+            .fetch_all(&self.connection_pool)
+            .await?;
+
+        Ok(res)
+    }
+}
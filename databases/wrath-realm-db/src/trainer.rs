@@ -0,0 +1,21 @@
+use anyhow::Result;
+
+pub struct DBTrainerSpell {
+    pub entry: u32,
+    pub spell: u32,
+    pub cost: u32,
+    pub required_level: u8,
+    pub required_skill: u16,
+    pub required_skill_value: u16,
+    pub required_spell: u32,
+}
+
+impl super::RealmDatabase {
+    pub async fn get_all_trainer_spells(&self) -> Result<Vec<DBTrainerSpell>> {
+        let res = sqlx::query_as!(DBTrainerSpell, "SELECT * FROM npc_trainer ORDER BY entry, required_level, spell")
+            .fetch_all(&self.connection_pool)
+            .await?;
+
+        Ok(res)
+    }
+}
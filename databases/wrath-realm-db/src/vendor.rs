@@ -0,0 +1,19 @@
+use anyhow::Result;
+
+pub struct DBVendorItem {
+    pub entry: u32,
+    pub slot: u8,
+    pub item: u32,
+    pub max_count: u8,
+    pub restock_time: u32,
+}
+
+impl super::RealmDatabase {
+    pub async fn get_all_vendor_items(&self) -> Result<Vec<DBVendorItem>> {
+        let res = sqlx::query_as!(DBVendorItem, "SELECT * FROM npc_vendor ORDER BY entry, slot")
+            .fetch_all(&self.connection_pool)
+            .await?;
+
+        Ok(res)
+    }
+}
@@ -0,0 +1,89 @@
+use crate::data::DataStorage;
+use crate::prelude::*;
+use crate::spell::{self, Aura, AuraEffect, SpellEffectContext};
+use crate::world::prelude::spell::*;
+use crate::world::prelude::{GameObject, Unit};
+use crate::world::World;
+
+//Run speed of a unit without any modifiers
+const BASE_RUN_SPEED: f32 = 7.0;
+
+//What the auras currently change about the character, so we know what to undo when they change
+#[derive(Default)]
+pub(super) struct AppliedAuraModifiers {
+    stunned: bool,
+    rooted: bool,
+    speed_modifier: f32,
+}
+
+impl super::Character {
+    pub(super) async fn tick_auras(&mut self, delta_time: f32, world: &World) -> Result<()> {
+        for tick in self.auras.tick(delta_time) {
+            spell::apply_periodic_aura_tick(self, &tick);
+        }
+
+        //Auras can also be changed by other units, so this is where everything gets picked up
+        let changed_slots = self.auras.take_changed_slots();
+        if changed_slots.is_empty() {
+            return Ok(());
+        }
+        self.update_aura_modifiers().await?;
+        self.mark_stats_dirty();
+
+        if let Some(map) = world.get_instance_manager().try_get_map_for_character(self).await {
+            handlers::send_aura_updates(&*self, &self.auras, &changed_slots, &map).await?;
+        }
+        Ok(())
+    }
+
+    //For auras the server puts on the character by itself, without anything being cast
+    pub(super) fn apply_aura_from_spell(&mut self, data_storage: &DataStorage, spell_id: u32) -> Result<()> {
+        let spell = data_storage
+            .get_spell_info(spell_id)
+            .ok_or_else(|| anyhow!("Spell {} does not exist", spell_id))?;
+        let level = self.get_level();
+        let effects = spell
+            .effects
+            .iter()
+            .filter(|effect| effect.effect == SPELL_EFFECT_APPLY_AURA)
+            .map(|effect| {
+                let context = SpellEffectContext {
+                    spell,
+                    effect,
+                    caster_level: level,
+                    caster_position: self.get_position().unwrap_or_default(),
+                    caster_home: None,
+                    target: self.get_guid(),
+                };
+                AuraEffect::new(effect, spell::calculate_effect_amount(&context))
+            })
+            .collect();
+        self.auras.apply(Aura::new(spell, self.get_guid(), level as u8, effects))?;
+        Ok(())
+    }
+
+    async fn update_aura_modifiers(&mut self) -> Result<()> {
+        let stunned = self.auras.has_aura_type(SPELL_AURA_MOD_STUN);
+        if stunned != self.aura_modifiers.stunned {
+            self.set_stunned(stunned);
+            self.aura_modifiers.stunned = stunned;
+        }
+
+        let rooted = self.auras.has_aura_type(SPELL_AURA_MOD_ROOT);
+        if rooted != self.aura_modifiers.rooted {
+            self.set_rooted(rooted).await?;
+            self.aura_modifiers.rooted = rooted;
+        }
+
+        //Slows are stored as negative percentages
+        let increase = self.auras.get_total_modifier(SPELL_AURA_MOD_INCREASE_SPEED, |_| true) as f32 / 100.0;
+        let decrease = self.auras.get_total_modifier(SPELL_AURA_MOD_DECREASE_SPEED, |_| true) as f32 / 100.0;
+        let speed_modifier = ((1.0 + increase) * (1.0 + decrease)).max(0.0) - 1.0;
+        if speed_modifier != self.aura_modifiers.speed_modifier {
+            self.aura_modifiers.speed_modifier = speed_modifier;
+            handlers::send_smsg_force_run_speed_change(self, BASE_RUN_SPEED * (1.0 + speed_modifier)).await?;
+        }
+
+        Ok(())
+    }
+}
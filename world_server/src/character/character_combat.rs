@@ -0,0 +1,193 @@
+use rand::Rng;
+
+use crate::combat::{self, MeleeHitOutcome};
+use crate::data::DataStorage;
+use crate::prelude::*;
+use crate::world::prelude::inventory::EquipmentSlot;
+use crate::world::prelude::unit_flags::UnitFlagIndex;
+use crate::world::prelude::{GameObject, Unit};
+use crate::world::World;
+use wow_world_messages::wrath::Power;
+
+//Bare hands hit for this much, this often
+const UNARMED_DAMAGE: (f32, f32) = (1.0, 2.0);
+const UNARMED_SWING_TIME: f32 = 2.0;
+const MELEE_RANGE: f32 = 5.0;
+
+#[derive(Default)]
+pub(super) struct MeleeState {
+    attack_target: Option<Guid>,
+    swing_timer: f32,
+    //So the client is told about being out of range only once, not on every tick
+    sent_range_error: bool,
+}
+
+//The result of a single swing, as it should be shown to everyone around
+pub struct MeleeSwing {
+    pub target: Guid,
+    pub outcome: MeleeHitOutcome,
+    pub damage: u32,
+    pub overkill: u32,
+}
+
+impl super::Character {
+    pub async fn start_attack(&mut self, world: &World, target: Guid) -> Result<()> {
+        if target == self.get_guid() || !self.is_alive() {
+            return handlers::send_attackswing_cant_attack(self).await;
+        }
+
+        let map = world
+            .get_instance_manager()
+            .try_get_map_for_character(self)
+            .await
+            .ok_or_else(|| anyhow!("Character {} is not on a map", self.name))?;
+        let Some(target_lock) = map.try_get_object(target).await.and_then(|weak| weak.upgrade()) else {
+            return handlers::send_attackswing_cant_attack(self).await;
+        };
+        let target_faction_template = match target_lock.read().await.as_unit() {
+            None => return handlers::send_attackswing_cant_attack(self).await,
+            Some(unit) if !unit.is_alive() => return handlers::send_attackswing_dead_target(self).await,
+            Some(unit) => unit.get_faction_template(),
+        };
+        //Quest givers, vendors and other players of the same side are not to be fought
+        if !world.get_data_storage().is_hostile(self.get_faction_template(), target_faction_template) {
+            return handlers::send_attackswing_cant_attack(self).await;
+        }
+
+        if self.melee_state.attack_target == Some(target) {
+            return Ok(());
+        }
+        self.melee_state.attack_target = Some(target);
+        self.melee_state.sent_range_error = false;
+        self.set_unit_flag_byte(UnitFlagIndex::InCombat, true);
+        handlers::send_attack_start(self, world, target).await
+    }
+
+    pub async fn stop_attack(&mut self, world: &World) -> Result<()> {
+        let Some(target) = self.melee_state.attack_target.take() else {
+            return Ok(());
+        };
+        self.set_unit_flag_byte(UnitFlagIndex::InCombat, false);
+        handlers::send_attack_stop(self, world, target).await
+    }
+
+    pub(super) fn is_in_combat(&self) -> bool {
+        self.melee_state.attack_target.is_some()
+    }
+
+    //Damage range and swing time of whatever is in the main hand
+    fn get_melee_weapon(&self, data_storage: &DataStorage) -> ((f32, f32), f32) {
+        let weapon = self
+            .equipped_items
+            .get_item(EquipmentSlot::MainHand)
+            .filter(|item| !item.is_broken())
+            .and_then(|item| data_storage.get_item_template(item.get_entry()));
+        let Some(template) = weapon else {
+            return (UNARMED_DAMAGE, UNARMED_SWING_TIME);
+        };
+
+        let damage = template
+            .damage
+            .first()
+            .map_or(UNARMED_DAMAGE, |damage| (damage.min, damage.max.max(damage.min)));
+        let swing_time = template.delay.map_or(UNARMED_SWING_TIME, |delay| delay as f32 / 1000.0);
+        (damage, swing_time)
+    }
+
+    pub(super) async fn tick_melee(&mut self, delta_time: f32, world: &World) -> Result<()> {
+        self.melee_state.swing_timer = (self.melee_state.swing_timer - delta_time).max(0.0);
+        let Some(target) = self.melee_state.attack_target else {
+            return Ok(());
+        };
+        //Dying takes a unit out of combat
+        if !self.is_alive() {
+            return self.stop_attack(world).await;
+        }
+        if self.melee_state.swing_timer > 0.0 || self.is_casting() {
+            return Ok(());
+        }
+
+        let map = world
+            .get_instance_manager()
+            .try_get_map_for_character(self)
+            .await
+            .ok_or_else(|| anyhow!("Character {} is not on a map", self.name))?;
+        let Some(target_lock) = map.try_get_object(target).await.and_then(|weak| weak.upgrade()) else {
+            return self.stop_attack(world).await;
+        };
+        let (target_position, target_level, target_armor, target_faction_template) = {
+            let target_object = target_lock.read().await;
+            match target_object.as_unit().filter(|unit| unit.is_alive()) {
+                Some(unit) => (
+                    target_object.get_position(),
+                    unit.get_level(),
+                    unit.get_armor(),
+                    unit.get_faction_template(),
+                ),
+                None => return self.stop_attack(world).await,
+            }
+        };
+        if !world.get_data_storage().is_hostile(self.get_faction_template(), target_faction_template) {
+            return self.stop_attack(world).await;
+        }
+
+        let own_position = self.get_position().unwrap_or_default();
+        let in_range = target_position.is_some_and(|position| position.distance_to(&own_position) <= MELEE_RANGE);
+        if !in_range {
+            if !self.melee_state.sent_range_error {
+                self.melee_state.sent_range_error = true;
+                handlers::send_attackswing_not_in_range(self).await?;
+            }
+            return Ok(());
+        }
+        self.melee_state.sent_range_error = false;
+
+        let ((min_damage, max_damage), swing_time) = self.get_melee_weapon(&world.get_data_storage());
+        self.melee_state.swing_timer = swing_time;
+
+        let level = self.get_level();
+        let outcome = combat::roll_melee_outcome(level, target_level, rand::thread_rng().gen_range(0.0..100.0));
+        let rolled_damage = rand::thread_rng().gen_range(min_damage..=max_damage).round() as u32;
+        let damage = combat::get_damage_for_outcome(outcome, rolled_damage);
+        let damage = if damage > 0 {
+            combat::mitigate_damage_by_armor(damage, target_armor, level)
+        } else {
+            0
+        };
+
+        let health_before = self
+            .modify_unit(world, target, |unit| {
+                let health = unit.get_health();
+                unit.set_health(health.saturating_sub(damage));
+                health
+            })
+            .await?;
+
+        if damage > 0 {
+            self.roll_weapon_durability_loss()?;
+        }
+
+        if self.get_power_type() == Power::Rage {
+            let rage = combat::get_rage_from_damage(damage, level, swing_time, outcome);
+            self.set_power(Power::Rage, self.get_power(Power::Rage) + rage);
+        }
+
+        let swing = MeleeSwing {
+            target,
+            outcome,
+            damage,
+            overkill: damage.saturating_sub(health_before),
+        };
+        handlers::send_attacker_state_update(self, world, &swing).await?;
+
+        if damage >= health_before {
+            self.stop_attack(world).await?;
+            if health_before > 0 {
+                self.give_kill_experience(&world.get_data_storage(), target, target_level).await?;
+                self.claim_loot(world, target).await?;
+                self.credit_quest_kill(&world.get_data_storage(), target).await?;
+            }
+        }
+        Ok(())
+    }
+}
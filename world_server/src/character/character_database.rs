@@ -2,14 +2,17 @@ use crate::data::{DataStorage, TutorialFlags, WorldZoneLocation};
 use crate::item::Item;
 use crate::packet::ServerMessageExt;
 use crate::prelude::*;
+use crate::world::guid_service::get_low_guid;
 use crate::world::prelude::*;
 use std::time::{SystemTime, UNIX_EPOCH};
 use wow_dbc::Indexable;
-use wow_world_base::wrath::{ObjectType, RaceClass};
+use wow_world_base::wrath::RaceClass;
 use wow_world_messages::wrath::{
-    Area, Class, Gender, InitialSpell, Map, MovementBlock, MovementBlock_UpdateFlag, MovementInfo, Object, Object_UpdateType, Power, Race, SkillInfo,
-    SkillInfoIndex, UpdateMask, Vector3d, SMSG_INITIAL_SPELLS, SMSG_UPDATE_OBJECT,
+    Area, Class, DungeonDifficulty, Gender, InitialSpell, Map, MovementInfo, Power, Race, RaidDifficulty, Vector3d, SMSG_INITIAL_SPELLS,
+    SMSG_UPDATE_OBJECT,
 };
+use wrath_realm_db::character::DBCharacterUpdateParameters;
+use wrath_realm_db::item_instance::DBItemInstance;
 
 use super::character_inventory::INVENTORY_SLOT_BAG_0;
 impl super::Character {
@@ -34,6 +37,11 @@ impl super::Character {
         });
 
         self.map = Map::try_from(db_entry.map as u32)?;
+        self.instance_id = db_entry.instance_id;
+        self.dungeon_difficulty = DungeonDifficulty::try_from(db_entry.dungeon_difficulty)?;
+        self.raid_difficulty = RaidDifficulty::try_from(db_entry.raid_difficulty)?;
+        self.set_bank_bag_slots(db_entry.bank_bag_slots);
+        self.gameplay_data.set_player_field_coinage(db_entry.money);
 
         //We don't set this field properly in character creation so consequently its wrong here
         self.area = Area::try_from(db_entry.zone as u32).unwrap_or(Area::NorthshireAbbey);
@@ -44,6 +52,7 @@ impl super::Character {
                 y: db_entry.y,
                 z: db_entry.z,
             },
+            orientation: db_entry.o,
             ..Default::default()
         };
 
@@ -65,15 +74,18 @@ impl super::Character {
         let race = Race::try_from(db_entry.race)?;
         let class = Class::try_from(db_entry.class)?;
 
-        if let Some(race_info) = data_storage.get_dbc_chr_races()?.get(race.as_int()) {
-            let display_id = match gender {
-                Gender::Male => race_info.male_display_id,
-                _ => race_info.female_display_id,
-            }
-            .id;
-            self.gameplay_data.set_unit_displayid(display_id);
-            self.gameplay_data.set_unit_nativedisplayid(display_id);
+        let race_info = data_storage
+            .get_dbc_chr_races()?
+            .get(race.as_int())
+            .ok_or_else(|| anyhow!("No raceinfo for this race"))?;
+        let display_id = match gender {
+            Gender::Male => race_info.male_display_id,
+            _ => race_info.female_display_id,
         }
+        .id;
+        self.gameplay_data.set_unit_displayid(display_id);
+        self.gameplay_data.set_unit_nativedisplayid(display_id);
+        self.gameplay_data.set_unit_factiontemplate(race_info.faction_id.id);
 
         let class_info = data_storage
             .get_dbc_chr_classes()?
@@ -82,58 +94,155 @@ impl super::Character {
 
         let power = Power::try_from(class_info.display_power as u8)?;
         self.gameplay_data.set_unit_bytes_0(race, class, gender, power);
-        self.gameplay_data.set_unit_health(100);
-        self.gameplay_data.set_unit_maxhealth(100);
-        self.gameplay_data.set_unit_level(1);
-        self.gameplay_data.set_unit_factiontemplate(1);
+        self.init_power(power);
+        self.gameplay_data.set_unit_level(db_entry.level as i32);
+        self.load_experience(data_storage, db_entry.xp, db_entry.explored_zones.as_deref());
+        self.load_rest_bonus(data_storage, db_entry.rest_bonus, db_entry.logout_time, db_entry.logout_resting != 0);
         self.gameplay_data.set_object_scale_x(1.0f32);
 
         //No playtime means it's our very first login
         self.needs_first_login = self.seconds_played_total == 0;
 
-        //TODO: this should be loaded from the DB, it's a placeholder
-        let race_class = RaceClass::try_from((race, class)).unwrap();
-        for (i, skill) in race_class.starter_skills().iter().enumerate() {
-            self.gameplay_data
-                .set_player_skill_info(SkillInfo::new(*skill, 0, 299, 300, 0, 0), SkillInfoIndex::try_from(i as u32).unwrap());
-        }
+        let race_class = RaceClass::try_from((race, class)).map_err(|_| anyhow!("{:?} can't be a {:?}", race, class))?;
+        self.load_skills(data_storage, race_class, realm_database.get_character_skills(character_id).await?)?;
         //TODO: learning some skills might learn spells, those need to be checked too?
-
-        //TODO: this should be loaded from the DB, it's a placeholder
+        self.load_spells(race_class, realm_database.get_character_spells(character_id).await?);
+        let talents = realm_database.get_character_talents(character_id).await?;
+        let actions = realm_database.get_character_actions(character_id).await?;
+        self.load_talents(data_storage, &db_entry, talents, actions);
         SMSG_INITIAL_SPELLS {
             unknown1: 0,
-            initial_spells: race_class
-                .starter_spells()
+            initial_spells: self
+                .known_spells
                 .iter()
-                .map(|x| InitialSpell { spell_id: *x, unknown1: 0 })
+                .copied()
+                .chain(self.get_talent_spells())
+                .map(|spell_id| InitialSpell { spell_id, unknown1: 0 })
                 .collect(),
             cooldowns: vec![],
         }
         .astd_send_to_character(&mut *self)
         .await?;
 
-        //TODO: load invetory here
-        realm_database.get_all_character_equipment(character_id).await?.iter().for_each(|x| {
-            self.set_item(Some(Item::from(x)), (x.slot_id, INVENTORY_SLOT_BAG_0))
-                .expect("This should never fail in this context");
-        });
+        //Bags need to be in place before the items inside of them
+        let (bag_contents, items): (Vec<_>, Vec<_>) = realm_database
+            .get_all_character_items(character_id)
+            .await?
+            .into_iter()
+            .partition(|db_item| db_item.container_guid != 0);
+        for db_item in items.iter().chain(bag_contents.iter()) {
+            let Some(template) = data_storage.get_item_template(db_item.entry) else {
+                warn!("Item {} of character {} has no item template {}", db_item.guid, self.name, db_item.entry);
+                self.unloaded_item_guids.push(db_item.guid);
+                continue;
+            };
+            let bag = if db_item.container_guid == 0 {
+                Some(INVENTORY_SLOT_BAG_0)
+            } else {
+                self.get_all_items()
+                    .into_iter()
+                    .find(|(_, item)| item.is_bag() && get_low_guid(item.get_guid()) == db_item.container_guid)
+                    .map(|(slot, _)| slot)
+            };
+            let Some(bag) = bag else {
+                warn!("Item {} is inside of bag {} which doesn't exist", db_item.guid, db_item.container_guid);
+                self.unloaded_item_guids.push(db_item.guid);
+                continue;
+            };
+            match self.set_item(Some(Item::from_database(db_item, template)), (db_item.slot, bag)) {
+                Ok(None) => {}
+                Ok(Some(displaced_item)) => {
+                    warn!("Item {} of character {} is in the same slot as another item", db_item.guid, self.name);
+                    self.unloaded_item_guids.push(get_low_guid(displaced_item.get_guid()));
+                }
+                Err(e) => {
+                    warn!("Could not place item {} of character {}: {}", db_item.guid, self.name, e);
+                    self.unloaded_item_guids.push(db_item.guid);
+                }
+            }
+        }
+        //Placing the items queued up container updates, but the client gets everything in the create blocks below
+        self.clear_object_updates();
+
+        //Equipment is in place, so the stats are complete and the character starts out fully healed
+        self.recalculate_stats(data_storage)?;
+        self.set_health(self.get_max_health());
+        if power == Power::Mana {
+            self.set_power(power, self.get_max_power(power));
+        }
+        self.load_death_state(world, db_entry.player_flags).await?;
+
+        let quest_statuses = realm_database.get_character_quest_statuses(character_id).await?;
+        let rewarded_quests = realm_database.get_character_rewarded_quests(character_id).await?;
+        self.load_quests(data_storage, quest_statuses, rewarded_quests);
+        self.load_reputations(data_storage, realm_database.get_character_reputations(character_id).await?);
+
+        let item_creates = self.get_all_items().into_iter().map(|(_, item)| item.build_create_update()).collect();
+        SMSG_UPDATE_OBJECT { objects: item_creates }.astd_send_to_character(&mut *self).await?;
+        Ok(())
+    }
+
+    pub async fn save_to_database(&mut self, world: &World) -> Result<()> {
+        let character_id = self.get_guid().guid() as u32;
+        let realm_database = world.get_realm_database();
 
-        let char_equipment = self.equipped_items.get_all_equipment();
-        let equiped_items = char_equipment
-            .iter()
-            .filter_map(|x| *x)
-            .map(|x| Object {
-                update_type: Object_UpdateType::CreateObject {
-                    guid3: x.update_state.object_guid().unwrap(),
-                    mask2: UpdateMask::Item(x.update_state.clone()),
-                    movement2: MovementBlock {
-                        update_flag: MovementBlock_UpdateFlag::empty(),
-                    },
-                    object_type: ObjectType::Item,
-                },
+        self.update_played_time();
+        let (talent_reset_cost, talent_reset_time) = self.get_talent_reset_for_database();
+
+        realm_database
+            .update_character(&DBCharacterUpdateParameters {
+                character_id,
+                zone: self.area.as_int() as u16,
+                level: self.gameplay_data.unit_level().unwrap_or(1) as u8,
+                map: self.map.as_int() as u16,
+                x: self.movement_info.position.x,
+                y: self.movement_info.position.y,
+                z: self.movement_info.position.z,
+                o: self.movement_info.orientation,
+                instance_id: self.instance_id,
+                tutorial_data: self.tutorial_flags.to_database_entry(),
+                playtime_total: self.seconds_played_total,
+                playtime_level: self.seconds_played_at_level,
+                dungeon_difficulty: self.dungeon_difficulty.as_int(),
+                raid_difficulty: self.raid_difficulty.as_int(),
+                bank_bag_slots: self.bank_bag_slots,
+                player_flags: self.gameplay_data.player_flags().unwrap_or(0) as u32,
+                xp: self.get_xp(),
+                rest_bonus: self.rest_bonus,
+                logout_time: self.last_playtime_calculation_timestamp,
+                logout_resting: self.is_in_rested_area() as u8,
+                explored_zones: self.get_explored_zones_for_database(),
+                money: self.get_money(),
+                talent_spec_count: self.get_talent_spec_count(),
+                active_talent_spec: self.get_active_talent_spec_index(),
+                talent_reset_cost,
+                talent_reset_time,
             })
-            .collect::<Vec<Object>>();
-        SMSG_UPDATE_OBJECT { objects: equiped_items }.astd_send_to_character(&mut *self).await?;
+            .await?;
+
+        let items = self
+            .get_all_items()
+            .into_iter()
+            .map(|(slot, item)| item.to_database_entry(slot))
+            .collect::<Result<Vec<DBItemInstance>>>()?;
+        realm_database
+            .replace_character_items(character_id, items, &self.unloaded_item_guids)
+            .await?;
+
+        let (quest_statuses, rewarded_quests) = self.get_quests_for_database(character_id);
+        let reputations = self.get_reputations_for_database(character_id);
+        realm_database
+            .replace_character_quests(character_id, quest_statuses, rewarded_quests, reputations)
+            .await?;
+
+        let spells = self.get_spells_for_database(character_id);
+        let skills = self.get_skills_for_database(character_id);
+        realm_database.replace_character_spells(character_id, spells, skills).await?;
+
+        let (talents, actions) = self.get_talents_for_database(character_id);
+        realm_database.replace_character_talents(character_id, talents, actions).await?;
+
+        trace!("Saved character {} to the database", self.name);
         Ok(())
     }
 }
@@ -0,0 +1,272 @@
+use crate::data::{PositionAndOrientation, WorldZoneLocation};
+use crate::handlers::movement_handler::TeleportationDistance;
+use crate::prelude::*;
+use crate::world::prelude::player_flags::PLAYER_FLAGS_GHOST;
+use crate::world::prelude::unit_flags::UNIT_NPC_FLAG_SPIRIT_HEALER;
+use crate::world::prelude::{GameObject, Unit};
+use crate::world::World;
+use bit_field::BitField;
+use wow_world_messages::wrath::{Map, Power, Race, SpellCastResult, Vector3d};
+use wrath_realm_db::corpse::DBCorpse;
+
+use super::character_durability::{DURABILITY_LOSS_AT_SPIRIT_HEALER, DURABILITY_LOSS_ON_DEATH};
+
+//Every ghost carries this aura, night elves additionally turn into a wisp
+const SPELL_GHOST: u32 = 8326;
+const SPELL_GHOST_WISP: u32 = 20584;
+const SPELL_RESURRECTION_SICKNESS: u32 = 15007;
+//Low level characters are spared from resurrection sickness
+const RESURRECTION_SICKNESS_MIN_LEVEL: u32 = 11;
+//Part of health and mana a character comes back with
+const RESURRECTION_RESTORE_FRACTION: f32 = 0.5;
+
+//A ghost has to wait this long after releasing, and get this close to its corpse, to reclaim it
+const CORPSE_RECLAIM_DELAY: f32 = 30.0;
+const CORPSE_RECLAIM_RADIUS: f32 = 39.0;
+
+//Graveyards are linked to zones per faction with these ids
+const GRAVEYARD_FACTION_ALLIANCE: u16 = 469;
+const GRAVEYARD_FACTION_HORDE: u16 = 67;
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LifeState {
+    #[default]
+    Alive,
+    //Died, but hasn't released the spirit yet
+    Dead,
+    Ghost,
+}
+
+#[derive(Debug, Clone)]
+pub struct CorpseLocation {
+    pub map: Map,
+    pub instance_id: u32,
+    pub position: PositionAndOrientation,
+}
+
+#[derive(Default)]
+pub(super) struct DeathState {
+    life_state: LifeState,
+    corpse: Option<CorpseLocation>,
+    reclaim_delay: f32,
+}
+
+impl super::Character {
+    pub fn get_life_state(&self) -> LifeState {
+        self.death_state.life_state
+    }
+
+    pub fn get_corpse_location(&self) -> Option<&CorpseLocation> {
+        self.death_state.corpse.as_ref()
+    }
+
+    fn get_graveyard_faction(&self) -> u16 {
+        match self.get_race() {
+            Race::Human | Race::Dwarf | Race::NightElf | Race::Gnome | Race::Draenei => GRAVEYARD_FACTION_ALLIANCE,
+            _ => GRAVEYARD_FACTION_HORDE,
+        }
+    }
+
+    fn set_ghost_flag(&mut self, ghost: bool) {
+        let mut player_flags = self.gameplay_data.player_flags().unwrap_or(0);
+        player_flags.set_bit(PLAYER_FLAGS_GHOST.trailing_zeros() as usize, ghost);
+        self.gameplay_data.set_player_flags(player_flags);
+    }
+
+    fn get_ghost_spells(&self) -> Vec<u32> {
+        if self.get_race() == Race::NightElf {
+            vec![SPELL_GHOST, SPELL_GHOST_WISP]
+        } else {
+            vec![SPELL_GHOST]
+        }
+    }
+
+    //Corpses on maps that aren't instanced are found on that map whatever instance id the character has
+    fn get_corpse_instance_id(&self, world: &World) -> u32 {
+        if world.get_instance_manager().is_instance(self.map) {
+            self.instance_id
+        } else {
+            0
+        }
+    }
+
+    //Characters that logged out as a ghost come back as one
+    pub(super) async fn load_death_state(&mut self, world: &World, player_flags: u32) -> Result<()> {
+        if player_flags as i32 & PLAYER_FLAGS_GHOST == 0 {
+            return Ok(());
+        }
+        self.death_state.life_state = LifeState::Ghost;
+        self.set_ghost_flag(true);
+        self.set_health(1);
+
+        let character_id = self.get_guid().guid() as u32;
+        if let Some(corpse) = world.get_realm_database().get_character_corpse(character_id).await? {
+            self.death_state.corpse = Some(CorpseLocation {
+                map: Map::try_from(corpse.map as u32)?,
+                instance_id: corpse.instance_id,
+                position: PositionAndOrientation {
+                    position: Vector3d {
+                        x: corpse.position_x,
+                        y: corpse.position_y,
+                        z: corpse.position_z,
+                    },
+                    orientation: corpse.orientation,
+                },
+            });
+        }
+        Ok(())
+    }
+
+    pub(super) async fn tick_death(&mut self, delta_time: f32, world: &World) -> Result<()> {
+        self.death_state.reclaim_delay = (self.death_state.reclaim_delay - delta_time).max(0.0);
+        match self.death_state.life_state {
+            //Whoever dealt the killing blow, this is where the character notices that it died
+            LifeState::Alive if self.get_health() == 0 => self.die(world).await,
+            LifeState::Ghost => self.ensure_ghost_auras(world).await,
+            _ => Ok(()),
+        }
+    }
+
+    async fn die(&mut self, world: &World) -> Result<()> {
+        self.death_state.life_state = LifeState::Dead;
+        if self.is_casting() {
+            self.interrupt_spell_cast(world, SpellCastResult::Interrupted).await?;
+        }
+        self.stop_attack(world).await?;
+        self.auras.clear();
+        self.lose_equipment_durability(DURABILITY_LOSS_ON_DEATH)?;
+        handlers::send_durability_damage_death(self).await
+    }
+
+    //The ghost auras are applied only once the character is on a map, so the client knows about it
+    //by the time the aura update arrives. That is after releasing, but also after logging in or
+    //teleporting as a ghost.
+    async fn ensure_ghost_auras(&mut self, world: &World) -> Result<()> {
+        let Some(map) = world.get_instance_manager().try_get_map_for_character(self).await else {
+            return Ok(());
+        };
+        if map.try_get_object(self.get_guid()).await.is_none() {
+            return Ok(());
+        }
+
+        for spell_id in self.get_ghost_spells() {
+            if !self.auras.has_spell(spell_id) {
+                self.apply_aura_from_spell(&world.get_data_storage(), spell_id)?;
+            }
+        }
+        Ok(())
+    }
+
+    //Leaves the corpse behind and sends the ghost off to the nearest graveyard
+    pub async fn release_spirit(&mut self, world: &World) -> Result<()> {
+        if self.death_state.life_state != LifeState::Dead {
+            return Ok(());
+        }
+
+        let instance_manager = world.get_instance_manager();
+        let map = instance_manager
+            .try_get_map_for_character(self)
+            .await
+            .ok_or_else(|| anyhow!("Character {} is not on a map", self.name))?;
+        let position = self.get_position().unwrap_or_default();
+        let display_id = self.gameplay_data.unit_displayid().unwrap_or(0);
+        map.spawn_corpse(&world.get_guid_service(), self.get_guid(), display_id, &position).await;
+
+        let instance_id = self.get_corpse_instance_id(world);
+        world
+            .get_realm_database()
+            .save_character_corpse(&DBCorpse {
+                character_id: self.get_guid().guid() as u32,
+                map: self.map.as_int() as u16,
+                instance_id,
+                position_x: position.position.x,
+                position_y: position.position.y,
+                position_z: position.position.z,
+                orientation: position.orientation,
+                display_id: display_id as u32,
+            })
+            .await?;
+        self.death_state.corpse = Some(CorpseLocation {
+            map: self.map,
+            instance_id,
+            position: position.clone(),
+        });
+
+        self.death_state.life_state = LifeState::Ghost;
+        self.death_state.reclaim_delay = CORPSE_RECLAIM_DELAY;
+        self.set_ghost_flag(true);
+        self.set_health(1);
+        handlers::send_corpse_reclaim_delay(self, CORPSE_RECLAIM_DELAY).await?;
+
+        let data_storage = world.get_data_storage();
+        let graveyard = data_storage.get_nearest_graveyard(self.map.as_int(), self.area.as_int(), &position.position, self.get_graveyard_faction());
+        match graveyard {
+            Some(graveyard) => {
+                trace!("Character {} released to graveyard {}", self.name, graveyard.id);
+                self.teleport_to(TeleportationDistance::Far(WorldZoneLocation {
+                    map: Map::try_from(graveyard.map)?,
+                    area: self.area,
+                    position: graveyard.position,
+                    orientation: position.orientation,
+                }));
+            }
+            None => warn!(
+                "No graveyard for character {} on map {}, the ghost stays at its corpse",
+                self.name, self.map
+            ),
+        }
+        Ok(())
+    }
+
+    pub async fn reclaim_corpse(&mut self, world: &World) -> Result<()> {
+        if self.death_state.life_state != LifeState::Ghost || self.death_state.reclaim_delay > 0.0 {
+            return Ok(());
+        }
+        let Some(corpse) = self.death_state.corpse.as_ref() else {
+            return Ok(());
+        };
+        let own_position = self.get_position().unwrap_or_default();
+        let same_map = corpse.map == self.map && corpse.instance_id == self.get_corpse_instance_id(world);
+        if !same_map || corpse.position.distance_to(&own_position) > CORPSE_RECLAIM_RADIUS {
+            return Ok(());
+        }
+
+        self.resurrect(world).await
+    }
+
+    pub async fn resurrect_with_spirit_healer(&mut self, world: &World, spirit_healer: Guid) -> Result<()> {
+        if self.death_state.life_state != LifeState::Ghost {
+            return Ok(());
+        }
+
+        self.get_npc(world, spirit_healer, UNIT_NPC_FLAG_SPIRIT_HEALER).await?;
+        self.resurrect(world).await?;
+        self.lose_equipment_durability(DURABILITY_LOSS_AT_SPIRIT_HEALER)?;
+        if self.get_level() >= RESURRECTION_SICKNESS_MIN_LEVEL {
+            self.apply_aura_from_spell(&world.get_data_storage(), SPELL_RESURRECTION_SICKNESS)?;
+        }
+        Ok(())
+    }
+
+    async fn resurrect(&mut self, world: &World) -> Result<()> {
+        self.death_state.life_state = LifeState::Alive;
+        self.set_ghost_flag(false);
+        for spell_id in self.get_ghost_spells() {
+            self.auras.remove_spell(spell_id);
+        }
+
+        let max_health = self.get_max_health();
+        self.set_health((max_health as f32 * RESURRECTION_RESTORE_FRACTION) as u32);
+        if self.get_power_type() == Power::Mana {
+            let max_mana = self.get_max_power(Power::Mana);
+            self.set_power(Power::Mana, (max_mana as f32 * RESURRECTION_RESTORE_FRACTION) as i32);
+        }
+
+        if let Some(corpse) = self.death_state.corpse.take() {
+            if let Some(map) = world.get_instance_manager().try_get_map(corpse.map, corpse.instance_id).await {
+                map.remove_corpse(self.get_guid()).await;
+            }
+        }
+        world.get_realm_database().delete_character_corpse(self.get_guid().guid() as u32).await
+    }
+}
@@ -0,0 +1,169 @@
+use crate::data::DataStorage;
+use crate::prelude::*;
+use crate::world::prelude::inventory::{EQUIPMENT_SLOTS_END, EQUIPMENT_SLOTS_START};
+use crate::world::prelude::unit_flags::UNIT_NPC_FLAG_REPAIR;
+use crate::world::World;
+use rand::Rng;
+use wow_dbc::Indexable;
+use wow_world_messages::wrath::EquipmentSlot;
+use wrath_realm_db::item_template::DBItemTemplate;
+
+use super::character_inventory::INVENTORY_SLOT_BAG_0;
+
+//Part of the max durability every piece of equipment loses on death, and on top of that when
+//resurrecting at a spirit healer
+pub const DURABILITY_LOSS_ON_DEATH: f32 = 0.1;
+pub const DURABILITY_LOSS_AT_SPIRIT_HEALER: f32 = 0.25;
+
+//Chance to lose a point of durability on the weapon with every swing that hits, and on a random
+//piece of equipment with every hit taken
+const WEAPON_DURABILITY_LOSS_CHANCE: f64 = 0.005;
+const ARMOR_DURABILITY_LOSS_CHANCE: f64 = 0.005;
+
+//Repair costs differ per subclass of these item classes
+const ITEM_CLASS_WEAPON: u8 = 2;
+const ITEM_CLASS_ARMOR: u8 = 4;
+
+fn get_equipment_positions() -> impl Iterator<Item = (u8, u8)> {
+    (EQUIPMENT_SLOTS_START..=EQUIPMENT_SLOTS_END).map(|slot| (slot, INVENTORY_SLOT_BAG_0))
+}
+
+//Anything that needs repairs costs at least a copper
+fn calculate_repair_cost(lost_durability: u32, cost_per_point: u32, quality_modifier: f32) -> u32 {
+    if lost_durability == 0 {
+        return 0;
+    }
+    ((lost_durability as f32 * cost_per_point as f32 * quality_modifier) as u32).max(1)
+}
+
+//The cost per point depends on the item level and subclass, better quality items cost more on top of that
+fn get_repair_cost(data_storage: &DataStorage, template: &DBItemTemplate, lost_durability: u32) -> Result<u32> {
+    let costs = data_storage
+        .get_dbc_durability_costs()?
+        .get(template.item_level as u32)
+        .ok_or_else(|| anyhow!("No durability costs for item level {}", template.item_level))?;
+    let cost_per_point = match template.class {
+        ITEM_CLASS_WEAPON => costs.weapon_sub_class_cost.get(template.subclass as usize).copied().unwrap_or(0),
+        ITEM_CLASS_ARMOR => costs.armor_sub_class_cost.get(template.subclass as usize).copied().unwrap_or(0),
+        _ => 0,
+    };
+    let quality_modifier = data_storage
+        .get_dbc_durability_quality()?
+        .get((template.quality as u32 + 1) * 2)
+        .map_or(1.0, |quality| quality.data);
+    Ok(calculate_repair_cost(lost_durability, cost_per_point.max(0) as u32, quality_modifier))
+}
+
+impl super::Character {
+    fn set_item_durability(&mut self, item_position: (u8, u8), durability: u32) -> Result<()> {
+        let item = self
+            .get_item_mut(item_position)
+            .ok_or_else(|| anyhow!("No item at {:?} to change the durability of", item_position))?;
+        let was_broken = item.is_broken();
+        item.set_durability(durability);
+        let is_broken = item.is_broken();
+        let values_update = item.build_values_update();
+        item.update_state.dirty_reset();
+        self.push_object_update(values_update);
+
+        //Broken equipment stops granting its stats
+        if was_broken != is_broken && get_equipment_positions().any(|position| position == item_position) {
+            self.mark_stats_dirty();
+        }
+        Ok(())
+    }
+
+    pub(super) fn lose_equipment_durability(&mut self, fraction: f32) -> Result<()> {
+        for position in get_equipment_positions() {
+            let Some(item) = self.get_item(position) else {
+                continue;
+            };
+            let loss = (item.get_max_durability() as f32 * fraction) as u32;
+            if loss > 0 {
+                let durability = item.get_durability().saturating_sub(loss);
+                self.set_item_durability(position, durability)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn lose_durability_point(&mut self, item_position: (u8, u8)) -> Result<()> {
+        match self.get_item(item_position) {
+            Some(item) if item.get_durability() > 0 => {
+                let durability = item.get_durability() - 1;
+                self.set_item_durability(item_position, durability)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub(super) fn roll_weapon_durability_loss(&mut self) -> Result<()> {
+        if rand::thread_rng().gen_bool(WEAPON_DURABILITY_LOSS_CHANCE) {
+            self.lose_durability_point((EquipmentSlot::MainHand as u8, INVENTORY_SLOT_BAG_0))?;
+        }
+        Ok(())
+    }
+
+    pub(super) fn roll_armor_durability_loss(&mut self) -> Result<()> {
+        let mut rng = rand::thread_rng();
+        if rng.gen_bool(ARMOR_DURABILITY_LOSS_CHANCE) {
+            let slot = rng.gen_range(EQUIPMENT_SLOTS_START..=EQUIPMENT_SLOTS_END);
+            self.lose_durability_point((slot, INVENTORY_SLOT_BAG_0))?;
+        }
+        Ok(())
+    }
+
+    //Repairs a single item, or everything that is equipped or carried around when the guid is zero
+    pub async fn repair_items(&mut self, world: &World, npc: Guid, item_guid: Guid, from_guild_bank: bool) -> Result<()> {
+        self.get_npc(world, npc, UNIT_NPC_FLAG_REPAIR).await?;
+        //TODO: guilds don't have a bank to pay for repairs yet
+        if from_guild_bank {
+            bail!("Character {} can't repair from a guild bank", self.name);
+        }
+
+        let data_storage = world.get_data_storage();
+        let mut positions: Vec<(u8, u8)> = get_equipment_positions().chain(self.get_carried_item_positions()).collect();
+        if !item_guid.is_zero() {
+            positions.retain(|position| self.get_item(*position).is_some_and(|item| item.get_guid() == item_guid));
+            if positions.is_empty() {
+                bail!("Character {} has no item {} to repair", self.name, item_guid);
+            }
+        }
+
+        for position in positions {
+            let Some(item) = self.get_item(position) else {
+                continue;
+            };
+            let max_durability = item.get_max_durability();
+            let lost_durability = max_durability - item.get_durability();
+            if lost_durability == 0 {
+                continue;
+            }
+            let template = data_storage
+                .get_item_template(item.get_entry())
+                .ok_or_else(|| anyhow!("No item template for item {}", item.get_entry()))?;
+            let cost = get_repair_cost(&data_storage, template, lost_durability)?;
+            //The client only offers repairs that can be paid for, so this stops at whatever is left once the money runs out
+            if self.get_money() < cost {
+                break;
+            }
+            self.remove_money(cost);
+            self.set_item_durability(position, max_durability)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repair_cost() {
+        assert_eq!(calculate_repair_cost(0, 5, 1.0), 0);
+        assert_eq!(calculate_repair_cost(10, 5, 1.0), 50);
+        assert_eq!(calculate_repair_cost(10, 5, 1.25), 62);
+        //Cheap items still cost something
+        assert_eq!(calculate_repair_cost(1, 0, 1.0), 1);
+    }
+}
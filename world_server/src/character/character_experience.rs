@@ -0,0 +1,248 @@
+use super::character_stats::NUM_STATS;
+use crate::data::DataStorage;
+use crate::prelude::*;
+use crate::world::guid_service::HighGuid;
+use crate::world::prelude::Unit;
+use wow_dbc::Indexable;
+use wow_world_messages::wrath::{Area, Power};
+
+//The client keeps a bit for every explorable area in this many 32 bit words
+const EXPLORED_ZONES_WORDS: usize = 128;
+
+//Areas within this many levels give the full exploration experience
+const EXPLORATION_LEVEL_RANGE: i32 = 5;
+const EXPLORATION_PENALTY_PER_LEVEL: i32 = 5;
+
+//What a level up added, for the chat messages of the client
+pub struct LevelUpGains {
+    pub level: u32,
+    pub health: u32,
+    pub mana: u32,
+    pub stats: [u32; NUM_STATS],
+}
+
+#[derive(Default)]
+pub(super) struct ExperienceState {
+    xp: u32,
+    explored_zones: Vec<u32>,
+}
+
+//Creatures at or below this level give no experience
+pub fn get_gray_level(level: u32) -> u32 {
+    match level {
+        0..=5 => 0,
+        6..=39 => level - 5 - level / 10,
+        40..=59 => level - 1 - level / 5,
+        _ => level - 9,
+    }
+}
+
+//How many levels below the character the experience of a kill reaches zero
+fn get_zero_difference(level: u32) -> u32 {
+    match level {
+        0..=7 => 5,
+        8..=9 => 6,
+        10..=11 => 7,
+        12..=15 => 8,
+        16..=19 => 9,
+        20..=29 => 11,
+        30..=39 => 12,
+        40..=44 => 13,
+        45..=49 => 14,
+        50..=54 => 15,
+        55..=59 => 16,
+        _ => 17,
+    }
+}
+
+//Every expansion raised the experience of its creatures
+fn get_base_kill_experience(level: u32) -> u32 {
+    match level {
+        0..=59 => 45,
+        60..=69 => 235,
+        _ => 580,
+    }
+}
+
+pub fn get_kill_experience(level: u32, victim_level: u32) -> u32 {
+    let base = level * 5 + get_base_kill_experience(level);
+    if victim_level >= level {
+        let level_difference = (victim_level - level).min(4);
+        (base * (20 + level_difference) / 10 + 1) / 2
+    } else if victim_level > get_gray_level(level) {
+        let zero_difference = get_zero_difference(level);
+        base * (zero_difference + victim_level).saturating_sub(level) / zero_difference
+    } else {
+        0
+    }
+}
+
+//Areas far below the character give less, areas far above give what an area slightly above would
+pub fn get_exploration_experience(level: u32, area_level: u32, base_xp: impl Fn(u32) -> u32) -> u32 {
+    let difference = level as i32 - area_level as i32;
+    if difference < -EXPLORATION_LEVEL_RANGE {
+        base_xp(level + EXPLORATION_LEVEL_RANGE as u32)
+    } else if difference > EXPLORATION_LEVEL_RANGE {
+        let percent = (100 - (difference - EXPLORATION_LEVEL_RANGE) * EXPLORATION_PENALTY_PER_LEVEL).max(0) as u32;
+        base_xp(area_level) * percent / 100
+    } else {
+        base_xp(area_level)
+    }
+}
+
+impl super::Character {
+    pub(super) fn load_experience(&mut self, data_storage: &DataStorage, xp: u32, explored_zones: Option<&[u8]>) {
+        let mut words = vec![0u32; EXPLORED_ZONES_WORDS];
+        for (word, bytes) in words.iter_mut().zip(explored_zones.unwrap_or_default().chunks_exact(4)) {
+            *word = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        }
+        for (index, word) in words.iter().enumerate().filter(|(_, word)| **word != 0) {
+            self.gameplay_data.set_player_explored_zones_1(*word, index as u32);
+        }
+        self.experience_state.explored_zones = words;
+        self.experience_state.xp = xp;
+        self.update_experience_fields(data_storage);
+    }
+
+    pub(super) fn get_xp(&self) -> u32 {
+        self.experience_state.xp
+    }
+
+    pub(super) fn get_explored_zones_for_database(&self) -> Vec<u8> {
+        self.experience_state.explored_zones.iter().flat_map(|word| word.to_le_bytes()).collect()
+    }
+
+    //None once the character reached the max level
+    pub(super) fn get_xp_for_next_level(&self, data_storage: &DataStorage) -> Option<u32> {
+        data_storage.get_xp_for_next_level(self.get_level() as u8)
+    }
+
+    fn update_experience_fields(&mut self, data_storage: &DataStorage) {
+        let next_level_xp = self.get_xp_for_next_level(data_storage).unwrap_or(0);
+        self.gameplay_data.set_player_xp(self.experience_state.xp as i32);
+        self.gameplay_data.set_player_next_level_xp(next_level_xp as i32);
+    }
+
+    //Only creatures are worth experience, players killing each other is a matter of honor
+    pub(super) async fn give_kill_experience(&mut self, data_storage: &DataStorage, victim: Guid, victim_level: u32) -> Result<()> {
+        if HighGuid::from_guid(victim) != Some(HighGuid::Unit) || self.get_xp_for_next_level(data_storage).is_none() {
+            return Ok(());
+        }
+        let experience = get_kill_experience(self.get_level(), victim_level);
+        if experience == 0 {
+            return Ok(());
+        }
+
+        let rested = self.consume_rest_bonus(experience);
+        handlers::send_kill_experience(self, victim, experience + rested, experience).await?;
+        self.give_experience(data_storage, experience + rested).await
+    }
+
+    //Entering an area for the first time marks it on the world map, and is worth some experience
+    pub async fn explore_area(&mut self, data_storage: &DataStorage, area: Area) -> Result<()> {
+        let Some(area_info) = data_storage.get_dbc_area_table()?.get(area.as_int()) else {
+            return Ok(());
+        };
+        let bit = area_info.area_bit;
+        let word_index = bit as usize / 32;
+        if bit < 0 || word_index >= EXPLORED_ZONES_WORDS {
+            return Ok(());
+        }
+
+        let word = self.experience_state.explored_zones[word_index];
+        let mask = 1u32 << (bit % 32);
+        if word & mask != 0 {
+            return Ok(());
+        }
+        self.experience_state.explored_zones[word_index] = word | mask;
+        self.gameplay_data.set_player_explored_zones_1(word | mask, word_index as u32);
+
+        let experience = if self.get_xp_for_next_level(data_storage).is_some() && area_info.exploration_level > 0 {
+            get_exploration_experience(self.get_level(), area_info.exploration_level as u32, |level| {
+                data_storage.get_exploration_base_xp(level as u8)
+            })
+        } else {
+            0
+        };
+        handlers::send_exploration_experience(self, area, experience).await?;
+        if experience > 0 {
+            self.give_experience(data_storage, experience).await?;
+        }
+        Ok(())
+    }
+
+    pub(super) async fn give_experience(&mut self, data_storage: &DataStorage, amount: u32) -> Result<()> {
+        let mut xp = self.experience_state.xp + amount;
+        while let Some(needed) = self.get_xp_for_next_level(data_storage) {
+            if xp < needed {
+                break;
+            }
+            xp -= needed;
+            self.level_up(data_storage).await?;
+        }
+        //There's nothing to gather experience for at the max level
+        if self.get_xp_for_next_level(data_storage).is_none() {
+            xp = 0;
+        }
+
+        self.experience_state.xp = xp;
+        self.update_experience_fields(data_storage);
+        Ok(())
+    }
+
+    async fn level_up(&mut self, data_storage: &DataStorage) -> Result<()> {
+        let before = self.get_derived_stats().clone();
+        let level = self.get_level() + 1;
+        self.gameplay_data.set_unit_level(level as i32);
+        self.recalculate_stats(data_storage)?;
+
+        let after = self.get_derived_stats();
+        let mut stats = [0; NUM_STATS];
+        for (stat, gain) in stats.iter_mut().enumerate() {
+            *gain = (after.stats[stat] - before.stats[stat]).max(0) as u32;
+        }
+        let gains = LevelUpGains {
+            level,
+            health: (after.max_health - before.max_health).max(0) as u32,
+            mana: (after.max_mana - before.max_mana).max(0) as u32,
+            stats,
+        };
+
+        self.set_health(self.get_max_health());
+        if self.get_power_type() == Power::Mana {
+            self.set_power(Power::Mana, self.get_max_power(Power::Mana));
+        }
+        self.update_played_time();
+        self.seconds_played_at_level = 0;
+        self.update_talent_points();
+        self.update_skill_max_values(data_storage)?;
+        handlers::send_levelup_info(self, &gains).await?;
+        handlers::send_talents_info(self).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kill_experience() {
+        //Same level, and the bonus for higher levels stops at 4 levels above
+        assert_eq!(get_kill_experience(10, 10), 95);
+        assert_eq!(get_kill_experience(10, 14), get_kill_experience(10, 20));
+        //Lower levels give less, until they turn gray
+        assert_eq!(get_kill_experience(10, 5), 27);
+        assert_eq!(get_gray_level(10), 4);
+        assert_eq!(get_kill_experience(10, 4), 0);
+    }
+
+    #[test]
+    fn exploration_experience() {
+        let base_xp = |level: u32| level * 10;
+        assert_eq!(get_exploration_experience(10, 12, base_xp), 120);
+        //Far above the character counts as 5 levels above
+        assert_eq!(get_exploration_experience(10, 30, base_xp), 150);
+        //10 levels below loses 25%
+        assert_eq!(get_exploration_experience(20, 10, base_xp), 75);
+    }
+}
@@ -1,8 +1,14 @@
+use crate::data::DataStorage;
 use crate::item::item_container::ItemContainer;
 use crate::{
     item::Item,
     prelude::*,
-    world::prelude::inventory::{self, get_compatible_equipment_slots_for_inventory_type, BagSlot, EquipmentSlot, BAG_SLOTS_END},
+    world::guid_service::GuidService,
+    world::prelude::inventory::{
+        self, get_compatible_equipment_slots_for_inventory_type, BagSlot, EquipmentSlot, BAG_SLOTS_END, BAG_SLOTS_START, BANK_BAG_SLOTS_END,
+        BANK_BAG_SLOTS_START, BANK_ITEM_SLOTS_END, BANK_ITEM_SLOTS_START, ITEM_FLAG_UNIQUE_EQUIPPABLE,
+    },
+    world::prelude::ReceiveUpdates,
 };
 use std::{
     collections::HashMap,
@@ -12,6 +18,7 @@ use std::{
 use wow_world_base::wrath::ItemSlot;
 use wow_world_messages::wrath::UpdateItem;
 use wow_world_messages::wrath::{InventoryType, VisibleItem, VisibleItemIndex};
+use wrath_realm_db::item_template::DBItemTemplate;
 
 //An identifier for the player inventory (the thing ItemSlot models a cell of)
 pub const INVENTORY_SLOT_BAG_0: u8 = 255;
@@ -56,10 +63,19 @@ impl<ItemType: InventoryStorable> CharacterInventory<ItemType> {
         self.items.get(&slot)
     }
 
+    pub fn get_item_mut(&mut self, slot: EquipmentSlot) -> Option<&mut ItemType> {
+        self.items.get_mut(&slot)
+    }
+
     pub fn take_item(&mut self, slot: EquipmentSlot) -> Option<ItemType> {
         self.items.remove(&slot)
     }
 
+    //Puts the item in this exact slot, returns the item that was there before
+    pub fn insert_item(&mut self, slot: EquipmentSlot, item: ItemType) -> Option<ItemType> {
+        self.items.insert(slot, item)
+    }
+
     pub fn get_all_equipment(&self) -> [Option<&ItemType>; (BAG_SLOTS_END + 1) as usize] {
         let mut result = [None; (BAG_SLOTS_END + 1) as usize];
         for (slot, item) in self.items.iter() {
@@ -115,13 +131,18 @@ impl BagInventory {
     fn to_index(slot: &BagSlot) -> usize {
         (*slot as usize) - (BagSlot::Item1 as usize)
     }
-    fn take_item(&mut self, index: BagSlot) -> Option<Item> {
-        let index = Self::to_index(&index);
-        if self.items[index].is_some() {
-            self.items[index].take()
-        } else {
-            None
-        }
+}
+
+impl BagInventory {
+    pub fn get_all_items(&self) -> Vec<(BagSlot, &Item)> {
+        self.items
+            .iter()
+            .enumerate()
+            .filter_map(|(index, item)| {
+                let slot = BagSlot::try_from(index as u8 + BagSlot::Item1 as u8).ok()?;
+                item.as_ref().map(|item| (slot, item))
+            })
+            .collect()
     }
 }
 
@@ -135,109 +156,815 @@ impl ItemContainer<BagSlot> for BagInventory {
     }
 }
 
+//Reasons an item can't be moved, these are reported back to the client
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum InventoryError {
+    ItemNotFound,
+    ItemDoesntGoToSlot,
+    ItemCantBeEquipped,
+    ItemsCantBeSwapped,
+    CantEquipLevel(u8),
+    CantEquipSkill,
+    YouCanNeverUseThatItem,
+    ItemUniqueEquippable,
+    CantEquipWithTwoHanded,
+    NotABag,
+    MustPurchaseThatBagSlot,
+    NonEmptyBagOverOtherBag,
+    CanOnlyDoWithEmptyBags,
+    InventoryFull,
+    BankFull,
+    BagFull,
+    ItemCantStack,
+    TriedToSplitMoreThanCount,
+    CouldntSplitItems,
+}
+
+//What an item position (slot, bag) points to
+#[derive(Clone, Copy)]
+enum ItemLocation {
+    Equipment(EquipmentSlot),
+    Backpack(BagSlot),
+    //Indices into the bank and bank bag containers, not the raw slots
+    Bank(u8),
+    BankBag(u8),
+    InBag { bag: u8, slot: u8 },
+}
+
+pub fn is_bag_slot(slot: u8) -> bool {
+    (BAG_SLOTS_START..=BAG_SLOTS_END).contains(&slot) || (BANK_BAG_SLOTS_START..=BANK_BAG_SLOTS_END).contains(&slot)
+}
+
+//Bags can't go inside of themselves, and only the bag slots can hold a bag with items in it
+fn check_bag_move(item: &Item, from: (u8, u8), to: (u8, u8)) -> Result<(), InventoryError> {
+    if from.1 == INVENTORY_SLOT_BAG_0 && to.1 == from.0 {
+        return Err(InventoryError::ItemsCantBeSwapped);
+    }
+
+    if item.contents.as_ref().is_some_and(|contents| !contents.is_empty()) && !(to.1 == INVENTORY_SLOT_BAG_0 && is_bag_slot(to.0)) {
+        return Err(if to.1 == INVENTORY_SLOT_BAG_0 {
+            InventoryError::CanOnlyDoWithEmptyBags
+        } else {
+            InventoryError::NonEmptyBagOverOtherBag
+        });
+    }
+    Ok(())
+}
+
+fn get_item_location(item_position: (u8, u8)) -> Result<ItemLocation> {
+    match item_position {
+        (slot, INVENTORY_SLOT_BAG_0) => {
+            if let Ok(equipment_slot) = EquipmentSlot::try_from(slot) {
+                Ok(ItemLocation::Equipment(equipment_slot))
+            } else if let Ok(bag_slot) = BagSlot::try_from(slot) {
+                Ok(ItemLocation::Backpack(bag_slot))
+            } else if (BANK_ITEM_SLOTS_START..=BANK_ITEM_SLOTS_END).contains(&slot) {
+                Ok(ItemLocation::Bank(slot - BANK_ITEM_SLOTS_START))
+            } else if (BANK_BAG_SLOTS_START..=BANK_BAG_SLOTS_END).contains(&slot) {
+                Ok(ItemLocation::BankBag(slot - BANK_BAG_SLOTS_START))
+            } else {
+                bail!("Inventory slot {} is not supported", slot)
+            }
+        }
+        (slot, bag) if is_bag_slot(bag) => Ok(ItemLocation::InBag { bag, slot }),
+        (_, bag) => bail!("{} is not a bag slot", bag),
+    }
+}
+
 impl crate::character::Character {
+    fn get_bag(&self, bag_slot: u8) -> Option<&Item> {
+        let item = match get_item_location((bag_slot, INVENTORY_SLOT_BAG_0)).ok()? {
+            ItemLocation::Equipment(equipment_slot) => self.equipped_items.get_item(equipment_slot),
+            ItemLocation::BankBag(index) => self.bank_bags.get_item(index),
+            _ => None,
+        };
+        item.filter(|item| item.is_bag())
+    }
+
+    fn get_bag_mut(&mut self, bag_slot: u8) -> Option<&mut Item> {
+        let item = match get_item_location((bag_slot, INVENTORY_SLOT_BAG_0)).ok()? {
+            ItemLocation::Equipment(equipment_slot) => self.equipped_items.get_item_mut(equipment_slot),
+            ItemLocation::BankBag(index) => self.bank_bags.get_item_mut(index),
+            _ => None,
+        };
+        item.filter(|item| item.is_bag())
+    }
+
+    pub fn get_item(&self, item_position: (u8, u8)) -> Option<&Item> {
+        match get_item_location(item_position).ok()? {
+            ItemLocation::Equipment(equipment_slot) => self.equipped_items.get_item(equipment_slot),
+            ItemLocation::Backpack(bag_slot) => self.bag_items[bag_slot].as_ref(),
+            ItemLocation::Bank(index) => self.bank_items.get_item(index),
+            ItemLocation::BankBag(index) => self.bank_bags.get_item(index),
+            ItemLocation::InBag { bag, slot } => self.get_bag(bag)?.contents.as_ref()?.get_item(slot),
+        }
+    }
+
+    pub fn get_item_mut(&mut self, item_position: (u8, u8)) -> Option<&mut Item> {
+        match get_item_location(item_position).ok()? {
+            ItemLocation::Equipment(equipment_slot) => self.equipped_items.get_item_mut(equipment_slot),
+            ItemLocation::Backpack(bag_slot) => self.bag_items[bag_slot].as_mut(),
+            ItemLocation::Bank(index) => self.bank_items.get_item_mut(index),
+            ItemLocation::BankBag(index) => self.bank_bags.get_item_mut(index),
+            ItemLocation::InBag { bag, slot } => self.get_bag_mut(bag)?.contents.as_mut()?.get_item_mut(slot),
+        }
+    }
+
+    //Checks that something can be stored at this position, without looking at the item itself
+    pub fn check_item_position(&self, item_position: (u8, u8)) -> Result<()> {
+        match get_item_location(item_position)? {
+            ItemLocation::BankBag(index) if index >= self.bank_bag_slots => bail!("Bank bag slot {} is not purchased yet", index),
+            ItemLocation::InBag { bag, slot } => {
+                let contents = self
+                    .get_bag(bag)
+                    .and_then(|bag| bag.contents.as_ref())
+                    .ok_or_else(|| anyhow!("There is no bag in slot {}", bag))?;
+                if slot >= contents.num_slots() {
+                    bail!("Bag in slot {} only has {} slots", bag, contents.num_slots());
+                }
+                Ok(())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    //Every item of the character, with its slot inside of the container that holds it
+    pub fn get_all_items(&self) -> Vec<(u8, &Item)> {
+        let equipment = self
+            .equipped_items
+            .get_all_equipment()
+            .into_iter()
+            .enumerate()
+            .filter_map(|(slot, item)| item.map(|item| (slot as u8, item)));
+        let backpack = self.bag_items.get_all_items().into_iter().map(|(slot, item)| (slot as u8, item));
+        let bank = self
+            .bank_items
+            .get_all_items()
+            .into_iter()
+            .map(|(index, item)| (index + BANK_ITEM_SLOTS_START, item));
+        let bank_bags = self
+            .bank_bags
+            .get_all_items()
+            .into_iter()
+            .map(|(index, item)| (index + BANK_BAG_SLOTS_START, item));
+
+        let items: Vec<(u8, &Item)> = equipment.chain(backpack).chain(bank).chain(bank_bags).collect();
+        let bag_contents: Vec<(u8, &Item)> = items
+            .iter()
+            .filter_map(|(_, item)| item.contents.as_ref())
+            .flat_map(|contents| contents.get_all_items())
+            .collect();
+        items.into_iter().chain(bag_contents).collect()
+    }
+
+    //Finds an empty slot in a single bag, INVENTORY_SLOT_BAG_0 being the backpack
+    fn find_free_slot_in_bag(&self, bag: u8) -> Option<(u8, u8)> {
+        if bag == INVENTORY_SLOT_BAG_0 {
+            (BagSlot::Item1 as u8..=BagSlot::Item16 as u8)
+                .find(|&slot| self.get_item((slot, INVENTORY_SLOT_BAG_0)).is_none())
+                .map(|slot| (slot, INVENTORY_SLOT_BAG_0))
+        } else {
+            Some((self.get_bag(bag)?.contents.as_ref()?.find_free_slot()?, bag))
+        }
+    }
+
+    //Finds an empty slot in the backpack and equipped bags, or the bank and bank bags
+    pub fn find_free_item_position(&self, in_bank: bool) -> Option<(u8, u8)> {
+        if in_bank {
+            if let Some(index) = self.bank_items.find_free_slot() {
+                return Some((index + BANK_ITEM_SLOTS_START, INVENTORY_SLOT_BAG_0));
+            }
+            (BANK_BAG_SLOTS_START..=BANK_BAG_SLOTS_END).find_map(|bag| self.find_free_slot_in_bag(bag))
+        } else {
+            std::iter::once(INVENTORY_SLOT_BAG_0)
+                .chain(BAG_SLOTS_START..=BAG_SLOTS_END)
+                .find_map(|bag| self.find_free_slot_in_bag(bag))
+        }
+    }
+
+    //Positions of the backpack and of the equipped bags, which is where items count for quests
+    pub(super) fn get_carried_item_positions(&self) -> Vec<(u8, u8)> {
+        let backpack = (BagSlot::Item1 as u8..=BagSlot::Item16 as u8).map(|slot| (slot, INVENTORY_SLOT_BAG_0));
+        let bags = (BAG_SLOTS_START..=BAG_SLOTS_END).flat_map(|bag| {
+            let num_slots = self
+                .get_bag(bag)
+                .and_then(|bag| bag.contents.as_ref())
+                .map_or(0, |contents| contents.num_slots());
+            (0..num_slots).map(move |slot| (slot, bag))
+        });
+        backpack.chain(bags).collect()
+    }
+
+    //Where in the backpack or bags this item is
+    pub(super) fn find_carried_item(&self, guid: Guid) -> Option<(u8, u8)> {
+        self.get_carried_item_positions()
+            .into_iter()
+            .find(|position| self.get_item(*position).is_some_and(|item| item.get_guid() == guid))
+    }
+
+    fn count_free_item_positions(&self) -> usize {
+        self.get_carried_item_positions()
+            .into_iter()
+            .filter(|position| self.get_item(*position).is_none())
+            .count()
+    }
+
+    //How many items of this entry the character carries around, the bank doesn't count
+    pub fn get_item_count(&self, entry: u32) -> u32 {
+        self.get_carried_item_positions()
+            .into_iter()
+            .filter_map(|position| self.get_item(position))
+            .filter(|item| item.get_entry() == entry)
+            .map(Item::get_stack_count)
+            .sum()
+    }
+
+    //Takes count items of this entry out of the backpack and bags, from as many stacks as needed
+    pub async fn destroy_items_by_entry(&mut self, entry: u32, count: u32) -> Result<()> {
+        let mut remaining = count;
+        for position in self.get_carried_item_positions() {
+            if remaining == 0 {
+                break;
+            }
+            let Some(stack_count) = self
+                .get_item(position)
+                .filter(|item| item.get_entry() == entry)
+                .map(Item::get_stack_count)
+            else {
+                continue;
+            };
+            if stack_count <= remaining {
+                self.destroy_item(position).await?;
+                remaining -= stack_count;
+            } else {
+                self.set_item_stack_count(position, stack_count - remaining)?;
+                remaining = 0;
+            }
+        }
+        if remaining > 0 {
+            bail!("Character {} is missing {} of item {}", self.name, remaining, entry);
+        }
+        Ok(())
+    }
+
+    //Items keep track of the container they are in, which is the character itself unless they
+    //are inside of a bag
+    fn set_item_container(&mut self, item: &mut Item, container: Guid) {
+        if item.update_state.item_contained() != Some(container) {
+            item.update_state.set_item_contained(container);
+            self.push_object_update(item.build_values_update());
+            item.update_state.dirty_reset();
+        }
+    }
+
+    pub(super) fn set_item_stack_count(&mut self, item_position: (u8, u8), stack_count: u32) -> Result<()> {
+        let item = self
+            .get_item_mut(item_position)
+            .ok_or_else(|| anyhow!("No item at {:?} to change the stack count of", item_position))?;
+        item.set_stack_count(stack_count);
+        let values_update = item.build_values_update();
+        item.update_state.dirty_reset();
+        self.push_object_update(values_update);
+        self.mark_quest_items_dirty();
+        Ok(())
+    }
+
+    //Takes the item out of the inventory for good, the client is told to forget about it
+    pub async fn destroy_item(&mut self, item_position: (u8, u8)) -> Result<()> {
+        let item = self
+            .set_item(None, item_position)?
+            .ok_or_else(|| anyhow!("No item at {:?} to destroy", item_position))?;
+        handlers::send_destroy_object(self, item.get_guid(), false).await
+    }
+
+    fn set_inventory_field(&mut self, slot: u8, item: Option<&Item>) -> Result<()> {
+        let guid = item.map_or(Guid::zero(), Item::get_guid);
+        self.gameplay_data.set_player_field_inv(ItemSlot::try_from(slot)?, guid);
+        Ok(())
+    }
+
     //This function is meant to be used both with inventory and equipment or bags
     //It sets the item in the slot, and returns the old item if there was one
     //Doesn't check if the item is compatible with the slot
-    //Slot is u16 because lower 8 bits contain slot data and upper 8 bits contain bag data
-    pub fn set_item(&mut self, item: Option<Item>, item_position: (u8, u8)) -> Result<Option<Item>> {
-        match item_position {
-            (slot, INVENTORY_SLOT_BAG_0) => {
-                if let Ok(equipment_slot) = EquipmentSlot::try_from(slot) {
-                    let previous_item = self.equipped_items.take_item(equipment_slot);
-                    if let Some(item) = item {
-                        if slot <= inventory::EQUIPMENT_SLOTS_END {
-                            //TODO: add display enchants
-                            self.gameplay_data.set_player_visible_item(
-                                VisibleItem::new(item.update_state.object_entry().unwrap() as u32, [0u16; 2]),
-                                VisibleItemIndex::try_from(slot).unwrap(),
-                            );
-                        }
-                        self.gameplay_data
-                            .set_player_field_inv(ItemSlot::try_from(slot).unwrap(), item.update_state.object_guid().unwrap());
-                        self.equipped_items.try_insert_item(item)?;
-                    } else {
-                        if slot <= inventory::EQUIPMENT_SLOTS_END {
-                            self.gameplay_data
-                                .set_player_visible_item(VisibleItem::new(0u32, [0u16; 2]), VisibleItemIndex::try_from(slot).unwrap());
-                        }
-                        self.gameplay_data.set_player_field_inv(ItemSlot::try_from(slot).unwrap(), Guid::zero());
+    pub fn set_item(&mut self, mut item: Option<Item>, item_position: (u8, u8)) -> Result<Option<Item>> {
+        self.check_item_position(item_position)?;
+        let location = get_item_location(item_position)?;
+        let slot = item_position.0;
+
+        let container = match location {
+            ItemLocation::InBag { bag, .. } => self.get_bag(bag).map(Item::get_guid).unwrap_or(Guid::zero()),
+            _ => self.get_guid(),
+        };
+        if let Some(item) = item.as_mut() {
+            self.set_item_container(item, container);
+        }
+
+        let previous_item = match location {
+            ItemLocation::Equipment(equipment_slot) => {
+                if slot <= inventory::EQUIPMENT_SLOTS_END {
+                    //TODO: add display enchants
+                    let entry = item.as_ref().map_or(0, |item| item.update_state.object_entry().unwrap() as u32);
+                    self.gameplay_data
+                        .set_player_visible_item(VisibleItem::new(entry, [0u16; 2]), VisibleItemIndex::try_from(slot)?);
+                }
+                self.set_inventory_field(slot, item.as_ref())?;
+                if slot <= inventory::EQUIPMENT_SLOTS_END {
+                    self.mark_stats_dirty();
+                }
+                match item {
+                    Some(item) => self.equipped_items.insert_item(equipment_slot, item),
+                    None => self.equipped_items.take_item(equipment_slot),
+                }
+            }
+            ItemLocation::Backpack(bag_slot) => {
+                self.set_inventory_field(slot, item.as_ref())?;
+                std::mem::replace(&mut self.bag_items[bag_slot], item)
+            }
+            ItemLocation::Bank(index) => {
+                self.set_inventory_field(slot, item.as_ref())?;
+                self.bank_items.replace_item(index, item)?
+            }
+            ItemLocation::BankBag(index) => {
+                self.set_inventory_field(slot, item.as_ref())?;
+                self.bank_bags.replace_item(index, item)?
+            }
+            ItemLocation::InBag { bag, slot } => {
+                let bag_item = self.get_bag_mut(bag).ok_or_else(|| anyhow!("There is no bag in slot {}", bag))?;
+                let previous_item = bag_item
+                    .contents
+                    .as_mut()
+                    .ok_or_else(|| anyhow!("Item in slot {} is not a bag", bag))?
+                    .replace_item(slot, item)?;
+                //The bag slots are fields of the container object, so the client needs to know
+                let container_update = bag_item.build_values_update();
+                self.push_object_update(container_update);
+                previous_item
+            }
+        };
+        self.mark_quest_items_dirty();
+        Ok(previous_item)
+    }
+
+    //Moves the item at the source position to the destination, and whatever was at the destination
+    //to the source position. Only refuses moves that would corrupt the inventory, use try_swap_items
+    //for moves requested by the client
+    pub fn swap_items(&mut self, source: (u8, u8), destination: (u8, u8)) -> Result<()> {
+        if source == destination {
+            return Ok(());
+        }
+        self.check_item_position(source)?;
+        self.check_item_position(destination)?;
+
+        for (from, to) in [(source, destination), (destination, source)] {
+            if let Some(item) = self.get_item(from) {
+                check_bag_move(item, from, to).map_err(|e| anyhow!("Can't move the item at {:?} to {:?}: {:?}", from, to, e))?;
+            }
+        }
+
+        //Both items are taken out before either is put back, so a failure can't lose an item.
+        //Putting them back then only touches positions that were just emptied without errors
+        let destination_item = self.set_item(None, destination)?;
+        let source_item = match self.set_item(None, source) {
+            Ok(source_item) => source_item,
+            Err(e) => {
+                self.set_item(destination_item, destination)?;
+                return Err(e);
+            }
+        };
+        self.set_item(source_item, destination)?;
+        self.set_item(destination_item, source)?;
+        Ok(())
+    }
+
+    //Checks if the item that is at "from" is allowed to be put at "to"
+    fn check_can_place_item(&self, item: &Item, template: &DBItemTemplate, from: (u8, u8), to: (u8, u8)) -> Result<(), InventoryError> {
+        check_bag_move(item, from, to)?;
+
+        match get_item_location(to).map_err(|_| InventoryError::ItemDoesntGoToSlot)? {
+            ItemLocation::Equipment(equipment_slot) => {
+                let inventory_type = InventoryType::try_from(template.inventory_type).map_err(|_| InventoryError::ItemCantBeEquipped)?;
+                if !get_compatible_equipment_slots_for_inventory_type(&inventory_type).contains(&equipment_slot) {
+                    return Err(InventoryError::ItemDoesntGoToSlot);
+                }
+
+                let class_bit = 1u32 << (self.get_class().as_int() - 1);
+                let race_bit = 1u32 << (self.get_race().as_int() - 1);
+                if template.allowed_classes_mask.is_some_and(|mask| mask & class_bit == 0)
+                    || template.allowed_races_mask.is_some_and(|mask| mask & race_bit == 0)
+                {
+                    return Err(InventoryError::YouCanNeverUseThatItem);
+                }
+
+                if let Some(required_level) = template.required_level {
+                    if self.gameplay_data.unit_level().unwrap_or(1) < required_level as i32 {
+                        return Err(InventoryError::CantEquipLevel(required_level));
                     }
-                    Ok(previous_item)
-                } else if let Ok(bag_slot) = inventory::BagSlot::try_from(slot) {
-                    //TODO: add persistent bag for character and complete the implementation here
-                    let previous_item = self.bag_items.take_item(bag_slot);
-                    if let Some(item) = item {
-                        self.gameplay_data
-                            .set_player_field_inv(ItemSlot::try_from(slot).unwrap(), item.update_state.object_guid().unwrap());
-                        self.bag_items[bag_slot] = Some(item);
-                    } else {
-                        self.gameplay_data.set_player_field_inv(ItemSlot::try_from(slot).unwrap(), Guid::zero());
-                        self.bag_items[bag_slot] = None;
+                }
+
+                if let Some(required_skill) = template.required_skill.as_ref().filter(|skill| skill.skill_id != 0) {
+                    let skill_value = self.get_skill_value(required_skill.skill_id);
+                    if skill_value == 0 || skill_value < required_skill.required_rank {
+                        return Err(InventoryError::CantEquipSkill);
                     }
-                    Ok(previous_item)
-                } else {
-                    todo!("Non-equipment inventory not implemented yet")
+                }
+
+                if template.flags & ITEM_FLAG_UNIQUE_EQUIPPABLE != 0 {
+                    let entry = item.update_state.object_entry();
+                    let already_equipped = self.equipped_items.get_all_equipment().into_iter().enumerate().any(|(slot, equipped)| {
+                        let slot = slot as u8;
+                        slot != equipment_slot as u8
+                            && (slot, INVENTORY_SLOT_BAG_0) != from
+                            && equipped.is_some_and(|equipped| equipped.update_state.object_entry() == entry)
+                    });
+                    if already_equipped {
+                        return Err(InventoryError::ItemUniqueEquippable);
+                    }
+                }
+
+                let main_hand = (EquipmentSlot::MainHand as u8, INVENTORY_SLOT_BAG_0);
+                let off_hand = (EquipmentSlot::Offhand as u8, INVENTORY_SLOT_BAG_0);
+                if equipment_slot == EquipmentSlot::Offhand && from != main_hand {
+                    let main_hand_is_two_handed = self
+                        .get_item(main_hand)
+                        .is_some_and(|main_hand_item| main_hand_item.get_inventory_type() == InventoryType::TwoHandedWeapon);
+                    if main_hand_is_two_handed {
+                        return Err(InventoryError::CantEquipWithTwoHanded);
+                    }
+                }
+                //The off-hand item gets moved to the bags when equipping a two-hander, so there needs to be room for it
+                if inventory_type == InventoryType::TwoHandedWeapon
+                    && from != off_hand
+                    && self.get_item(off_hand).is_some()
+                    && self.find_free_item_position(false).is_none()
+                {
+                    return Err(InventoryError::InventoryFull);
+                }
+            }
+            ItemLocation::BankBag(_) => {
+                if template.inventory_type != InventoryType::Bag.as_int() {
+                    return Err(InventoryError::NotABag);
                 }
             }
-            (_, _) => todo!("Bags not implemented yet"),
+            _ => {}
         }
+        Ok(())
     }
 
-    //Attempt to auto-equip (right click equipable item from inventory) an item.
-    //Returns the item that was prevously in that equipment slot, or None if the slot was empty.
-    pub fn auto_equip_item_from_bag(&mut self, item_position: (u8, u8)) -> Result<Option<Item>> {
-        match item_position {
-            (slot, INVENTORY_SLOT_BAG_0) => {
-                if let Ok(bag_slot) = inventory::BagSlot::try_from(slot) {
-                    let item_to_equip = self.bag_items.take_item(bag_slot);
-                    if let Some(item) = item_to_equip {
-                        let item_inventory_type = item.get_inventory_type();
-                        let possible_equip_slots = get_compatible_equipment_slots_for_inventory_type(&item_inventory_type);
-
-                        let mut best_slot_candidate = None;
-                        for possible_equip_slot in possible_equip_slots {
-                            if best_slot_candidate.is_none() {
-                                best_slot_candidate = Some(possible_equip_slot);
-                            }
-                            if self.equipped_items.get_item(*possible_equip_slot).is_none() {
-                                //If we find a slot that has no other items inside, we have landed
-                                //on a winner automatically. We can stop.
-                                best_slot_candidate = Some(possible_equip_slot);
-                                break;
-                            }
-                        }
-
-                        if let Some(picked_slot) = best_slot_candidate {
-                            //Replace the item
-                            let previous_item_in_slot = self.set_item(Some(item), (*picked_slot as u8, INVENTORY_SLOT_BAG_0))?;
-                            return Ok(previous_item_in_slot);
-                        } else {
-                            warn!("No slot found to auto-equip this item into!");
-                        }
-                    } else {
-                        warn!("Attempting to auto-equip a non-existant item");
-                    }
+    pub fn check_can_swap_items(
+        &self,
+        source: (u8, u8),
+        destination: (u8, u8),
+        source_template: Option<&DBItemTemplate>,
+        destination_template: Option<&DBItemTemplate>,
+    ) -> Result<(), InventoryError> {
+        for position in [source, destination] {
+            if let Err(e) = self.check_item_position(position) {
+                trace!("Invalid item position {:?}: {}", position, e);
+                return Err(match get_item_location(position) {
+                    Ok(ItemLocation::BankBag(_)) => InventoryError::MustPurchaseThatBagSlot,
+                    _ => InventoryError::ItemDoesntGoToSlot,
+                });
+            }
+        }
+
+        let source_item = self.get_item(source).ok_or(InventoryError::ItemNotFound)?;
+        let source_template = source_template.ok_or(InventoryError::ItemNotFound)?;
+        self.check_can_place_item(source_item, source_template, source, destination)?;
+
+        if let (Some(destination_item), Some(destination_template)) = (self.get_item(destination), destination_template) {
+            self.check_can_place_item(destination_item, destination_template, destination, source)?;
+        }
+        Ok(())
+    }
+
+    //Validates and performs the move, the client is told when it's not allowed
+    pub async fn try_swap_items(&mut self, data_storage: &DataStorage, source: (u8, u8), destination: (u8, u8)) -> Result<()> {
+        if source == destination {
+            return Ok(());
+        }
+
+        let templates: Vec<Option<&DBItemTemplate>> = [source, destination]
+            .iter()
+            .map(|position| self.get_item(*position).and_then(|item| data_storage.get_item_template(item.get_entry())))
+            .collect();
+
+        //Dropping an item onto a stack of the same item tops off that stack, if there's room
+        if let (Some(source_item), Some(destination_item), Some(template)) = (self.get_item(source), self.get_item(destination), templates[0]) {
+            let destination_has_room = destination_item.get_stack_count() < template.stackable.max(1) as u32;
+            if source_item.get_entry() == destination_item.get_entry() && destination_has_room {
+                let amount = source_item.get_stack_count();
+                return self.merge_stacks(source, destination, amount, template).await;
+            }
+        }
+
+        if let Err(error) = self.check_can_swap_items(source, destination, templates[0], templates[1]) {
+            let source_guid = self.get_item(source).map_or(Guid::zero(), Item::get_guid);
+            let destination_guid = self.get_item(destination).map_or(Guid::zero(), Item::get_guid);
+            return handlers::send_inventory_change_failure(self, error, source_guid, destination_guid).await;
+        }
+
+        self.swap_items(source, destination)?;
+
+        //Two-handed weapons don't leave room for an off-hand item
+        let main_hand = (EquipmentSlot::MainHand as u8, INVENTORY_SLOT_BAG_0);
+        let off_hand = (EquipmentSlot::Offhand as u8, INVENTORY_SLOT_BAG_0);
+        let main_hand_is_two_handed = self
+            .get_item(main_hand)
+            .is_some_and(|item| item.get_inventory_type() == InventoryType::TwoHandedWeapon);
+        if main_hand_is_two_handed && self.get_item(off_hand).is_some() {
+            let free_position = self
+                .find_free_item_position(false)
+                .ok_or_else(|| anyhow!("No room to unequip the off-hand, this should have been checked"))?;
+            self.swap_items(off_hand, free_position)?;
+        }
+        Ok(())
+    }
+
+    //Moves up to amount items of the source stack onto the destination stack, as far as there's room for them.
+    //Both need to be the same item
+    async fn merge_stacks(&mut self, source: (u8, u8), destination: (u8, u8), amount: u32, template: &DBItemTemplate) -> Result<()> {
+        let source_count = self.get_item(source).map_or(0, Item::get_stack_count);
+        let destination_count = self.get_item(destination).map_or(0, Item::get_stack_count);
+        let moved = amount.min((template.stackable.max(1) as u32).saturating_sub(destination_count));
+        if moved == 0 {
+            let source_guid = self.get_item(source).map_or(Guid::zero(), Item::get_guid);
+            let destination_guid = self.get_item(destination).map_or(Guid::zero(), Item::get_guid);
+            return handlers::send_inventory_change_failure(self, InventoryError::ItemCantStack, source_guid, destination_guid).await;
+        }
+
+        self.set_item_stack_count(destination, destination_count + moved)?;
+        if moved >= source_count {
+            self.destroy_item(source).await
+        } else {
+            self.set_item_stack_count(source, source_count - moved)
+        }
+    }
+
+    //Takes amount items off of the source stack, and puts them in a new stack at the destination
+    //or on top of the stack that's already there
+    pub async fn try_split_item(
+        &mut self,
+        data_storage: &DataStorage,
+        guid_service: &GuidService,
+        source: (u8, u8),
+        destination: (u8, u8),
+        amount: u32,
+    ) -> Result<()> {
+        let Some(source_item) = self.get_item(source) else {
+            return handlers::send_inventory_change_failure(self, InventoryError::ItemNotFound, Guid::zero(), Guid::zero()).await;
+        };
+        let source_guid = source_item.get_guid();
+        let source_count = source_item.get_stack_count();
+        let template = data_storage
+            .get_item_template(source_item.get_entry())
+            .ok_or_else(|| anyhow!("No item template for item {}", source_item.get_entry()))?;
+
+        if amount == 0 || source == destination {
+            return Ok(());
+        }
+        if amount >= source_count {
+            return handlers::send_inventory_change_failure(self, InventoryError::TriedToSplitMoreThanCount, source_guid, Guid::zero()).await;
+        }
+
+        match self.get_item(destination) {
+            Some(destination_item) if destination_item.get_entry() == template.id => self.merge_stacks(source, destination, amount, template).await,
+            Some(destination_item) => {
+                let destination_guid = destination_item.get_guid();
+                handlers::send_inventory_change_failure(self, InventoryError::CouldntSplitItems, source_guid, destination_guid).await
+            }
+            None => {
+                let placement_check = match self.check_item_position(destination) {
+                    Ok(()) => self.check_can_place_item(source_item, template, source, destination),
+                    Err(_) => Err(InventoryError::ItemDoesntGoToSlot),
+                };
+                if let Err(error) = placement_check {
+                    return handlers::send_inventory_change_failure(self, error, source_guid, Guid::zero()).await;
                 }
+
+                let new_item = Item::new(guid_service.generate_item_guid(), self.get_guid(), template, amount);
+                self.push_object_update(new_item.build_create_update());
+                self.set_item_stack_count(source, source_count - amount)?;
+                self.set_item(Some(new_item), destination)?;
+                Ok(())
             }
-            (_, _) => todo!("Bags not implemented yet"),
         }
-        bail!("Item not from bag 1");
     }
 
-    #[allow(dead_code)]
-    fn has_item_in_slot(&self, item_position: (u8, u8)) -> bool {
-        match item_position {
-            (slot, INVENTORY_SLOT_BAG_0) => {
-                if let Ok(equipment_slot) = EquipmentSlot::try_from(slot) {
-                    self.equipped_items.get_item(equipment_slot).is_some()
-                } else if let Ok(bag_slot) = inventory::BagSlot::try_from(slot) {
-                    self.bag_items[bag_slot].is_some()
+    //Whether the backpack and bags have room for all of these (entry, count) pairs as new stacks
+    pub fn has_room_for_new_items(&self, data_storage: &DataStorage, items: &[(u32, u32)]) -> Result<bool> {
+        let mut positions_needed = 0;
+        for (entry, count) in items {
+            let template = data_storage
+                .get_item_template(*entry)
+                .ok_or_else(|| anyhow!("No item template for item {}", entry))?;
+            positions_needed += count.div_ceil(template.stackable.max(1) as u32) as usize;
+        }
+        Ok(self.count_free_item_positions() >= positions_needed)
+    }
+
+    //Creates count brand new items, in as many stacks as needed, in the first free spots of the backpack and bags.
+    //Either all of them are created or none are, returns false and tells the client when there is no room
+    pub async fn try_create_item(&mut self, data_storage: &DataStorage, guid_service: &GuidService, entry: u32, count: u32) -> Result<bool> {
+        let template = data_storage
+            .get_item_template(entry)
+            .ok_or_else(|| anyhow!("No item template for item {}", entry))?;
+        if !self.has_room_for_new_items(data_storage, &[(entry, count)])? {
+            handlers::send_inventory_change_failure(self, InventoryError::InventoryFull, Guid::zero(), Guid::zero()).await?;
+            return Ok(false);
+        }
+
+        let mut remaining = count;
+        while remaining > 0 {
+            let position = self
+                .find_free_item_position(false)
+                .ok_or_else(|| anyhow!("Character {} ran out of room while creating item {}", self.name, entry))?;
+
+            let stack_count = remaining.min(template.stackable.max(1) as u32);
+            let new_item = Item::new(guid_service.generate_item_guid(), self.get_guid(), template, stack_count);
+            self.push_object_update(new_item.build_create_update());
+            self.set_item(Some(new_item), position)?;
+            remaining -= stack_count;
+        }
+        Ok(true)
+    }
+
+    //Destroys a whole stack when amount is 0, or just part of it otherwise
+    pub async fn try_destroy_item(&mut self, item_position: (u8, u8), amount: u32) -> Result<()> {
+        let Some(item) = self.get_item(item_position) else {
+            return handlers::send_inventory_change_failure(self, InventoryError::ItemNotFound, Guid::zero(), Guid::zero()).await;
+        };
+        if item.contents.as_ref().is_some_and(|contents| !contents.is_empty()) {
+            let guid = item.get_guid();
+            return handlers::send_inventory_change_failure(self, InventoryError::CanOnlyDoWithEmptyBags, guid, Guid::zero()).await;
+        }
+
+        let stack_count = item.get_stack_count();
+        if amount == 0 || amount >= stack_count {
+            self.destroy_item(item_position).await
+        } else {
+            self.set_item_stack_count(item_position, stack_count - amount)
+        }
+    }
+
+    //Attempt to auto-equip (right click equipable item from inventory) an item.
+    //The item that was previously equipped goes to where the equipped item came from.
+    pub async fn try_auto_equip_item(&mut self, data_storage: &DataStorage, item_position: (u8, u8)) -> Result<()> {
+        let Some(item) = self.get_item(item_position) else {
+            return handlers::send_inventory_change_failure(self, InventoryError::ItemNotFound, Guid::zero(), Guid::zero()).await;
+        };
+
+        let item_inventory_type = item.get_inventory_type();
+        let possible_equip_slots = get_compatible_equipment_slots_for_inventory_type(&item_inventory_type);
+
+        let mut best_slot_candidate = None;
+        for possible_equip_slot in possible_equip_slots {
+            if best_slot_candidate.is_none() {
+                best_slot_candidate = Some(possible_equip_slot);
+            }
+            if self.equipped_items.get_item(*possible_equip_slot).is_none() {
+                //If we find a slot that has no other items inside, we have landed
+                //on a winner automatically. We can stop.
+                best_slot_candidate = Some(possible_equip_slot);
+                break;
+            }
+        }
+
+        match best_slot_candidate {
+            Some(picked_slot) => {
+                self.try_swap_items(data_storage, item_position, (*picked_slot as u8, INVENTORY_SLOT_BAG_0))
+                    .await
+            }
+            None => {
+                let guid = item.get_guid();
+                handlers::send_inventory_change_failure(self, InventoryError::ItemCantBeEquipped, guid, Guid::zero()).await
+            }
+        }
+    }
+
+    //Moves an item to the first free slot of either the bank or the regular inventory
+    pub async fn try_auto_store_item(&mut self, data_storage: &DataStorage, item_position: (u8, u8), in_bank: bool) -> Result<()> {
+        match self.find_free_item_position(in_bank) {
+            Some(destination) => self.try_swap_items(data_storage, item_position, destination).await,
+            None => {
+                let error = if in_bank {
+                    InventoryError::BankFull
                 } else {
-                    todo!("Non-equipment inventory not implemented yet")
-                }
+                    InventoryError::InventoryFull
+                };
+                let guid = self.get_item(item_position).map_or(Guid::zero(), Item::get_guid);
+                handlers::send_inventory_change_failure(self, error, guid, Guid::zero()).await
             }
-            (_, _) => todo!("Bags not implemented yet"),
         }
     }
+
+    //Moves an item to the first free slot of one specific bag
+    pub async fn try_auto_store_bag_item(&mut self, data_storage: &DataStorage, item_position: (u8, u8), destination_bag: u8) -> Result<()> {
+        match self.find_free_slot_in_bag(destination_bag) {
+            Some(destination) => self.try_swap_items(data_storage, item_position, destination).await,
+            None => {
+                let guid = self.get_item(item_position).map_or(Guid::zero(), Item::get_guid);
+                handlers::send_inventory_change_failure(self, InventoryError::BagFull, guid, Guid::zero()).await
+            }
+        }
+    }
+
+    pub fn set_bank_bag_slots(&mut self, bank_bag_slots: u8) {
+        self.bank_bag_slots = bank_bag_slots;
+        let (a, b, _, d) = self.gameplay_data.player_bytes_2().unwrap_or_default();
+        self.gameplay_data.set_player_bytes_2(a, b, bank_bag_slots, d);
+    }
+
+    #[allow(dead_code)]
+    fn has_item_in_slot(&self, item_position: (u8, u8)) -> bool {
+        self.get_item(item_position).is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::character::Character;
+    use std::sync::Weak;
+
+    const BACKPACK_SLOT: (u8, u8) = (23, INVENTORY_SLOT_BAG_0);
+
+    fn template(id: u32, inventory_type: InventoryType) -> DBItemTemplate {
+        DBItemTemplate {
+            id,
+            inventory_type: inventory_type.as_int(),
+            container_slots: if inventory_type == InventoryType::Bag { 4 } else { 0 },
+            ..Default::default()
+        }
+    }
+
+    fn equipment_slot(slot: EquipmentSlot) -> (u8, u8) {
+        (slot as u8, INVENTORY_SLOT_BAG_0)
+    }
+
+    fn give_item(character: &mut Character, guid: u64, template: &DBItemTemplate, position: (u8, u8)) {
+        let item = Item::new(Guid::new(guid), character.get_guid(), template, 1);
+        character.set_item(Some(item), position).unwrap();
+    }
+
+    fn check(character: &Character, template: &DBItemTemplate, from: (u8, u8), to: (u8, u8)) -> Result<(), InventoryError> {
+        character.check_can_place_item(character.get_item(from).unwrap(), template, from, to)
+    }
+
+    #[test]
+    fn wrong_equipment_slot() {
+        let mut character = Character::new(Weak::new(), Guid::new(1));
+        let helmet = template(1, InventoryType::Head);
+        give_item(&mut character, 10, &helmet, BACKPACK_SLOT);
+
+        let to_chest = check(&character, &helmet, BACKPACK_SLOT, equipment_slot(EquipmentSlot::Chest));
+        assert_eq!(to_chest, Err(InventoryError::ItemDoesntGoToSlot));
+        assert_eq!(check(&character, &helmet, BACKPACK_SLOT, equipment_slot(EquipmentSlot::Head)), Ok(()));
+    }
+
+    #[test]
+    fn bag_inside_of_itself() {
+        let mut character = Character::new(Weak::new(), Guid::new(1));
+        let bag = template(1, InventoryType::Bag);
+        let bag_slot = equipment_slot(EquipmentSlot::Bag1);
+        give_item(&mut character, 10, &bag, bag_slot);
+
+        assert_eq!(
+            check(&character, &bag, bag_slot, (0, bag_slot.0)),
+            Err(InventoryError::ItemsCantBeSwapped)
+        );
+    }
+
+    #[test]
+    fn non_empty_bag_into_other_bag() {
+        let mut character = Character::new(Weak::new(), Guid::new(1));
+        let bag = template(1, InventoryType::Bag);
+        let (full_bag_slot, empty_bag_slot) = (equipment_slot(EquipmentSlot::Bag1), equipment_slot(EquipmentSlot::Bag2));
+        give_item(&mut character, 10, &bag, full_bag_slot);
+        give_item(&mut character, 11, &bag, empty_bag_slot);
+        give_item(&mut character, 12, &template(2, InventoryType::NonEquip), (0, full_bag_slot.0));
+
+        let into_bag = check(&character, &bag, full_bag_slot, (0, empty_bag_slot.0));
+        assert_eq!(into_bag, Err(InventoryError::NonEmptyBagOverOtherBag));
+        assert_eq!(
+            check(&character, &bag, full_bag_slot, BACKPACK_SLOT),
+            Err(InventoryError::CanOnlyDoWithEmptyBags)
+        );
+        assert_eq!(check(&character, &bag, empty_bag_slot, (1, full_bag_slot.0)), Ok(()));
+    }
+
+    #[test]
+    fn unique_equippable() {
+        let mut character = Character::new(Weak::new(), Guid::new(1));
+        let ring = DBItemTemplate {
+            flags: ITEM_FLAG_UNIQUE_EQUIPPABLE,
+            ..template(1, InventoryType::Finger)
+        };
+        give_item(&mut character, 10, &ring, equipment_slot(EquipmentSlot::Finger1));
+        give_item(&mut character, 11, &ring, BACKPACK_SLOT);
+
+        let second_ring = check(&character, &ring, BACKPACK_SLOT, equipment_slot(EquipmentSlot::Finger2));
+        assert_eq!(second_ring, Err(InventoryError::ItemUniqueEquippable));
+        //Swapping with the equipped copy is fine
+        assert_eq!(check(&character, &ring, BACKPACK_SLOT, equipment_slot(EquipmentSlot::Finger1)), Ok(()));
+    }
 }
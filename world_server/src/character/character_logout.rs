@@ -61,6 +61,10 @@ impl super::Character {
             return Ok(());
         }
 
+        self.save_to_database(&world)
+            .await
+            .unwrap_or_else(|e| warn!("Could not save character {} during logout: Error {}", self.name, e));
+
         world
             .get_instance_manager()
             .try_get_map_for_character(self)
@@ -0,0 +1,383 @@
+use crate::group::{self, LootAward, LootRoll};
+use crate::loot::{Loot, LootItem};
+use crate::prelude::*;
+use crate::world::guid_service::HighGuid;
+use crate::world::prelude::inventory::ITEM_FLAG_HAS_LOOT;
+use crate::world::prelude::GameObject;
+use crate::world::World;
+use std::collections::HashMap;
+use std::sync::Arc;
+use wow_world_messages::wrath::{GroupLootSetting, InventoryError, LootMethodError, LootSlotType};
+
+//A little more than the client allows, positions lag behind
+const LOOT_RANGE: f32 = 10.0;
+//Group members further away from the kill than this don't get a share
+const GROUP_LOOT_RANGE: f32 = 75.0;
+
+//Containers keep their loot with the character that opened them, until everything is taken
+struct ContainerLoot {
+    item_position: (u8, u8),
+    loot: Loot,
+}
+
+#[derive(Default)]
+pub(super) struct LootState {
+    //The corpse or container whose loot window is open
+    looting: Option<Guid>,
+    //Loot is rolled once per container, by the guid of the container item
+    containers: HashMap<Guid, ContainerLoot>,
+}
+
+impl super::Character {
+    pub async fn open_corpse_loot(&mut self, world: &World, guid: Guid) -> Result<()> {
+        if HighGuid::from_guid(guid) != Some(HighGuid::Unit) {
+            bail!("Character {} tried to loot {}, which is not a creature", self.name, guid);
+        }
+        let map = world
+            .get_instance_manager()
+            .try_get_map_for_character(self)
+            .await
+            .ok_or_else(|| anyhow!("Character {} is not on a map", self.name))?;
+        let Some(creature_lock) = map.try_get_creature(guid).await else {
+            return handlers::send_loot_error(self, guid, LootMethodError::DidntKill).await;
+        };
+        {
+            let creature = creature_lock.read().await;
+            if !creature.can_be_looted_by(self.get_guid()) {
+                return handlers::send_loot_error(self, guid, LootMethodError::DidntKill).await;
+            }
+            let in_range = creature
+                .get_position()
+                .is_some_and(|position| position.distance_to(&self.get_position().unwrap_or_default()) <= LOOT_RANGE);
+            if !in_range {
+                return handlers::send_loot_error(self, guid, LootMethodError::TooFar).await;
+            }
+        }
+
+        self.open_loot(world, guid).await
+    }
+
+    pub async fn open_container(&mut self, world: &World, item_position: (u8, u8)) -> Result<()> {
+        let Some(item) = self.get_item(item_position) else {
+            return handlers::send_inventory_change_failure(self, InventoryError::ItemNotFound, Guid::zero(), Guid::zero()).await;
+        };
+        let (guid, entry) = (item.get_guid(), item.get_entry());
+        let data_storage = world.get_data_storage();
+        let template = data_storage
+            .get_item_template(entry)
+            .ok_or_else(|| anyhow!("No item template for item {}", entry))?;
+        if template.flags & ITEM_FLAG_HAS_LOOT == 0 {
+            bail!("Item {} can't be opened", entry);
+        }
+
+        //Opening the same container again shows whatever was left in it
+        if let Some(container) = self.loot_state.containers.get_mut(&guid) {
+            container.item_position = item_position;
+        } else {
+            let (min_money, max_money) = template
+                .container_loot_info
+                .as_ref()
+                .map_or((0, 0), |info| (info.money_loot_min, info.money_loot_max));
+            let loot_template = data_storage.get_item_loot_template(entry).unwrap_or_default();
+            let loot = Loot::generate(loot_template, min_money, max_money);
+            self.loot_state.containers.insert(guid, ContainerLoot { item_position, loot });
+        }
+        self.open_loot(world, guid).await
+    }
+
+    async fn open_loot(&mut self, world: &World, guid: Guid) -> Result<()> {
+        if let Some(previous) = self.loot_state.looting.filter(|previous| *previous != guid) {
+            self.release_loot(world, previous).await?;
+        }
+        self.loot_state.looting = Some(guid);
+
+        let loot_info = self
+            .modify_loot(world, |loot| {
+                (loot.money, loot.master_looter, loot.looters.clone(), loot.get_remaining_items())
+            })
+            .await?;
+        let Some((money, master_looter, looters, items)) = loot_info else {
+            return handlers::send_loot_error(self, guid, LootMethodError::DidntKill).await;
+        };
+
+        let looter = self.get_guid();
+        let is_master_looter = master_looter == Some(looter);
+        let data_storage = world.get_data_storage();
+        let items: Vec<_> = items
+            .into_iter()
+            .filter_map(|(slot, item)| {
+                let needs_quest_item = item.quest_required && self.needs_quest_item(&data_storage, item.item);
+                get_loot_slot_type(&item, looter, is_master_looter, needs_quest_item).map(|slot_type| (slot, item, slot_type))
+            })
+            .collect();
+        handlers::send_loot_response(self, &data_storage, guid, money, &items).await?;
+        if is_master_looter && items.iter().any(|(_, item, _)| item.master_loot) {
+            handlers::send_loot_master_list(self, &looters).await?;
+        }
+        Ok(())
+    }
+
+    //Works on the loot of whatever is being looted right now, None if that's nothing or the loot is gone
+    async fn modify_loot<T>(&mut self, world: &World, modify: impl FnOnce(&mut Loot) -> T) -> Result<Option<T>> {
+        let Some(source) = self.loot_state.looting else {
+            return Ok(None);
+        };
+        self.modify_loot_of(world, source, modify).await
+    }
+
+    async fn modify_loot_of<T>(&mut self, world: &World, source: Guid, modify: impl FnOnce(&mut Loot) -> T) -> Result<Option<T>> {
+        if let Some(container) = self.loot_state.containers.get_mut(&source) {
+            return Ok(Some(modify(&mut container.loot)));
+        }
+
+        let map = world
+            .get_instance_manager()
+            .try_get_map_for_character(self)
+            .await
+            .ok_or_else(|| anyhow!("Character {} is not on a map", self.name))?;
+        let Some(creature_lock) = map.try_get_creature(source).await else {
+            return Ok(None);
+        };
+        let mut creature = creature_lock.write().await;
+        Ok(creature.get_loot_mut().map(modify))
+    }
+
+    pub async fn store_loot_item(&mut self, world: &World, slot: u8) -> Result<()> {
+        let Some(source) = self.loot_state.looting else {
+            return Ok(());
+        };
+        let Some(item) = self.modify_loot(world, |loot| loot.get_remaining_item(slot).cloned()).await?.flatten() else {
+            return Ok(());
+        };
+        if item.quest_required && !self.needs_quest_item(&world.get_data_storage(), item.item) {
+            bail!("Character {} tried to loot quest item {} without the quest", self.name, item.item);
+        }
+        if !item.can_be_taken_by(self.get_guid()) {
+            bail!("Character {} tried to loot item {}, which isn't theirs to take", self.name, item.item);
+        }
+
+        self.take_loot_item(world, source, slot, &item).await
+    }
+
+    async fn take_loot_item(&mut self, world: &World, source: Guid, slot: u8, item: &LootItem) -> Result<()> {
+        //The item stays in the loot when it doesn't fit
+        if !self
+            .try_create_item(&world.get_data_storage(), &world.get_guid_service(), item.item, item.count)
+            .await?
+        {
+            return Ok(());
+        }
+        self.modify_loot_of(world, source, |loot| loot.mark_looted(slot)).await?;
+        if self.loot_state.looting == Some(source) {
+            handlers::send_loot_removed(self, slot).await?;
+        }
+        Ok(())
+    }
+
+    //Everyone that may loot gets an equal share, whoever takes the money keeps what can't be split
+    pub async fn take_loot_money(&mut self, world: &World) -> Result<()> {
+        let Some((money, looters)) = self
+            .modify_loot(world, |loot| (std::mem::take(&mut loot.money), loot.looters.clone()))
+            .await?
+        else {
+            return Ok(());
+        };
+        if money == 0 {
+            return Ok(());
+        }
+
+        let others: Vec<Guid> = looters.into_iter().filter(|looter| *looter != self.get_guid()).collect();
+        let share = money / (others.len() as u32 + 1);
+        if share > 0 {
+            let group_manager = world.get_group_manager();
+            for other in &others {
+                group_manager.give_award(*other, LootAward::Money(share)).await;
+            }
+        }
+
+        let own_share = money - share * others.len() as u32;
+        self.add_money(own_share);
+        handlers::send_loot_money(self, own_share, others.is_empty()).await
+    }
+
+    pub async fn give_master_loot(&mut self, world: &World, source: Guid, slot: u8, target: Guid) -> Result<()> {
+        let looter = self.get_guid();
+        let given = self
+            .modify_loot_of(world, source, |loot| {
+                let can_give = loot.master_looter == Some(looter)
+                    && loot.can_be_opened_by(target)
+                    && loot.get_remaining_item(slot).is_some_and(|item| item.master_loot);
+                if can_give {
+                    loot.assign_item(slot, Some(target));
+                }
+                can_give
+            })
+            .await?
+            .unwrap_or(false);
+        if !given {
+            bail!("Character {} can't give slot {} of {} to {}", self.name, slot, source, target);
+        }
+
+        world.get_group_manager().give_award(target, LootAward::Item { source, slot }).await;
+        Ok(())
+    }
+
+    pub async fn release_loot(&mut self, world: &World, guid: Guid) -> Result<()> {
+        handlers::send_loot_release_response(self, guid).await?;
+        if self.loot_state.looting != Some(guid) {
+            return Ok(());
+        }
+        self.loot_state.looting = None;
+
+        //Emptied containers are used up
+        if let Some(container) = self.loot_state.containers.get(&guid) {
+            if container.loot.is_empty() {
+                let item_position = container.item_position;
+                self.loot_state.containers.remove(&guid);
+                if self.get_item(item_position).is_some_and(|item| item.get_guid() == guid) {
+                    self.try_destroy_item(item_position, 1).await?;
+                }
+            }
+            return Ok(());
+        }
+
+        let map = world.get_instance_manager().try_get_map_for_character(self).await;
+        if let Some(map) = map {
+            if let Some(creature_lock) = map.try_get_creature(guid).await {
+                creature_lock.write().await.on_loot_released();
+            }
+        }
+        Ok(())
+    }
+
+    //The killer loots alone, unless they're in a group. Then the group's loot method decides who gets what.
+    pub(super) async fn claim_loot(&mut self, world: &World, victim: Guid) -> Result<()> {
+        if HighGuid::from_guid(victim) != Some(HighGuid::Unit) {
+            return Ok(());
+        }
+        let map = world
+            .get_instance_manager()
+            .try_get_map_for_character(self)
+            .await
+            .ok_or_else(|| anyhow!("Character {} is not on a map", self.name))?;
+        let Some(creature_lock) = map.try_get_creature(victim).await else {
+            return Ok(());
+        };
+        let data_storage = world.get_data_storage();
+        let (mut loot, victim_position) = {
+            let creature = creature_lock.read().await;
+            (creature.generate_loot(&data_storage), creature.get_position().unwrap_or_default())
+        };
+
+        let group_manager = world.get_group_manager();
+        let Some(group) = group_manager.get_group_of(self.get_guid()).await else {
+            loot.looters = vec![self.get_guid()];
+            creature_lock.write().await.set_loot(loot);
+            return Ok(());
+        };
+
+        //Members that were around for the kill, and the class bit of each for need before greed
+        let mut eligible = vec![(self.get_guid(), self.get_class().as_int())];
+        for member in group.members.iter().filter(|member| member.guid != self.get_guid()) {
+            let Some(object_lock) = map.try_get_object(member.guid).await.and_then(|weak| weak.upgrade()) else {
+                continue;
+            };
+            let object = object_lock.read().await;
+            if let Some(character) = object.as_character() {
+                let in_range = character
+                    .get_position()
+                    .is_some_and(|position| position.distance_to(&victim_position) <= GROUP_LOOT_RANGE);
+                if in_range {
+                    eligible.push((character.get_guid(), character.get_class().as_int()));
+                }
+            }
+        }
+        let eligible_guids: Vec<Guid> = eligible.iter().map(|(guid, _)| *guid).collect();
+
+        let round_robin_looter = group_manager
+            .next_round_robin_looter(group.guid, &eligible_guids)
+            .await
+            .unwrap_or(self.get_guid());
+        let get_quality = |item: u32| data_storage.get_item_template(item).map_or(0, |template| template.quality);
+        let rolled_slots = group::distribute_loot(&mut loot, &group, &eligible_guids, round_robin_looter, get_quality);
+
+        let mut rolls = Vec::new();
+        for slot in rolled_slots {
+            let Some(item) = loot.items.get(slot as usize) else {
+                continue;
+            };
+            let allowed_classes = data_storage
+                .get_item_template(item.item)
+                .and_then(|template| template.allowed_classes_mask);
+            let need_allowed = eligible
+                .iter()
+                .filter(|(_, class)| {
+                    group.loot_method != GroupLootSetting::NeedBeforeGreed || allowed_classes.map_or(true, |mask| mask & (1 << (class - 1)) != 0)
+                })
+                .map(|(guid, _)| *guid)
+                .collect();
+            rolls.push(LootRoll::new(
+                Arc::downgrade(&map),
+                victim,
+                slot,
+                item.item,
+                item.count,
+                eligible_guids.clone(),
+                need_allowed,
+            ));
+        }
+        creature_lock.write().await.set_loot(loot);
+
+        for roll in rolls {
+            handlers::send_loot_start_roll(self, &map, &roll).await?;
+            group_manager.start_roll(roll).await;
+        }
+        Ok(())
+    }
+
+    //Hands over loot that was won in a roll, given by the master looter or split from the money
+    pub(super) async fn tick_loot_awards(&mut self, world: &World) -> Result<()> {
+        //Wait until the character is done changing maps
+        if world.get_instance_manager().try_get_map_for_character(self).await.is_none() {
+            return Ok(());
+        }
+        for award in world.get_group_manager().take_awards(self.get_guid()).await {
+            match award {
+                LootAward::Money(amount) => {
+                    self.add_money(amount);
+                    handlers::send_loot_money(self, amount, false).await?;
+                }
+                LootAward::Item { source, slot } => {
+                    let looter = self.get_guid();
+                    let item = self
+                        .modify_loot_of(world, source, |loot| {
+                            loot.get_remaining_item(slot).filter(|item| item.can_be_taken_by(looter)).cloned()
+                        })
+                        .await?
+                        .flatten();
+                    //Whatever doesn't fit in the bags stays on the corpse, reserved for its new owner
+                    if let Some(item) = item {
+                        self.take_loot_item(world, source, slot, &item).await?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+//How an item shows up in the loot window of the looter, None hides it
+fn get_loot_slot_type(item: &LootItem, looter: Guid, is_master_looter: bool, needs_quest_item: bool) -> Option<LootSlotType> {
+    if item.quest_required {
+        //Quest items only show up for characters that still need them for a quest
+        needs_quest_item.then_some(LootSlotType::AllowLoot)
+    } else if item.roll_pending {
+        Some(LootSlotType::RollOngoing)
+    } else if item.master_loot {
+        Some(if is_master_looter { LootSlotType::Master } else { LootSlotType::Locked })
+    } else if item.owner.is_some_and(|owner| owner != looter) {
+        None
+    } else {
+        Some(LootSlotType::AllowLoot)
+    }
+}
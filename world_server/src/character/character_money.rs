@@ -0,0 +1,19 @@
+//The client can't show more than this, in copper
+const MAX_MONEY: u32 = 0x7FFFFFFF;
+
+impl super::Character {
+    pub fn get_money(&self) -> u32 {
+        self.gameplay_data.player_field_coinage().unwrap_or(0)
+    }
+
+    //Anything over the cap is lost
+    pub fn add_money(&mut self, amount: u32) {
+        let money = self.get_money().saturating_add(amount).min(MAX_MONEY);
+        self.gameplay_data.set_player_field_coinage(money);
+    }
+
+    pub fn remove_money(&mut self, amount: u32) {
+        let money = self.get_money().saturating_sub(amount);
+        self.gameplay_data.set_player_field_coinage(money);
+    }
+}
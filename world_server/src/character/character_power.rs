@@ -0,0 +1,201 @@
+use super::character_stats::{STAT_INTELLECT, STAT_SPIRIT};
+use crate::data::{DataStorage, SpellInfo};
+use crate::prelude::*;
+use crate::world::prelude::Unit;
+use wow_dbc::Indexable;
+use wow_world_messages::wrath::Power;
+
+//Regeneration is applied in steps, so the client isn't sent a power update every tick
+const REGEN_INTERVAL: f32 = 2.0;
+//Spending mana stops the spirit based mana regeneration for this long
+const FIVE_SECOND_RULE: f32 = 5.0;
+
+//Per second, rage and runic power are stored ten times larger than the client shows them
+const ENERGY_REGEN: f32 = 10.0;
+const RAGE_DECAY: f32 = 10.0;
+const RUNIC_POWER_DECAY: f32 = 15.0;
+
+const MAX_RAGE: i32 = 1000;
+const MAX_ENERGY: i32 = 100;
+const MAX_RUNIC_POWER: i32 = 1000;
+
+//Spirit up to this much regenerates health at the base rate, anything above it at a higher rate
+const HEALTH_REGEN_BASE_SPIRIT: i32 = 50;
+//Low level characters get a fixed part of their health back instead, this much per level each interval
+const LOW_LEVEL_HEALTH_REGEN_BELOW: u32 = 15;
+const LOW_LEVEL_HEALTH_REGEN_FRACTION: f32 = 0.2;
+
+//Two runes of each type, in the order blood, unholy, frost
+const NUM_RUNES: usize = 6;
+const RUNE_COOLDOWN: f32 = 10.0;
+
+#[derive(Default)]
+pub(super) struct PowerState {
+    regen_timer: f32,
+    mana_regen_delay: f32,
+    //Regeneration comes in fractions, which are kept until they add up to whole points
+    health_remainder: f32,
+    power_remainder: f32,
+    rune_cooldowns: [f32; NUM_RUNES],
+}
+
+//Adds whole points to the value and keeps the rest for next time
+fn apply_regen(value: i32, max: i32, amount: f32, remainder: &mut f32) -> i32 {
+    let total = *remainder + amount;
+    let whole = total.trunc();
+    let new_value = (value + whole as i32).clamp(0, max);
+    //Nothing carries over once it's full or empty
+    *remainder = if new_value == 0 || new_value == max { 0.0 } else { total - whole };
+    new_value
+}
+
+impl super::Character {
+    //Rage and runic power start out empty, energy full, mana comes from the stats
+    pub(super) fn init_power(&mut self, power: Power) {
+        let max = match power {
+            Power::Rage => MAX_RAGE,
+            Power::Energy => MAX_ENERGY,
+            Power::RunicPower => MAX_RUNIC_POWER,
+            _ => return,
+        };
+        self.set_max_power(power, max);
+        if power == Power::Energy {
+            self.set_power(power, max);
+        }
+    }
+
+    pub(super) fn on_mana_spent(&mut self) {
+        self.power_state.mana_regen_delay = FIVE_SECOND_RULE;
+    }
+
+    pub(super) fn tick_power(&mut self, delta_time: f32, data_storage: &DataStorage) -> Result<()> {
+        let state = &mut self.power_state;
+        for cooldown in state.rune_cooldowns.iter_mut() {
+            *cooldown = (*cooldown - delta_time).max(0.0);
+        }
+        state.mana_regen_delay = (state.mana_regen_delay - delta_time).max(0.0);
+        state.regen_timer += delta_time;
+        if state.regen_timer < REGEN_INTERVAL {
+            return Ok(());
+        }
+        let elapsed = std::mem::take(&mut state.regen_timer);
+
+        if !self.is_alive() {
+            return Ok(());
+        }
+        self.regenerate_health(elapsed, data_storage)?;
+        self.regenerate_power(elapsed, data_storage)
+    }
+
+    fn regenerate_health(&mut self, elapsed: f32, data_storage: &DataStorage) -> Result<()> {
+        let (health, max_health) = (self.get_health(), self.get_max_health());
+        if self.is_in_combat() || health >= max_health {
+            self.power_state.health_remainder = 0.0;
+            return Ok(());
+        }
+
+        let level = self.get_level();
+        let per_interval = if level < LOW_LEVEL_HEALTH_REGEN_BELOW {
+            LOW_LEVEL_HEALTH_REGEN_FRACTION * max_health as f32 / level as f32
+        } else {
+            let spirit = self.get_derived_stats().stats[STAT_SPIRIT];
+            let base_spirit = spirit.min(HEALTH_REGEN_BASE_SPIRIT);
+            let index = self.get_class_level_index();
+            let base_ratio = data_storage.get_dbc_gt_oct_regen_hp()?.get(index).map_or(0.0, |row| row.data);
+            let spirit_ratio = data_storage.get_dbc_gt_regen_hp_per_spt()?.get(index).map_or(0.0, |row| row.data);
+            base_spirit as f32 * base_ratio + (spirit - base_spirit).max(0) as f32 * spirit_ratio
+        };
+
+        let amount = per_interval * elapsed / REGEN_INTERVAL;
+        let new_health = apply_regen(health as i32, max_health as i32, amount, &mut self.power_state.health_remainder);
+        if new_health != health as i32 {
+            self.set_health(new_health as u32);
+        }
+        Ok(())
+    }
+
+    fn regenerate_power(&mut self, elapsed: f32, data_storage: &DataStorage) -> Result<()> {
+        let power = self.get_power_type();
+        let per_second = match power {
+            Power::Mana if self.power_state.mana_regen_delay <= 0.0 => {
+                let stats = &self.get_derived_stats().stats;
+                let (intellect, spirit) = (stats[STAT_INTELLECT], stats[STAT_SPIRIT]);
+                let index = self.get_class_level_index();
+                let ratio = data_storage.get_dbc_gt_regen_mp_per_spt()?.get(index).map_or(0.0, |row| row.data);
+                (intellect as f32).sqrt() * spirit as f32 * ratio
+            }
+            Power::Energy => ENERGY_REGEN,
+            Power::Rage if !self.is_in_combat() => -RAGE_DECAY,
+            Power::RunicPower if !self.is_in_combat() => -RUNIC_POWER_DECAY,
+            _ => 0.0,
+        };
+        if per_second == 0.0 {
+            return Ok(());
+        }
+
+        let value = self.get_power(power);
+        let max = self.get_max_power(power);
+        let new_value = apply_regen(value, max, per_second * elapsed, &mut self.power_state.power_remainder);
+        if new_value != value {
+            self.set_power(power, new_value);
+        }
+        Ok(())
+    }
+
+    fn count_ready_runes(&self, first_rune: usize) -> u32 {
+        self.power_state.rune_cooldowns[first_rune..first_rune + 2]
+            .iter()
+            .filter(|cooldown| **cooldown <= 0.0)
+            .count() as u32
+    }
+
+    pub(super) fn has_runes_for(&self, spell: &SpellInfo) -> bool {
+        spell.rune_cost.as_ref().map_or(true, |cost| {
+            self.count_ready_runes(0) >= cost.blood && self.count_ready_runes(2) >= cost.unholy && self.count_ready_runes(4) >= cost.frost
+        })
+    }
+
+    //Puts the runes the spell costs on cooldown, they give runic power in return
+    pub(super) async fn spend_runes(&mut self, spell: &SpellInfo) -> Result<()> {
+        let Some(cost) = spell.rune_cost.as_ref() else {
+            return Ok(());
+        };
+        for (first_rune, amount) in [(0, cost.blood), (2, cost.unholy), (4, cost.frost)] {
+            let cooldowns = &mut self.power_state.rune_cooldowns[first_rune..first_rune + 2];
+            for cooldown in cooldowns.iter_mut().filter(|cooldown| **cooldown <= 0.0).take(amount as usize) {
+                *cooldown = RUNE_COOLDOWN;
+            }
+        }
+        if cost.runic_power > 0 {
+            let runic_power = self.get_power(Power::RunicPower);
+            self.set_power(Power::RunicPower, runic_power + cost.runic_power as i32);
+        }
+        handlers::send_resync_runes(self, &self.get_rune_progress()).await
+    }
+
+    //How far along every rune is, from 0 when just used to 255 when ready
+    fn get_rune_progress(&self) -> Vec<(u8, u8)> {
+        self.power_state
+            .rune_cooldowns
+            .iter()
+            .enumerate()
+            .map(|(rune, cooldown)| ((rune / 2) as u8, (255.0 * (1.0 - cooldown / RUNE_COOLDOWN)) as u8))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn regen_keeps_fractions() {
+        let mut remainder = 0.0;
+        assert_eq!(apply_regen(10, 100, 1.5, &mut remainder), 11);
+        assert_eq!(apply_regen(11, 100, 1.5, &mut remainder), 13);
+        //Decay stops at zero and forgets what was left over
+        assert_eq!(apply_regen(1, 100, -2.5, &mut remainder), 0);
+        assert_eq!(remainder, 0.0);
+        assert_eq!(apply_regen(99, 100, 5.0, &mut remainder), 100);
+    }
+}
@@ -0,0 +1,478 @@
+use super::character_experience::get_gray_level;
+use crate::data::{DataStorage, QuestTemplate, MAX_QUEST_KILL_OBJECTIVES};
+use crate::prelude::*;
+use crate::world::get_next_reset_time;
+use crate::world::guid_service::{get_entry, HighGuid};
+use crate::world::prelude::*;
+use std::collections::HashMap;
+use std::time::{SystemTime, UNIX_EPOCH};
+use wow_world_messages::wrath::{InventoryError, QuestFailedReason, QuestGiverStatus};
+use wrath_realm_db::character_quest::{DBCharacterQuestStatus, DBCharacterRewardedQuest};
+
+pub const MAX_QUEST_LOG_SIZE: usize = 25;
+
+//Bits of the state field of a quest log slot
+const QUEST_STATE_COMPLETE: u32 = 1;
+const QUEST_STATE_FAILED: u32 = 2;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QuestStatus {
+    Incomplete = 0,
+    Complete = 1,
+    Failed = 2,
+}
+
+impl QuestStatus {
+    fn from_database(status: u8) -> Self {
+        match status {
+            1 => QuestStatus::Complete,
+            2 => QuestStatus::Failed,
+            _ => QuestStatus::Incomplete,
+        }
+    }
+
+    fn get_state_bits(self) -> u32 {
+        match self {
+            QuestStatus::Incomplete => 0,
+            QuestStatus::Complete => QUEST_STATE_COMPLETE,
+            QuestStatus::Failed => QUEST_STATE_FAILED,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct QuestLogEntry {
+    quest_id: u32,
+    status: QuestStatus,
+    kill_counts: [u16; MAX_QUEST_KILL_OBJECTIVES],
+    explored: bool,
+}
+
+#[derive(Default)]
+pub(super) struct QuestState {
+    log: [Option<QuestLogEntry>; MAX_QUEST_LOG_SIZE],
+    //Turned in quests, with the unix time they were turned in at
+    rewarded: HashMap<u32, u32>,
+    //Items moved around, so the item objectives need to be checked again
+    items_dirty: bool,
+}
+
+//Characters far above the level of the quest get less experience for it
+pub fn get_quest_experience(level: u32, quest_level: u32, reward_xp: u32) -> u32 {
+    let tenths = match level.saturating_sub(quest_level) {
+        0..=5 => 10,
+        6 => 8,
+        7 => 6,
+        8 => 4,
+        9 => 2,
+        _ => 1,
+    };
+    reward_xp * tenths / 10
+}
+
+//Two kill counters share each 32 bit field of the quest log
+fn pack_kill_counts(low: u16, high: u16) -> u32 {
+    low as u32 | (high as u32) << 16
+}
+
+fn get_unix_time() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs()
+}
+
+impl super::Character {
+    pub(super) fn load_quests(&mut self, data_storage: &DataStorage, statuses: Vec<DBCharacterQuestStatus>, rewarded: Vec<DBCharacterRewardedQuest>) {
+        for status in statuses {
+            let slot = status.slot as usize;
+            if slot >= MAX_QUEST_LOG_SIZE || self.quest_state.log[slot].is_some() {
+                warn!("Quest {} of character {} is in invalid slot {}", status.quest, self.name, slot);
+                continue;
+            }
+            if data_storage.get_quest_template(status.quest).is_none() {
+                warn!("Character {} has quest {}, which doesn't exist", self.name, status.quest);
+                continue;
+            }
+            self.quest_state.log[slot] = Some(QuestLogEntry {
+                quest_id: status.quest,
+                status: QuestStatus::from_database(status.status),
+                kill_counts: [status.kill_count_1, status.kill_count_2, status.kill_count_3, status.kill_count_4],
+                explored: status.explored != 0,
+            });
+            self.update_quest_log_fields(slot);
+        }
+        self.quest_state.rewarded = rewarded.into_iter().map(|quest| (quest.quest, quest.reward_time)).collect();
+    }
+
+    pub(super) fn get_quests_for_database(&self, character_id: u32) -> (Vec<DBCharacterQuestStatus>, Vec<DBCharacterRewardedQuest>) {
+        let statuses = self
+            .quest_state
+            .log
+            .iter()
+            .enumerate()
+            .filter_map(|(slot, entry)| entry.as_ref().map(|entry| (slot, entry)))
+            .map(|(slot, entry)| DBCharacterQuestStatus {
+                character_id,
+                quest: entry.quest_id,
+                slot: slot as u8,
+                status: entry.status as u8,
+                explored: entry.explored as u8,
+                kill_count_1: entry.kill_counts[0],
+                kill_count_2: entry.kill_counts[1],
+                kill_count_3: entry.kill_counts[2],
+                kill_count_4: entry.kill_counts[3],
+            })
+            .collect();
+        let rewarded = self
+            .quest_state
+            .rewarded
+            .iter()
+            .map(|(quest, reward_time)| DBCharacterRewardedQuest {
+                character_id,
+                quest: *quest,
+                reward_time: *reward_time,
+            })
+            .collect();
+        (statuses, rewarded)
+    }
+
+    fn update_quest_log_fields(&mut self, slot: usize) {
+        let (quest_id, state, kill_counts) = match &self.quest_state.log[slot] {
+            Some(entry) => (entry.quest_id, entry.status.get_state_bits(), entry.kill_counts),
+            None => (0, 0, [0; MAX_QUEST_KILL_OBJECTIVES]),
+        };
+        let index = slot as u32;
+        self.gameplay_data.set_player_quest_log_1_1(quest_id, index);
+        self.gameplay_data.set_player_quest_log_1_2(state, index);
+        self.gameplay_data
+            .set_player_quest_log_1_3(pack_kill_counts(kill_counts[0], kill_counts[1]), index);
+        self.gameplay_data
+            .set_player_quest_log_1_4(pack_kill_counts(kill_counts[2], kill_counts[3]), index);
+        //There are no timed quests yet
+        self.gameplay_data.set_player_quest_log_1_5(0, index);
+    }
+
+    fn find_quest_slot(&self, quest_id: u32) -> Option<usize> {
+        self.quest_state
+            .log
+            .iter()
+            .position(|entry| entry.as_ref().is_some_and(|entry| entry.quest_id == quest_id))
+    }
+
+    //None if the quest is not in the quest log
+    pub fn get_quest_status(&self, quest_id: u32) -> Option<QuestStatus> {
+        self.find_quest_slot(quest_id)
+            .and_then(|slot| self.quest_state.log[slot].as_ref())
+            .map(|entry| entry.status)
+    }
+
+    //Daily quests can be done again once the daily reset passed
+    fn is_quest_rewarded(&self, template: &QuestTemplate, now: u64) -> bool {
+        self.quest_state
+            .rewarded
+            .get(&template.info.id)
+            .is_some_and(|&reward_time| !template.is_daily() || get_next_reset_time(reward_time as u64, false) > now)
+    }
+
+    //Why the character can't take this quest, None if they can
+    pub fn get_quest_failed_reason(&self, template: &QuestTemplate) -> Option<QuestFailedReason> {
+        let info = &template.info;
+        if self.find_quest_slot(info.id).is_some() {
+            Some(QuestFailedReason::QuestAlreadyOn)
+        } else if self.is_quest_rewarded(template, get_unix_time()) {
+            Some(if template.is_daily() {
+                QuestFailedReason::DailyQuestCompletedToday
+            } else {
+                QuestFailedReason::QuestAlreadyDone
+            })
+        } else if self.get_level() < info.min_level as u32 {
+            Some(QuestFailedReason::QuestFailedLowLevel)
+        } else if info.required_races != 0 && info.required_races & (1 << (self.get_race().as_int() - 1)) == 0 {
+            Some(QuestFailedReason::QuestFailedWrongRace)
+        } else if info.required_classes != 0 && info.required_classes & (1 << (self.get_class().as_int() - 1)) == 0 {
+            Some(QuestFailedReason::DontHaveReq)
+        } else if info.prev_quest_id != 0 && !self.quest_state.rewarded.contains_key(&info.prev_quest_id) {
+            Some(QuestFailedReason::DontHaveReq)
+        } else {
+            None
+        }
+    }
+
+    //The mark above the head of a quest giver, from the point of view of this character
+    pub fn get_quest_giver_status(&self, data_storage: &DataStorage, entry: u32) -> QuestGiverStatus {
+        let mut has_incomplete = false;
+        for quest_id in data_storage.get_creature_quest_enders(entry) {
+            match self.get_quest_status(*quest_id) {
+                Some(QuestStatus::Complete) => return QuestGiverStatus::Reward,
+                Some(_) => has_incomplete = true,
+                None => {}
+            }
+        }
+
+        let gray_level = get_gray_level(self.get_level());
+        let mut has_low_level = false;
+        for template in data_storage
+            .get_creature_quest_starters(entry)
+            .iter()
+            .filter_map(|quest_id| data_storage.get_quest_template(*quest_id))
+            .filter(|template| self.get_quest_failed_reason(template).is_none())
+        {
+            if template.get_level_for(self.get_level()) > gray_level {
+                return QuestGiverStatus::Available;
+            }
+            has_low_level = true;
+        }
+
+        if has_incomplete {
+            QuestGiverStatus::Incomplete
+        } else if has_low_level {
+            QuestGiverStatus::LowLevelAvailable
+        } else {
+            QuestGiverStatus::None
+        }
+    }
+
+    //Quests this creature has for the character, and whether they are in the quest log already
+    pub fn get_quest_giver_menu(&self, data_storage: &DataStorage, entry: u32) -> Vec<(u32, bool)> {
+        let to_turn_in = data_storage
+            .get_creature_quest_enders(entry)
+            .iter()
+            .filter(|quest_id| self.find_quest_slot(**quest_id).is_some())
+            .map(|quest_id| (*quest_id, true));
+        let to_take = data_storage
+            .get_creature_quest_starters(entry)
+            .iter()
+            .filter(|quest_id| {
+                data_storage
+                    .get_quest_template(**quest_id)
+                    .is_some_and(|template| self.get_quest_failed_reason(template).is_none())
+            })
+            .map(|quest_id| (*quest_id, false));
+        to_turn_in.chain(to_take).collect()
+    }
+
+    pub async fn accept_quest(&mut self, data_storage: &DataStorage, quest_id: u32) -> Result<()> {
+        let template = data_storage
+            .get_quest_template(quest_id)
+            .ok_or_else(|| anyhow!("Quest {} doesn't exist", quest_id))?;
+        if let Some(reason) = self.get_quest_failed_reason(template) {
+            return handlers::send_quest_invalid(self, reason).await;
+        }
+        let Some(slot) = self.quest_state.log.iter().position(Option::is_none) else {
+            return handlers::send_questlog_full(self).await;
+        };
+
+        self.quest_state.log[slot] = Some(QuestLogEntry {
+            quest_id,
+            status: QuestStatus::Incomplete,
+            kill_counts: [0; MAX_QUEST_KILL_OBJECTIVES],
+            explored: false,
+        });
+        self.update_quest_log_fields(slot);
+        //Items that were picked up before taking the quest count too
+        self.update_quest_completion(data_storage, slot).await
+    }
+
+    pub fn abandon_quest(&mut self, slot: u8) -> Result<()> {
+        let entry = self
+            .quest_state
+            .log
+            .get_mut(slot as usize)
+            .and_then(Option::take)
+            .ok_or_else(|| anyhow!("Character {} has no quest in slot {} to abandon", self.name, slot))?;
+        trace!("Character {} abandoned quest {}", self.name, entry.quest_id);
+        self.update_quest_log_fields(slot as usize);
+        Ok(())
+    }
+
+    fn are_quest_objectives_done(&self, template: &QuestTemplate, entry: &QuestLogEntry) -> bool {
+        let kills_done = template
+            .kill_objectives
+            .iter()
+            .zip(entry.kill_counts)
+            .all(|(objective, kill_count)| kill_count >= objective.count);
+        let items_done = template
+            .item_objectives
+            .iter()
+            .all(|objective| self.get_item_count(objective.target) >= objective.count as u32);
+        kills_done && items_done && (!template.requires_exploration || entry.explored)
+    }
+
+    //Losing quest items makes a complete quest incomplete again
+    async fn update_quest_completion(&mut self, data_storage: &DataStorage, slot: usize) -> Result<()> {
+        let Some(entry) = self.quest_state.log[slot].as_ref().filter(|entry| entry.status != QuestStatus::Failed) else {
+            return Ok(());
+        };
+        let Some(template) = data_storage.get_quest_template(entry.quest_id) else {
+            return Ok(());
+        };
+        let done = self.are_quest_objectives_done(template, entry);
+        let status = if done { QuestStatus::Complete } else { QuestStatus::Incomplete };
+        if entry.status == status {
+            return Ok(());
+        }
+
+        let quest_id = entry.quest_id;
+        if let Some(entry) = self.quest_state.log[slot].as_mut() {
+            entry.status = status;
+        }
+        self.update_quest_log_fields(slot);
+        if done {
+            handlers::send_quest_update_complete(self, quest_id).await?;
+        }
+        Ok(())
+    }
+
+    pub(super) async fn credit_quest_kill(&mut self, data_storage: &DataStorage, victim: Guid) -> Result<()> {
+        if HighGuid::from_guid(victim) != Some(HighGuid::Unit) {
+            return Ok(());
+        }
+        let victim_entry = get_entry(victim);
+
+        for slot in 0..MAX_QUEST_LOG_SIZE {
+            let Some(entry) = self.quest_state.log[slot]
+                .as_mut()
+                .filter(|entry| entry.status == QuestStatus::Incomplete)
+            else {
+                continue;
+            };
+            let Some(template) = data_storage.get_quest_template(entry.quest_id) else {
+                continue;
+            };
+            let objective_index = (0..template.kill_objectives.len()).find(|&index| {
+                let objective = &template.kill_objectives[index];
+                objective.target == victim_entry && entry.kill_counts[index] < objective.count
+            });
+            let Some(objective_index) = objective_index else {
+                continue;
+            };
+
+            entry.kill_counts[objective_index] += 1;
+            let (quest_id, kill_count) = (entry.quest_id, entry.kill_counts[objective_index]);
+            let required_count = template.kill_objectives[objective_index].count;
+            self.update_quest_log_fields(slot);
+            handlers::send_quest_update_add_kill(self, quest_id, victim_entry, kill_count, required_count, victim).await?;
+            self.update_quest_completion(data_storage, slot).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn explore_quest_area(&mut self, data_storage: &DataStorage, quest_id: u32) -> Result<()> {
+        let Some(slot) = self.find_quest_slot(quest_id) else {
+            return Ok(());
+        };
+        if let Some(entry) = self.quest_state.log[slot].as_mut() {
+            if entry.explored {
+                return Ok(());
+            }
+            entry.explored = true;
+        }
+        self.update_quest_completion(data_storage, slot).await
+    }
+
+    //Quest items only drop for characters that still need them
+    pub fn needs_quest_item(&self, data_storage: &DataStorage, item: u32) -> bool {
+        self.quest_state
+            .log
+            .iter()
+            .flatten()
+            .filter(|entry| entry.status == QuestStatus::Incomplete)
+            .filter_map(|entry| data_storage.get_quest_template(entry.quest_id))
+            .flat_map(|template| template.item_objectives.iter())
+            .any(|objective| objective.target == item && self.get_item_count(item) < objective.count as u32)
+    }
+
+    pub(super) fn mark_quest_items_dirty(&mut self) {
+        self.quest_state.items_dirty = true;
+    }
+
+    pub(super) async fn tick_quests(&mut self, data_storage: &DataStorage) -> Result<()> {
+        if !std::mem::take(&mut self.quest_state.items_dirty) {
+            return Ok(());
+        }
+        for slot in 0..MAX_QUEST_LOG_SIZE {
+            self.update_quest_completion(data_storage, slot).await?;
+        }
+        Ok(())
+    }
+
+    //Takes the quest items, hands out the rewards and moves the quest from the log to the finished quests
+    pub async fn reward_quest(&mut self, world: &World, quest_id: u32, reward_choice: u32) -> Result<()> {
+        let data_storage = world.get_data_storage();
+        let template = data_storage
+            .get_quest_template(quest_id)
+            .ok_or_else(|| anyhow!("Quest {} doesn't exist", quest_id))?;
+        let slot = self
+            .find_quest_slot(quest_id)
+            .ok_or_else(|| anyhow!("Character {} doesn't have quest {}", self.name, quest_id))?;
+        if self.get_quest_status(quest_id) != Some(QuestStatus::Complete) {
+            bail!("Character {} tried to turn in quest {} before completing it", self.name, quest_id);
+        }
+
+        let choice_item = if template.reward_choice_items.is_empty() {
+            None
+        } else {
+            Some(
+                template
+                    .reward_choice_items
+                    .get(reward_choice as usize)
+                    .ok_or_else(|| anyhow!("Quest {} has no reward choice {}", quest_id, reward_choice))?,
+            )
+        };
+        let reward_items: Vec<(u32, u32)> = template
+            .reward_items
+            .iter()
+            .chain(choice_item)
+            .map(|reward| (reward.item, reward.count as u32))
+            .collect();
+        //The quest stays in the log until every reward fits
+        if !self.has_room_for_new_items(&data_storage, &reward_items)? {
+            return handlers::send_inventory_change_failure(self, InventoryError::InventoryFull, Guid::zero(), Guid::zero()).await;
+        }
+
+        for objective in &template.item_objectives {
+            self.destroy_items_by_entry(objective.target, objective.count as u32).await?;
+        }
+        let guid_service = world.get_guid_service();
+        for (item, count) in &reward_items {
+            self.try_create_item(&data_storage, &guid_service, *item, *count).await?;
+        }
+        self.add_money(template.info.reward_money);
+
+        self.quest_state.log[slot] = None;
+        self.update_quest_log_fields(slot);
+        self.quest_state.rewarded.insert(quest_id, get_unix_time() as u32);
+
+        let experience = if self.get_xp_for_next_level(&data_storage).is_some() {
+            get_quest_experience(self.get_level(), template.get_level_for(self.get_level()), template.info.reward_xp)
+        } else {
+            0
+        };
+        handlers::send_quest_giver_quest_complete(self, quest_id, experience, template.info.reward_money, &reward_items).await?;
+        if experience > 0 {
+            self.give_experience(&data_storage, experience).await?;
+        }
+        for reward in &template.reward_factions {
+            self.modify_reputation(&data_storage, reward.faction, reward.value).await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quest_experience() {
+        assert_eq!(get_quest_experience(5, 5, 170), 170);
+        assert_eq!(get_quest_experience(2, 5, 170), 170);
+        assert_eq!(get_quest_experience(11, 5, 170), 136);
+        assert_eq!(get_quest_experience(14, 5, 170), 34);
+        assert_eq!(get_quest_experience(60, 5, 170), 17);
+    }
+
+    #[test]
+    fn kill_counts_share_a_field() {
+        assert_eq!(pack_kill_counts(3, 0), 3);
+        assert_eq!(pack_kill_counts(3, 2), 0x0002_0003);
+    }
+}
@@ -0,0 +1,65 @@
+use crate::data::DataStorage;
+use crate::prelude::*;
+use wow_dbc::Indexable;
+use wrath_realm_db::character_quest::DBCharacterReputation;
+
+//Exalted is capped at 999 into the rank, hated goes down to -42000
+const MIN_REPUTATION: i32 = -42000;
+const MAX_REPUTATION: i32 = 42999;
+
+#[derive(Clone, Copy, Debug)]
+pub struct Reputation {
+    //Where the faction shows up in the reputation list of the client (See Faction.dbc)
+    pub reputation_index: u32,
+    //Gained on top of the base reputation from Faction.dbc
+    pub standing: i32,
+}
+
+fn get_reputation_index(data_storage: &DataStorage, faction: u32) -> Option<u32> {
+    let faction_info = data_storage.get_dbc_faction().ok()?.get(faction)?;
+    (faction_info.reputation_index >= 0).then_some(faction_info.reputation_index as u32)
+}
+
+impl super::Character {
+    pub(super) fn load_reputations(&mut self, data_storage: &DataStorage, reputations: Vec<DBCharacterReputation>) {
+        for reputation in reputations {
+            let Some(reputation_index) = get_reputation_index(data_storage, reputation.faction) else {
+                warn!(
+                    "Character {} has reputation with faction {}, which has none",
+                    self.name, reputation.faction
+                );
+                continue;
+            };
+            self.reputations.insert(
+                reputation.faction,
+                Reputation {
+                    reputation_index,
+                    standing: reputation.standing,
+                },
+            );
+        }
+    }
+
+    pub(super) fn get_reputations_for_database(&self, character_id: u32) -> Vec<DBCharacterReputation> {
+        self.reputations
+            .iter()
+            .map(|(faction, reputation)| DBCharacterReputation {
+                character_id,
+                faction: *faction,
+                standing: reputation.standing,
+            })
+            .collect()
+    }
+
+    pub async fn modify_reputation(&mut self, data_storage: &DataStorage, faction: u32, amount: i32) -> Result<()> {
+        let reputation_index = get_reputation_index(data_storage, faction).ok_or_else(|| anyhow!("Faction {} has no reputation to gain", faction))?;
+        let reputation = self.reputations.entry(faction).or_insert(Reputation {
+            reputation_index,
+            standing: 0,
+        });
+        reputation.standing = (reputation.standing + amount).clamp(MIN_REPUTATION, MAX_REPUTATION);
+
+        let standing = reputation.standing;
+        handlers::send_faction_standing(self, faction, reputation_index, standing).await
+    }
+}
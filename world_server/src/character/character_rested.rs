@@ -1,4 +1,13 @@
+use crate::data::DataStorage;
 use crate::prelude::*;
+use crate::world::prelude::player_flags::PLAYER_FLAGS_RESTING;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+//Resting in an inn or city fills up 5% of a level per 8 hours, elsewhere it takes four times as long
+const REST_SECONDS_PER_LEVEL: f32 = 8.0 * 3600.0 / 0.05;
+const REST_OUTSIDE_FRACTION: f32 = 0.25;
+//Rested experience doesn't pile up beyond one and a half levels
+const MAX_REST_LEVELS: f32 = 1.5;
 
 #[derive(PartialEq, Debug)]
 pub(super) enum RestedState {
@@ -17,7 +26,8 @@ impl super::Character {
     pub fn handle_enter_inn(&mut self) -> Result<()> {
         if self.rested_state == RestedState::NotRested {
             self.rested_state = RestedState::Rested(RestedLocation::Inn);
-            self.set_rested_bytes(true)?;
+            let player_flags = self.gameplay_data.player_flags().unwrap_or(0);
+            self.gameplay_data.set_player_flags(player_flags | PLAYER_FLAGS_RESTING);
         }
         Ok(())
     }
@@ -36,4 +46,49 @@ impl super::Character {
             },
         }
     }
+
+    //Whatever built up while the character was logged out gets added on login
+    pub(super) fn load_rest_bonus(&mut self, data_storage: &DataStorage, rest_bonus: f32, logout_time: u32, logout_resting: bool) {
+        self.set_rest_bonus(rest_bonus);
+        if logout_time == 0 {
+            return;
+        }
+        let unix_time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() as u32;
+        let offline_seconds = unix_time.saturating_sub(logout_time) as f32;
+        self.add_rest_bonus(data_storage, offline_seconds, logout_resting);
+    }
+
+    pub(super) fn tick_rested(&mut self, delta_time: f32, data_storage: &DataStorage) {
+        if self.is_in_rested_area() {
+            self.add_rest_bonus(data_storage, delta_time, true);
+        }
+    }
+
+    fn add_rest_bonus(&mut self, data_storage: &DataStorage, seconds: f32, resting: bool) {
+        let Some(next_level_xp) = self.get_xp_for_next_level(data_storage) else {
+            return;
+        };
+        let rate = if resting { 1.0 } else { REST_OUTSIDE_FRACTION };
+        let gained = seconds * rate * next_level_xp as f32 / REST_SECONDS_PER_LEVEL;
+        let max_rest_bonus = next_level_xp as f32 * MAX_REST_LEVELS;
+        self.set_rest_bonus((self.rest_bonus + gained).min(max_rest_bonus));
+    }
+
+    //Rested experience doubles the experience of kills, for as long as it lasts
+    pub(super) fn consume_rest_bonus(&mut self, experience: u32) -> u32 {
+        let bonus = experience.min(self.rest_bonus as u32);
+        self.set_rest_bonus(self.rest_bonus - bonus as f32);
+        bonus
+    }
+
+    //Resting adds fractions every tick, the client only hears about whole points
+    fn set_rest_bonus(&mut self, rest_bonus: f32) {
+        let rest_bonus = rest_bonus.max(0.0);
+        let points = rest_bonus as i32;
+        self.rest_bonus = rest_bonus;
+        if self.gameplay_data.player_rest_state_experience() != Some(points) {
+            self.gameplay_data.set_player_rest_state_experience(points);
+            self.set_rested_bytes(points > 0);
+        }
+    }
 }
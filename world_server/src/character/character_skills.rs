@@ -0,0 +1,112 @@
+use crate::data::{get_max_skill_value_for_level, DataStorage, SkillRange};
+use crate::prelude::*;
+use crate::world::prelude::Unit;
+use wow_world_base::wrath::RaceClass;
+use wow_world_messages::wrath::{Skill, SkillInfo, SkillInfoIndex};
+use wrath_realm_db::character_spell::DBCharacterSkill;
+
+//The client has room for this many skills
+const MAX_SKILLS: usize = 128;
+
+pub struct CharacterSkill {
+    skill: Skill,
+    value: u16,
+    max_value: u16,
+}
+
+impl super::Character {
+    //0 for skills the character doesn't have
+    pub fn get_skill_value(&self, skill_id: u16) -> u16 {
+        self.skills
+            .iter()
+            .find(|skill| skill.skill.as_int() as u16 == skill_id)
+            .map_or(0, |skill| skill.value)
+    }
+
+    //Skills keep the slot in the skill fields they were first given
+    fn set_skill(&mut self, skill: Skill, value: u16, max_value: u16) -> Result<()> {
+        let index = match self.skills.iter().position(|character_skill| character_skill.skill == skill) {
+            Some(index) => index,
+            None if self.skills.len() < MAX_SKILLS => {
+                self.skills.push(CharacterSkill { skill, value, max_value });
+                self.skills.len() - 1
+            }
+            None => bail!("Character {} has no room for skill {:?}", self.name, skill),
+        };
+
+        let value = value.min(max_value);
+        self.skills[index].value = value;
+        self.skills[index].max_value = max_value;
+        self.gameplay_data
+            .set_player_skill_info(SkillInfo::new(skill, 0, value, max_value, 0, 0), SkillInfoIndex::try_from(index as u32)?);
+        Ok(())
+    }
+
+    fn has_skill(&self, skill: Skill) -> bool {
+        self.skills.iter().any(|character_skill| character_skill.skill == skill)
+    }
+
+    fn learn_skill(&mut self, data_storage: &DataStorage, skill: Skill) -> Result<()> {
+        let (value, max_value) = data_storage.get_skill_range(skill.as_int() as u16).get_starting_values(self.get_level());
+        self.set_skill(skill, value, max_value)
+    }
+
+    //Characters that never had their skills saved start out with the ones of their race and class
+    pub(super) fn load_skills(&mut self, data_storage: &DataStorage, race_class: RaceClass, db_skills: Vec<DBCharacterSkill>) -> Result<()> {
+        if db_skills.is_empty() {
+            for skill in race_class.starter_skills() {
+                self.learn_skill(data_storage, *skill)?;
+            }
+            return Ok(());
+        }
+
+        for db_skill in db_skills {
+            let Ok(skill) = Skill::try_from(db_skill.skill) else {
+                warn!("Character {} has unknown skill {}", self.name, db_skill.skill);
+                continue;
+            };
+            self.set_skill(skill, db_skill.value, db_skill.max_value)?;
+        }
+        Ok(())
+    }
+
+    //Spells that belong to a skill the character doesn't have yet teach that skill
+    pub(super) fn learn_spell_skills(&mut self, data_storage: &DataStorage, spell_id: u32) -> Result<()> {
+        for skill_id in data_storage.get_spell_skill_lines(spell_id) {
+            let Ok(skill) = Skill::try_from(*skill_id) else {
+                continue;
+            };
+            if !self.has_skill(skill) {
+                self.learn_skill(data_storage, skill)?;
+            }
+        }
+        Ok(())
+    }
+
+    //Skills that grow with the character's level get room for another few points
+    pub(super) fn update_skill_max_values(&mut self, data_storage: &DataStorage) -> Result<()> {
+        let max_value = get_max_skill_value_for_level(self.get_level());
+        let level_skills: Vec<(Skill, u16)> = self
+            .skills
+            .iter()
+            .filter(|skill| data_storage.get_skill_range(skill.skill.as_int() as u16) == SkillRange::Level)
+            .map(|skill| (skill.skill, skill.value))
+            .collect();
+        for (skill, value) in level_skills {
+            self.set_skill(skill, value, max_value)?;
+        }
+        Ok(())
+    }
+
+    pub(super) fn get_skills_for_database(&self, character_id: u32) -> Vec<DBCharacterSkill> {
+        self.skills
+            .iter()
+            .map(|skill| DBCharacterSkill {
+                character_id,
+                skill: skill.skill.as_int() as u16,
+                value: skill.value,
+                max_value: skill.max_value,
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,394 @@
+use std::collections::HashMap;
+
+use crate::data::{DataStorage, PositionAndOrientation, SpellEffectInfo, SpellInfo};
+use crate::handlers::movement_handler::TeleportationDistance;
+use crate::prelude::*;
+use crate::spell::{self, Aura, AuraEffect, SpellEffectContext, SpellEffectOutcome};
+use crate::world::prelude::spell::{SPELL_INTERRUPT_FLAG_MOVEMENT, SPELL_TARGET_UNIT_CASTER};
+use crate::world::prelude::{GameObject, Unit};
+use crate::world::World;
+use wow_world_base::wrath::RaceClass;
+use wow_world_messages::wrath::{Power, SpellCastResult, SpellCastTargets};
+use wrath_realm_db::character_spell::DBCharacterSpell;
+
+//Moving further than this while casting counts as moving
+const CAST_MOVEMENT_TOLERANCE: f32 = 0.1;
+
+//A spell that is being cast right now
+pub struct SpellCast {
+    pub spell: SpellInfo,
+    pub cast_count: u8,
+    pub targets: SpellCastTargets,
+    remaining_cast_time: f32,
+    start_position: PositionAndOrientation,
+}
+
+#[derive(Default)]
+pub(super) struct SpellCastState {
+    current_cast: Option<SpellCast>,
+    global_cooldown: f32,
+    //Remaining cooldowns in seconds, by spell id and by spell category
+    cooldowns: HashMap<u32, f32>,
+    category_cooldowns: HashMap<u32, f32>,
+}
+
+pub fn get_unit_target(targets: &SpellCastTargets) -> Option<Guid> {
+    targets.target_flags.get_unit().map(|unit| unit.unit_target)
+}
+
+impl super::Character {
+    pub fn knows_spell(&self, spell_id: u32) -> bool {
+        self.known_spells.contains(&spell_id) || self.knows_talent_spell(spell_id)
+    }
+
+    pub fn is_casting(&self) -> bool {
+        self.spell_cast_state.current_cast.is_some()
+    }
+
+    fn get_spell_power_cost(&self, spell: &SpellInfo) -> Option<(Power, i32)> {
+        let power = Power::try_from(spell.power_type as u8).ok()?;
+        let level = self.gameplay_data.unit_level().unwrap_or(1) as u32;
+        let cost = spell.get_power_cost(level, self.get_max_power(power) as u32);
+        Some((power, cost as i32))
+    }
+
+    async fn check_can_cast_spell(&self, world: &World, spell: &SpellInfo, targets: &SpellCastTargets) -> Result<(), SpellCastResult> {
+        if !self.is_alive() {
+            return Err(SpellCastResult::CasterDead);
+        }
+        if !self.knows_spell(spell.id) {
+            return Err(SpellCastResult::NotKnown);
+        }
+        if self.is_casting() {
+            return Err(SpellCastResult::SpellInProgress);
+        }
+
+        let state = &self.spell_cast_state;
+        if state.global_cooldown > 0.0 && spell.start_recovery_time > 0.0 {
+            return Err(SpellCastResult::NotReady);
+        }
+        if state.cooldowns.contains_key(&spell.id) || (spell.category != 0 && state.category_cooldowns.contains_key(&spell.category)) {
+            return Err(SpellCastResult::NotReady);
+        }
+
+        if let Some((power, cost)) = self.get_spell_power_cost(spell) {
+            if self.get_power(power) < cost {
+                return Err(SpellCastResult::NoPower);
+            }
+        }
+        if !self.has_runes_for(spell) {
+            return Err(SpellCastResult::NoPower);
+        }
+
+        if let Some(target_guid) = get_unit_target(targets).filter(|guid| *guid != self.get_guid()) {
+            let target_position = self.find_object_position(world, target_guid).await.ok_or(SpellCastResult::BadTargets)?;
+            let own_position = self.get_position().ok_or(SpellCastResult::BadTargets)?;
+            if spell.max_range > 0.0 && own_position.distance_to(&target_position) > spell.max_range {
+                return Err(SpellCastResult::OutOfRange);
+            }
+            if spell.is_harmful() {
+                let target_faction_template = self
+                    .find_unit_faction_template(world, target_guid)
+                    .await
+                    .ok_or(SpellCastResult::BadTargets)?;
+                if !world.get_data_storage().is_hostile(self.get_faction_template(), target_faction_template) {
+                    return Err(SpellCastResult::TargetFriendly);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    async fn find_object_position(&self, world: &World, guid: Guid) -> Option<PositionAndOrientation> {
+        let map = world.get_instance_manager().try_get_map_for_character(self).await?;
+        let object = map.try_get_object(guid).await?.upgrade()?;
+        let object = object.read().await;
+        object.get_position()
+    }
+
+    async fn find_unit_faction_template(&self, world: &World, guid: Guid) -> Option<u32> {
+        let map = world.get_instance_manager().try_get_map_for_character(self).await?;
+        let object = map.try_get_object(guid).await?.upgrade()?;
+        let object = object.read().await;
+        object.as_unit().map(|unit| unit.get_faction_template())
+    }
+
+    //Validates the cast and starts casting, the client is told if it's not allowed
+    pub async fn try_cast_spell(
+        &mut self,
+        world: &World,
+        data_storage: &DataStorage,
+        spell_id: u32,
+        cast_count: u8,
+        targets: SpellCastTargets,
+    ) -> Result<()> {
+        let Some(spell) = data_storage.get_spell_info(spell_id) else {
+            return handlers::send_cast_failed(self, cast_count, spell_id, SpellCastResult::NotKnown).await;
+        };
+
+        if let Err(result) = self.check_can_cast_spell(world, spell, &targets).await {
+            return handlers::send_cast_failed(self, cast_count, spell_id, result).await;
+        }
+
+        //Spells off the global cooldown don't touch it, and a running one is never shortened
+        if spell.start_recovery_time > 0.0 {
+            let state = &mut self.spell_cast_state;
+            state.global_cooldown = state.global_cooldown.max(spell.start_recovery_time);
+        }
+        let cast = SpellCast {
+            spell: spell.clone(),
+            cast_count,
+            targets,
+            remaining_cast_time: spell.cast_time,
+            start_position: self.get_position().unwrap_or_default(),
+        };
+        handlers::send_spell_start(self, world, &cast).await?;
+        self.spell_cast_state.current_cast = Some(cast);
+
+        if spell.cast_time <= 0.0 {
+            self.finish_spell_cast(world).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn interrupt_spell_cast(&mut self, world: &World, result: SpellCastResult) -> Result<()> {
+        if let Some(cast) = self.spell_cast_state.current_cast.take() {
+            trace!(
+                "Character {} had their cast of spell {} interrupted: {:?}",
+                self.name,
+                cast.spell.id,
+                result
+            );
+            handlers::send_spell_failure(self, world, &cast, result).await?;
+        }
+        Ok(())
+    }
+
+    //Only cancels when the client is still casting the spell it wants to cancel
+    pub async fn cancel_spell_cast(&mut self, world: &World, spell_id: u32) -> Result<()> {
+        let is_casting_spell = self.spell_cast_state.current_cast.as_ref().is_some_and(|cast| cast.spell.id == spell_id);
+        if is_casting_spell {
+            self.interrupt_spell_cast(world, SpellCastResult::Interrupted).await?;
+        }
+        Ok(())
+    }
+
+    async fn finish_spell_cast(&mut self, world: &World) -> Result<()> {
+        let Some(cast) = self.spell_cast_state.current_cast.take() else {
+            return Ok(());
+        };
+        let spell = &cast.spell;
+
+        //Things may have changed while casting, like power being drained
+        if let Some((power, cost)) = self.get_spell_power_cost(spell) {
+            let current_power = self.get_power(power);
+            if current_power < cost {
+                return handlers::send_spell_failure(self, world, &cast, SpellCastResult::NoPower).await;
+            }
+            self.set_power(power, current_power - cost);
+            if power == Power::Mana && cost > 0 {
+                self.on_mana_spent();
+            }
+        }
+        if !self.has_runes_for(spell) {
+            return handlers::send_spell_failure(self, world, &cast, SpellCastResult::NoPower).await;
+        }
+        self.spend_runes(spell).await?;
+
+        if spell.recovery_time > 0.0 {
+            self.spell_cast_state.cooldowns.insert(spell.id, spell.recovery_time);
+        }
+        if spell.category != 0 && spell.category_recovery_time > 0.0 {
+            self.spell_cast_state
+                .category_cooldowns
+                .insert(spell.category, spell.category_recovery_time);
+        }
+
+        let hits = self.apply_spell_effects(world, &cast).await;
+        handlers::send_spell_go(self, world, &cast, hits).await
+    }
+
+    //Effects that fail are skipped, the rest of the spell still goes through. Returns the units that were hit.
+    async fn apply_spell_effects(&mut self, world: &World, cast: &SpellCast) -> Vec<Guid> {
+        let mut hits = vec![];
+        let caster_level = self.gameplay_data.unit_level().unwrap_or(1) as u32;
+        //All aura effects of the cast end up in a single aura per target
+        let mut aura_effects: HashMap<Guid, Vec<AuraEffect>> = HashMap::new();
+        for effect in cast.spell.effects.iter() {
+            let Some(handler) = spell::get_effect_handler(effect.effect) else {
+                trace!("Spell {} has effect {}, which is not implemented", cast.spell.id, effect.effect);
+                continue;
+            };
+
+            let context = SpellEffectContext {
+                spell: &cast.spell,
+                effect,
+                caster_level,
+                caster_position: self.get_position().unwrap_or_default(),
+                caster_home: self.bind_location.clone(),
+                target: self.get_effect_target(effect, &cast.targets),
+            };
+
+            let result = match handler(&context) {
+                Ok(SpellEffectOutcome::ApplyAura { target, effect }) => {
+                    aura_effects.entry(target).or_default().push(effect);
+                    Ok(())
+                }
+                Ok(outcome) => self.apply_spell_effect_outcome(world, outcome).await,
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(()) if !hits.contains(&context.target) => hits.push(context.target),
+                Ok(()) => {}
+                Err(e) => warn!("Could not apply effect {} of spell {}: {}", effect.index, cast.spell.id, e),
+            }
+        }
+
+        for (target, effects) in aura_effects {
+            let aura = Aura::new(&cast.spell, self.get_guid(), caster_level as u8, effects);
+            if let Err(e) = self
+                .modify_unit(world, target, |unit| unit.get_auras_mut().apply(aura))
+                .await
+                .and_then(|result| result)
+            {
+                warn!("Could not apply the aura of spell {}: {}", cast.spell.id, e);
+                hits.retain(|hit| *hit != target);
+            }
+        }
+        hits
+    }
+
+    //Without a selected unit, effects land on the caster
+    fn get_effect_target(&self, effect: &SpellEffectInfo, targets: &SpellCastTargets) -> Guid {
+        if effect.implicit_target_a == SPELL_TARGET_UNIT_CASTER {
+            return self.get_guid();
+        }
+        get_unit_target(targets).unwrap_or_else(|| self.get_guid())
+    }
+
+    async fn apply_spell_effect_outcome(&mut self, world: &World, outcome: SpellEffectOutcome) -> Result<()> {
+        match outcome {
+            SpellEffectOutcome::Damage { target, amount } => {
+                let (health_before, target_level) = self
+                    .modify_unit(world, target, |unit| {
+                        let health = unit.get_health();
+                        unit.set_health(health.saturating_sub(amount));
+                        (health, unit.get_level())
+                    })
+                    .await?;
+                if health_before > 0 && amount >= health_before {
+                    self.give_kill_experience(&world.get_data_storage(), target, target_level).await?;
+                    self.claim_loot(world, target).await?;
+                    self.credit_quest_kill(&world.get_data_storage(), target).await?;
+                }
+                Ok(())
+            }
+            SpellEffectOutcome::Heal { target, amount } => {
+                self.modify_unit(world, target, |unit| {
+                    if unit.is_alive() {
+                        unit.set_health(unit.get_health().saturating_add(amount));
+                    }
+                })
+                .await
+            }
+            SpellEffectOutcome::Energize { target, power, amount } => {
+                self.modify_unit(world, target, |unit| unit.set_power(power, unit.get_power(power) + amount))
+                    .await
+            }
+            SpellEffectOutcome::Teleport { target, destination } => {
+                if target != self.get_guid() {
+                    bail!("Only the caster can be teleported for now");
+                }
+                self.teleport_to(TeleportationDistance::Far(destination));
+                Ok(())
+            }
+            //These are gathered by apply_spell_effects, so all aura effects of a cast end up in one aura
+            SpellEffectOutcome::ApplyAura { .. } => bail!("Aura effects can't be applied one by one"),
+            SpellEffectOutcome::Summon { entry, position } => {
+                let map = world
+                    .get_instance_manager()
+                    .try_get_map_for_character(self)
+                    .await
+                    .ok_or_else(|| anyhow!("Character {} is not on a map to summon on", self.name))?;
+                map.summon_creature(&world.get_realm_database(), &world.get_guid_service(), entry, &position)
+                    .await?;
+                Ok(())
+            }
+            SpellEffectOutcome::CreateItem { item, count } => {
+                self.try_create_item(&world.get_data_storage(), &world.get_guid_service(), item, count)
+                    .await?;
+                Ok(())
+            }
+            SpellEffectOutcome::LearnSpell { spell } => self.learn_spell(spell).await,
+            SpellEffectOutcome::ActivateTalentSpec { spec } => self.activate_talent_spec(&world.get_data_storage(), spec).await,
+        }
+    }
+
+    //The caster itself is already locked, other units are looked up on the map
+    pub(super) async fn modify_unit<T>(&mut self, world: &World, target: Guid, modify: impl FnOnce(&mut dyn Unit) -> T) -> Result<T> {
+        if target == self.get_guid() {
+            return Ok(modify(self));
+        }
+
+        let map = world
+            .get_instance_manager()
+            .try_get_map_for_character(self)
+            .await
+            .ok_or_else(|| anyhow!("Character {} is not on a map", self.name))?;
+        let object_lock = map
+            .try_get_object(target)
+            .await
+            .and_then(|weak| weak.upgrade())
+            .ok_or_else(|| anyhow!("Target {} is no longer on the map", target))?;
+        let mut object = object_lock.write().await;
+        let unit = object.as_unit_mut().ok_or_else(|| anyhow!("Target {} is not a unit", target))?;
+        Ok(modify(unit))
+    }
+
+    //Characters that never had their spells saved start out with the ones of their race and class
+    pub(super) fn load_spells(&mut self, race_class: RaceClass, db_spells: Vec<DBCharacterSpell>) {
+        if db_spells.is_empty() {
+            self.known_spells.extend(race_class.starter_spells().iter().copied());
+        } else {
+            self.known_spells.extend(db_spells.into_iter().map(|db_spell| db_spell.spell));
+        }
+    }
+
+    pub(super) fn get_spells_for_database(&self, character_id: u32) -> Vec<DBCharacterSpell> {
+        self.known_spells.iter().map(|&spell| DBCharacterSpell { character_id, spell }).collect()
+    }
+
+    pub async fn learn_spell(&mut self, spell_id: u32) -> Result<()> {
+        if self.known_spells.insert(spell_id) {
+            handlers::send_learned_spell(self, spell_id).await?;
+        }
+        Ok(())
+    }
+
+    pub(super) async fn tick_spell_casting(&mut self, delta_time: f32, world: &World) -> Result<()> {
+        let state = &mut self.spell_cast_state;
+        state.global_cooldown = (state.global_cooldown - delta_time).max(0.0);
+        for cooldowns in [&mut state.cooldowns, &mut state.category_cooldowns] {
+            cooldowns.retain(|_, remaining| {
+                *remaining -= delta_time;
+                *remaining > 0.0
+            });
+        }
+
+        let current_position = self.get_position().unwrap_or_default();
+        let Some(cast) = self.spell_cast_state.current_cast.as_mut() else {
+            return Ok(());
+        };
+
+        let has_moved = cast.start_position.distance_to(&current_position) > CAST_MOVEMENT_TOLERANCE;
+        if has_moved && cast.spell.interrupt_flags & SPELL_INTERRUPT_FLAG_MOVEMENT != 0 {
+            return self.interrupt_spell_cast(world, SpellCastResult::Moving).await;
+        }
+
+        cast.remaining_cast_time -= delta_time;
+        if cast.remaining_cast_time <= 0.0 {
+            self.finish_spell_cast(world).await?;
+        }
+        Ok(())
+    }
+}
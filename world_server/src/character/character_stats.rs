@@ -0,0 +1,296 @@
+use crate::data::DataStorage;
+use crate::prelude::*;
+use crate::world::prelude::inventory::EQUIPMENT_SLOTS_END;
+use crate::world::prelude::item_stats::*;
+use crate::world::prelude::spell::SPELL_AURA_MOD_STAT;
+use crate::world::prelude::Unit;
+use wow_dbc::Indexable;
+use wow_world_messages::wrath::{Class, Power};
+
+//Stats are strength, agility, stamina, intellect and spirit, in that order
+pub(super) const NUM_STATS: usize = 5;
+const STAT_STRENGTH: usize = 0;
+const STAT_AGILITY: usize = 1;
+const STAT_STAMINA: usize = 2;
+pub(super) const STAT_INTELLECT: usize = 3;
+pub(super) const STAT_SPIRIT: usize = 4;
+
+//The level stats tables don't go beyond the level cap
+const MAX_STATS_LEVEL: u32 = 80;
+//The crit and regeneration tables have a row for every level of every class
+const CLASS_TABLE_LEVELS: u32 = 100;
+
+//The first points of stamina and intellect are worth less than the ones after
+const LOW_STAT_THRESHOLD: i32 = 20;
+const HEALTH_PER_STAMINA: i32 = 10;
+const MANA_PER_INTELLECT: i32 = 15;
+const ARMOR_PER_AGILITY: i32 = 2;
+
+#[derive(Default, Debug, Clone, PartialEq)]
+pub(super) struct BaseStats {
+    pub stats: [i32; NUM_STATS],
+    pub health: i32,
+    pub mana: i32,
+}
+
+//Everything equipment and auras add on top of the base stats
+#[derive(Default, Debug, Clone, PartialEq)]
+pub(super) struct StatBonuses {
+    pub stats: [i32; NUM_STATS],
+    pub health: i32,
+    pub mana: i32,
+    pub armor: i32,
+    pub attack_power: i32,
+    pub spell_power: i32,
+}
+
+//Crit chances as fractions, the per point values are multiplied by agility and intellect
+#[derive(Default, Debug, Clone, PartialEq)]
+pub(super) struct CritRatios {
+    pub melee_base: f32,
+    pub melee_per_agility: f32,
+    pub spell_base: f32,
+    pub spell_per_intellect: f32,
+}
+
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct DerivedStats {
+    pub stats: [i32; NUM_STATS],
+    pub max_health: i32,
+    pub max_mana: i32,
+    pub armor: i32,
+    pub attack_power: i32,
+    pub ranged_attack_power: i32,
+    pub melee_crit: f32,
+    pub spell_crit: f32,
+    pub spell_power: i32,
+}
+
+#[derive(Default)]
+pub(super) struct StatsState {
+    derived: DerivedStats,
+    //Set whenever equipment or auras change, the stats get recalculated on the next tick
+    dirty: bool,
+}
+
+fn split_low_stat(value: i32, per_point: i32) -> i32 {
+    let low = value.clamp(0, LOW_STAT_THRESHOLD);
+    low + (value - low).max(0) * per_point
+}
+
+pub(super) fn calculate_stats(class: Class, level: u32, base: &BaseStats, bonuses: &StatBonuses, crit: &CritRatios) -> DerivedStats {
+    let mut stats = [0; NUM_STATS];
+    for (stat, value) in stats.iter_mut().enumerate() {
+        *value = (base.stats[stat] + bonuses.stats[stat]).max(0);
+    }
+    let level = level as i32;
+    let strength = stats[STAT_STRENGTH];
+    let agility = stats[STAT_AGILITY];
+
+    let max_mana = if base.mana > 0 {
+        base.mana + split_low_stat(stats[STAT_INTELLECT], MANA_PER_INTELLECT) + bonuses.mana
+    } else {
+        0
+    };
+
+    let attack_power = match class {
+        Class::Warrior | Class::Paladin | Class::DeathKnight => level * 3 + strength * 2 - 20,
+        Class::Hunter | Class::Rogue | Class::Shaman => level * 2 + strength + agility - 20,
+        Class::Druid => strength * 2 - 20,
+        _ => strength - 10,
+    };
+    let ranged_attack_power = match class {
+        Class::Hunter => level * 2 + agility - 10,
+        Class::Warrior | Class::Rogue => level + agility - 10,
+        _ => 0,
+    };
+
+    DerivedStats {
+        stats,
+        max_health: (base.health + split_low_stat(stats[STAT_STAMINA], HEALTH_PER_STAMINA) + bonuses.health).max(1),
+        max_mana,
+        armor: (bonuses.armor + agility * ARMOR_PER_AGILITY).max(0),
+        attack_power: (attack_power + bonuses.attack_power).max(0),
+        ranged_attack_power: (ranged_attack_power + bonuses.attack_power).max(0),
+        melee_crit: (crit.melee_base + agility as f32 * crit.melee_per_agility) * 100.0,
+        spell_crit: (crit.spell_base + stats[STAT_INTELLECT] as f32 * crit.spell_per_intellect) * 100.0,
+        spell_power: bonuses.spell_power.max(0),
+    }
+}
+
+impl super::Character {
+    pub fn get_derived_stats(&self) -> &DerivedStats {
+        &self.stats_state.derived
+    }
+
+    pub(super) fn mark_stats_dirty(&mut self) {
+        self.stats_state.dirty = true;
+    }
+
+    pub(super) fn tick_stats(&mut self, data_storage: &DataStorage) -> Result<()> {
+        if self.stats_state.dirty {
+            self.recalculate_stats(data_storage)?;
+        }
+        Ok(())
+    }
+
+    fn get_base_stats(&self, data_storage: &DataStorage) -> Result<BaseStats> {
+        let race = self.get_race().as_int();
+        let class = self.get_class().as_int();
+        let level = self.get_level().clamp(1, MAX_STATS_LEVEL) as u8;
+
+        let level_stats = data_storage
+            .get_player_level_stats(race, class, level)
+            .ok_or_else(|| anyhow!("No level stats for race {} class {} level {}", race, class, level))?;
+        let class_level_stats = data_storage
+            .get_player_class_level_stats(class, level)
+            .ok_or_else(|| anyhow!("No class level stats for class {} level {}", class, level))?;
+
+        Ok(BaseStats {
+            stats: [
+                level_stats.strength as i32,
+                level_stats.agility as i32,
+                level_stats.stamina as i32,
+                level_stats.intellect as i32,
+                level_stats.spirit as i32,
+            ],
+            health: class_level_stats.base_health as i32,
+            mana: class_level_stats.base_mana as i32,
+        })
+    }
+
+    fn get_stat_bonuses(&self, data_storage: &DataStorage) -> StatBonuses {
+        let mut bonuses = StatBonuses::default();
+        let equipment = self.equipped_items.get_all_equipment();
+        for item in equipment
+            .iter()
+            .take(EQUIPMENT_SLOTS_END as usize + 1)
+            .flatten()
+            .filter(|item| !item.is_broken())
+        {
+            let Some(template) = data_storage.get_item_template(item.get_entry()) else {
+                continue;
+            };
+            bonuses.armor += template.granted_armor.unwrap_or(0) as i32;
+            for stat in &template.granted_stats {
+                let value = stat.stat_value as i32;
+                match stat.stat_type {
+                    ITEM_MOD_MANA => bonuses.mana += value,
+                    ITEM_MOD_HEALTH => bonuses.health += value,
+                    ITEM_MOD_STRENGTH => bonuses.stats[STAT_STRENGTH] += value,
+                    ITEM_MOD_AGILITY => bonuses.stats[STAT_AGILITY] += value,
+                    ITEM_MOD_STAMINA => bonuses.stats[STAT_STAMINA] += value,
+                    ITEM_MOD_INTELLECT => bonuses.stats[STAT_INTELLECT] += value,
+                    ITEM_MOD_SPIRIT => bonuses.stats[STAT_SPIRIT] += value,
+                    ITEM_MOD_ATTACK_POWER => bonuses.attack_power += value,
+                    ITEM_MOD_SPELL_POWER => bonuses.spell_power += value,
+                    _ => {}
+                }
+            }
+        }
+
+        //A misc value of -1 means all stats
+        for (stat, bonus) in bonuses.stats.iter_mut().enumerate() {
+            *bonus += self
+                .auras
+                .get_total_modifier(SPELL_AURA_MOD_STAT, |effect| effect.misc_value == stat as i32 || effect.misc_value == -1);
+        }
+        bonuses
+    }
+
+    pub(super) fn get_class_level_index(&self) -> u32 {
+        let class_index = self.get_class().as_int() as u32 - 1;
+        let level = self.get_level().clamp(1, MAX_STATS_LEVEL);
+        class_index * CLASS_TABLE_LEVELS + level - 1
+    }
+
+    fn get_crit_ratios(&self, data_storage: &DataStorage) -> Result<CritRatios> {
+        let class_index = self.get_class().as_int() as u32 - 1;
+        let level_index = self.get_class_level_index();
+
+        let read = |value: Option<f32>| value.unwrap_or(0.0);
+        Ok(CritRatios {
+            melee_base: read(data_storage.get_dbc_gt_chance_to_melee_crit_base()?.get(class_index).map(|row| row.data)),
+            melee_per_agility: read(data_storage.get_dbc_gt_chance_to_melee_crit()?.get(level_index).map(|row| row.data)),
+            spell_base: read(data_storage.get_dbc_gt_chance_to_spell_crit_base()?.get(class_index).map(|row| row.data)),
+            spell_per_intellect: read(data_storage.get_dbc_gt_chance_to_spell_crit()?.get(level_index).map(|row| row.data)),
+        })
+    }
+
+    pub(super) fn recalculate_stats(&mut self, data_storage: &DataStorage) -> Result<()> {
+        self.stats_state.dirty = false;
+        let base = self.get_base_stats(data_storage)?;
+        let bonuses = self.get_stat_bonuses(data_storage);
+        let crit = self.get_crit_ratios(data_storage)?;
+        let derived = calculate_stats(self.get_class(), self.get_level(), &base, &bonuses, &crit);
+
+        for (stat, value) in derived.stats.iter().enumerate() {
+            self.set_stat(stat, *value);
+        }
+        self.gameplay_data.set_unit_base_health(base.health);
+        self.gameplay_data.set_unit_maxhealth(derived.max_health);
+        //Lowering the max health shouldn't leave the character with more than that
+        self.set_health(self.get_health());
+        if self.get_power_type() == Power::Mana {
+            self.gameplay_data.set_unit_base_mana(base.mana);
+            self.set_max_power(Power::Mana, derived.max_mana);
+            self.set_power(Power::Mana, self.get_power(Power::Mana));
+        }
+        self.gameplay_data.set_unit_resistances(derived.armor);
+        self.gameplay_data.set_unit_attack_power(derived.attack_power);
+        self.gameplay_data.set_unit_ranged_attack_power(derived.ranged_attack_power);
+        self.gameplay_data.set_player_crit_percentage(derived.melee_crit);
+        self.gameplay_data.set_player_spell_crit_percentage1(derived.spell_crit);
+        self.gameplay_data.set_player_field_mod_damage_done_pos(derived.spell_power);
+        self.gameplay_data.set_player_field_mod_healing_done_pos(derived.spell_power);
+
+        self.stats_state.derived = derived;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn derived_stats() {
+        let base = BaseStats {
+            stats: [30, 20, 25, 18, 20],
+            health: 100,
+            mana: 0,
+        };
+        let bonuses = StatBonuses {
+            stats: [5, 0, 5, 0, 0],
+            armor: 50,
+            ..Default::default()
+        };
+        let crit = CritRatios {
+            melee_base: 0.05,
+            melee_per_agility: 0.001,
+            ..Default::default()
+        };
+
+        let derived = calculate_stats(Class::Warrior, 10, &base, &bonuses, &crit);
+        assert_eq!(derived.stats, [35, 20, 30, 18, 20]);
+        //20 stamina worth 1 health each, the other 10 worth 10 each
+        assert_eq!(derived.max_health, 220);
+        assert_eq!(derived.max_mana, 0);
+        assert_eq!(derived.armor, 90);
+        assert_eq!(derived.attack_power, 80);
+        assert_eq!(derived.ranged_attack_power, 20);
+        assert!((derived.melee_crit - 7.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn mana_from_intellect() {
+        let base = BaseStats {
+            stats: [10, 10, 10, 40, 10],
+            health: 50,
+            mana: 100,
+        };
+        let derived = calculate_stats(Class::Mage, 10, &base, &StatBonuses::default(), &CritRatios::default());
+        assert_eq!(derived.max_mana, 100 + 20 + 20 * MANA_PER_INTELLECT);
+        assert_eq!(derived.attack_power, 0);
+    }
+}
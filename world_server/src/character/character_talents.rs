@@ -0,0 +1,416 @@
+use crate::data::{ActionBar, DataStorage};
+use crate::prelude::*;
+use crate::world::prelude::Unit;
+use crate::world::World;
+use std::collections::{HashMap, HashSet};
+use std::time::{SystemTime, UNIX_EPOCH};
+use wow_world_messages::wrath::ActionButton;
+use wrath_realm_db::character::DBCharacter;
+use wrath_realm_db::character_talent::{DBCharacterAction, DBCharacterTalent};
+
+pub const MAX_TALENT_SPECS: usize = 2;
+
+//Every tier of a talent tab needs this many more points spent in the tab
+const TALENT_POINTS_PER_TIER: u32 = 5;
+
+//Resetting talents gets more expensive every time, and cheaper again over time. In copper.
+const TALENT_RESET_MIN_COST: u32 = 10000;
+const TALENT_RESET_COST_STEP: u32 = 50000;
+const TALENT_RESET_MAX_COST: u32 = 500000;
+const TALENT_RESET_DECAY_SECONDS: u32 = 30 * 24 * 60 * 60;
+
+const DUAL_SPEC_MIN_LEVEL: u32 = 40;
+const DUAL_SPEC_COST: u32 = 10000000;
+//Casting these switches between the specs, they are learned together with the second spec
+const SPELL_ACTIVATE_PRIMARY_SPEC: u32 = 63645;
+const SPELL_ACTIVATE_SECONDARY_SPEC: u32 = 63644;
+
+#[derive(Default)]
+struct TalentSpec {
+    //Rank of every learned talent by talent id, counting from 0
+    talents: HashMap<u32, u8>,
+    action_bar: ActionBar,
+}
+
+impl TalentSpec {
+    fn get_spent_points(&self) -> u32 {
+        self.talents.values().map(|&rank| rank as u32 + 1).sum()
+    }
+}
+
+pub(super) struct TalentState {
+    specs: [TalentSpec; MAX_TALENT_SPECS],
+    spec_count: u8,
+    active_spec: u8,
+    //Spells taught by the talents of the active spec
+    talent_spells: HashSet<u32>,
+    reset_cost: u32,
+    reset_time: u32,
+}
+
+impl Default for TalentState {
+    fn default() -> Self {
+        Self {
+            specs: Default::default(),
+            spec_count: 1,
+            active_spec: 0,
+            talent_spells: HashSet::new(),
+            reset_cost: 0,
+            reset_time: 0,
+        }
+    }
+}
+
+//The first talent point comes at level 10, and another one with every level after that
+fn get_talent_points_for_level(level: u32) -> u32 {
+    level.saturating_sub(9)
+}
+
+//Every month without a reset brings the cost down by a step
+fn get_next_talent_reset_cost(last_cost: u32, seconds_since_last_reset: u32) -> u32 {
+    let months = seconds_since_last_reset / TALENT_RESET_DECAY_SECONDS;
+    let cost = last_cost.saturating_sub(months.saturating_mul(TALENT_RESET_COST_STEP));
+    if cost < TALENT_RESET_MIN_COST {
+        TALENT_RESET_MIN_COST
+    } else if cost < TALENT_RESET_COST_STEP {
+        TALENT_RESET_COST_STEP
+    } else {
+        (cost + TALENT_RESET_COST_STEP).min(TALENT_RESET_MAX_COST)
+    }
+}
+
+fn get_unix_time() -> u32 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() as u32
+}
+
+fn get_talent_spells(data_storage: &DataStorage, spec: &TalentSpec) -> HashSet<u32> {
+    spec.talents
+        .iter()
+        .filter_map(|(&talent, &rank)| data_storage.get_talent_info(talent)?.rank_spells.get(rank as usize).copied())
+        .collect()
+}
+
+impl super::Character {
+    pub(super) fn load_talents(
+        &mut self,
+        data_storage: &DataStorage,
+        db_entry: &DBCharacter,
+        db_talents: Vec<DBCharacterTalent>,
+        db_actions: Vec<DBCharacterAction>,
+    ) {
+        let state = &mut self.talent_state;
+        state.spec_count = db_entry.talent_spec_count.clamp(1, MAX_TALENT_SPECS as u8);
+        state.active_spec = db_entry.active_talent_spec.min(state.spec_count - 1);
+        state.reset_cost = db_entry.talent_reset_cost;
+        state.reset_time = db_entry.talent_reset_time;
+
+        for db_talent in db_talents {
+            let Some(spec) = state.specs.get_mut(db_talent.spec as usize) else {
+                warn!("Character {} has talents in spec {} which doesn't exist", self.name, db_talent.spec);
+                continue;
+            };
+            spec.talents.insert(db_talent.talent, db_talent.talent_rank);
+        }
+        for db_action in db_actions {
+            let Some(spec) = state
+                .specs
+                .get_mut(db_action.spec as usize)
+                .filter(|spec| (db_action.button as usize) < spec.action_bar.data.len())
+            else {
+                warn!(
+                    "Character {} has an action in button {} of spec {}",
+                    self.name, db_action.button, db_action.spec
+                );
+                continue;
+            };
+            let action_button = ActionButton {
+                action: db_action.action,
+                action_type: db_action.action_type,
+                misc: db_action.misc,
+            };
+            spec.action_bar.set_action_button(db_action.button as usize, action_button);
+        }
+
+        state.talent_spells = get_talent_spells(data_storage, &state.specs[state.active_spec as usize]);
+        self.update_talent_points();
+    }
+
+    pub(super) fn get_talents_for_database(&self, character_id: u32) -> (Vec<DBCharacterTalent>, Vec<DBCharacterAction>) {
+        let specs = self.talent_state.specs.iter().enumerate();
+        let talents = specs
+            .clone()
+            .flat_map(|(spec, talent_spec)| {
+                talent_spec.talents.iter().map(move |(&talent, &talent_rank)| DBCharacterTalent {
+                    character_id,
+                    spec: spec as u8,
+                    talent,
+                    talent_rank,
+                })
+            })
+            .collect();
+        //Empty buttons aren't worth storing
+        let actions = specs
+            .flat_map(|(spec, talent_spec)| {
+                talent_spec
+                    .action_bar
+                    .data
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, button)| **button != ActionButton::default())
+                    .map(move |(button, action_button)| DBCharacterAction {
+                        character_id,
+                        spec: spec as u8,
+                        button: button as u8,
+                        action: action_button.action,
+                        action_type: action_button.action_type,
+                        misc: action_button.misc,
+                    })
+            })
+            .collect();
+        (talents, actions)
+    }
+
+    //The cost and unix time of the last reset
+    pub(super) fn get_talent_reset_for_database(&self) -> (u32, u32) {
+        (self.talent_state.reset_cost, self.talent_state.reset_time)
+    }
+
+    fn get_active_talent_spec(&self) -> &TalentSpec {
+        &self.talent_state.specs[self.talent_state.active_spec as usize]
+    }
+
+    pub fn get_action_bar(&self) -> &ActionBar {
+        &self.get_active_talent_spec().action_bar
+    }
+
+    pub fn set_action_bar_button(&mut self, slot: u8, action_button: ActionButton) {
+        let active_spec = self.talent_state.active_spec as usize;
+        self.talent_state.specs[active_spec]
+            .action_bar
+            .set_action_button(slot as usize, action_button);
+    }
+
+    pub(super) fn knows_talent_spell(&self, spell_id: u32) -> bool {
+        self.talent_state.talent_spells.contains(&spell_id)
+    }
+
+    pub(super) fn get_talent_spells(&self) -> impl Iterator<Item = u32> + '_ {
+        self.talent_state.talent_spells.iter().copied()
+    }
+
+    pub fn get_talent_spec_count(&self) -> u8 {
+        self.talent_state.spec_count
+    }
+
+    pub fn get_active_talent_spec_index(&self) -> u8 {
+        self.talent_state.active_spec
+    }
+
+    //Talents of every spec as (talent id, rank) pairs
+    pub fn get_talent_specs(&self) -> impl Iterator<Item = Vec<(u32, u8)>> + '_ {
+        self.talent_state.specs[..self.talent_state.spec_count as usize]
+            .iter()
+            .map(|spec| spec.talents.iter().map(|(&talent, &rank)| (talent, rank)).collect())
+    }
+
+    pub fn get_free_talent_points(&self) -> u32 {
+        get_talent_points_for_level(self.get_level()).saturating_sub(self.get_active_talent_spec().get_spent_points())
+    }
+
+    pub(super) fn update_talent_points(&mut self) {
+        self.gameplay_data.set_player_character_points1(self.get_free_talent_points() as i32);
+    }
+
+    pub fn get_talent_reset_cost(&self) -> u32 {
+        let state = &self.talent_state;
+        get_next_talent_reset_cost(state.reset_cost, get_unix_time().saturating_sub(state.reset_time))
+    }
+
+    //The client asks for one rank at a time, counting from 0
+    fn learn_talent_rank(&mut self, data_storage: &DataStorage, talent_id: u32, rank: u8) -> Result<()> {
+        let talent = data_storage
+            .get_talent_info(talent_id)
+            .ok_or_else(|| anyhow!("Talent {} doesn't exist", talent_id))?;
+        if talent.class_mask & (1u32 << (self.get_class().as_int() - 1)) == 0 {
+            bail!("Character {} can't learn talent {} of another class", self.name, talent_id);
+        }
+
+        let spec = self.get_active_talent_spec();
+        let current_rank = spec.talents.get(&talent_id).copied();
+        let expected_rank = current_rank.map_or(0, |current_rank| current_rank + 1);
+        if rank != expected_rank || rank > talent.get_max_rank() {
+            bail!("Character {} can't learn rank {} of talent {}", self.name, rank, talent_id);
+        }
+        let rank_spell = talent
+            .rank_spells
+            .get(rank as usize)
+            .copied()
+            .ok_or_else(|| anyhow!("Talent {} has no spell for rank {}", talent_id, rank))?;
+        if self.get_free_talent_points() == 0 {
+            bail!("Character {} has no talent points left", self.name);
+        }
+        let points_in_tab: u32 = spec
+            .talents
+            .iter()
+            .filter(|(&other, _)| data_storage.get_talent_info(other).is_some_and(|other| other.tab == talent.tab))
+            .map(|(_, &rank)| rank as u32 + 1)
+            .sum();
+        if points_in_tab < talent.tier * TALENT_POINTS_PER_TIER {
+            bail!("Character {} needs more points in tab {} for talent {}", self.name, talent.tab, talent_id);
+        }
+        let has_prerequisites = talent
+            .prerequisites
+            .iter()
+            .all(|(prerequisite, min_rank)| spec.talents.get(prerequisite).is_some_and(|rank| rank >= min_rank));
+        if !has_prerequisites {
+            bail!("Character {} is missing the prerequisites of talent {}", self.name, talent_id);
+        }
+
+        let state = &mut self.talent_state;
+        state.specs[state.active_spec as usize].talents.insert(talent_id, rank);
+        if let Some(previous_spell) = current_rank.and_then(|current_rank| talent.rank_spells.get(current_rank as usize)) {
+            state.talent_spells.remove(previous_spell);
+        }
+        state.talent_spells.insert(rank_spell);
+        Ok(())
+    }
+
+    pub async fn learn_talent(&mut self, data_storage: &DataStorage, talent_id: u32, rank: u8) -> Result<()> {
+        let previous_spell = self
+            .get_active_talent_spec()
+            .talents
+            .get(&talent_id)
+            .and_then(|&current_rank| data_storage.get_talent_info(talent_id)?.rank_spells.get(current_rank as usize).copied());
+        self.learn_talent_rank(data_storage, talent_id, rank)?;
+
+        let spell = data_storage
+            .get_talent_info(talent_id)
+            .and_then(|talent| talent.rank_spells.get(rank as usize).copied())
+            .ok_or_else(|| anyhow!("Talent {} has no spell for rank {}", talent_id, rank))?;
+        match previous_spell {
+            Some(previous_spell) => handlers::send_superceded_spell(self, previous_spell, spell).await?,
+            None => handlers::send_learned_spell(self, spell).await?,
+        }
+        self.update_talent_points();
+        handlers::send_talents_info(self).await
+    }
+
+    //Talents picked in the talent preview, every talent up to the given rank
+    pub async fn learn_preview_talents(&mut self, data_storage: &DataStorage, talents: &[(u32, u8)]) -> Result<()> {
+        let spells_before = self.talent_state.talent_spells.clone();
+        let mut result = Ok(());
+        'talents: for &(talent_id, target_rank) in talents {
+            loop {
+                let next_rank = self.get_active_talent_spec().talents.get(&talent_id).map_or(0, |rank| rank + 1);
+                if next_rank > target_rank {
+                    break;
+                }
+                //Whatever was learned up to here stays learned
+                if let Err(e) = self.learn_talent_rank(data_storage, talent_id, next_rank) {
+                    result = Err(e);
+                    break 'talents;
+                }
+            }
+        }
+
+        self.send_talent_spell_changes(&spells_before).await?;
+        self.update_talent_points();
+        handlers::send_talents_info(self).await?;
+        result
+    }
+
+    //Tells the client about every talent spell that came or went since the given ones
+    async fn send_talent_spell_changes(&self, spells_before: &HashSet<u32>) -> Result<()> {
+        for &spell in spells_before.difference(&self.talent_state.talent_spells) {
+            handlers::send_removed_spell(self, spell).await?;
+        }
+        for &spell in self.talent_state.talent_spells.difference(spells_before) {
+            handlers::send_learned_spell(self, spell).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn reset_talents(&mut self, world: &World, trainer: Guid) -> Result<()> {
+        self.get_trainer(world, trainer).await?;
+        let cost = self.get_talent_reset_cost();
+        if self.get_money() < cost {
+            bail!("Character {} can't afford to reset their talents", self.name);
+        }
+
+        self.remove_money(cost);
+        let spells_before = std::mem::take(&mut self.talent_state.talent_spells);
+        let state = &mut self.talent_state;
+        state.specs[state.active_spec as usize].talents.clear();
+        state.reset_cost = cost;
+        state.reset_time = get_unix_time();
+
+        self.send_talent_spell_changes(&spells_before).await?;
+        self.update_talent_points();
+        handlers::send_talents_info(self).await
+    }
+
+    pub async fn buy_dual_talent_spec(&mut self, world: &World, trainer: Guid) -> Result<()> {
+        self.get_trainer(world, trainer).await?;
+        if self.talent_state.spec_count as usize >= MAX_TALENT_SPECS {
+            bail!("Character {} already has a second talent spec", self.name);
+        }
+        if self.get_level() < DUAL_SPEC_MIN_LEVEL || self.get_money() < DUAL_SPEC_COST {
+            bail!("Character {} can't buy a second talent spec", self.name);
+        }
+
+        self.remove_money(DUAL_SPEC_COST);
+        self.talent_state.spec_count = MAX_TALENT_SPECS as u8;
+        self.learn_spell(SPELL_ACTIVATE_PRIMARY_SPEC).await?;
+        self.learn_spell(SPELL_ACTIVATE_SECONDARY_SPEC).await?;
+        handlers::send_talents_info(self).await
+    }
+
+    //The other spec comes with its own talents and action bars
+    pub(super) async fn activate_talent_spec(&mut self, data_storage: &DataStorage, spec: u8) -> Result<()> {
+        if spec >= self.talent_state.spec_count {
+            bail!("Character {} has no talent spec {}", self.name, spec);
+        }
+        if spec == self.talent_state.active_spec {
+            return Ok(());
+        }
+        if self.is_in_combat() {
+            bail!("Character {} can't switch talent specs in combat", self.name);
+        }
+
+        self.talent_state.active_spec = spec;
+        let spells = get_talent_spells(data_storage, &self.talent_state.specs[spec as usize]);
+        let spells_before = std::mem::replace(&mut self.talent_state.talent_spells, spells);
+
+        self.send_talent_spell_changes(&spells_before).await?;
+        self.update_talent_points();
+        handlers::send_talents_info(self).await?;
+        handlers::send_action_buttons(self).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn talent_points() {
+        assert_eq!(get_talent_points_for_level(9), 0);
+        assert_eq!(get_talent_points_for_level(10), 1);
+        assert_eq!(get_talent_points_for_level(80), 71);
+    }
+
+    #[test]
+    fn talent_reset_cost_escalates() {
+        assert_eq!(get_next_talent_reset_cost(0, 0), 10000);
+        assert_eq!(get_next_talent_reset_cost(10000, 0), 50000);
+        assert_eq!(get_next_talent_reset_cost(50000, 0), 100000);
+        assert_eq!(get_next_talent_reset_cost(500000, 0), 500000);
+    }
+
+    #[test]
+    fn talent_reset_cost_decays() {
+        //Two months after a 15 gold reset, the next one costs 10 gold rather than 20
+        assert_eq!(get_next_talent_reset_cost(150000, 2 * TALENT_RESET_DECAY_SECONDS), 100000);
+        assert_eq!(get_next_talent_reset_cost(150000, 12 * TALENT_RESET_DECAY_SECONDS), 10000);
+    }
+}
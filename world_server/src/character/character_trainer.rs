@@ -0,0 +1,62 @@
+use crate::creature::Creature;
+use crate::prelude::*;
+use crate::world::guid_service::get_entry;
+use crate::world::prelude::unit_flags::UNIT_NPC_FLAG_TRAINER;
+use crate::world::prelude::Unit;
+use crate::world::World;
+use smol::lock::RwLock;
+use std::sync::Arc;
+use wow_world_messages::wrath::{TrainerSpellState, TrainingFailureReason};
+use wrath_realm_db::trainer::DBTrainerSpell;
+
+impl super::Character {
+    //Class trainers only teach characters of their own class
+    pub async fn get_trainer(&self, world: &World, trainer: Guid) -> Result<Arc<RwLock<Creature>>> {
+        let creature_lock = self.get_npc(world, trainer, UNIT_NPC_FLAG_TRAINER).await?;
+        if !creature_lock.read().await.can_train(self.get_class()) {
+            bail!("Character {} can't train at trainer {}", self.name, trainer);
+        }
+        Ok(creature_lock)
+    }
+
+    //Gray for spells that are known already, red for the ones the character doesn't qualify for yet
+    pub fn get_trainer_spell_state(&self, trainer_spell: &DBTrainerSpell) -> TrainerSpellState {
+        if self.knows_spell(trainer_spell.spell) {
+            TrainerSpellState::Gray
+        } else if self.meets_trainer_spell_requirements(trainer_spell) {
+            TrainerSpellState::Green
+        } else {
+            TrainerSpellState::Red
+        }
+    }
+
+    fn meets_trainer_spell_requirements(&self, trainer_spell: &DBTrainerSpell) -> bool {
+        self.get_level() >= trainer_spell.required_level as u32
+            && (trainer_spell.required_skill == 0 || self.get_skill_value(trainer_spell.required_skill) >= trainer_spell.required_skill_value)
+            && (trainer_spell.required_spell == 0 || self.knows_spell(trainer_spell.required_spell))
+    }
+
+    pub async fn learn_trainer_spell(&mut self, world: &World, trainer: Guid, spell_id: u32) -> Result<()> {
+        self.get_trainer(world, trainer).await?;
+
+        let data_storage = world.get_data_storage();
+        let Some(trainer_spell) = data_storage
+            .get_trainer_spells(get_entry(trainer))
+            .iter()
+            .find(|trainer_spell| trainer_spell.spell == spell_id)
+        else {
+            return handlers::send_trainer_buy_failed(self, trainer, spell_id, TrainingFailureReason::Unavailable).await;
+        };
+        if self.get_trainer_spell_state(trainer_spell) != TrainerSpellState::Green {
+            return handlers::send_trainer_buy_failed(self, trainer, spell_id, TrainingFailureReason::Unavailable).await;
+        }
+        if self.get_money() < trainer_spell.cost {
+            return handlers::send_trainer_buy_failed(self, trainer, spell_id, TrainingFailureReason::NotEnoughMoney).await;
+        }
+
+        self.remove_money(trainer_spell.cost);
+        self.learn_spell(spell_id).await?;
+        self.learn_spell_skills(&data_storage, spell_id)?;
+        handlers::send_trainer_buy_succeeded(self, trainer, spell_id).await
+    }
+}
@@ -0,0 +1,189 @@
+use crate::item::Item;
+use crate::prelude::*;
+use crate::world::guid_service::get_entry;
+use crate::world::prelude::unit_flags::UNIT_NPC_FLAG_VENDOR;
+use crate::world::World;
+use std::time::{SystemTime, UNIX_EPOCH};
+use wow_world_messages::wrath::{BuyResult, InventoryError, SellItemResult};
+
+//Items sold to a vendor can be bought back from these slots until they are pushed out or the character logs out
+pub const BUYBACK_SLOT_COUNT: usize = 12;
+//The client refers to the buyback slots with these inventory slots
+pub const BUYBACK_SLOT_START: u32 = 74;
+
+struct BuybackItem {
+    item: Item,
+    price: u32,
+    timestamp: u32,
+}
+
+#[derive(Default)]
+pub(super) struct BuybackState {
+    slots: [Option<BuybackItem>; BUYBACK_SLOT_COUNT],
+}
+
+//The first free slot, or else the one that was filled the longest ago
+fn find_buyback_slot(timestamps: &[Option<u32>]) -> usize {
+    timestamps.iter().position(Option::is_none).unwrap_or_else(|| {
+        timestamps
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, timestamp)| **timestamp)
+            .map_or(0, |(index, _)| index)
+    })
+}
+
+impl super::Character {
+    //Amount is the number of items, vendors sell them in bundles of the buy count of the template
+    pub async fn buy_item_from_vendor(&mut self, world: &World, vendor: Guid, item: u32, vendor_slot: u32, amount: u32) -> Result<()> {
+        let data_storage = world.get_data_storage();
+        let creature_lock = self.get_npc(world, vendor, UNIT_NPC_FLAG_VENDOR).await?;
+
+        //The client counts the vendor slots from 1
+        let vendor_items = data_storage.get_vendor_items(get_entry(vendor));
+        let Some(vendor_item) = vendor_slot
+            .checked_sub(1)
+            .and_then(|index| vendor_items.get(index as usize))
+            .filter(|vendor_item| vendor_item.item == item)
+        else {
+            return handlers::send_buy_failed(self, vendor, item, BuyResult::CantFindItem).await;
+        };
+        let template = data_storage
+            .get_item_template(item)
+            .ok_or_else(|| anyhow!("No item template for item {}", item))?;
+        let buy_count = template.buy_count.max(1) as u32;
+        if amount == 0 || amount % buy_count != 0 {
+            bail!(
+                "Character {} tried to buy {} of item {}, which is sold by {}",
+                self.name,
+                amount,
+                item,
+                buy_count
+            );
+        }
+
+        let remaining = creature_lock.read().await.get_vendor_item_count(vendor_item);
+        if remaining.is_some_and(|remaining| remaining < amount) {
+            return handlers::send_buy_failed(self, vendor, item, BuyResult::ItemAlreadySold).await;
+        }
+        let price = template.buy_price.saturating_mul(amount / buy_count);
+        if self.get_money() < price {
+            return handlers::send_buy_failed(self, vendor, item, BuyResult::NotEnoughtMoney).await;
+        }
+        if !self.has_room_for_new_items(&data_storage, &[(item, amount)])? {
+            return handlers::send_inventory_change_failure(self, InventoryError::InventoryFull, Guid::zero(), Guid::zero()).await;
+        }
+
+        //Nothing is paid for items that couldn't be created
+        if !self.try_create_item(&data_storage, &world.get_guid_service(), item, amount).await? {
+            return Ok(());
+        }
+        let remaining = {
+            let mut creature = creature_lock.write().await;
+            creature.take_vendor_items(vendor_item, amount);
+            creature.get_vendor_item_count(vendor_item)
+        };
+        self.remove_money(price);
+        handlers::send_buy_item(self, vendor, vendor_slot, remaining, amount).await
+    }
+
+    //Amount 0 sells the whole stack
+    pub async fn sell_item_to_vendor(&mut self, world: &World, vendor: Guid, item_guid: Guid, amount: u32) -> Result<()> {
+        let data_storage = world.get_data_storage();
+        self.get_npc(world, vendor, UNIT_NPC_FLAG_VENDOR).await?;
+
+        let Some(position) = self.find_carried_item(item_guid) else {
+            return handlers::send_sell_failed(self, vendor, item_guid, SellItemResult::CantFindItem).await;
+        };
+        let item = self.get_item(position).unwrap();
+        let template = data_storage
+            .get_item_template(item.get_entry())
+            .ok_or_else(|| anyhow!("No item template for item {}", item.get_entry()))?;
+        if template.sell_price == 0 {
+            return handlers::send_sell_failed(self, vendor, item_guid, SellItemResult::CantSellItem).await;
+        }
+        if item.contents.as_ref().is_some_and(|contents| !contents.is_empty()) {
+            return handlers::send_sell_failed(self, vendor, item_guid, SellItemResult::OnlyEmptyBag).await;
+        }
+
+        let stack_count = item.get_stack_count();
+        let (sold_item, amount) = if amount == 0 || amount >= stack_count {
+            (self.set_item(None, position)?.unwrap(), stack_count)
+        } else {
+            //The sold part of the stack becomes an item of its own in the buyback slot
+            let sold_item = Item::new(world.get_guid_service().generate_item_guid(), self.get_guid(), template, amount);
+            self.push_object_update(sold_item.build_create_update());
+            self.set_item_stack_count(position, stack_count - amount)?;
+            (sold_item, amount)
+        };
+
+        let price = template.sell_price.saturating_mul(amount);
+        self.add_money(price);
+        self.add_to_buyback(sold_item, price).await
+    }
+
+    async fn add_to_buyback(&mut self, item: Item, price: u32) -> Result<()> {
+        let timestamps: Vec<Option<u32>> = self
+            .buyback_state
+            .slots
+            .iter()
+            .map(|slot| slot.as_ref().map(|buyback| buyback.timestamp))
+            .collect();
+        let index = find_buyback_slot(&timestamps);
+
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() as u32;
+        self.gameplay_data.set_player_field_vendorbuyback_slot_1(item.get_guid(), index as u32);
+        self.gameplay_data.set_player_field_buyback_price_1(price, index as u32);
+        self.gameplay_data.set_player_field_buyback_timestamp_1(timestamp, index as u32);
+
+        let pushed_out = self.buyback_state.slots[index].replace(BuybackItem { item, price, timestamp });
+        if let Some(pushed_out) = pushed_out {
+            handlers::send_destroy_object(self, pushed_out.item.get_guid(), false).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn buy_back_item(&mut self, world: &World, vendor: Guid, slot: u32) -> Result<()> {
+        self.get_npc(world, vendor, UNIT_NPC_FLAG_VENDOR).await?;
+
+        let Some(index) = slot
+            .checked_sub(BUYBACK_SLOT_START)
+            .map(|index| index as usize)
+            .filter(|index| self.buyback_state.slots.get(*index).is_some_and(Option::is_some))
+        else {
+            return handlers::send_buy_failed(self, vendor, 0, BuyResult::CantFindItem).await;
+        };
+        let buyback = self.buyback_state.slots[index].as_ref().unwrap();
+        let entry = buyback.item.get_entry();
+        if self.get_money() < buyback.price {
+            return handlers::send_buy_failed(self, vendor, entry, BuyResult::NotEnoughtMoney).await;
+        }
+        let Some(position) = self.find_free_item_position(false) else {
+            let item_guid = buyback.item.get_guid();
+            return handlers::send_inventory_change_failure(self, InventoryError::InventoryFull, item_guid, Guid::zero()).await;
+        };
+
+        let buyback = self.buyback_state.slots[index].take().unwrap();
+        self.gameplay_data.set_player_field_vendorbuyback_slot_1(Guid::zero(), index as u32);
+        self.gameplay_data.set_player_field_buyback_price_1(0, index as u32);
+        self.gameplay_data.set_player_field_buyback_timestamp_1(0, index as u32);
+        self.remove_money(buyback.price);
+        self.set_item(Some(buyback.item), position)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn buyback_fills_free_slots_first() {
+        assert_eq!(find_buyback_slot(&[Some(5), None, Some(3), None]), 1);
+    }
+
+    #[test]
+    fn buyback_replaces_the_oldest_item() {
+        assert_eq!(find_buyback_slot(&[Some(5), Some(2), Some(3)]), 1);
+    }
+}
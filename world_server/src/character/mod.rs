@@ -2,27 +2,52 @@ use self::character_inventory::{BagInventory, GameplayCharacterInventory};
 
 use super::world::prelude::*;
 use crate::client::Client;
-use crate::data::{ActionBar, DataStorage, PositionAndOrientation, TutorialFlags, WorldZoneLocation};
+use crate::creature::Creature;
+use crate::data::{DataStorage, PositionAndOrientation, TutorialFlags, WorldZoneLocation};
 use crate::handlers::login_handler::LogoutState;
 use crate::handlers::movement_handler::TeleportationState;
+use crate::item::item_container::BagContents;
 use crate::prelude::*;
-use crate::world::prelude::unit_flags::UnitFlagIndex;
+use crate::spell::AuraContainer;
+use crate::world::prelude::inventory::{BANK_ITEM_SLOTS_END, BANK_ITEM_SLOTS_START, MAX_BANK_BAG_SLOTS};
+use crate::world::prelude::unit_flags::{UnitFlagIndex, UNIT_NPC_FLAG_SPIRIT_HEALER};
 use bit_field::BitField;
 use smol::lock::RwLock;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, Weak};
+use std::time::{SystemTime, UNIX_EPOCH};
 use wow_world_messages::wrath::{
-    ActionButton, Area, Class, Gender, Map, MovementInfo, ObjectType, Power, Race, RelationType, UnitStandState, UpdateMask, UpdatePlayer,
+    Area, Class, DungeonDifficulty, Gender, Map, MovementInfo, ObjectType, Power, Race, RaidDifficulty, RelationType, UnitStandState, UpdateMask,
+    UpdatePlayer,
 };
 use wrath_realm_db::RealmDatabase;
 
+mod character_auras;
 mod character_cinematic;
+pub mod character_combat;
 mod character_database;
+pub mod character_death;
+mod character_durability;
+pub mod character_experience;
 mod character_first_login;
 pub mod character_inventory;
 mod character_logout;
+mod character_loot;
+mod character_money;
 mod character_movement;
+mod character_power;
+pub mod character_quests;
+pub mod character_reputation;
 mod character_rested;
+mod character_skills;
+pub mod character_spells;
+pub mod character_stats;
+mod character_talents;
+mod character_trainer;
+pub mod character_vendor;
+
+//Characters have to stand this close to a creature to talk to it
+const NPC_INTERACTION_RANGE: f32 = 10.0;
 
 pub struct Character {
     pub client: Weak<Client>,
@@ -33,15 +58,22 @@ pub struct Character {
     pub map: wow_world_messages::wrath::Map,
     pub area: wow_world_messages::wrath::Area,
     pub instance_id: u32,
+    pub dungeon_difficulty: DungeonDifficulty,
+    pub raid_difficulty: RaidDifficulty,
     pub bind_location: Option<WorldZoneLocation>,
     pub tutorial_flags: TutorialFlags,
-    pub action_bar: ActionBar,
 
     //Stuff to keep track of playtime
     pub seconds_played_total: u32,
     pub seconds_played_at_level: u32,
     pub last_playtime_calculation_timestamp: u32,
 
+    //Rested experience that is left, in experience points
+    rest_bonus: f32,
+
+    //Periodic saving
+    autosave_cooldown: f32,
+
     //required for world updates and implenting ReceiveUpdates trait
     pending_object_updates: Vec<wow_world_messages::wrath::Object>,
 
@@ -66,6 +98,32 @@ pub struct Character {
     //items
     pub equipped_items: GameplayCharacterInventory,
     pub bag_items: BagInventory,
+    pub bank_items: BagContents,
+    pub bank_bags: BagContents,
+    pub bank_bag_slots: u8,
+    //Items in the database that couldn't be loaded, saving leaves them alone so they aren't lost
+    unloaded_item_guids: Vec<u32>,
+
+    //Every skill the character knows, in the order of the skill fields
+    skills: Vec<character_skills::CharacterSkill>,
+
+    //spells
+    pub known_spells: HashSet<u32>,
+
+    //Reputation gained with each faction, by faction id
+    pub reputations: HashMap<u32, character_reputation::Reputation>,
+    spell_cast_state: character_spells::SpellCastState,
+    pub auras: AuraContainer,
+    melee_state: character_combat::MeleeState,
+    aura_modifiers: character_auras::AppliedAuraModifiers,
+    death_state: character_death::DeathState,
+    stats_state: character_stats::StatsState,
+    experience_state: character_experience::ExperienceState,
+    power_state: character_power::PowerState,
+    loot_state: character_loot::LootState,
+    quest_state: character_quests::QuestState,
+    buyback_state: character_vendor::BuybackState,
+    talent_state: character_talents::TalentState,
 }
 
 impl Character {
@@ -78,12 +136,15 @@ impl Character {
             map: Map::EasternKingdoms,
             area: Area::NorthshireAbbey,
             instance_id: 0,
+            dungeon_difficulty: DungeonDifficulty::default(),
+            raid_difficulty: RaidDifficulty::default(),
             bind_location: None,
             tutorial_flags: TutorialFlags::default(),
-            action_bar: ActionBar::new(),
             seconds_played_total: 0,
             seconds_played_at_level: 0,
             last_playtime_calculation_timestamp: 0,
+            rest_bonus: 0.0,
+            autosave_cooldown: 0f32,
             pending_object_updates: vec![],
             in_range_objects: HashMap::new(),
             recently_removed_guids: vec![],
@@ -96,19 +157,40 @@ impl Character {
             cinematic_state: character_cinematic::CharacterCinematicState::None,
             equipped_items: GameplayCharacterInventory::new(),
             bag_items: BagInventory::default(),
+            bank_items: BagContents::new(BANK_ITEM_SLOTS_END - BANK_ITEM_SLOTS_START + 1),
+            bank_bags: BagContents::new(MAX_BANK_BAG_SLOTS),
+            bank_bag_slots: 0,
+            unloaded_item_guids: vec![],
+            skills: Vec::new(),
+            known_spells: HashSet::new(),
+            reputations: HashMap::new(),
+            spell_cast_state: character_spells::SpellCastState::default(),
+            auras: AuraContainer::default(),
+            melee_state: character_combat::MeleeState::default(),
+            aura_modifiers: character_auras::AppliedAuraModifiers::default(),
+            death_state: character_death::DeathState::default(),
+            stats_state: character_stats::StatsState::default(),
+            experience_state: character_experience::ExperienceState::default(),
+            power_state: character_power::PowerState::default(),
+            loot_state: character_loot::LootState::default(),
+            quest_state: character_quests::QuestState::default(),
+            buyback_state: character_vendor::BuybackState::default(),
+            talent_state: character_talents::TalentState::default(),
         }
     }
 
     pub async fn load(client: Weak<Client>, guid: Guid, world: &World, data_storage: &DataStorage) -> Result<Self> {
         let mut character = Self::new(client, guid);
         character.load_from_database_internal(world, data_storage).await?;
+        character.autosave_cooldown = world.get_autosave_interval().as_secs_f32();
         Ok(character)
     }
 
-    pub async fn send_packets_before_add_to_map(&self) -> Result<()> {
+    pub async fn send_packets_before_add_to_map(&self, world: &World) -> Result<()> {
         handlers::send_contact_list(self, RelationType::empty().set_friend().set_ignored().set_muted().set_recruitafriend()).await?;
         handlers::send_bind_update(self).await?;
-        handlers::send_dungeon_difficulty(self).await?;
+        handlers::send_dungeon_difficulty(self, world).await?;
+        handlers::send_raid_difficulty(self, world).await?;
         handlers::send_action_buttons(self).await?;
         handlers::send_initial_world_states(self).await?;
         handlers::send_login_set_time_speed(self).await
@@ -124,14 +206,15 @@ impl Character {
         Ok(())
     }
 
-    pub async fn zone_update(&mut self, area: Area) -> Result<()> {
+    pub async fn zone_update(&mut self, data_storage: &DataStorage, area: Area) -> Result<()> {
         if self.area == area {
             return Ok(());
         }
 
         trace!("Received zone update for character {} into zone {}", self.name, area);
         self.area = area;
-        handlers::send_initial_world_states(self).await
+        handlers::send_initial_world_states(self).await?;
+        self.explore_area(data_storage, area).await
     }
 
     pub fn reset_time_sync(&mut self) {
@@ -142,7 +225,17 @@ impl Character {
     pub async fn tick(&mut self, delta_time: f32, world: Arc<World>) -> Result<()> {
         self.try_perform_first_time_login_if_required().await?;
         self.tick_time_sync(delta_time).await?;
+        self.tick_autosave(delta_time, &world).await;
         self.tick_logout_state(delta_time, world.clone()).await?;
+        self.tick_death(delta_time, &world).await?;
+        self.tick_spell_casting(delta_time, &world).await?;
+        self.tick_auras(delta_time, &world).await?;
+        self.tick_stats(&world.get_data_storage())?;
+        self.tick_rested(delta_time, &world.get_data_storage());
+        self.tick_power(delta_time, &world.get_data_storage())?;
+        self.tick_melee(delta_time, &world).await?;
+        self.tick_loot_awards(&world).await?;
+        self.tick_quests(&world.get_data_storage()).await?;
 
         self.handle_queued_teleport(world)
             .await
@@ -160,6 +253,25 @@ impl Character {
         Ok(())
     }
 
+    async fn tick_autosave(&mut self, delta_time: f32, world: &World) {
+        self.autosave_cooldown -= delta_time;
+        if self.autosave_cooldown < 0f32 {
+            self.autosave_cooldown = world.get_autosave_interval().as_secs_f32();
+            self.save_to_database(world)
+                .await
+                .unwrap_or_else(|e| warn!("Could not autosave character {}: Error {}", self.name, e));
+        }
+    }
+
+    //Adds the time passed since the last calculation to the playtime counters
+    pub fn update_played_time(&mut self) {
+        let unix_time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() as u32;
+        let delta_seconds = unix_time.saturating_sub(self.last_playtime_calculation_timestamp);
+        self.seconds_played_total += delta_seconds;
+        self.seconds_played_at_level += delta_seconds;
+        self.last_playtime_calculation_timestamp = unix_time;
+    }
+
     pub async fn try_get_self_arc(&self) -> Result<Arc<RwLock<Self>>> {
         let client = self
             .client
@@ -168,6 +280,34 @@ impl Character {
         client.get_active_character().await
     }
 
+    //Looks up a creature the character wants to talk to, which has to be close by and have one of the npc flags
+    pub async fn get_npc(&self, world: &World, guid: Guid, npc_flags: i32) -> Result<Arc<RwLock<Creature>>> {
+        let map = world
+            .get_instance_manager()
+            .try_get_map_for_character(self)
+            .await
+            .ok_or_else(|| anyhow!("Character {} is not on a map", self.name))?;
+        let creature_lock = map
+            .try_get_creature(guid)
+            .await
+            .ok_or_else(|| anyhow!("Creature {} is not on the map", guid))?;
+        {
+            let creature = creature_lock.read().await;
+            //Ghosts can only talk to spirit healers
+            let can_talk = match self.get_life_state() {
+                character_death::LifeState::Alive => true,
+                _ => npc_flags & UNIT_NPC_FLAG_SPIRIT_HEALER != 0,
+            };
+            let in_range = creature
+                .get_position()
+                .is_some_and(|position| position.distance_to(&self.get_position().unwrap_or_default()) <= NPC_INTERACTION_RANGE);
+            if !can_talk || !creature.is_alive() || !creature.has_npc_flag(npc_flags) || !in_range {
+                bail!("Character {} can't talk to {}", self.name, guid);
+            }
+        }
+        Ok(creature_lock)
+    }
+
     pub fn set_selection(&mut self, new_selection: Option<Guid>) {
         let guid = new_selection.unwrap_or_else(Guid::zero);
         self.gameplay_data.set_unit_target(guid);
@@ -211,17 +351,13 @@ impl Character {
         self.gameplay_data.set_player_features(a, b, action_bars, d);
     }
 
-    pub fn set_action_bar_button(&mut self, slot: u8, action_button: ActionButton) {
-        self.action_bar.set_action_button(slot as usize, action_button);
-    }
-
-    fn set_rested_bytes(&mut self, rested: bool) -> Result<()> {
-        let _value = match rested {
+    fn set_rested_bytes(&mut self, rested: bool) {
+        let value = match rested {
             true => 1,
             false => 2,
         };
-        //self.set_byte(PlayerFields::Bytes2 as usize, 3, value)
-        Ok(())
+        let (a, b, c, _) = self.gameplay_data.player_bytes_2().unwrap_or_default();
+        self.gameplay_data.set_player_bytes_2(a, b, c, value);
     }
 
     pub fn get_race(&self) -> Race {
@@ -240,6 +376,74 @@ impl Character {
         self.gameplay_data.unit_bytes_0().map_or(Power::Mana, |(_, _, _, power)| power)
     }
 
+    pub fn get_power(&self, power: Power) -> i32 {
+        let value = match power.as_int() {
+            0 => self.gameplay_data.unit_power1(),
+            1 => self.gameplay_data.unit_power2(),
+            2 => self.gameplay_data.unit_power3(),
+            3 => self.gameplay_data.unit_power4(),
+            4 => self.gameplay_data.unit_power5(),
+            5 => self.gameplay_data.unit_power6(),
+            6 => self.gameplay_data.unit_power7(),
+            _ => None,
+        };
+        value.unwrap_or(0)
+    }
+
+    pub fn get_max_power(&self, power: Power) -> i32 {
+        let value = match power.as_int() {
+            0 => self.gameplay_data.unit_maxpower1(),
+            1 => self.gameplay_data.unit_maxpower2(),
+            2 => self.gameplay_data.unit_maxpower3(),
+            3 => self.gameplay_data.unit_maxpower4(),
+            4 => self.gameplay_data.unit_maxpower5(),
+            5 => self.gameplay_data.unit_maxpower6(),
+            6 => self.gameplay_data.unit_maxpower7(),
+            _ => None,
+        };
+        value.unwrap_or(0)
+    }
+
+    //Clamped between 0 and the max power
+    pub fn set_power(&mut self, power: Power, value: i32) {
+        let value = value.clamp(0, self.get_max_power(power));
+        match power.as_int() {
+            0 => self.gameplay_data.set_unit_power1(value),
+            1 => self.gameplay_data.set_unit_power2(value),
+            2 => self.gameplay_data.set_unit_power3(value),
+            3 => self.gameplay_data.set_unit_power4(value),
+            4 => self.gameplay_data.set_unit_power5(value),
+            5 => self.gameplay_data.set_unit_power6(value),
+            6 => self.gameplay_data.set_unit_power7(value),
+            _ => {}
+        }
+    }
+
+    pub fn set_max_power(&mut self, power: Power, value: i32) {
+        match power.as_int() {
+            0 => self.gameplay_data.set_unit_maxpower1(value),
+            1 => self.gameplay_data.set_unit_maxpower2(value),
+            2 => self.gameplay_data.set_unit_maxpower3(value),
+            3 => self.gameplay_data.set_unit_maxpower4(value),
+            4 => self.gameplay_data.set_unit_maxpower5(value),
+            5 => self.gameplay_data.set_unit_maxpower6(value),
+            6 => self.gameplay_data.set_unit_maxpower7(value),
+            _ => {}
+        }
+    }
+
+    //Stats are strength, agility, stamina, intellect and spirit, in that order
+    pub fn set_stat(&mut self, stat: usize, value: i32) {
+        match stat {
+            0 => self.gameplay_data.set_unit_stat0(value),
+            1 => self.gameplay_data.set_unit_stat1(value),
+            2 => self.gameplay_data.set_unit_stat2(value),
+            3 => self.gameplay_data.set_unit_stat3(value),
+            4 => self.gameplay_data.set_unit_stat4(value),
+            _ => {}
+        }
+    }
+
     //-------------------
     //END STUFF THAT NEEDS TO MOVE TO UpdateMaskExt
     //-------------------
@@ -273,6 +477,14 @@ impl GameObject for Character {
         Some(self)
     }
 
+    fn as_unit(&self) -> Option<&dyn Unit> {
+        Some(self)
+    }
+
+    fn as_unit_mut(&mut self) -> Option<&mut dyn Unit> {
+        Some(self)
+    }
+
     fn get_position(&self) -> Option<PositionAndOrientation> {
         Some(PositionAndOrientation {
             position: self.movement_info.position,
@@ -325,6 +537,59 @@ impl GameObject for Character {
     }
 }
 
+impl Unit for Character {
+    fn get_level(&self) -> u32 {
+        self.gameplay_data.unit_level().unwrap_or(1) as u32
+    }
+
+    fn get_armor(&self) -> u32 {
+        self.get_derived_stats().armor as u32
+    }
+
+    fn get_faction_template(&self) -> u32 {
+        self.gameplay_data.unit_factiontemplate().unwrap_or(0).max(0) as u32
+    }
+
+    fn get_health(&self) -> u32 {
+        self.gameplay_data.unit_health().unwrap_or(0).max(0) as u32
+    }
+
+    fn get_max_health(&self) -> u32 {
+        self.gameplay_data.unit_maxhealth().unwrap_or(0).max(0) as u32
+    }
+
+    fn set_health(&mut self, health: u32) {
+        let health = health.min(self.get_max_health());
+        //All damage taken ends up here, and wears down the equipment
+        if health < self.get_health() {
+            self.roll_armor_durability_loss()
+                .unwrap_or_else(|e| warn!("Could not damage the equipment of {}: Error {}", self.name, e));
+        }
+        self.gameplay_data.set_unit_health(health as i32);
+    }
+
+    fn get_power(&self, power: Power) -> i32 {
+        Character::get_power(self, power)
+    }
+
+    fn set_power(&mut self, power: Power, value: i32) {
+        Character::set_power(self, power, value)
+    }
+
+    fn get_auras(&self) -> &AuraContainer {
+        &self.auras
+    }
+
+    fn get_auras_mut(&mut self) -> &mut AuraContainer {
+        &mut self.auras
+    }
+
+    //Ghosts are kept at 1 health, but they are not alive
+    fn is_alive(&self) -> bool {
+        self.get_life_state() == character_death::LifeState::Alive && self.get_health() > 0
+    }
+}
+
 #[async_trait::async_trait]
 impl ReceiveUpdates for Character {
     fn push_object_update(&mut self, object_update: wow_world_messages::wrath::Object) {
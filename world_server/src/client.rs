@@ -154,7 +154,15 @@ impl Client {
         }
         self.read_socket.write().await.shutdown(smol::net::Shutdown::Both)?;
         self.write_socket.lock().await.shutdown(std::net::Shutdown::Both)?;
-        //Save character to db?
+        //The active character is saved during cleanup, see ClientManager::cleanup_disconnected_clients
+        Ok(())
+    }
+
+    pub async fn save_active_character(&self, world: &World) -> Result<()> {
+        let active_character = self.data.read().await.active_character.clone();
+        if let Some(character_lock) = active_character {
+            character_lock.write().await.save_to_database(world).await?;
+        }
         Ok(())
     }
 
@@ -201,12 +209,13 @@ impl Client {
     pub async fn login_active_character(&self, world: &World) -> Result<()> {
         let data = self.data.read().await;
         let character_lock = data.active_character.as_ref().unwrap();
-        let character = character_lock.read().await;
-        character.send_packets_before_add_to_map().await?;
+        let mut character = character_lock.write().await;
+        character.send_packets_before_add_to_map(world).await?;
 
+        let map = character.map;
         world
             .get_instance_manager()
-            .get_or_create_map(&(*character), character.map)
+            .get_or_create_map(&mut character, map)
             .await?
             .push_object(Arc::downgrade(character_lock))
             .await;
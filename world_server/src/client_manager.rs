@@ -39,8 +39,9 @@ impl ClientManager {
     }
 
     async fn cleanup_disconnected_clients(&self, world: Arc<World>) -> Result<()> {
-        let to_remove = {
+        let (to_remove, departed_characters) = {
             let mut result = vec![];
+            let mut departed_characters = vec![];
             let clients = self.clients.read().await;
             for (id, client) in clients.iter() {
                 //Cleanup is two-staged. Sockets are already closed here, but we take this frame to
@@ -50,7 +51,14 @@ impl ClientManager {
                     data.client_state.clone()
                 };
                 if client_state == ClientState::DisconnectPendingCleanup {
+                    client
+                        .save_active_character(&world)
+                        .await
+                        .unwrap_or_else(|e| warn!("Could not save character of disconnected client: Error {}", e));
                     world.get_instance_manager().handle_client_disconnected(client).await?;
+                    if let Ok(character_lock) = client.get_active_character().await {
+                        departed_characters.push(character_lock.read().await.get_guid());
+                    }
                     //insert more cleanup actions here
                     client.disconnected_post_cleanup().await?;
                 } else if client_state == ClientState::Disconnected {
@@ -59,8 +67,17 @@ impl ClientManager {
                     result.push(*id);
                 }
             }
-            result
+            (result, departed_characters)
         };
+
+        //Their group is told once the clients are unlocked again, finding the other members needs them
+        for guid in departed_characters {
+            world.get_group_manager().remove_invites(guid).await;
+            handlers::leave_group(self, &world, guid)
+                .await
+                .unwrap_or_else(|e| warn!("Could not remove disconnected character from their group: Error {}", e));
+        }
+
         if to_remove.is_empty() {
             return Ok(());
         }
@@ -72,6 +89,16 @@ impl ClientManager {
         Ok(())
     }
 
+    pub async fn save_all_characters(&self, world: &World) {
+        let clients = self.clients.read().await;
+        for (_, client) in clients.iter() {
+            client
+                .save_active_character(world)
+                .await
+                .unwrap_or_else(|e| warn!("Could not save character during shutdown: Error {}", e));
+        }
+    }
+
     pub async fn accept_realm_connections(&self, packet_handle_sender: Sender<PacketToHandle>) -> Result<()> {
         let realm_id: i32 = std::env::var("REALM_ID")?.parse()?;
         let bind_ip = self.auth_db.get_realm_bind_ip(realm_id).await?;
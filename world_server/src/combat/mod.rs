@@ -0,0 +1,138 @@
+//Chances in percent for an attack against a unit of the same level
+const BASE_MISS_CHANCE: f32 = 5.0;
+const BASE_DODGE_CHANCE: f32 = 5.0;
+const BASE_PARRY_CHANCE: f32 = 5.0;
+const BASE_CRIT_CHANCE: f32 = 5.0;
+//Every level the victim is above the attacker shifts the chances by this much
+const AVOIDANCE_PER_LEVEL: f32 = 0.5;
+const CRIT_PER_LEVEL: f32 = 0.2;
+
+const CRIT_DAMAGE_MULTIPLIER: u32 = 2;
+const MAX_ARMOR_REDUCTION: f32 = 0.75;
+
+//Rage is stored ten times larger than the client shows it
+const RAGE_PER_POINT: f32 = 10.0;
+const RAGE_HIT_FACTOR: f32 = 3.5;
+const RAGE_CRIT_FACTOR: f32 = 7.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MeleeHitOutcome {
+    Miss,
+    Dodge,
+    Parry,
+    Crit,
+    Hit,
+}
+
+impl MeleeHitOutcome {
+    pub fn deals_damage(&self) -> bool {
+        matches!(self, MeleeHitOutcome::Hit | MeleeHitOutcome::Crit)
+    }
+}
+
+//A single roll on the attack table, roll goes from 0 to 100
+pub fn roll_melee_outcome(attacker_level: u32, victim_level: u32, roll: f32) -> MeleeHitOutcome {
+    let level_difference = victim_level as f32 - attacker_level as f32;
+    let avoidance_bonus = level_difference * AVOIDANCE_PER_LEVEL;
+
+    let miss = (BASE_MISS_CHANCE + avoidance_bonus).max(0.0);
+    let dodge = miss + (BASE_DODGE_CHANCE + avoidance_bonus).max(0.0);
+    let parry = dodge + (BASE_PARRY_CHANCE + avoidance_bonus).max(0.0);
+    let crit = parry + (BASE_CRIT_CHANCE - level_difference * CRIT_PER_LEVEL).max(0.0);
+
+    if roll < miss {
+        MeleeHitOutcome::Miss
+    } else if roll < dodge {
+        MeleeHitOutcome::Dodge
+    } else if roll < parry {
+        MeleeHitOutcome::Parry
+    } else if roll < crit {
+        MeleeHitOutcome::Crit
+    } else {
+        MeleeHitOutcome::Hit
+    }
+}
+
+pub fn get_damage_for_outcome(outcome: MeleeHitOutcome, rolled_damage: u32) -> u32 {
+    match outcome {
+        MeleeHitOutcome::Crit => rolled_damage * CRIT_DAMAGE_MULTIPLIER,
+        MeleeHitOutcome::Hit => rolled_damage,
+        _ => 0,
+    }
+}
+
+//Fraction of physical damage that armor takes away, depending on the level of the attacker
+pub fn get_armor_reduction(armor: u32, attacker_level: u32) -> f32 {
+    let level = attacker_level as f32;
+    let level_factor = if attacker_level < 60 {
+        400.0 + 85.0 * level
+    } else {
+        400.0 + 85.0 * level + 4.5 * 85.0 * (level - 59.0)
+    };
+    let armor = armor as f32;
+    (armor / (armor + level_factor)).clamp(0.0, MAX_ARMOR_REDUCTION)
+}
+
+pub fn mitigate_damage_by_armor(damage: u32, armor: u32, attacker_level: u32) -> u32 {
+    let reduction = get_armor_reduction(armor, attacker_level);
+    let mitigated = (damage as f32 * (1.0 - reduction)).round().max(1.0) as u32;
+    mitigated.min(damage)
+}
+
+//Rage for dealing melee damage, slower weapons make up for hitting less often
+pub fn get_rage_from_damage(damage: u32, attacker_level: u32, swing_time: f32, outcome: MeleeHitOutcome) -> i32 {
+    if !outcome.deals_damage() {
+        return 0;
+    }
+    let level = attacker_level as f32;
+    let conversion = 0.0091107836 * level * level + 3.225598133 * level + 4.2652911;
+    let hit_factor = match outcome {
+        MeleeHitOutcome::Crit => RAGE_CRIT_FACTOR,
+        _ => RAGE_HIT_FACTOR,
+    };
+    let damage_rage = 7.5 * damage as f32 / conversion;
+    let rage = (damage_rage + hit_factor * swing_time / 2.0).min(damage_rage * 2.0);
+    (rage * RAGE_PER_POINT).round() as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn attack_table_against_same_level() {
+        assert_eq!(roll_melee_outcome(10, 10, 0.0), MeleeHitOutcome::Miss);
+        assert_eq!(roll_melee_outcome(10, 10, 7.0), MeleeHitOutcome::Dodge);
+        assert_eq!(roll_melee_outcome(10, 10, 12.0), MeleeHitOutcome::Parry);
+        assert_eq!(roll_melee_outcome(10, 10, 17.0), MeleeHitOutcome::Crit);
+        assert_eq!(roll_melee_outcome(10, 10, 20.0), MeleeHitOutcome::Hit);
+    }
+
+    #[test]
+    fn higher_level_victims_avoid_more() {
+        //5 levels higher means 7.5% to miss instead of 5%
+        assert_eq!(roll_melee_outcome(10, 15, 6.0), MeleeHitOutcome::Miss);
+        //And 5 levels lower means 2.5%
+        assert_eq!(roll_melee_outcome(15, 10, 3.0), MeleeHitOutcome::Dodge);
+    }
+
+    #[test]
+    fn armor_mitigation() {
+        assert_eq!(mitigate_damage_by_armor(100, 0, 10), 100);
+        //1250 armor at level 10 is exactly half of the damage
+        assert_eq!(mitigate_damage_by_armor(100, 1250, 10), 50);
+        //Never more than the cap, and a hit always does at least one damage
+        assert_eq!(mitigate_damage_by_armor(100, 1_000_000, 80), 25);
+        assert_eq!(mitigate_damage_by_armor(1, 1_000_000, 80), 1);
+    }
+
+    #[test]
+    fn rage_from_damage() {
+        assert_eq!(get_rage_from_damage(100, 10, 2.0, MeleeHitOutcome::Miss), 0);
+        //Small hits are capped at twice the rage of the damage alone
+        assert_eq!(get_rage_from_damage(1, 80, 3.6, MeleeHitOutcome::Hit), 0);
+        let hit = get_rage_from_damage(20, 10, 2.0, MeleeHitOutcome::Hit);
+        let crit = get_rage_from_damage(20, 10, 2.0, MeleeHitOutcome::Crit);
+        assert!(hit > 0 && crit > hit);
+    }
+}
@@ -2,8 +2,18 @@ use wow_world_messages::wrath::InventoryType;
 
 pub const EQUIPMENT_SLOTS_START: u8 = 0;
 pub const EQUIPMENT_SLOTS_END: u8 = 18;
-pub const _BAG_SLOTS_START: u8 = 19;
+pub const BAG_SLOTS_START: u8 = 19;
 pub const BAG_SLOTS_END: u8 = 22;
+pub const BANK_ITEM_SLOTS_START: u8 = 39;
+pub const BANK_ITEM_SLOTS_END: u8 = 66;
+pub const BANK_BAG_SLOTS_START: u8 = 67;
+pub const BANK_BAG_SLOTS_END: u8 = 73;
+pub const MAX_BANK_BAG_SLOTS: u8 = BANK_BAG_SLOTS_END - BANK_BAG_SLOTS_START + 1;
+
+//Bits of the item template flags
+pub const ITEM_FLAG_HAS_LOOT: u32 = 0x4;
+pub const ITEM_FLAG_UNIQUE_EQUIPPABLE: u32 = 0x80000;
+
 #[derive(Eq, PartialEq, Hash, Debug, Clone, Copy)]
 pub enum EquipmentSlot {
     Head = 0,
@@ -81,7 +91,8 @@ pub const fn get_compatible_equipment_slots_for_inventory_type(inventory_type: &
         InventoryType::Thrown => &[EquipmentSlot::Ranged],
         InventoryType::RangedRight => &[EquipmentSlot::Ranged],
         InventoryType::Relic => &[EquipmentSlot::Ranged],
-        _ => unimplemented!(),
+        InventoryType::Quiver => &[EquipmentSlot::Bag1, EquipmentSlot::Bag2, EquipmentSlot::Bag3, EquipmentSlot::Bag4],
+        _ => &[],
     }
 }
 
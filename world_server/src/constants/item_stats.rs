@@ -0,0 +1,10 @@
+//Stat types of the stats granted by item templates
+pub const ITEM_MOD_MANA: u8 = 0;
+pub const ITEM_MOD_HEALTH: u8 = 1;
+pub const ITEM_MOD_AGILITY: u8 = 3;
+pub const ITEM_MOD_STRENGTH: u8 = 4;
+pub const ITEM_MOD_INTELLECT: u8 = 5;
+pub const ITEM_MOD_SPIRIT: u8 = 6;
+pub const ITEM_MOD_STAMINA: u8 = 7;
+pub const ITEM_MOD_ATTACK_POWER: u8 = 38;
+pub const ITEM_MOD_SPELL_POWER: u8 = 45;
@@ -1,4 +1,7 @@
 pub mod cinematic;
 pub mod factions;
 pub mod inventory;
+pub mod item_stats;
+pub mod player_flags;
+pub mod spell;
 pub mod unit_flags;
@@ -0,0 +1,6 @@
+//Bits of PLAYER_FLAGS
+pub const PLAYER_FLAGS_GHOST: i32 = 0x10;
+pub const PLAYER_FLAGS_RESTING: i32 = 0x20;
+
+//Bits of the flags shown on the character select screen
+pub const CHARACTER_FLAG_GHOST: u32 = 0x2000;
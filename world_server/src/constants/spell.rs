@@ -0,0 +1,31 @@
+//Spell.dbc InterruptFlags
+pub const SPELL_INTERRUPT_FLAG_MOVEMENT: u32 = 0x01;
+
+//Spell.dbc Effect ids
+pub const SPELL_EFFECT_SCHOOL_DAMAGE: u32 = 2;
+pub const SPELL_EFFECT_TELEPORT_UNITS: u32 = 5;
+pub const SPELL_EFFECT_APPLY_AURA: u32 = 6;
+pub const SPELL_EFFECT_HEAL: u32 = 10;
+pub const SPELL_EFFECT_CREATE_ITEM: u32 = 24;
+pub const SPELL_EFFECT_SUMMON: u32 = 28;
+pub const SPELL_EFFECT_ENERGIZE: u32 = 30;
+pub const SPELL_EFFECT_LEARN_SPELL: u32 = 36;
+pub const SPELL_EFFECT_ACTIVATE_TALENT_SPEC: u32 = 162;
+
+//Spell.dbc EffectImplicitTargetA and EffectImplicitTargetB
+pub const SPELL_TARGET_UNIT_CASTER: u32 = 1;
+pub const SPELL_TARGET_UNIT_TARGET_ENEMY: u32 = 6;
+pub const SPELL_TARGET_DEST_HOME: u32 = 9;
+
+//Spell.dbc Attributes
+pub const SPELL_ATTRIBUTE_CANT_CANCEL: u32 = 0x8000_0000;
+
+//Spell.dbc EffectAura ids
+pub const SPELL_AURA_PERIODIC_DAMAGE: u32 = 3;
+pub const SPELL_AURA_PERIODIC_HEAL: u32 = 8;
+pub const SPELL_AURA_MOD_STUN: u32 = 12;
+pub const SPELL_AURA_PERIODIC_ENERGIZE: u32 = 24;
+pub const SPELL_AURA_MOD_ROOT: u32 = 26;
+pub const SPELL_AURA_MOD_STAT: u32 = 29;
+pub const SPELL_AURA_MOD_INCREASE_SPEED: u32 = 31;
+pub const SPELL_AURA_MOD_DECREASE_SPEED: u32 = 33;
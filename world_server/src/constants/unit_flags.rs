@@ -70,6 +70,18 @@ pub enum UnitFlagIndex {
     //Unk31 = 0x80000000,
 }
 
+//Bits of UNIT_DYNAMIC_FLAGS
+pub const UNIT_DYNAMIC_FLAG_LOOTABLE: i32 = 0x1;
+pub const UNIT_DYNAMIC_FLAG_DEAD: i32 = 0x20;
+
+//Bits of UNIT_NPC_FLAGS
+pub const UNIT_NPC_FLAG_GOSSIP: i32 = 0x1;
+pub const UNIT_NPC_FLAG_TRAINER: i32 = 0x10;
+pub const UNIT_NPC_FLAG_VENDOR: i32 = 0x80;
+pub const UNIT_NPC_FLAG_REPAIR: i32 = 0x1000;
+pub const UNIT_NPC_FLAG_SPIRIT_HEALER: i32 = 0x4000;
+pub const UNIT_NPC_FLAG_BANKER: i32 = 0x20000;
+
 #[test]
 fn test_unit_flags_indices() {
     assert_eq!(1 << (UnitFlagIndex::Unk0 as usize), UnitFlags::Unk0 as usize);
@@ -0,0 +1,121 @@
+use crate::character::Character;
+use crate::data::PositionAndOrientation;
+use crate::prelude::*;
+use crate::world::prelude::*;
+use smol::lock::RwLock;
+use std::collections::HashMap;
+use std::sync::Weak;
+use wow_world_messages::wrath::{MovementInfo, ObjectType, UpdateCorpse, UpdateMask};
+
+//What a character leaves behind after releasing its spirit
+pub struct Corpse {
+    pub gameplay_data: UpdateCorpse,
+    pub movement_info: MovementInfo,
+
+    //things required make GameObject working
+    in_range_objects: HashMap<Guid, Weak<RwLock<dyn GameObject>>>,
+}
+
+impl Corpse {
+    pub fn new(guid: Guid, owner: Guid, display_id: i32, position: &PositionAndOrientation) -> Self {
+        let gameplay_data = UpdateCorpse::builder()
+            .set_object_guid(guid)
+            .set_object_scale_x(1.0)
+            .set_corpse_owner(owner)
+            .set_corpse_display_id(display_id)
+            .finalize();
+
+        Self {
+            gameplay_data,
+            movement_info: MovementInfo {
+                position: position.position,
+                orientation: position.orientation,
+                ..Default::default()
+            },
+            in_range_objects: HashMap::new(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl GameObject for Corpse {
+    fn get_guid(&self) -> Guid {
+        self.gameplay_data.object_guid().unwrap()
+    }
+
+    fn get_type(&self) -> ObjectType {
+        ObjectType::Corpse
+    }
+
+    fn get_update_mask(&self) -> UpdateMask {
+        UpdateMask::Corpse(self.gameplay_data.clone())
+    }
+
+    fn clear_update_mask_header(&mut self) {
+        self.gameplay_data.dirty_reset();
+    }
+
+    async fn on_pushed_to_map(&mut self, _map_manager: &MapManager) -> Result<()> {
+        Ok(())
+    }
+
+    fn as_character(&self) -> Option<&Character> {
+        None
+    }
+
+    fn as_unit(&self) -> Option<&dyn Unit> {
+        None
+    }
+
+    fn as_unit_mut(&mut self) -> Option<&mut dyn Unit> {
+        None
+    }
+
+    fn get_position(&self) -> Option<PositionAndOrientation> {
+        Some(PositionAndOrientation {
+            position: self.movement_info.position,
+            orientation: self.movement_info.orientation,
+        })
+    }
+
+    fn get_movement_info(&self) -> &MovementInfo {
+        &self.movement_info
+    }
+
+    fn is_in_range(&self, guid: Guid) -> bool {
+        self.in_range_objects.contains_key(&guid)
+    }
+
+    fn add_in_range_object(&mut self, guid: Guid, object: Weak<RwLock<dyn GameObject>>) -> Result<()> {
+        assert!(!self.is_in_range(guid));
+        self.in_range_objects.insert(guid, object);
+        Ok(())
+    }
+
+    fn get_in_range_guids(&self) -> Vec<Guid> {
+        self.in_range_objects.keys().copied().collect()
+    }
+
+    fn remove_in_range_object(&mut self, guid: Guid) -> Result<()> {
+        self.in_range_objects.remove(&guid);
+        Ok(())
+    }
+
+    fn clear_in_range_objects(&mut self) {
+        self.in_range_objects.clear();
+    }
+
+    fn get_recently_removed_range_guids(&self) -> &[Guid] {
+        &[]
+    }
+
+    fn clear_recently_removed_range_guids(&mut self) {}
+
+    fn as_update_receiver(&self) -> Option<&dyn ReceiveUpdates> {
+        None
+    }
+
+    fn as_update_receiver_mut(&mut self) -> Option<&mut dyn ReceiveUpdates> {
+        None
+    }
+}
@@ -0,0 +1,321 @@
+use crate::character::Character;
+use crate::data::{DataStorage, PositionAndOrientation};
+use crate::loot::Loot;
+use crate::prelude::*;
+use crate::spell::{self, AuraContainer};
+use crate::world::prelude::spell::SPELL_AURA_MOD_STUN;
+use crate::world::prelude::unit_flags::{UnitFlagIndex, UNIT_DYNAMIC_FLAG_DEAD, UNIT_DYNAMIC_FLAG_LOOTABLE};
+use crate::world::prelude::*;
+use bit_field::BitField;
+use rand::Rng;
+use smol::lock::RwLock;
+use std::collections::HashMap;
+use std::sync::Weak;
+use wow_world_messages::wrath::{Class, MovementInfo, ObjectType, Power, UpdateMask, UpdateUnit};
+use wrath_realm_db::creature::DBCreatureTemplate;
+use wrath_realm_db::vendor::DBVendorItem;
+
+//Supply of a limited vendor item that has been bought from
+struct VendorStock {
+    remaining: u32,
+    //Seconds until the supply is back to the max
+    restock_timer: f32,
+}
+
+pub struct Creature {
+    pub gameplay_data: UpdateUnit,
+    pub movement_info: MovementInfo,
+    pub auras: AuraContainer,
+    is_dead: bool,
+    loot_id: u32,
+    money_range: (u32, u32),
+    loot: Option<Loot>,
+    gossip_menu_id: u32,
+    //Only characters of this class can train here, 0 for everyone
+    trainer_class: u8,
+    //By item id, items that were never bought or have been restocked aren't in here
+    vendor_stock: HashMap<u32, VendorStock>,
+
+    //things required make GameObject working
+    in_range_objects: HashMap<Guid, Weak<RwLock<dyn GameObject>>>,
+}
+
+impl Creature {
+    pub fn new(guid: Guid, position: &PositionAndOrientation, template: &DBCreatureTemplate) -> Self {
+        let level = rand::thread_rng().gen_range(template.min_level..=template.max_level.max(template.min_level));
+        let health = if template.max_level > template.min_level {
+            let level_fraction = (level - template.min_level) as f32 / (template.max_level - template.min_level) as f32;
+            template.min_health + ((template.max_health.saturating_sub(template.min_health)) as f32 * level_fraction) as u32
+        } else {
+            template.max_health
+        };
+
+        let gameplay_data = UpdateUnit::builder()
+            .set_object_guid(guid)
+            .set_object_entry(template.entry as i32)
+            .set_object_scale_x(template.scale)
+            .set_unit_health(health as i32)
+            .set_unit_maxhealth(health as i32)
+            .set_unit_level(level as i32)
+            .set_unit_factiontemplate(template.faction as i32)
+            .set_unit_displayid(template.display_id as i32)
+            .set_unit_nativedisplayid(template.display_id as i32)
+            .set_unit_npc_flags(template.npc_flags as i32)
+            .set_unit_flags(template.unit_flags as i32)
+            .finalize();
+
+        Self {
+            gameplay_data,
+            movement_info: MovementInfo {
+                position: position.position,
+                orientation: position.orientation,
+                ..Default::default()
+            },
+            auras: AuraContainer::default(),
+            is_dead: false,
+            loot_id: template.loot_id,
+            money_range: (template.min_gold, template.max_gold),
+            loot: None,
+            gossip_menu_id: template.gossip_menu_id,
+            trainer_class: template.trainer_class,
+            vendor_stock: HashMap::new(),
+            in_range_objects: HashMap::new(),
+        }
+    }
+
+    pub async fn tick(&mut self, delta_time: f32, map: &MapManager, data_storage: &DataStorage) -> Result<()> {
+        //Whoever dealt the killing blow, this is where the creature notices that it died
+        if !self.is_alive() && !self.is_dead {
+            self.die(data_storage);
+        }
+
+        self.vendor_stock.retain(|_, stock| {
+            stock.restock_timer -= delta_time;
+            stock.restock_timer > 0.0
+        });
+
+        for tick in self.auras.tick(delta_time) {
+            spell::apply_periodic_aura_tick(self, &tick);
+        }
+
+        let changed_slots = self.auras.take_changed_slots();
+        if !changed_slots.is_empty() {
+            let stunned = self.auras.has_aura_type(SPELL_AURA_MOD_STUN);
+            let mut unit_flags = self.gameplay_data.unit_flags().unwrap_or(0);
+            unit_flags.set_bit(UnitFlagIndex::Stunned as usize, stunned);
+            self.gameplay_data.set_unit_flags(unit_flags);
+
+            handlers::send_aura_updates(&*self, &self.auras, &changed_slots, map).await?;
+        }
+        Ok(())
+    }
+
+    fn die(&mut self, data_storage: &DataStorage) {
+        self.is_dead = true;
+        self.auras.clear();
+        let dynamic_flags = self.gameplay_data.unit_dynamic_flags().unwrap_or(0);
+        self.gameplay_data.set_unit_dynamic_flags(dynamic_flags | UNIT_DYNAMIC_FLAG_DEAD);
+
+        //Kills by characters hand out the loot right away, anything else leaves it to whoever comes by
+        if self.loot.is_none() {
+            let loot = self.generate_loot(data_storage);
+            self.set_loot(loot);
+        }
+    }
+
+    pub fn generate_loot(&self, data_storage: &DataStorage) -> Loot {
+        let template = data_storage.get_creature_loot_template(self.loot_id).unwrap_or_default();
+        Loot::generate(template, self.money_range.0, self.money_range.1)
+    }
+
+    pub fn set_loot(&mut self, loot: Loot) {
+        self.set_lootable(!loot.is_empty());
+        self.loot = Some(loot);
+    }
+
+    fn set_lootable(&mut self, lootable: bool) {
+        let dynamic_flags = self.gameplay_data.unit_dynamic_flags().unwrap_or(0);
+        let dynamic_flags = if lootable {
+            dynamic_flags | UNIT_DYNAMIC_FLAG_LOOTABLE
+        } else {
+            dynamic_flags & !UNIT_DYNAMIC_FLAG_LOOTABLE
+        };
+        self.gameplay_data.set_unit_dynamic_flags(dynamic_flags);
+    }
+
+    pub fn can_be_looted_by(&self, looter: Guid) -> bool {
+        self.loot.as_ref().is_some_and(|loot| loot.can_be_opened_by(looter))
+    }
+
+    pub fn get_loot_mut(&mut self) -> Option<&mut Loot> {
+        self.loot.as_mut()
+    }
+
+    pub fn has_npc_flag(&self, npc_flag: i32) -> bool {
+        self.gameplay_data.unit_npc_flags().unwrap_or(0) & npc_flag != 0
+    }
+
+    pub fn get_gossip_menu_id(&self) -> u32 {
+        self.gossip_menu_id
+    }
+
+    pub fn can_train(&self, class: Class) -> bool {
+        self.trainer_class == 0 || self.trainer_class == class.as_int()
+    }
+
+    //How many are left of an item in limited supply, None if the vendor never runs out
+    pub fn get_vendor_item_count(&self, vendor_item: &DBVendorItem) -> Option<u32> {
+        (vendor_item.max_count > 0).then(|| {
+            self.vendor_stock
+                .get(&vendor_item.item)
+                .map_or(vendor_item.max_count as u32, |stock| stock.remaining)
+        })
+    }
+
+    //The restock timer starts with the first purchase, and brings back the whole supply at once
+    pub fn take_vendor_items(&mut self, vendor_item: &DBVendorItem, count: u32) {
+        if vendor_item.max_count == 0 {
+            return;
+        }
+        let stock = self.vendor_stock.entry(vendor_item.item).or_insert(VendorStock {
+            remaining: vendor_item.max_count as u32,
+            restock_timer: vendor_item.restock_time as f32,
+        });
+        stock.remaining = stock.remaining.saturating_sub(count);
+    }
+
+    //Once everything is taken the corpse stops sparkling
+    pub fn on_loot_released(&mut self) {
+        if self.loot.as_ref().is_some_and(Loot::is_empty) {
+            self.loot = None;
+            self.set_lootable(false);
+        }
+    }
+}
+
+impl Unit for Creature {
+    fn get_level(&self) -> u32 {
+        self.gameplay_data.unit_level().unwrap_or(1) as u32
+    }
+
+    //TODO: creature templates don't have armor yet
+    fn get_armor(&self) -> u32 {
+        0
+    }
+
+    fn get_faction_template(&self) -> u32 {
+        self.gameplay_data.unit_factiontemplate().unwrap_or(0).max(0) as u32
+    }
+
+    fn get_health(&self) -> u32 {
+        self.gameplay_data.unit_health().unwrap_or(0).max(0) as u32
+    }
+
+    fn get_max_health(&self) -> u32 {
+        self.gameplay_data.unit_maxhealth().unwrap_or(0).max(0) as u32
+    }
+
+    fn set_health(&mut self, health: u32) {
+        let health = health.min(self.get_max_health());
+        self.gameplay_data.set_unit_health(health as i32);
+    }
+
+    //TODO: creatures don't have power yet
+    fn get_power(&self, _power: Power) -> i32 {
+        0
+    }
+
+    fn set_power(&mut self, _power: Power, _value: i32) {}
+
+    fn get_auras(&self) -> &AuraContainer {
+        &self.auras
+    }
+
+    fn get_auras_mut(&mut self) -> &mut AuraContainer {
+        &mut self.auras
+    }
+}
+
+#[async_trait::async_trait]
+impl GameObject for Creature {
+    fn get_guid(&self) -> Guid {
+        self.gameplay_data.object_guid().unwrap()
+    }
+
+    fn get_type(&self) -> ObjectType {
+        ObjectType::Unit
+    }
+
+    fn get_update_mask(&self) -> UpdateMask {
+        UpdateMask::Unit(self.gameplay_data.clone())
+    }
+
+    fn clear_update_mask_header(&mut self) {
+        self.gameplay_data.dirty_reset();
+    }
+
+    async fn on_pushed_to_map(&mut self, _map_manager: &MapManager) -> Result<()> {
+        Ok(())
+    }
+
+    fn as_character(&self) -> Option<&Character> {
+        None
+    }
+
+    fn as_unit(&self) -> Option<&dyn Unit> {
+        Some(self)
+    }
+
+    fn as_unit_mut(&mut self) -> Option<&mut dyn Unit> {
+        Some(self)
+    }
+
+    fn get_position(&self) -> Option<PositionAndOrientation> {
+        Some(PositionAndOrientation {
+            position: self.movement_info.position,
+            orientation: self.movement_info.orientation,
+        })
+    }
+
+    fn get_movement_info(&self) -> &MovementInfo {
+        &self.movement_info
+    }
+
+    fn is_in_range(&self, guid: Guid) -> bool {
+        self.in_range_objects.contains_key(&guid)
+    }
+
+    fn add_in_range_object(&mut self, guid: Guid, object: Weak<RwLock<dyn GameObject>>) -> Result<()> {
+        assert!(!self.is_in_range(guid));
+        self.in_range_objects.insert(guid, object);
+        Ok(())
+    }
+
+    fn get_in_range_guids(&self) -> Vec<Guid> {
+        self.in_range_objects.keys().copied().collect()
+    }
+
+    fn remove_in_range_object(&mut self, guid: Guid) -> Result<()> {
+        //Creatures don't receive out-of-range updates, so no need to remember removed guids
+        self.in_range_objects.remove(&guid);
+        Ok(())
+    }
+
+    fn clear_in_range_objects(&mut self) {
+        self.in_range_objects.clear();
+    }
+
+    fn get_recently_removed_range_guids(&self) -> &[Guid] {
+        &[]
+    }
+
+    fn clear_recently_removed_range_guids(&mut self) {}
+
+    fn as_update_receiver(&self) -> Option<&dyn ReceiveUpdates> {
+        None
+    }
+
+    fn as_update_receiver_mut(&mut self) -> Option<&mut dyn ReceiveUpdates> {
+        None
+    }
+}
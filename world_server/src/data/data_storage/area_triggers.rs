@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use wow_dbc::{
@@ -26,6 +27,8 @@ pub enum AreaTriggerShape {
 pub enum AreaTriggerPurpose {
     Teleport(DBAreaTriggerTeleport),
     RestedArea,
+    //Entering it counts as exploring the area this quest sends the character to
+    Quest(u32),
     Unknown,
 }
 
@@ -45,6 +48,13 @@ impl super::DataStorage {
         let mut area_triggers_local: Option<wow_dbc::wrath_tables::area_trigger::AreaTrigger> = None;
         super::load_standard_dbc(dbc_path, &mut area_triggers_local).await?;
 
+        let quest_triggers: HashMap<u32, u32> = realm_db
+            .get_all_areatrigger_quest_relations()
+            .await?
+            .into_iter()
+            .map(|relation| (relation.id, relation.quest))
+            .collect();
+
         if let Some(area_triggers_local) = area_triggers_local {
             for areatrigger in area_triggers_local.rows().iter() {
                 let shape = if areatrigger.radius > 0.0 {
@@ -62,6 +72,8 @@ impl super::DataStorage {
                     AreaTriggerPurpose::Teleport(teleport_data)
                 } else if let Ok(_rested_area_data) = realm_db.get_areatrigger_rested_zone(areatrigger.id.id as u32).await {
                     AreaTriggerPurpose::RestedArea
+                } else if let Some(quest) = quest_triggers.get(&(areatrigger.id.id as u32)) {
+                    AreaTriggerPurpose::Quest(*quest)
                 } else {
                     AreaTriggerPurpose::Unknown
                 };
@@ -0,0 +1,110 @@
+use std::collections::HashMap;
+use wow_dbc::DbcTable;
+
+use crate::prelude::*;
+
+//What matters about a FactionTemplate.dbc row to tell who can fight whom
+#[derive(Clone, Debug, Default)]
+pub struct FactionTemplateInfo {
+    pub faction: u32,
+    //Bitmasks of the groups (player, alliance, horde, monster) this template is part of, and is hostile to
+    pub faction_group: u32,
+    pub enemy_group: u32,
+    //Factions listed here take precedence over the groups
+    pub enemies: Vec<u32>,
+    pub friends: Vec<u32>,
+}
+
+impl FactionTemplateInfo {
+    pub fn is_hostile_to(&self, other: &FactionTemplateInfo) -> bool {
+        if other.faction != 0 {
+            if self.enemies.contains(&other.faction) {
+                return true;
+            }
+            if self.friends.contains(&other.faction) {
+                return false;
+            }
+        }
+        self.enemy_group & other.faction_group != 0
+    }
+}
+
+impl super::DataStorage {
+    pub(super) fn build_faction_template_infos(&mut self) -> Result<()> {
+        self.faction_template_infos = self
+            .get_dbc_faction_template()?
+            .rows()
+            .iter()
+            .map(|row| {
+                let info = FactionTemplateInfo {
+                    faction: row.faction.id.max(0) as u32,
+                    faction_group: row.faction_group as u32,
+                    enemy_group: row.enemy_group as u32,
+                    enemies: row
+                        .enemies
+                        .iter()
+                        .filter(|&&faction| faction > 0)
+                        .map(|&faction| faction as u32)
+                        .collect(),
+                    friends: row.friend.iter().filter(|&&faction| faction > 0).map(|&faction| faction as u32).collect(),
+                };
+                (row.id.id as u32, info)
+            })
+            .collect();
+        info!("Built info for {} faction templates", self.faction_template_infos.len());
+        Ok(())
+    }
+
+    //Unknown faction templates are not hostile to anyone
+    pub fn is_hostile(&self, faction_template: u32, other_faction_template: u32) -> bool {
+        let (Some(info), Some(other)) = (
+            self.faction_template_infos.get(&faction_template),
+            self.faction_template_infos.get(&other_faction_template),
+        ) else {
+            return false;
+        };
+        info.is_hostile_to(other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GROUP_PLAYER: u32 = 1;
+    const GROUP_ALLIANCE: u32 = 2;
+    const GROUP_HORDE: u32 = 4;
+    const GROUP_MONSTER: u32 = 8;
+
+    fn template(faction: u32, faction_group: u32, enemy_group: u32) -> FactionTemplateInfo {
+        FactionTemplateInfo {
+            faction,
+            faction_group,
+            enemy_group,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn faction_hostility() {
+        let human = template(1, GROUP_PLAYER | GROUP_ALLIANCE, GROUP_HORDE | GROUP_MONSTER);
+        let stormwind = template(72, GROUP_ALLIANCE, GROUP_HORDE | GROUP_MONSTER);
+        let kobold = template(26, GROUP_MONSTER, GROUP_PLAYER | GROUP_ALLIANCE | GROUP_HORDE);
+        let spirit_healer = template(35, 0, 0);
+        assert!(human.is_hostile_to(&kobold));
+        assert!(!human.is_hostile_to(&stormwind));
+        assert!(!human.is_hostile_to(&human));
+        assert!(!human.is_hostile_to(&spirit_healer));
+
+        let friendly_kobold = FactionTemplateInfo {
+            friends: vec![1],
+            ..kobold.clone()
+        };
+        assert!(!friendly_kobold.is_hostile_to(&human));
+        let enemy_of_stormwind = FactionTemplateInfo {
+            enemies: vec![72],
+            ..template(1, GROUP_PLAYER, 0)
+        };
+        assert!(enemy_of_stormwind.is_hostile_to(&stormwind));
+    }
+}
@@ -0,0 +1,133 @@
+use wrath_realm_db::trainer::DBTrainerSpell;
+use wrath_realm_db::vendor::DBVendorItem;
+use wrath_realm_db::RealmDatabase;
+
+use crate::prelude::*;
+
+//Values of the option_type column in gossip_menu_option
+const GOSSIP_OPTION_MENU: u8 = 0;
+const GOSSIP_OPTION_VENDOR: u8 = 1;
+const GOSSIP_OPTION_BANKER: u8 = 2;
+const GOSSIP_OPTION_SPIRIT_HEALER: u8 = 3;
+const GOSSIP_OPTION_TRAINER: u8 = 4;
+const GOSSIP_OPTION_UNLEARN_TALENTS: u8 = 5;
+const GOSSIP_OPTION_DUAL_SPEC: u8 = 6;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GossipOptionAction {
+    //Opens another gossip menu
+    Menu(u32),
+    Vendor,
+    Banker,
+    SpiritHealer,
+    Trainer,
+    UnlearnTalents,
+    DualSpec,
+}
+
+pub struct GossipMenuOption {
+    pub id: u32,
+    pub icon: u8,
+    pub text: String,
+    pub action: GossipOptionAction,
+}
+
+pub struct GossipMenu {
+    //Greeting at the top of the menu (See npc_text)
+    pub text_id: u32,
+    pub options: Vec<GossipMenuOption>,
+}
+
+impl super::DataStorage {
+    pub(super) async fn load_gossip(&mut self, realm_db: &RealmDatabase) -> Result<()> {
+        info!("Loading gossip menus");
+        self.npc_texts = realm_db
+            .get_all_npc_texts()
+            .await?
+            .into_iter()
+            .map(|npc_text| (npc_text.id, npc_text.text))
+            .collect();
+
+        self.gossip_menus = realm_db
+            .get_all_gossip_menus()
+            .await?
+            .into_iter()
+            .map(|menu| {
+                let gossip_menu = GossipMenu {
+                    text_id: menu.text_id,
+                    options: Vec::new(),
+                };
+                (menu.entry, gossip_menu)
+            })
+            .collect();
+
+        for option in realm_db.get_all_gossip_menu_options().await? {
+            let Some(menu) = self.gossip_menus.get_mut(&option.menu_id) else {
+                warn!("Gossip option of menu {} which doesn't exist", option.menu_id);
+                continue;
+            };
+            let action = match option.option_type {
+                GOSSIP_OPTION_MENU => GossipOptionAction::Menu(option.action_menu_id),
+                GOSSIP_OPTION_VENDOR => GossipOptionAction::Vendor,
+                GOSSIP_OPTION_BANKER => GossipOptionAction::Banker,
+                GOSSIP_OPTION_SPIRIT_HEALER => GossipOptionAction::SpiritHealer,
+                GOSSIP_OPTION_TRAINER => GossipOptionAction::Trainer,
+                GOSSIP_OPTION_UNLEARN_TALENTS => GossipOptionAction::UnlearnTalents,
+                GOSSIP_OPTION_DUAL_SPEC => GossipOptionAction::DualSpec,
+                other => {
+                    warn!("Gossip menu {} has an option of unknown type {}", option.menu_id, other);
+                    continue;
+                }
+            };
+            menu.options.push(GossipMenuOption {
+                id: option.option_id as u32,
+                icon: option.option_icon,
+                text: option.option_text,
+                action,
+            });
+        }
+        info!("Loaded {} gossip menus", self.gossip_menus.len());
+
+        info!("Loading vendor items");
+        for vendor_item in realm_db.get_all_vendor_items().await? {
+            if self.get_item_template(vendor_item.item).is_none() {
+                warn!("Vendor {} sells item {} which doesn't exist", vendor_item.entry, vendor_item.item);
+                continue;
+            }
+            self.vendor_items.entry(vendor_item.entry).or_default().push(vendor_item);
+        }
+        info!("Loaded items for {} vendors", self.vendor_items.len());
+
+        info!("Loading trainer spells");
+        for trainer_spell in realm_db.get_all_trainer_spells().await? {
+            if self.get_spell_info(trainer_spell.spell).is_none() {
+                warn!(
+                    "Trainer {} teaches spell {} which doesn't exist",
+                    trainer_spell.entry, trainer_spell.spell
+                );
+                continue;
+            }
+            self.trainer_spells.entry(trainer_spell.entry).or_default().push(trainer_spell);
+        }
+        info!("Loaded spells for {} trainers", self.trainer_spells.len());
+        Ok(())
+    }
+
+    pub fn get_npc_text(&self, text_id: u32) -> Option<&str> {
+        self.npc_texts.get(&text_id).map(String::as_str)
+    }
+
+    pub fn get_gossip_menu(&self, menu_id: u32) -> Option<&GossipMenu> {
+        self.gossip_menus.get(&menu_id)
+    }
+
+    //Items in the order they show up in the vendor window
+    pub fn get_vendor_items(&self, entry: u32) -> &[DBVendorItem] {
+        self.vendor_items.get(&entry).map_or(&[], Vec::as_slice)
+    }
+
+    //Spells in the order they show up in the trainer window
+    pub fn get_trainer_spells(&self, entry: u32) -> &[DBTrainerSpell] {
+        self.trainer_spells.get(&entry).map_or(&[], Vec::as_slice)
+    }
+}
@@ -0,0 +1,130 @@
+use std::sync::Arc;
+
+use wow_dbc::{wrath_tables::world_safe_locs::WorldSafeLocs, DbcTable};
+use wow_world_messages::wrath::Vector3d;
+use wrath_realm_db::RealmDatabase;
+
+use crate::prelude::*;
+
+//Graveyards linked to a zone with this faction can be used by everyone
+const GRAVEYARD_FACTION_ANY: u16 = 0;
+
+#[derive(Debug, Clone)]
+pub struct Graveyard {
+    pub id: u32,
+    pub map: u32,
+    pub position: Vector3d,
+    //Zones whose dead release here, with the faction that is allowed to
+    zones: Vec<(u32, u16)>,
+}
+
+impl Graveyard {
+    //WorldSafeLocs also has locations that aren't graveyards, only the ones linked to a zone are
+    fn is_usable_by(&self, faction: u16) -> bool {
+        self.zones.iter().any(|(_, f)| *f == GRAVEYARD_FACTION_ANY || *f == faction)
+    }
+
+    fn is_linked_to(&self, zone: u32, faction: u16) -> bool {
+        self.zones
+            .iter()
+            .any(|(z, f)| *z == zone && (*f == GRAVEYARD_FACTION_ANY || *f == faction))
+    }
+
+    fn distance_to(&self, position: &Vector3d) -> f32 {
+        let (a, b) = (&self.position, position);
+        ((a.x - b.x).powi(2) + (a.y - b.y).powi(2) + (a.z - b.z).powi(2)).sqrt()
+    }
+}
+
+//Graveyards linked to the zone win, otherwise any graveyard on the map this faction may use
+pub fn find_nearest_graveyard<'a>(
+    graveyards: impl Iterator<Item = &'a Graveyard>,
+    map: u32,
+    zone: u32,
+    position: &Vector3d,
+    faction: u16,
+) -> Option<&'a Graveyard> {
+    let on_map: Vec<&Graveyard> = graveyards.filter(|g| g.map == map && g.is_usable_by(faction)).collect();
+    let linked: Vec<&Graveyard> = on_map.iter().copied().filter(|g| g.is_linked_to(zone, faction)).collect();
+    let candidates = if linked.is_empty() { on_map } else { linked };
+
+    candidates
+        .into_iter()
+        .min_by(|a, b| a.distance_to(position).total_cmp(&b.distance_to(position)))
+}
+
+impl super::DataStorage {
+    pub(super) async fn load_graveyards(&mut self, dbc_path: impl Into<&str>, realm_db: Arc<RealmDatabase>) -> Result<()> {
+        let mut world_safe_locs: Option<WorldSafeLocs> = None;
+        super::load_standard_dbc(dbc_path, &mut world_safe_locs).await?;
+        let Some(world_safe_locs) = world_safe_locs else {
+            return Ok(());
+        };
+
+        for location in world_safe_locs.rows().iter() {
+            let id = location.id.id as u32;
+            self.graveyards.insert(
+                id,
+                Graveyard {
+                    id,
+                    map: location.continent.id as u32,
+                    position: Vector3d {
+                        x: location.loc[0],
+                        y: location.loc[1],
+                        z: location.loc[2],
+                    },
+                    zones: vec![],
+                },
+            );
+        }
+
+        for link in realm_db.get_all_graveyard_zones().await? {
+            match self.graveyards.get_mut(&link.id) {
+                Some(graveyard) => graveyard.zones.push((link.ghost_zone, link.faction)),
+                None => warn!(
+                    "graveyard_zone links zone {} to graveyard {}, which does not exist",
+                    link.ghost_zone, link.id
+                ),
+            }
+        }
+        info!("Loaded {} graveyards", self.graveyards.len());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graveyard(id: u32, map: u32, x: f32, zones: Vec<(u32, u16)>) -> Graveyard {
+        Graveyard {
+            id,
+            map,
+            position: Vector3d { x, y: 0.0, z: 0.0 },
+            zones,
+        }
+    }
+
+    #[test]
+    fn nearest_graveyard_prefers_the_zone_and_respects_factions() {
+        let graveyards = vec![
+            graveyard(1, 0, 10.0, vec![(1, 0)]),
+            graveyard(2, 0, 100.0, vec![(12, 469)]),
+            graveyard(3, 0, 50.0, vec![(12, 67)]),
+            graveyard(4, 1, 0.0, vec![(1, 0)]),
+            //Not linked to any zone, so not a graveyard at all
+            graveyard(5, 0, 0.0, vec![]),
+        ];
+        let position = Vector3d::default();
+        let nearest = |zone, faction| find_nearest_graveyard(graveyards.iter(), 0, zone, &position, faction).map(|g| g.id);
+
+        assert_eq!(nearest(12, 469), Some(2));
+        assert_eq!(nearest(12, 67), Some(3));
+        //Without a link to the zone, the closest one on the map is used, but not one of the other faction
+        assert_eq!(nearest(40, 469), Some(1));
+        assert_eq!(
+            find_nearest_graveyard(graveyards.iter(), 0, 40, &Vector3d { x: 60.0, y: 0.0, z: 0.0 }, 469).map(|g| g.id),
+            Some(2)
+        );
+    }
+}
@@ -0,0 +1,42 @@
+use std::collections::HashMap;
+use wrath_realm_db::loot::DBLootTemplate;
+use wrath_realm_db::RealmDatabase;
+
+use crate::prelude::*;
+
+fn group_by_entry(rows: Vec<DBLootTemplate>) -> HashMap<u32, Vec<DBLootTemplate>> {
+    let mut templates: HashMap<u32, Vec<DBLootTemplate>> = HashMap::new();
+    for row in rows {
+        templates.entry(row.entry).or_default().push(row);
+    }
+    templates
+}
+
+impl super::DataStorage {
+    pub(super) async fn load_loot_templates(&mut self, realm_db: &RealmDatabase) -> Result<()> {
+        info!("Loading loot templates");
+        self.creature_loot_templates = group_by_entry(realm_db.get_all_creature_loot_templates().await?);
+        self.gameobject_loot_templates = group_by_entry(realm_db.get_all_gameobject_loot_templates().await?);
+        self.item_loot_templates = group_by_entry(realm_db.get_all_item_loot_templates().await?);
+        info!(
+            "Loaded {} creature, {} gameobject and {} item loot templates",
+            self.creature_loot_templates.len(),
+            self.gameobject_loot_templates.len(),
+            self.item_loot_templates.len()
+        );
+        Ok(())
+    }
+
+    pub fn get_creature_loot_template(&self, loot_id: u32) -> Option<&[DBLootTemplate]> {
+        self.creature_loot_templates.get(&loot_id).map(Vec::as_slice)
+    }
+
+    #[allow(dead_code)] //Unused until gameobjects like chests are spawned on maps
+    pub fn get_gameobject_loot_template(&self, entry: u32) -> Option<&[DBLootTemplate]> {
+        self.gameobject_loot_templates.get(&entry).map(Vec::as_slice)
+    }
+
+    pub fn get_item_loot_template(&self, item: u32) -> Option<&[DBLootTemplate]> {
+        self.item_loot_templates.get(&item).map(Vec::as_slice)
+    }
+}
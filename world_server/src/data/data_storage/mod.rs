@@ -1,19 +1,104 @@
 use crate::prelude::*;
 use smol::io::{AsyncReadExt, BufReader};
-use std::{path::PathBuf, sync::Arc};
-use wow_dbc::wrath_tables::{area_trigger::AreaTriggerKey, chr_classes::ChrClasses, chr_races::ChrRaces};
-use wrath_realm_db::RealmDatabase;
+use std::{collections::HashMap, path::PathBuf, sync::Arc};
+use wow_dbc::wrath_tables::{
+    area_table::AreaTable, area_trigger::AreaTriggerKey, chr_classes::ChrClasses, chr_races::ChrRaces, durability_costs::DurabilityCosts,
+    durability_quality::DurabilityQuality, faction::Faction, faction_template::FactionTemplate, gt_chance_to_melee_crit::GtChanceToMeleeCrit,
+    gt_chance_to_melee_crit_base::GtChanceToMeleeCritBase, gt_chance_to_spell_crit::GtChanceToSpellCrit,
+    gt_chance_to_spell_crit_base::GtChanceToSpellCritBase, gt_oct_regen_hp::GtOCTRegenHP, gt_regen_hp_per_spt::GtRegenHPPerSpt,
+    gt_regen_mp_per_spt::GtRegenMPPerSpt, skill_line::SkillLine, skill_line_ability::SkillLineAbility, spell::Spell,
+    spell_cast_times::SpellCastTimes, spell_duration::SpellDuration, spell_radius::SpellRadius, spell_range::SpellRange,
+    spell_rune_cost::SpellRuneCost, talent::Talent, talent_tab::TalentTab,
+};
+use wow_world_messages::wrath::Vector3d;
+use wrath_realm_db::loot::DBLootTemplate;
+use wrath_realm_db::player_create_info::{DBPlayerClassLevelStats, DBPlayerLevelStats};
+use wrath_realm_db::trainer::DBTrainerSpell;
+use wrath_realm_db::vendor::DBVendorItem;
+use wrath_realm_db::{item_template::DBItemTemplate, RealmDatabase};
 
 mod area_triggers;
 pub use area_triggers::*;
 
+mod factions;
+pub use factions::*;
+
+mod gossip;
+pub use gossip::*;
+
+mod graveyards;
+pub use graveyards::*;
+
+mod loot;
+
+mod quests;
+pub use quests::*;
+
+mod skills;
+pub use skills::*;
+
+mod spells;
+pub use spells::*;
+
+mod talents;
+pub use talents::*;
+
 #[derive(Default)]
 pub struct DataStorage {
     dbc_chr_races: Option<ChrRaces>,
     dbc_chr_classes: Option<ChrClasses>,
     dbc_chr_map: Option<wow_dbc::wrath_tables::map::Map>,
     dbc_char_start_outfit: Option<wow_dbc::wrath_tables::char_start_outfit::CharStartOutfit>,
-    area_triggers: std::collections::hash_map::HashMap<AreaTriggerKey, AreaTrigger>,
+    dbc_bank_bag_slot_prices: Option<wow_dbc::wrath_tables::bank_bag_slot_prices::BankBagSlotPrices>,
+    dbc_spell: Option<Spell>,
+    dbc_spell_cast_times: Option<SpellCastTimes>,
+    dbc_spell_duration: Option<SpellDuration>,
+    dbc_spell_range: Option<SpellRange>,
+    dbc_spell_radius: Option<SpellRadius>,
+    dbc_spell_rune_cost: Option<SpellRuneCost>,
+    dbc_area_table: Option<AreaTable>,
+    dbc_gt_chance_to_melee_crit: Option<GtChanceToMeleeCrit>,
+    dbc_gt_chance_to_melee_crit_base: Option<GtChanceToMeleeCritBase>,
+    dbc_gt_chance_to_spell_crit: Option<GtChanceToSpellCrit>,
+    dbc_gt_chance_to_spell_crit_base: Option<GtChanceToSpellCritBase>,
+    dbc_gt_oct_regen_hp: Option<GtOCTRegenHP>,
+    dbc_gt_regen_hp_per_spt: Option<GtRegenHPPerSpt>,
+    dbc_gt_regen_mp_per_spt: Option<GtRegenMPPerSpt>,
+    dbc_faction: Option<Faction>,
+    dbc_faction_template: Option<FactionTemplate>,
+    dbc_durability_costs: Option<DurabilityCosts>,
+    dbc_durability_quality: Option<DurabilityQuality>,
+    dbc_talent: Option<Talent>,
+    dbc_talent_tab: Option<TalentTab>,
+    dbc_skill_line: Option<SkillLine>,
+    dbc_skill_line_ability: Option<SkillLineAbility>,
+    area_triggers: HashMap<AreaTriggerKey, AreaTrigger>,
+    spell_infos: HashMap<u32, SpellInfo>,
+    talent_infos: HashMap<u32, TalentInfo>,
+    faction_template_infos: HashMap<u32, FactionTemplateInfo>,
+    skill_ranges: HashMap<u16, SkillRange>,
+    //Skill line ids by spell id
+    spell_skill_lines: HashMap<u32, Vec<u16>>,
+    graveyards: HashMap<u32, Graveyard>,
+    item_templates: HashMap<u32, DBItemTemplate>,
+    //By (race, class, level) and (class, level)
+    player_level_stats: HashMap<(u8, u8, u8), DBPlayerLevelStats>,
+    player_class_level_stats: HashMap<(u8, u8), DBPlayerClassLevelStats>,
+    xp_for_next_level: HashMap<u8, u32>,
+    exploration_base_xp: HashMap<u8, u32>,
+    //By loot id for creatures, by entry for gameobjects and items
+    creature_loot_templates: HashMap<u32, Vec<DBLootTemplate>>,
+    gameobject_loot_templates: HashMap<u32, Vec<DBLootTemplate>>,
+    item_loot_templates: HashMap<u32, Vec<DBLootTemplate>>,
+    quest_templates: HashMap<u32, QuestTemplate>,
+    //Quest ids by creature entry
+    creature_quest_starters: HashMap<u32, Vec<u32>>,
+    creature_quest_enders: HashMap<u32, Vec<u32>>,
+    npc_texts: HashMap<u32, String>,
+    gossip_menus: HashMap<u32, GossipMenu>,
+    //By creature entry
+    vendor_items: HashMap<u32, Vec<DBVendorItem>>,
+    trainer_spells: HashMap<u32, Vec<DBTrainerSpell>>,
 }
 
 async fn load_standard_dbc<T: wow_dbc::DbcTable>(folder_path: impl Into<&str>, table: &mut Option<T>) -> Result<()> {
@@ -57,10 +142,74 @@ impl DataStorage {
         load_standard_dbc(dbc_path, &mut self.dbc_chr_classes).await?;
         load_standard_dbc(dbc_path, &mut self.dbc_chr_map).await?;
         load_standard_dbc(dbc_path, &mut self.dbc_char_start_outfit).await?;
-        self.load_area_triggers(dbc_path, realm_db).await?;
+        load_standard_dbc(dbc_path, &mut self.dbc_bank_bag_slot_prices).await?;
+        load_standard_dbc(dbc_path, &mut self.dbc_spell).await?;
+        load_standard_dbc(dbc_path, &mut self.dbc_spell_cast_times).await?;
+        load_standard_dbc(dbc_path, &mut self.dbc_spell_duration).await?;
+        load_standard_dbc(dbc_path, &mut self.dbc_spell_range).await?;
+        load_standard_dbc(dbc_path, &mut self.dbc_spell_radius).await?;
+        load_standard_dbc(dbc_path, &mut self.dbc_spell_rune_cost).await?;
+        load_standard_dbc(dbc_path, &mut self.dbc_area_table).await?;
+        load_standard_dbc(dbc_path, &mut self.dbc_gt_chance_to_melee_crit).await?;
+        load_standard_dbc(dbc_path, &mut self.dbc_gt_chance_to_melee_crit_base).await?;
+        load_standard_dbc(dbc_path, &mut self.dbc_gt_chance_to_spell_crit).await?;
+        load_standard_dbc(dbc_path, &mut self.dbc_gt_chance_to_spell_crit_base).await?;
+        load_standard_dbc(dbc_path, &mut self.dbc_gt_oct_regen_hp).await?;
+        load_standard_dbc(dbc_path, &mut self.dbc_gt_regen_hp_per_spt).await?;
+        load_standard_dbc(dbc_path, &mut self.dbc_gt_regen_mp_per_spt).await?;
+        load_standard_dbc(dbc_path, &mut self.dbc_faction).await?;
+        load_standard_dbc(dbc_path, &mut self.dbc_faction_template).await?;
+        load_standard_dbc(dbc_path, &mut self.dbc_durability_costs).await?;
+        load_standard_dbc(dbc_path, &mut self.dbc_durability_quality).await?;
+        load_standard_dbc(dbc_path, &mut self.dbc_talent).await?;
+        load_standard_dbc(dbc_path, &mut self.dbc_talent_tab).await?;
+        load_standard_dbc(dbc_path, &mut self.dbc_skill_line).await?;
+        load_standard_dbc(dbc_path, &mut self.dbc_skill_line_ability).await?;
+        self.build_spell_infos()?;
+        self.build_talent_infos()?;
+        self.build_skill_infos()?;
+        self.build_faction_template_infos()?;
+        self.load_area_triggers(dbc_path, realm_db.clone()).await?;
+        self.load_graveyards(dbc_path, realm_db.clone()).await?;
         info!("Finished loading DBC files");
         info!("Loading SQL data");
         info!("Loading item templates");
+        self.item_templates = realm_db
+            .get_all_item_templates()
+            .await?
+            .into_iter()
+            .map(|template| (template.id, template))
+            .collect();
+        info!("Loaded {} item templates", self.item_templates.len());
+        info!("Loading player level stats");
+        self.player_level_stats = realm_db
+            .get_all_player_level_stats()
+            .await?
+            .into_iter()
+            .map(|stats| ((stats.race, stats.class, stats.level), stats))
+            .collect();
+        self.player_class_level_stats = realm_db
+            .get_all_player_class_level_stats()
+            .await?
+            .into_iter()
+            .map(|stats| ((stats.class, stats.level), stats))
+            .collect();
+        info!("Loaded {} player level stats", self.player_level_stats.len());
+        self.xp_for_next_level = realm_db
+            .get_all_player_xp_for_level()
+            .await?
+            .into_iter()
+            .map(|row| (row.level, row.xp_for_next_level))
+            .collect();
+        self.exploration_base_xp = realm_db
+            .get_all_exploration_base_xp()
+            .await?
+            .into_iter()
+            .map(|row| (row.level, row.base_xp))
+            .collect();
+        self.load_loot_templates(&realm_db).await?;
+        self.load_quests(&realm_db).await?;
+        self.load_gossip(&realm_db).await?;
         Ok(())
     }
 
@@ -72,10 +221,74 @@ impl DataStorage {
         dbc_char_start_outfit,
         get_dbc_char_start_outfit
     );
+    define_dbc_getter!(
+        wow_dbc::wrath_tables::bank_bag_slot_prices::BankBagSlotPrices,
+        dbc_bank_bag_slot_prices,
+        get_dbc_bank_bag_slot_prices
+    );
+    define_dbc_getter!(Spell, dbc_spell, get_dbc_spell);
+    define_dbc_getter!(SpellCastTimes, dbc_spell_cast_times, get_dbc_spell_cast_times);
+    define_dbc_getter!(SpellDuration, dbc_spell_duration, get_dbc_spell_duration);
+    define_dbc_getter!(SpellRange, dbc_spell_range, get_dbc_spell_range);
+    define_dbc_getter!(SpellRadius, dbc_spell_radius, get_dbc_spell_radius);
+    define_dbc_getter!(SpellRuneCost, dbc_spell_rune_cost, get_dbc_spell_rune_cost);
+    define_dbc_getter!(AreaTable, dbc_area_table, get_dbc_area_table);
+    define_dbc_getter!(GtChanceToMeleeCrit, dbc_gt_chance_to_melee_crit, get_dbc_gt_chance_to_melee_crit);
+    define_dbc_getter!(
+        GtChanceToMeleeCritBase,
+        dbc_gt_chance_to_melee_crit_base,
+        get_dbc_gt_chance_to_melee_crit_base
+    );
+    define_dbc_getter!(GtChanceToSpellCrit, dbc_gt_chance_to_spell_crit, get_dbc_gt_chance_to_spell_crit);
+    define_dbc_getter!(
+        GtChanceToSpellCritBase,
+        dbc_gt_chance_to_spell_crit_base,
+        get_dbc_gt_chance_to_spell_crit_base
+    );
+    define_dbc_getter!(GtOCTRegenHP, dbc_gt_oct_regen_hp, get_dbc_gt_oct_regen_hp);
+    define_dbc_getter!(GtRegenHPPerSpt, dbc_gt_regen_hp_per_spt, get_dbc_gt_regen_hp_per_spt);
+    define_dbc_getter!(GtRegenMPPerSpt, dbc_gt_regen_mp_per_spt, get_dbc_gt_regen_mp_per_spt);
+    define_dbc_getter!(Faction, dbc_faction, get_dbc_faction);
+    define_dbc_getter!(FactionTemplate, dbc_faction_template, get_dbc_faction_template);
+    define_dbc_getter!(DurabilityCosts, dbc_durability_costs, get_dbc_durability_costs);
+    define_dbc_getter!(DurabilityQuality, dbc_durability_quality, get_dbc_durability_quality);
+    define_dbc_getter!(Talent, dbc_talent, get_dbc_talent);
+    define_dbc_getter!(TalentTab, dbc_talent_tab, get_dbc_talent_tab);
+    define_dbc_getter!(SkillLine, dbc_skill_line, get_dbc_skill_line);
+    define_dbc_getter!(SkillLineAbility, dbc_skill_line_ability, get_dbc_skill_line_ability);
 
     //Area triggers need special treatment from joint DBC and Mysql data sources, so they don't use
     //forward_dbc_getter
     pub fn get_area_trigger(&self, key: impl Into<AreaTriggerKey>) -> Option<&AreaTrigger> {
         self.area_triggers.get(&key.into())
     }
+
+    pub fn get_spell_info(&self, spell_id: u32) -> Option<&SpellInfo> {
+        self.spell_infos.get(&spell_id)
+    }
+
+    pub fn get_nearest_graveyard(&self, map: u32, zone: u32, position: &Vector3d, faction: u16) -> Option<&Graveyard> {
+        find_nearest_graveyard(self.graveyards.values(), map, zone, position, faction)
+    }
+
+    pub fn get_player_level_stats(&self, race: u8, class: u8, level: u8) -> Option<&DBPlayerLevelStats> {
+        self.player_level_stats.get(&(race, class, level))
+    }
+
+    pub fn get_player_class_level_stats(&self, class: u8, level: u8) -> Option<&DBPlayerClassLevelStats> {
+        self.player_class_level_stats.get(&(class, level))
+    }
+
+    //Characters at the max level don't have a next level, so there's nothing here for them
+    pub fn get_xp_for_next_level(&self, level: u8) -> Option<u32> {
+        self.xp_for_next_level.get(&level).copied()
+    }
+
+    pub fn get_exploration_base_xp(&self, level: u8) -> u32 {
+        self.exploration_base_xp.get(&level).copied().unwrap_or(0)
+    }
+
+    pub fn get_item_template(&self, item_id: u32) -> Option<&DBItemTemplate> {
+        self.item_templates.get(&item_id)
+    }
 }
@@ -0,0 +1,151 @@
+use std::collections::HashMap;
+use wrath_realm_db::quest::{DBQuestRelation, DBQuestRewardFaction, DBQuestRewardItem, DBQuestTemplate};
+use wrath_realm_db::RealmDatabase;
+
+use super::AreaTriggerPurpose;
+use crate::prelude::*;
+
+//Values of the objective_type column in quest_objective
+const QUEST_OBJECTIVE_KILL: u8 = 0;
+const QUEST_OBJECTIVE_ITEM: u8 = 1;
+
+//The quest log has this many kill counters and item objectives per quest
+pub const MAX_QUEST_KILL_OBJECTIVES: usize = 4;
+pub const MAX_QUEST_ITEM_OBJECTIVES: usize = 6;
+
+pub const QUEST_FLAG_DAILY: u32 = 0x1000;
+
+#[derive(Clone, Copy, Debug)]
+pub struct QuestObjective {
+    //Creature entry for kills, item id for items
+    pub target: u32,
+    pub count: u16,
+}
+
+pub struct QuestTemplate {
+    pub info: DBQuestTemplate,
+    pub kill_objectives: Vec<QuestObjective>,
+    pub item_objectives: Vec<QuestObjective>,
+    //The character gets all reward items, but only one of the choice items
+    pub reward_items: Vec<DBQuestRewardItem>,
+    pub reward_choice_items: Vec<DBQuestRewardItem>,
+    pub reward_factions: Vec<DBQuestRewardFaction>,
+    //Set when an area trigger has to be explored to complete the quest
+    pub requires_exploration: bool,
+}
+
+impl QuestTemplate {
+    fn new(info: DBQuestTemplate) -> Self {
+        Self {
+            info,
+            kill_objectives: Vec::new(),
+            item_objectives: Vec::new(),
+            reward_items: Vec::new(),
+            reward_choice_items: Vec::new(),
+            reward_factions: Vec::new(),
+            requires_exploration: false,
+        }
+    }
+
+    pub fn is_daily(&self) -> bool {
+        self.info.flags & QUEST_FLAG_DAILY != 0
+    }
+
+    //Quests of level -1 are always at the level of the character
+    pub fn get_level_for(&self, character_level: u32) -> u32 {
+        if self.info.quest_level < 0 {
+            character_level
+        } else {
+            self.info.quest_level as u32
+        }
+    }
+}
+
+fn group_by_creature(relations: Vec<DBQuestRelation>) -> HashMap<u32, Vec<u32>> {
+    let mut quests: HashMap<u32, Vec<u32>> = HashMap::new();
+    for relation in relations {
+        quests.entry(relation.id).or_default().push(relation.quest);
+    }
+    quests
+}
+
+impl super::DataStorage {
+    pub(super) async fn load_quests(&mut self, realm_db: &RealmDatabase) -> Result<()> {
+        info!("Loading quests");
+        self.quest_templates = realm_db
+            .get_all_quest_templates()
+            .await?
+            .into_iter()
+            .map(|template| (template.id, QuestTemplate::new(template)))
+            .collect();
+
+        for objective in realm_db.get_all_quest_objectives().await? {
+            let Some(quest) = self.quest_templates.get_mut(&objective.quest) else {
+                warn!("Objective of quest {} which doesn't exist", objective.quest);
+                continue;
+            };
+            let (objectives, max_objectives) = match objective.objective_type {
+                QUEST_OBJECTIVE_KILL => (&mut quest.kill_objectives, MAX_QUEST_KILL_OBJECTIVES),
+                QUEST_OBJECTIVE_ITEM => (&mut quest.item_objectives, MAX_QUEST_ITEM_OBJECTIVES),
+                other => {
+                    warn!("Quest {} has an objective of unknown type {}", objective.quest, other);
+                    continue;
+                }
+            };
+            if objectives.len() >= max_objectives {
+                warn!(
+                    "Quest {} has more than {} objectives of type {}",
+                    objective.quest, max_objectives, objective.objective_type
+                );
+                continue;
+            }
+            objectives.push(QuestObjective {
+                target: objective.target,
+                count: objective.count,
+            });
+        }
+
+        for reward in realm_db.get_all_quest_reward_items().await? {
+            if let Some(quest) = self.quest_templates.get_mut(&reward.quest) {
+                if reward.choice != 0 {
+                    quest.reward_choice_items.push(reward);
+                } else {
+                    quest.reward_items.push(reward);
+                }
+            }
+        }
+
+        for reward in realm_db.get_all_quest_reward_factions().await? {
+            if let Some(quest) = self.quest_templates.get_mut(&reward.quest) {
+                quest.reward_factions.push(reward);
+            }
+        }
+
+        for area_trigger in self.area_triggers.values() {
+            if let AreaTriggerPurpose::Quest(quest_id) = area_trigger.purpose {
+                if let Some(quest) = self.quest_templates.get_mut(&quest_id) {
+                    quest.requires_exploration = true;
+                }
+            }
+        }
+
+        self.creature_quest_starters = group_by_creature(realm_db.get_all_creature_quest_starters().await?);
+        self.creature_quest_enders = group_by_creature(realm_db.get_all_creature_quest_enders().await?);
+        info!("Loaded {} quests", self.quest_templates.len());
+        Ok(())
+    }
+
+    pub fn get_quest_template(&self, quest_id: u32) -> Option<&QuestTemplate> {
+        self.quest_templates.get(&quest_id)
+    }
+
+    //Quests this creature hands out
+    pub fn get_creature_quest_starters(&self, entry: u32) -> &[u32] {
+        self.creature_quest_starters.get(&entry).map_or(&[], Vec::as_slice)
+    }
+
+    //Quests that are turned in at this creature
+    pub fn get_creature_quest_enders(&self, entry: u32) -> &[u32] {
+        self.creature_quest_enders.get(&entry).map_or(&[], Vec::as_slice)
+    }
+}
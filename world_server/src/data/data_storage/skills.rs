@@ -0,0 +1,85 @@
+use std::collections::HashMap;
+use wow_dbc::DbcTable;
+
+use crate::prelude::*;
+
+//Values of the category_id column in SkillLine.dbc
+const SKILL_CATEGORY_WEAPON: i32 = 6;
+const SKILL_CATEGORY_LANGUAGE: i32 = 10;
+
+const LANGUAGE_SKILL_VALUE: u16 = 300;
+const MAX_SKILL_VALUE_PER_LEVEL: u16 = 5;
+
+//How a skill's maximum value is decided
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SkillRange {
+    //Languages are known fully right away
+    Language,
+    //Weapon skills (and defense) can be raised further with every level
+    Level,
+    //Armor proficiencies and class skills are either known or not
+    Mono,
+}
+
+impl SkillRange {
+    //The value and max value a character starts out with when learning the skill
+    pub fn get_starting_values(self, level: u32) -> (u16, u16) {
+        match self {
+            SkillRange::Language => (LANGUAGE_SKILL_VALUE, LANGUAGE_SKILL_VALUE),
+            SkillRange::Level => (1, get_max_skill_value_for_level(level)),
+            SkillRange::Mono => (1, 1),
+        }
+    }
+}
+
+pub fn get_max_skill_value_for_level(level: u32) -> u16 {
+    (level as u16).saturating_mul(MAX_SKILL_VALUE_PER_LEVEL)
+}
+
+impl super::DataStorage {
+    pub(super) fn build_skill_infos(&mut self) -> Result<()> {
+        self.skill_ranges = self
+            .get_dbc_skill_line()?
+            .rows()
+            .iter()
+            .map(|row| {
+                let range = match row.category_id.id {
+                    SKILL_CATEGORY_LANGUAGE => SkillRange::Language,
+                    SKILL_CATEGORY_WEAPON => SkillRange::Level,
+                    _ => SkillRange::Mono,
+                };
+                (row.id.id as u16, range)
+            })
+            .collect();
+
+        let mut spell_skill_lines: HashMap<u32, Vec<u16>> = HashMap::new();
+        for row in self.get_dbc_skill_line_ability()?.rows() {
+            spell_skill_lines.entry(row.spell.id as u32).or_default().push(row.skill_line.id as u16);
+        }
+        self.spell_skill_lines = spell_skill_lines;
+        info!("Built info for {} skills", self.skill_ranges.len());
+        Ok(())
+    }
+
+    pub fn get_skill_range(&self, skill_id: u16) -> SkillRange {
+        self.skill_ranges.get(&skill_id).copied().unwrap_or(SkillRange::Mono)
+    }
+
+    //The skills a spell belongs to, learning the spell teaches those as well
+    pub fn get_spell_skill_lines(&self, spell_id: u32) -> &[u16] {
+        self.spell_skill_lines.get(&spell_id).map_or(&[], Vec::as_slice)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn skill_starting_values() {
+        assert_eq!(SkillRange::Language.get_starting_values(1), (300, 300));
+        assert_eq!(SkillRange::Level.get_starting_values(1), (1, 5));
+        assert_eq!(SkillRange::Level.get_starting_values(55), (1, 275));
+        assert_eq!(SkillRange::Mono.get_starting_values(80), (1, 1));
+    }
+}
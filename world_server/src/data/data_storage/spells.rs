@@ -0,0 +1,167 @@
+use wow_dbc::{wrath_tables::spell::SpellRow, DbcTable, Indexable};
+
+use crate::prelude::*;
+use crate::world::prelude::spell::SPELL_TARGET_UNIT_TARGET_ENEMY;
+
+pub const MAX_SPELL_EFFECTS: usize = 3;
+
+//Death knight spells cost runes of these types, and give runic power back
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SpellRuneCost {
+    pub blood: u32,
+    pub unholy: u32,
+    pub frost: u32,
+    pub runic_power: u32,
+}
+
+//The parts of a Spell.dbc effect column that the server cares about
+#[derive(Debug, Clone, Default)]
+pub struct SpellEffectInfo {
+    pub index: u8,
+    pub effect: u32,
+    pub base_points: i32,
+    pub die_sides: i32,
+    pub real_points_per_level: f32,
+    pub implicit_target_a: u32,
+    pub implicit_target_b: u32,
+    pub radius: f32,
+    pub aura: u32,
+    pub aura_period: u32, //in ms
+    pub misc_value: i32,
+    pub misc_value_b: i32,
+    pub item_type: u32,
+    pub trigger_spell: u32,
+}
+
+//A row of Spell.dbc, with the indices into the smaller spell DBCs already resolved
+#[derive(Debug, Clone, Default)]
+pub struct SpellInfo {
+    pub id: u32,
+    pub name: String,
+    pub attributes: u32,
+    pub school_mask: u32,
+    pub category: u32,
+    pub cast_time: f32, //in seconds
+    pub recovery_time: f32,
+    pub category_recovery_time: f32,
+    pub start_recovery_category: u32,
+    pub start_recovery_time: f32, //the global cooldown
+    pub interrupt_flags: u32,
+    pub duration: Option<f32>, //None means it lasts until cancelled
+    pub max_stacks: u32,
+    pub proc_charges: u32,
+    pub power_type: i32,
+    pub mana_cost: u32,
+    pub mana_cost_per_level: u32,
+    pub mana_cost_percentage: u32,
+    pub min_range: f32,
+    pub max_range: f32,
+    pub speed: f32,
+    pub base_level: u32,
+    pub spell_level: u32,
+    pub max_level: u32,
+    pub rune_cost: Option<SpellRuneCost>,
+    pub effects: Vec<SpellEffectInfo>,
+}
+
+impl SpellInfo {
+    pub fn get_power_cost(&self, caster_level: u32, base_power: u32) -> u32 {
+        //Costs scale with the level of the caster, up to the max level of the spell if it has one
+        let mut levels_above_spell = caster_level.saturating_sub(self.spell_level);
+        if self.max_level > 0 {
+            levels_above_spell = levels_above_spell.min(self.max_level.saturating_sub(self.spell_level));
+        }
+        self.mana_cost + self.mana_cost_per_level * levels_above_spell + base_power * self.mana_cost_percentage / 100
+    }
+
+    //Spells meant to be cast on an enemy
+    pub fn is_harmful(&self) -> bool {
+        self.effects
+            .iter()
+            .any(|effect| effect.implicit_target_a == SPELL_TARGET_UNIT_TARGET_ENEMY)
+    }
+}
+
+//Values in the DBC are in milliseconds, we use seconds everywhere else
+fn ms_to_seconds(value: i32) -> f32 {
+    value.max(0) as f32 / 1000.0
+}
+
+impl super::DataStorage {
+    pub(super) fn build_spell_infos(&mut self) -> Result<()> {
+        let spells = self.get_dbc_spell()?;
+        let cast_times = self.get_dbc_spell_cast_times()?;
+        let durations = self.get_dbc_spell_duration()?;
+        let ranges = self.get_dbc_spell_range()?;
+        let radii = self.get_dbc_spell_radius()?;
+        let rune_costs = self.get_dbc_spell_rune_cost()?;
+
+        let build = |row: &SpellRow| {
+            let effects = (0..MAX_SPELL_EFFECTS)
+                .filter(|&i| row.effect[i] != 0)
+                .map(|i| SpellEffectInfo {
+                    index: i as u8,
+                    effect: row.effect[i] as u32,
+                    base_points: row.effect_base_points[i],
+                    die_sides: row.effect_die_sides[i],
+                    real_points_per_level: row.effect_real_points_per_level[i],
+                    implicit_target_a: row.implicit_target_a[i] as u32,
+                    implicit_target_b: row.implicit_target_b[i] as u32,
+                    radius: radii.get(row.effect_radius_index[i]).map_or(0.0, |radius| radius.radius),
+                    aura: row.effect_aura[i] as u32,
+                    aura_period: row.effect_aura_period[i] as u32,
+                    misc_value: row.effect_misc_value[i],
+                    misc_value_b: row.effect_misc_value_b[i],
+                    item_type: row.effect_item_type[i] as u32,
+                    trigger_spell: row.effect_trigger_spell[i] as u32,
+                })
+                .collect();
+            let range = ranges.get(row.range_index);
+
+            SpellInfo {
+                id: row.id.id as u32,
+                name: row.name_lang.en_gb.clone(),
+                attributes: row.attributes as u32,
+                school_mask: row.school_mask as u32,
+                category: row.category.id as u32,
+                cast_time: cast_times
+                    .get(row.casting_time_index)
+                    .map_or(0.0, |cast_time| ms_to_seconds(cast_time.base)),
+                recovery_time: ms_to_seconds(row.recovery_time),
+                category_recovery_time: ms_to_seconds(row.category_recovery_time),
+                start_recovery_category: row.start_recovery_category as u32,
+                start_recovery_time: ms_to_seconds(row.start_recovery_time),
+                interrupt_flags: row.interrupt_flags as u32,
+                duration: durations
+                    .get(row.duration_index)
+                    .filter(|duration| duration.duration >= 0)
+                    .map(|duration| ms_to_seconds(duration.duration)),
+                max_stacks: row.cumulative_aura.max(0) as u32,
+                proc_charges: row.proc_charges.max(0) as u32,
+                power_type: row.power_type,
+                mana_cost: row.mana_cost.max(0) as u32,
+                mana_cost_per_level: row.mana_cost_per_level.max(0) as u32,
+                mana_cost_percentage: row.mana_cost_pct.max(0) as u32,
+                //Index 0 is for hostile targets, 1 for friendly ones
+                min_range: range.map_or(0.0, |range| range.range_min[0]),
+                max_range: range.map_or(0.0, |range| range.range_max[0]),
+                speed: row.speed,
+                base_level: row.base_level.max(0) as u32,
+                spell_level: row.spell_level.max(0) as u32,
+                max_level: row.max_level.max(0) as u32,
+                rune_cost: rune_costs.get(row.rune_cost_id).map(|cost| SpellRuneCost {
+                    blood: cost.rune_cost[0].max(0) as u32,
+                    unholy: cost.rune_cost[1].max(0) as u32,
+                    frost: cost.rune_cost[2].max(0) as u32,
+                    runic_power: cost.runic_power.max(0) as u32,
+                }),
+                effects,
+            }
+        };
+
+        let spell_infos = spells.rows().iter().map(build).map(|info| (info.id, info)).collect();
+        self.spell_infos = spell_infos;
+        info!("Built info for {} spells", self.spell_infos.len());
+        Ok(())
+    }
+}
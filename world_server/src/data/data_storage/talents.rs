@@ -0,0 +1,64 @@
+use std::collections::HashMap;
+use wow_dbc::{DbcTable, Indexable};
+
+use crate::prelude::*;
+
+//Talent.dbc has room for this many ranks and prerequisites per talent
+const MAX_TALENT_RANKS: usize = 9;
+const MAX_TALENT_PREREQUISITES: usize = 3;
+
+//A row of Talent.dbc, with the class of its tab already resolved
+#[derive(Debug, Clone, Default)]
+pub struct TalentInfo {
+    pub id: u32,
+    pub tab: u32,
+    pub class_mask: u32,
+    pub tier: u32,
+    //The spell every rank teaches, the first rank comes first
+    pub rank_spells: Vec<u32>,
+    //Talents that need at least this rank before this one can be learned, counting from 0
+    pub prerequisites: Vec<(u32, u8)>,
+}
+
+impl TalentInfo {
+    pub fn get_max_rank(&self) -> u8 {
+        self.rank_spells.len().saturating_sub(1) as u8
+    }
+}
+
+impl super::DataStorage {
+    pub(super) fn build_talent_infos(&mut self) -> Result<()> {
+        let tabs = self.get_dbc_talent_tab()?;
+
+        let mut talent_infos = HashMap::new();
+        for row in self.get_dbc_talent()?.rows() {
+            let Some(tab) = tabs.get(row.tab_id) else {
+                warn!("Talent {} is in tab {} which doesn't exist", row.id.id, row.tab_id.id);
+                continue;
+            };
+            let info = TalentInfo {
+                id: row.id.id as u32,
+                tab: row.tab_id.id as u32,
+                class_mask: tab.class_mask as u32,
+                tier: row.tier_id.max(0) as u32,
+                rank_spells: row.spell_rank[..MAX_TALENT_RANKS]
+                    .iter()
+                    .take_while(|&&spell| spell > 0)
+                    .map(|&spell| spell as u32)
+                    .collect(),
+                prerequisites: (0..MAX_TALENT_PREREQUISITES)
+                    .filter(|&i| row.prereq_talent[i] > 0)
+                    .map(|i| (row.prereq_talent[i] as u32, row.prereq_rank[i].max(0) as u8))
+                    .collect(),
+            };
+            talent_infos.insert(info.id, info);
+        }
+        self.talent_infos = talent_infos;
+        info!("Built info for {} talents", self.talent_infos.len());
+        Ok(())
+    }
+
+    pub fn get_talent_info(&self, talent_id: u32) -> Option<&TalentInfo> {
+        self.talent_infos.get(&talent_id)
+    }
+}
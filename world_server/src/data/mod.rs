@@ -22,6 +22,13 @@ pub struct PositionAndOrientation {
     pub orientation: f32,
 }
 
+impl PositionAndOrientation {
+    pub fn distance_to(&self, other: &PositionAndOrientation) -> f32 {
+        let (a, b) = (&self.position, &other.position);
+        ((a.x - b.x).powi(2) + (a.y - b.y).powi(2) + (a.z - b.z).powi(2)).sqrt()
+    }
+}
+
 impl From<WorldZoneLocation> for PositionAndOrientation {
     fn from(wzl: WorldZoneLocation) -> Self {
         Self {
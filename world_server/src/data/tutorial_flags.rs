@@ -84,6 +84,10 @@ impl TutorialFlags {
         }
     }
 
+    pub fn to_database_entry(&self) -> Vec<u8> {
+        self.flag_data.iter().flat_map(|a| a.to_le_bytes()).collect()
+    }
+
     pub fn reset(&mut self) {
         self.flag_data = [0; 8];
     }
@@ -0,0 +1,100 @@
+use crate::prelude::*;
+use crate::world::prelude::MapManager;
+use rand::Rng;
+use std::sync::Weak;
+use wow_world_messages::wrath::RollVote;
+
+//In seconds, whoever hasn't voted by then passes
+pub const ROLL_TIMEOUT: f32 = 60.0;
+
+#[derive(Clone)]
+pub struct LootRoll {
+    pub map: Weak<MapManager>,
+    //The corpse the item lies on
+    pub source: Guid,
+    pub slot: u8,
+    pub item: u32,
+    pub count: u32,
+    pub eligible: Vec<Guid>,
+    //Need before greed only lets characters that can use the item roll need
+    pub need_allowed: Vec<Guid>,
+    votes: Vec<(Guid, RollVote)>,
+    pub timer: f32,
+}
+
+impl LootRoll {
+    pub fn new(map: Weak<MapManager>, source: Guid, slot: u8, item: u32, count: u32, eligible: Vec<Guid>, need_allowed: Vec<Guid>) -> Self {
+        Self {
+            map,
+            source,
+            slot,
+            item,
+            count,
+            eligible,
+            need_allowed,
+            votes: Vec::new(),
+            timer: ROLL_TIMEOUT,
+        }
+    }
+
+    pub fn vote(&mut self, voter: Guid, vote: RollVote) -> Result<()> {
+        if !self.eligible.contains(&voter) {
+            bail!("{} is not rolling for slot {} of {}", voter, self.slot, self.source);
+        }
+        if self.votes.iter().any(|(guid, _)| *guid == voter) {
+            bail!("{} already voted for slot {} of {}", voter, self.slot, self.source);
+        }
+        if vote == RollVote::Need && !self.need_allowed.contains(&voter) {
+            bail!("{} may not roll need on item {}", voter, self.item);
+        }
+        self.votes.push((voter, vote));
+        Ok(())
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.timer <= 0.0 || self.votes.len() >= self.eligible.len()
+    }
+
+    //Everyone that didn't pass rolls from 1 to 100, passes get 0
+    pub fn roll_numbers(&self) -> Vec<(Guid, RollVote, u8)> {
+        let mut rng = rand::thread_rng();
+        self.votes
+            .iter()
+            .map(|&(guid, vote)| {
+                let number = if vote == RollVote::Pass { 0 } else { rng.gen_range(1..=100) };
+                (guid, vote, number)
+            })
+            .collect()
+    }
+}
+
+//Need beats greed and disenchant, within the same vote the highest number wins
+pub fn find_roll_winner(results: &[(Guid, RollVote, u8)]) -> Option<(Guid, RollVote, u8)> {
+    let priority = |vote: RollVote| if vote == RollVote::Need { 1 } else { 0 };
+    results
+        .iter()
+        .filter(|(_, vote, _)| *vote != RollVote::Pass)
+        .max_by_key(|(_, vote, number)| (priority(*vote), *number))
+        .copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn need_beats_greed() {
+        let (first, second, third) = (Guid::new(1), Guid::new(2), Guid::new(3));
+        let results = [(first, RollVote::Greed, 99), (second, RollVote::Need, 2), (third, RollVote::Need, 50)];
+        assert_eq!(find_roll_winner(&results), Some((third, RollVote::Need, 50)));
+
+        let results = [
+            (first, RollVote::Greed, 10),
+            (second, RollVote::Disenchant, 40),
+            (third, RollVote::Pass, 0),
+        ];
+        assert_eq!(find_roll_winner(&results), Some((second, RollVote::Disenchant, 40)));
+
+        assert_eq!(find_roll_winner(&[(first, RollVote::Pass, 0)]), None);
+    }
+}
@@ -0,0 +1,376 @@
+use crate::loot::Loot;
+use crate::prelude::*;
+use crate::world::guid_service::HighGuid;
+use smol::lock::{Mutex, RwLock};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, Ordering};
+use wow_world_messages::wrath::{GroupLootSetting, ItemQuality, RollVote};
+
+mod loot_roll;
+pub use loot_roll::*;
+
+pub const MAX_GROUP_SIZE: usize = 5;
+
+#[derive(Clone, Debug)]
+pub struct GroupMember {
+    pub guid: Guid,
+    pub name: String,
+}
+
+#[derive(Clone, Debug)]
+pub struct Group {
+    pub guid: Guid,
+    pub leader: Guid,
+    pub members: Vec<GroupMember>,
+    pub loot_method: GroupLootSetting,
+    pub master_looter: Guid,
+    pub loot_threshold: ItemQuality,
+    //Instances the group has entered, by map and difficulty, so every member ends up in the same one
+    instances: HashMap<(u32, u8), u32>,
+    //Whose turn it is to loot under round robin, as an index into members
+    round_robin_index: usize,
+}
+
+impl Group {
+    fn new(guid: Guid, leader: GroupMember) -> Self {
+        Self {
+            guid,
+            leader: leader.guid,
+            master_looter: leader.guid,
+            members: vec![leader],
+            loot_method: GroupLootSetting::GroupLoot,
+            loot_threshold: ItemQuality::Uncommon,
+            instances: HashMap::new(),
+            round_robin_index: 0,
+        }
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.members.len() >= MAX_GROUP_SIZE
+    }
+
+    pub fn get_member_guids(&self) -> Vec<Guid> {
+        self.members.iter().map(|member| member.guid).collect()
+    }
+
+    //The first member in line that is around to loot, who then goes to the back of the line
+    fn next_round_robin_looter(&mut self, eligible: &[Guid]) -> Option<Guid> {
+        let num_members = self.members.len();
+        for offset in 0..num_members {
+            let index = (self.round_robin_index + offset) % num_members;
+            if eligible.contains(&self.members[index].guid) {
+                self.round_robin_index = index + 1;
+                return Some(self.members[index].guid);
+            }
+        }
+        None
+    }
+}
+
+//What happened to a group after a member left it
+pub enum GroupChange {
+    Remaining(Group),
+    //Nobody is left to group with, these are the members that were still in it
+    Disbanded(Vec<Guid>),
+}
+
+pub enum LootAward {
+    Item { source: Guid, slot: u8 },
+    Money(u32),
+}
+
+//Everyone who may loot gets the loot window, items at or above the threshold are rolled for or handed out by the master looter.
+//Quest items are left to everyone. Returns the slots to roll for.
+pub fn distribute_loot(loot: &mut Loot, group: &Group, eligible: &[Guid], round_robin_looter: Guid, get_quality: impl Fn(u32) -> u8) -> Vec<u8> {
+    let threshold = group.loot_threshold.as_int();
+    let master_looter_present = eligible.contains(&group.master_looter);
+    loot.looters = eligible.to_vec();
+
+    let mut rolls = Vec::new();
+    for (slot, item) in loot.items.iter_mut().enumerate().filter(|(_, item)| !item.quest_required) {
+        let above_threshold = get_quality(item.item) >= threshold;
+        match group.loot_method {
+            GroupLootSetting::FreeForAll => {}
+            GroupLootSetting::MasterLoot if above_threshold && master_looter_present => item.master_loot = true,
+            GroupLootSetting::GroupLoot | GroupLootSetting::NeedBeforeGreed if above_threshold => {
+                item.roll_pending = true;
+                rolls.push(slot as u8);
+            }
+            _ => item.owner = Some(round_robin_looter),
+        }
+    }
+
+    match group.loot_method {
+        //Whoever's turn it is gets the whole corpse
+        GroupLootSetting::RoundRobin => loot.looters = vec![round_robin_looter],
+        GroupLootSetting::MasterLoot if master_looter_present => loot.master_looter = Some(group.master_looter),
+        _ => {}
+    }
+    rolls
+}
+
+pub struct GroupManager {
+    next_group_id: AtomicU32,
+    //Group lists carry a counter, so the client can tell which one is the latest
+    list_counter: AtomicU32,
+    groups: RwLock<HashMap<Guid, Group>>,
+    //Invites that haven't been answered yet, by the guid of the invited character
+    invites: RwLock<HashMap<Guid, GroupMember>>,
+    rolls: Mutex<Vec<LootRoll>>,
+    //Loot that goes into the bags of its new owner on their next tick
+    awards: Mutex<HashMap<Guid, Vec<LootAward>>>,
+}
+
+impl Default for GroupManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GroupManager {
+    pub fn new() -> Self {
+        Self {
+            next_group_id: AtomicU32::new(1),
+            list_counter: AtomicU32::new(0),
+            groups: RwLock::new(HashMap::new()),
+            invites: RwLock::new(HashMap::new()),
+            rolls: Mutex::new(Vec::new()),
+            awards: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub async fn get_group_of(&self, member: Guid) -> Option<Group> {
+        let groups = self.groups.read().await;
+        groups.values().find(|group| group.members.iter().any(|m| m.guid == member)).cloned()
+    }
+
+    pub async fn get_group_instance(&self, member: Guid, map: u32, difficulty: u8) -> Option<u32> {
+        self.get_group_of(member).await?.instances.get(&(map, difficulty)).copied()
+    }
+
+    //Does nothing for characters that aren't in a group
+    pub async fn bind_group_instance(&self, member: Guid, map: u32, difficulty: u8, instance_id: u32) {
+        let mut groups = self.groups.write().await;
+        if let Some(group) = groups.values_mut().find(|group| group.members.iter().any(|m| m.guid == member)) {
+            group.instances.insert((map, difficulty), instance_id);
+        }
+    }
+
+    pub async fn add_invite(&self, invitee: Guid, inviter: GroupMember) {
+        self.invites.write().await.insert(invitee, inviter);
+    }
+
+    pub async fn has_invite(&self, invitee: Guid) -> bool {
+        self.invites.read().await.contains_key(&invitee)
+    }
+
+    pub async fn take_invite(&self, invitee: Guid) -> Option<GroupMember> {
+        self.invites.write().await.remove(&invitee)
+    }
+
+    //Both the invites to and from the character
+    pub async fn remove_invites(&self, character: Guid) {
+        self.invites
+            .write()
+            .await
+            .retain(|invitee, inviter| *invitee != character && inviter.guid != character);
+    }
+
+    //Joins the group of the inviter, who starts a new one if they weren't in a group yet
+    pub async fn add_member(&self, inviter: GroupMember, invitee: GroupMember) -> Result<Group> {
+        let mut groups = self.groups.write().await;
+        if groups.values().any(|group| group.members.iter().any(|m| m.guid == invitee.guid)) {
+            bail!("{} is already in a group", invitee.name);
+        }
+
+        let existing = groups
+            .values()
+            .find(|group| group.members.iter().any(|m| m.guid == inviter.guid))
+            .map(|group| group.guid);
+        let group_guid = existing.unwrap_or_else(|| HighGuid::Group.new_guid(0, self.next_group_id.fetch_add(1, Ordering::Relaxed)));
+        let group = groups.entry(group_guid).or_insert_with(|| Group::new(group_guid, inviter));
+        if group.is_full() {
+            bail!("The group of {} is full", invitee.name);
+        }
+        group.members.push(invitee);
+        Ok(group.clone())
+    }
+
+    pub async fn remove_member(&self, member: Guid) -> Option<GroupChange> {
+        let mut groups = self.groups.write().await;
+        let group = groups.values_mut().find(|group| group.members.iter().any(|m| m.guid == member))?;
+        group.members.retain(|m| m.guid != member);
+
+        if group.members.len() < 2 {
+            let group_guid = group.guid;
+            let remaining = group.get_member_guids();
+            groups.remove(&group_guid);
+            return Some(GroupChange::Disbanded(remaining));
+        }
+
+        if group.leader == member {
+            group.leader = group.members[0].guid;
+        }
+        if group.master_looter == member {
+            group.master_looter = group.leader;
+        }
+        group.round_robin_index %= group.members.len();
+        Some(GroupChange::Remaining(group.clone()))
+    }
+
+    pub async fn set_loot_method(&self, leader: Guid, method: GroupLootSetting, master_looter: Guid, threshold: ItemQuality) -> Result<Group> {
+        let mut groups = self.groups.write().await;
+        let group = groups
+            .values_mut()
+            .find(|group| group.leader == leader)
+            .ok_or_else(|| anyhow!("{} is not the leader of a group", leader))?;
+        group.loot_method = method;
+        if group.members.iter().any(|m| m.guid == master_looter) {
+            group.master_looter = master_looter;
+        }
+        group.loot_threshold = threshold;
+        Ok(group.clone())
+    }
+
+    pub async fn next_round_robin_looter(&self, group: Guid, eligible: &[Guid]) -> Option<Guid> {
+        self.groups.write().await.get_mut(&group)?.next_round_robin_looter(eligible)
+    }
+
+    pub fn next_list_counter(&self) -> u32 {
+        self.list_counter.fetch_add(1, Ordering::Relaxed)
+    }
+
+    pub async fn give_award(&self, owner: Guid, award: LootAward) {
+        self.awards.lock().await.entry(owner).or_default().push(award);
+    }
+
+    pub async fn take_awards(&self, owner: Guid) -> Vec<LootAward> {
+        self.awards.lock().await.remove(&owner).unwrap_or_default()
+    }
+
+    pub async fn start_roll(&self, roll: LootRoll) {
+        self.rolls.lock().await.push(roll);
+    }
+
+    //Returns the roll that was voted on, so everyone rolling can be told
+    pub async fn vote(&self, source: Guid, slot: u8, voter: Guid, vote: RollVote) -> Result<LootRoll> {
+        let mut rolls = self.rolls.lock().await;
+        let roll = rolls
+            .iter_mut()
+            .find(|roll| roll.source == source && roll.slot == slot)
+            .ok_or_else(|| anyhow!("There is no roll for slot {} of {}", slot, source))?;
+        roll.vote(voter, vote)?;
+        Ok(roll.clone())
+    }
+
+    pub async fn tick(&self, delta_time: f32) -> Result<()> {
+        let finished = {
+            let mut rolls = self.rolls.lock().await;
+            for roll in rolls.iter_mut() {
+                roll.timer -= delta_time;
+            }
+            let (finished, ongoing): (Vec<LootRoll>, Vec<LootRoll>) = std::mem::take(&mut *rolls).into_iter().partition(LootRoll::is_finished);
+            *rolls = ongoing;
+            finished
+        };
+
+        for roll in finished {
+            self.finish_roll(roll).await?;
+        }
+        Ok(())
+    }
+
+    async fn finish_roll(&self, roll: LootRoll) -> Result<()> {
+        let Some(map) = roll.map.upgrade() else {
+            return Ok(());
+        };
+        let results = roll.roll_numbers();
+        let winner = find_roll_winner(&results);
+
+        if let Some(creature_lock) = map.try_get_creature(roll.source).await {
+            if let Some(loot) = creature_lock.write().await.get_loot_mut() {
+                loot.assign_item(roll.slot, winner.map(|(guid, _, _)| guid));
+            }
+        }
+        handlers::send_loot_roll_results(&map, &roll, &results, winner).await?;
+        if let Some((winner, _, _)) = winner {
+            let award = LootAward::Item {
+                source: roll.source,
+                slot: roll.slot,
+            };
+            self.give_award(winner, award).await;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::loot::LootItem;
+
+    fn loot_with_items(items: &[u32]) -> Loot {
+        Loot {
+            items: items
+                .iter()
+                .map(|item| LootItem {
+                    item: *item,
+                    count: 1,
+                    quest_required: false,
+                    looted: false,
+                    owner: None,
+                    roll_pending: false,
+                    master_loot: false,
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn loot_methods() {
+        let (leader, other) = (Guid::new(1), Guid::new(2));
+        let mut group = Group::new(
+            Guid::new(100),
+            GroupMember {
+                guid: leader,
+                name: "Leader".into(),
+            },
+        );
+        //Item 1 is poor, item 2 uncommon
+        let get_quality = |item: u32| if item == 1 { 0 } else { 2 };
+
+        let mut loot = loot_with_items(&[1, 2]);
+        assert_eq!(distribute_loot(&mut loot, &group, &[leader, other], other, get_quality), vec![1]);
+        assert_eq!(loot.items[0].owner, Some(other));
+        assert!(loot.items[1].roll_pending);
+
+        group.loot_method = GroupLootSetting::MasterLoot;
+        let mut loot = loot_with_items(&[1, 2]);
+        assert!(distribute_loot(&mut loot, &group, &[leader, other], other, get_quality).is_empty());
+        assert!(loot.items[1].master_loot);
+        assert_eq!(loot.master_looter, Some(leader));
+
+        group.loot_method = GroupLootSetting::RoundRobin;
+        let mut loot = loot_with_items(&[1, 2]);
+        distribute_loot(&mut loot, &group, &[leader, other], other, get_quality);
+        assert_eq!(loot.looters, vec![other]);
+        assert!(loot.items.iter().all(|item| item.owner == Some(other)));
+    }
+
+    #[test]
+    fn round_robin_skips_absent_members() {
+        let member = |guid: u64| GroupMember {
+            guid: Guid::new(guid),
+            name: String::new(),
+        };
+        let mut group = Group::new(Guid::new(100), member(1));
+        group.members.push(member(2));
+        group.members.push(member(3));
+
+        let eligible = [Guid::new(1), Guid::new(3)];
+        assert_eq!(group.next_round_robin_looter(&eligible), Some(Guid::new(1)));
+        assert_eq!(group.next_round_robin_looter(&eligible), Some(Guid::new(3)));
+        assert_eq!(group.next_round_robin_looter(&eligible), Some(Guid::new(1)));
+    }
+}
@@ -0,0 +1,77 @@
+use crate::character::Character;
+use crate::client_manager::ClientManager;
+use crate::packet::ServerMessageExt;
+use crate::prelude::*;
+use crate::world::prelude::inventory::MAX_BANK_BAG_SLOTS;
+use crate::world::prelude::unit_flags::UNIT_NPC_FLAG_BANKER;
+use crate::world::World;
+use wow_dbc::Indexable;
+use wow_world_messages::wrath::{
+    BuyBankSlotResult, CMSG_AUTOBANK_ITEM, CMSG_AUTOSTORE_BANK_ITEM, CMSG_BANKER_ACTIVATE, CMSG_BUY_BANK_SLOT, SMSG_BUY_BANK_SLOT_RESULT,
+    SMSG_SHOW_BANK,
+};
+
+pub async fn handle_cmsg_banker_activate(client_manager: &ClientManager, client_id: u64, world: &World, data: &CMSG_BANKER_ACTIVATE) -> Result<()> {
+    let client = client_manager.get_authenticated_client(client_id).await?;
+    let character_lock = client.get_active_character().await?;
+    let character = character_lock.read().await;
+
+    send_show_bank(&character, world, data.guid).await
+}
+
+pub async fn send_show_bank(character: &Character, world: &World, banker: Guid) -> Result<()> {
+    character.get_npc(world, banker, UNIT_NPC_FLAG_BANKER).await?;
+    SMSG_SHOW_BANK { guid: banker }.astd_send_to_character(character).await
+}
+
+pub async fn handle_cmsg_buy_bank_slot(client_manager: &ClientManager, client_id: u64, _data: &CMSG_BUY_BANK_SLOT) -> Result<()> {
+    let client = client_manager.get_authenticated_client(client_id).await?;
+    let character_lock = client.get_active_character().await?;
+    let mut character = character_lock.write().await;
+
+    let next_slot = character.bank_bag_slots + 1;
+    let result = if next_slot > MAX_BANK_BAG_SLOTS {
+        BuyBankSlotResult::FailedTooMany
+    } else {
+        let price = client_manager
+            .data_storage
+            .get_dbc_bank_bag_slot_prices()?
+            .get(next_slot as u32)
+            .ok_or_else(|| anyhow!("No price for bank bag slot {}", next_slot))?
+            .cost;
+        if character.get_money() < price {
+            BuyBankSlotResult::InsufficientFunds
+        } else {
+            character.remove_money(price);
+            character.set_bank_bag_slots(next_slot);
+            BuyBankSlotResult::Ok
+        }
+    };
+
+    SMSG_BUY_BANK_SLOT_RESULT { result }.astd_send_to_character(&*character).await
+}
+
+pub async fn handle_cmsg_autobank_item(client_manager: &ClientManager, client_id: u64, _world: &World, data: &CMSG_AUTOBANK_ITEM) -> Result<()> {
+    let client = client_manager.get_authenticated_client(client_id).await?;
+    let character_lock = client.get_active_character().await?;
+    let mut character = character_lock.write().await;
+
+    character
+        .try_auto_store_item(&client_manager.data_storage, (data.slot_index, data.bag_index), true)
+        .await
+}
+
+pub async fn handle_cmsg_autostore_bank_item(
+    client_manager: &ClientManager,
+    client_id: u64,
+    _world: &World,
+    data: &CMSG_AUTOSTORE_BANK_ITEM,
+) -> Result<()> {
+    let client = client_manager.get_authenticated_client(client_id).await?;
+    let character_lock = client.get_active_character().await?;
+    let mut character = character_lock.write().await;
+
+    character
+        .try_auto_store_item(&client_manager.data_storage, (data.slot_index, data.bag_index), false)
+        .await
+}
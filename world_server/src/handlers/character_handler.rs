@@ -1,33 +1,43 @@
+use crate::character::character_inventory::InventoryError;
 use crate::character::character_inventory::SimpleCharacterInventory;
 use crate::character::character_inventory::SimpleItemDescription;
 use crate::character::character_inventory::INVENTORY_SLOT_BAG_0;
 use crate::character::Character;
 use crate::client_manager::ClientManager;
 use crate::constants::inventory::*;
+use crate::constants::player_flags::{CHARACTER_FLAG_GHOST, PLAYER_FLAGS_GHOST};
 use crate::data::DataStorage;
+use crate::item::parse_enchantments;
 use crate::packet::*;
 use crate::prelude::*;
+use crate::world::guid_service::{get_low_guid, player_guid};
 use crate::world::prelude::GameObject;
 use crate::world::World;
 use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::convert::TryInto;
 use wow_dbc::DbcTable;
+use wow_world_messages::wrath::SMSG_INVENTORY_CHANGE_FAILURE_InventoryResult;
 use wow_world_messages::wrath::WorldResult;
 use wow_world_messages::wrath::CMSG_AUTOEQUIP_ITEM;
+use wow_world_messages::wrath::CMSG_AUTOSTORE_BAG_ITEM;
 use wow_world_messages::wrath::CMSG_CHAR_CREATE;
 use wow_world_messages::wrath::CMSG_CHAR_DELETE;
+use wow_world_messages::wrath::CMSG_DESTROYITEM;
 use wow_world_messages::wrath::CMSG_PLAYER_LOGIN;
+use wow_world_messages::wrath::CMSG_SPLIT_ITEM;
 use wow_world_messages::wrath::CMSG_STANDSTATECHANGE;
 use wow_world_messages::wrath::CMSG_SWAP_INV_ITEM;
+use wow_world_messages::wrath::CMSG_SWAP_ITEM;
 use wow_world_messages::wrath::SMSG_ACTION_BUTTONS;
 use wow_world_messages::wrath::SMSG_BINDPOINTUPDATE;
 use wow_world_messages::wrath::SMSG_CHAR_CREATE;
 use wow_world_messages::wrath::SMSG_CHAR_DELETE;
+use wow_world_messages::wrath::SMSG_INVENTORY_CHANGE_FAILURE;
 use wow_world_messages::wrath::SMSG_LOGIN_VERIFY_WORLD;
 use wow_world_messages::wrath::{Area, CharacterGear, Class, Gender, InventoryType, Map, Race, SMSG_CHAR_ENUM};
 use wrath_realm_db::character::DBCharacterCreateParameters;
-use wrath_realm_db::RealmDatabase;
+use wrath_realm_db::item_instance::{DBCharacterEquipmentDisplayInfo, DBItemInstance};
 
 pub async fn handle_cmsg_char_enum(client_manager: &ClientManager, world: &World, client_id: u64) -> Result<()> {
     let client = client_manager.get_authenticated_client(client_id).await?;
@@ -39,11 +49,11 @@ pub async fn handle_cmsg_char_enum(client_manager: &ClientManager, world: &World
 
     let mut characters_to_send = Vec::<wow_world_messages::wrath::Character>::new();
     for character in db_characters {
-        let equipment: HashMap<u8, wrath_realm_db::character_equipment::DBCharacterEquipmentDisplayInfo> = {
+        let equipment: HashMap<u8, DBCharacterEquipmentDisplayInfo> = {
             let equipped_items = world.get_realm_database().get_all_character_equipment_display_info(character.id).await?;
             let mut hashmap = HashMap::default();
             for item in equipped_items {
-                hashmap.insert(item.slot_id, item);
+                hashmap.insert(item.slot, item);
             }
             hashmap
         };
@@ -54,7 +64,7 @@ pub async fn handle_cmsg_char_enum(client_manager: &ClientManager, world: &World
                 CharacterGear {
                     equipment_display_id: equipped.displayid.unwrap_or(0),
                     inventory_type: InventoryType::try_from(equipped.inventory_type.unwrap_or(0)).unwrap(),
-                    enchantment: equipped.enchant.unwrap_or(0),
+                    enchantment: parse_enchantments(&equipped.enchantments).first().copied().unwrap_or(0),
                 }
             } else {
                 CharacterGear {
@@ -66,17 +76,18 @@ pub async fn handle_cmsg_char_enum(client_manager: &ClientManager, world: &World
             equipped_items_to_send.push(gear);
         }
 
-        let character_flags = 0; //todo: stuff like being ghost, hide cloak, hide helmet, etc
+        //TODO: hide cloak, hide helmet, etc
+        let character_flags = if character.player_flags as i32 & PLAYER_FLAGS_GHOST != 0 {
+            CHARACTER_FLAG_GHOST
+        } else {
+            0
+        };
         let first_login = character.playtime_total == 0;
 
         assert_eq!(equipped_items_to_send.len(), 23);
 
         characters_to_send.push(wow_world_messages::wrath::Character {
-            //TODO: restore functionality of the HighGuid that the non-wow_world_messages version
-            //has
-            //
-            //let guid = Guid::new(character.id, HighGuid::Player);
-            guid: wow_world_messages::Guid::from(character.id as u64),
+            guid: player_guid(character.id),
             name: character.name,
             race: Race::try_from(character.race).unwrap_or(Race::Human),
             class: Class::try_from(character.class).unwrap_or(Class::Warrior),
@@ -187,7 +198,7 @@ pub async fn handle_cmsg_char_create(client_manager: &ClientManager, client_id:
         data.class,
         data.gender,
         &client_manager.data_storage,
-        &realm_db,
+        world,
     )
     .await?;
 
@@ -203,7 +214,7 @@ pub async fn handle_cmsg_char_delete(client_manager: &ClientManager, client_id:
     let account_id = client.data.read().await.account_id.unwrap();
     let realm_db = world.get_realm_database();
 
-    let character_id = data.guid.guid() as u32;
+    let character_id = get_low_guid(data.guid);
 
     let result = match realm_db.delete_character(character_id, account_id).await {
         Ok(_) => WorldResult::CharDeleteSuccess,
@@ -220,7 +231,7 @@ async fn give_character_start_equipment(
     class: Class,
     gender: Gender,
     data_storage: &DataStorage,
-    realm_db: &RealmDatabase,
+    world: &World,
 ) -> Result<()> {
     let start_outfit_info = data_storage
         .get_dbc_char_start_outfit()?
@@ -229,27 +240,48 @@ async fn give_character_start_equipment(
         .find(|row| row.class_id.id == class.as_int() as i32 && row.race_id.id == race.as_int() as i32 && row.sex_id == gender.as_int() as i8)
         .ok_or_else(|| anyhow!("Class/Race/Gender combination not found for starting outfit"))?;
 
+    let realm_db = world.get_realm_database();
+    let guid_service = world.get_guid_service();
     let mut dummy_inventory = SimpleCharacterInventory::new();
-    let slot_ids: Vec<i32> = start_outfit_info
-        .inventory_type
-        .iter()
-        .zip(start_outfit_info.item_id)
-        .map(|(&inv_type, item_id)| {
-            let inventory_type: InventoryType = (inv_type as u8).try_into()?;
+    let mut items = vec![];
+    for (&inv_type, item_id) in start_outfit_info.inventory_type.iter().zip(start_outfit_info.item_id) {
+        if item_id <= 0 {
+            continue;
+        }
 
-            let sid = SimpleItemDescription {
-                item_id: item_id as u32,
-                inventory_type,
-            };
+        let Ok(inventory_type) = InventoryType::try_from(inv_type as u8) else {
+            continue;
+        };
+        let sid = SimpleItemDescription {
+            item_id: item_id as u32,
+            inventory_type,
+        };
+        //Items that can't be equipped (food, reagents, etc) are not given out yet
+        let Ok(slot) = dummy_inventory.try_insert_item(sid) else {
+            continue;
+        };
 
-            dummy_inventory.try_insert_item(sid).map(|res| res.to_owned())
-        })
-        .map(|res: anyhow::Result<EquipmentSlot, anyhow::Error>| if let Ok(s) = res { s as i32 } else { -1 })
-        .collect();
+        //Items without a template in the DB simply don't get any durability
+        let durability = data_storage
+            .get_item_template(item_id as u32)
+            .map_or(0, |template| template.max_durability);
+
+        items.push(DBItemInstance {
+            guid: get_low_guid(guid_service.generate_item_guid()),
+            entry: item_id as u32,
+            owner_guid: character_id,
+            container_guid: 0,
+            slot: slot as u8,
+            stack_count: 1,
+            durability: durability as u32,
+            enchantments: String::new(),
+            flags: 0,
+            creator_guid: 0,
+            charges: String::new(),
+        });
+    }
 
-    realm_db
-        .give_character_start_equipment(character_id, start_outfit_info.item_id, slot_ids)
-        .await?;
+    realm_db.create_items(items).await?;
 
     Ok(())
 }
@@ -305,7 +337,7 @@ pub async fn send_bind_update(character: &Character) -> Result<()> {
 pub async fn send_action_buttons(character: &Character) -> Result<()> {
     SMSG_ACTION_BUTTONS {
         behavior: wow_world_messages::wrath::SMSG_ACTION_BUTTONS_ActionBarBehavior::Initial {
-            data: character.action_bar.data,
+            data: character.get_action_bar().data,
         },
     }
     .astd_send_to_character(character)
@@ -317,14 +349,19 @@ pub async fn handle_cmsg_swap_inv_item(client_manager: &ClientManager, _world: &
     let character_lock = client.get_active_character().await?;
     let mut character = character_lock.write().await;
 
-    //TODO: Add checks here
-    let src = data.destination_slot.as_int();
-    let dst = data.source_slot.as_int();
-    let dst_item = character.set_item(None, (dst, INVENTORY_SLOT_BAG_0))?;
-    let src_item = character.set_item(dst_item, (src, INVENTORY_SLOT_BAG_0))?;
-    character.set_item(src_item, (dst, INVENTORY_SLOT_BAG_0))?;
+    let source = (data.source_slot.as_int(), INVENTORY_SLOT_BAG_0);
+    let destination = (data.destination_slot.as_int(), INVENTORY_SLOT_BAG_0);
+    character.try_swap_items(&client_manager.data_storage, source, destination).await
+}
 
-    Ok(())
+pub async fn handle_cmsg_swap_item(client_manager: &ClientManager, _world: &World, client_id: u64, data: &CMSG_SWAP_ITEM) -> Result<()> {
+    let client = client_manager.get_authenticated_client(client_id).await?;
+    let character_lock = client.get_active_character().await?;
+    let mut character = character_lock.write().await;
+
+    let source = (data.source_slot, data.source_bag);
+    let destination = (data.destination_slot, data.destination_bag);
+    character.try_swap_items(&client_manager.data_storage, source, destination).await
 }
 
 pub async fn handle_cmsg_autoequip_item(client_manager: &ClientManager, _world: &World, client_id: u64, data: &CMSG_AUTOEQUIP_ITEM) -> Result<()> {
@@ -332,9 +369,149 @@ pub async fn handle_cmsg_autoequip_item(client_manager: &ClientManager, _world:
     let character_lock = client.get_active_character().await?;
     let mut character = character_lock.write().await;
 
-    let previously_equipped_item = character.auto_equip_item_from_bag((data.source_slot, data.source_bag))?;
+    character
+        .try_auto_equip_item(&client_manager.data_storage, (data.source_slot, data.source_bag))
+        .await
+}
 
-    //The item that we had equipped (may be None) now goes into that slot
-    character.set_item(previously_equipped_item, (data.source_slot, data.source_bag))?;
-    Ok(())
+pub async fn handle_cmsg_split_item(client_manager: &ClientManager, world: &World, client_id: u64, data: &CMSG_SPLIT_ITEM) -> Result<()> {
+    let client = client_manager.get_authenticated_client(client_id).await?;
+    let character_lock = client.get_active_character().await?;
+    let mut character = character_lock.write().await;
+
+    let source = (data.source_slot, data.source_bag);
+    let destination = (data.destination_slot, data.destination_bag);
+    character
+        .try_split_item(
+            &client_manager.data_storage,
+            &world.get_guid_service(),
+            source,
+            destination,
+            data.amount as u32,
+        )
+        .await
+}
+
+pub async fn handle_cmsg_destroyitem(client_manager: &ClientManager, client_id: u64, data: &CMSG_DESTROYITEM) -> Result<()> {
+    let client = client_manager.get_authenticated_client(client_id).await?;
+    let character_lock = client.get_active_character().await?;
+    let mut character = character_lock.write().await;
+
+    character.try_destroy_item((data.slot, data.bag), data.amount as u32).await
+}
+
+pub async fn handle_cmsg_autostore_bag_item(client_manager: &ClientManager, client_id: u64, data: &CMSG_AUTOSTORE_BAG_ITEM) -> Result<()> {
+    let client = client_manager.get_authenticated_client(client_id).await?;
+    let character_lock = client.get_active_character().await?;
+    let mut character = character_lock.write().await;
+
+    character
+        .try_auto_store_bag_item(&client_manager.data_storage, (data.source_slot, data.source_bag), data.destination_bag)
+        .await
+}
+
+pub async fn send_inventory_change_failure(character: &Character, error: InventoryError, item1: Guid, item2: Guid) -> Result<()> {
+    use SMSG_INVENTORY_CHANGE_FAILURE_InventoryResult as Failure;
+
+    let bag_type_subclass = 0;
+    let result = match error {
+        InventoryError::ItemNotFound => Failure::ItemNotFound {
+            bag_type_subclass,
+            item1,
+            item2,
+        },
+        InventoryError::ItemDoesntGoToSlot => Failure::ItemDoesntGoToSlot {
+            bag_type_subclass,
+            item1,
+            item2,
+        },
+        InventoryError::ItemCantBeEquipped => Failure::ItemCantBeEquipped {
+            bag_type_subclass,
+            item1,
+            item2,
+        },
+        InventoryError::ItemsCantBeSwapped => Failure::ItemsCantBeSwapped {
+            bag_type_subclass,
+            item1,
+            item2,
+        },
+        InventoryError::CantEquipLevel(required_level) => Failure::CantEquipLevelI {
+            bag_type_subclass,
+            item1,
+            item2,
+            required_level: required_level as u32,
+        },
+        InventoryError::CantEquipSkill => Failure::CantEquipSkill {
+            bag_type_subclass,
+            item1,
+            item2,
+        },
+        InventoryError::YouCanNeverUseThatItem => Failure::YouCanNeverUseThatItem {
+            bag_type_subclass,
+            item1,
+            item2,
+        },
+        InventoryError::ItemUniqueEquippable => Failure::ItemUniqueEquippable {
+            bag_type_subclass,
+            item1,
+            item2,
+        },
+        InventoryError::CantEquipWithTwoHanded => Failure::CantEquipWithTwohanded {
+            bag_type_subclass,
+            item1,
+            item2,
+        },
+        InventoryError::NotABag => Failure::NotABag {
+            bag_type_subclass,
+            item1,
+            item2,
+        },
+        InventoryError::MustPurchaseThatBagSlot => Failure::MustPurchaseThatBagSlot {
+            bag_type_subclass,
+            item1,
+            item2,
+        },
+        InventoryError::NonEmptyBagOverOtherBag => Failure::NonemptyBagOverOtherBag {
+            bag_type_subclass,
+            item1,
+            item2,
+        },
+        InventoryError::CanOnlyDoWithEmptyBags => Failure::CanOnlyDoWithEmptyBags {
+            bag_type_subclass,
+            item1,
+            item2,
+        },
+        InventoryError::InventoryFull => Failure::InventoryFull {
+            bag_type_subclass,
+            item1,
+            item2,
+        },
+        InventoryError::BankFull => Failure::BankFull {
+            bag_type_subclass,
+            item1,
+            item2,
+        },
+        InventoryError::BagFull => Failure::BagFull {
+            bag_type_subclass,
+            item1,
+            item2,
+        },
+        InventoryError::ItemCantStack => Failure::ItemCantStack {
+            bag_type_subclass,
+            item1,
+            item2,
+        },
+        InventoryError::TriedToSplitMoreThanCount => Failure::TriedToSplitMoreThanCount {
+            bag_type_subclass,
+            item1,
+            item2,
+        },
+        InventoryError::CouldntSplitItems => Failure::CouldntSplitItems {
+            bag_type_subclass,
+            item1,
+            item2,
+        },
+    };
+
+    SMSG_INVENTORY_CHANGE_FAILURE { result }.astd_send_to_character(character).await
 }
@@ -0,0 +1,100 @@
+use crate::character::character_combat::MeleeSwing;
+use crate::character::Character;
+use crate::client_manager::ClientManager;
+use crate::combat::MeleeHitOutcome;
+use crate::packet::ServerMessageExt;
+use crate::prelude::*;
+use crate::world::prelude::GameObject;
+use crate::world::World;
+use wow_world_messages::wrath::{
+    DamageInfo, SMSG_ATTACKERSTATEUPDATE_HitInfo, VictimState, CMSG_ATTACKSWING, SMSG_ATTACKERSTATEUPDATE, SMSG_ATTACKSTART, SMSG_ATTACKSTOP,
+    SMSG_ATTACKSWING_CANT_ATTACK, SMSG_ATTACKSWING_DEADTARGET, SMSG_ATTACKSWING_NOTINRANGE,
+};
+
+//Physical damage, as a spell school mask
+const SPELL_SCHOOL_MASK_NORMAL: u32 = 0x01;
+
+pub async fn handle_cmsg_attackswing(client_manager: &ClientManager, client_id: u64, world: &World, data: &CMSG_ATTACKSWING) -> Result<()> {
+    let client = client_manager.get_authenticated_client(client_id).await?;
+    let character_lock = client.get_active_character().await?;
+    let mut character = character_lock.write().await;
+
+    character.start_attack(world, data.guid).await
+}
+
+pub async fn handle_cmsg_attackstop(client_manager: &ClientManager, client_id: u64, world: &World) -> Result<()> {
+    let client = client_manager.get_authenticated_client(client_id).await?;
+    let character_lock = client.get_active_character().await?;
+    let mut character = character_lock.write().await;
+
+    character.stop_attack(world).await
+}
+
+pub async fn send_attack_start(character: &Character, world: &World, victim: Guid) -> Result<()> {
+    SMSG_ATTACKSTART {
+        attacker: character.get_guid(),
+        victim,
+    }
+    .astd_send_to_all_in_range(character, true, world)
+    .await
+}
+
+pub async fn send_attack_stop(character: &Character, world: &World, enemy: Guid) -> Result<()> {
+    SMSG_ATTACKSTOP {
+        player: character.get_guid(),
+        enemy,
+        unknown1: 0,
+    }
+    .astd_send_to_all_in_range(character, true, world)
+    .await
+}
+
+pub async fn send_attackswing_not_in_range(character: &Character) -> Result<()> {
+    SMSG_ATTACKSWING_NOTINRANGE {}.astd_send_to_character(character).await
+}
+
+pub async fn send_attackswing_dead_target(character: &Character) -> Result<()> {
+    SMSG_ATTACKSWING_DEADTARGET {}.astd_send_to_character(character).await
+}
+
+pub async fn send_attackswing_cant_attack(character: &Character) -> Result<()> {
+    SMSG_ATTACKSWING_CANT_ATTACK {}.astd_send_to_character(character).await
+}
+
+pub async fn send_attacker_state_update(character: &Character, world: &World, swing: &MeleeSwing) -> Result<()> {
+    let mut hit_info = SMSG_ATTACKERSTATEUPDATE_HitInfo::empty();
+    if swing.outcome.deals_damage() {
+        hit_info = hit_info.set_affects_victim();
+    }
+    let victim_state = match swing.outcome {
+        MeleeHitOutcome::Miss => {
+            hit_info = hit_info.set_miss();
+            VictimState::Intact
+        }
+        MeleeHitOutcome::Dodge => VictimState::Dodge,
+        MeleeHitOutcome::Parry => VictimState::Parry,
+        MeleeHitOutcome::Crit => {
+            hit_info = hit_info.set_critical_hit();
+            VictimState::Hit
+        }
+        MeleeHitOutcome::Hit => VictimState::Hit,
+    };
+
+    SMSG_ATTACKERSTATEUPDATE {
+        hit_info,
+        attacker: character.get_guid(),
+        target: swing.target,
+        total_damage: swing.damage,
+        overkill: swing.overkill,
+        damages: vec![DamageInfo {
+            spell_school_mask: SPELL_SCHOOL_MASK_NORMAL,
+            damage_float: swing.damage as f32,
+            damage_uint: swing.damage,
+        }],
+        victim_state,
+        unknown1: 0,
+        unknown2: 0,
+    }
+    .astd_send_to_all_in_range(character, true, world)
+    .await
+}
@@ -0,0 +1,73 @@
+use crate::character::Character;
+use crate::client_manager::ClientManager;
+use crate::packet::ServerMessageExt;
+use crate::prelude::*;
+use crate::world::World;
+use wow_world_messages::wrath::{
+    MSG_CORPSE_QUERY_Server, MSG_CORPSE_QUERY_Server_CorpseQueryResult, CMSG_SPIRIT_HEALER_ACTIVATE, SMSG_CORPSE_RECLAIM_DELAY,
+    SMSG_DURABILITY_DAMAGE_DEATH, SMSG_SPIRIT_HEALER_CONFIRM,
+};
+
+pub async fn handle_cmsg_repop_request(client_manager: &ClientManager, client_id: u64, world: &World) -> Result<()> {
+    let client = client_manager.get_authenticated_client(client_id).await?;
+    let character_lock = client.get_active_character().await?;
+    let mut character = character_lock.write().await;
+
+    character.release_spirit(world).await
+}
+
+pub async fn handle_cmsg_reclaim_corpse(client_manager: &ClientManager, client_id: u64, world: &World) -> Result<()> {
+    let client = client_manager.get_authenticated_client(client_id).await?;
+    let character_lock = client.get_active_character().await?;
+    let mut character = character_lock.write().await;
+
+    character.reclaim_corpse(world).await
+}
+
+pub async fn handle_cmsg_spirit_healer_activate(
+    client_manager: &ClientManager,
+    client_id: u64,
+    world: &World,
+    data: &CMSG_SPIRIT_HEALER_ACTIVATE,
+) -> Result<()> {
+    let client = client_manager.get_authenticated_client(client_id).await?;
+    let character_lock = client.get_active_character().await?;
+    let mut character = character_lock.write().await;
+
+    character.resurrect_with_spirit_healer(world, data.guid).await
+}
+
+//The client asks this to point ghosts to their corpse on the map and minimap
+pub async fn handle_msg_corpse_query(client_manager: &ClientManager, client_id: u64) -> Result<()> {
+    let client = client_manager.get_authenticated_client(client_id).await?;
+    let character_lock = client.get_active_character().await?;
+    let character = character_lock.read().await;
+
+    let result = match character.get_corpse_location() {
+        Some(corpse) => MSG_CORPSE_QUERY_Server_CorpseQueryResult::Found {
+            map: corpse.map,
+            position: corpse.position.position,
+            corpse_map: corpse.map,
+        },
+        None => MSG_CORPSE_QUERY_Server_CorpseQueryResult::NotFound,
+    };
+    MSG_CORPSE_QUERY_Server { result, unknown: 0 }.astd_send_to_character(&*character).await
+}
+
+pub async fn send_corpse_reclaim_delay(character: &Character, delay_seconds: f32) -> Result<()> {
+    SMSG_CORPSE_RECLAIM_DELAY {
+        delay: (delay_seconds * 1000.0) as u32,
+    }
+    .astd_send_to_character(character)
+    .await
+}
+
+//Asks a ghost whether it wants to be resurrected by the spirit healer
+pub async fn send_spirit_healer_confirm(character: &Character, spirit_healer: Guid) -> Result<()> {
+    SMSG_SPIRIT_HEALER_CONFIRM { guid: spirit_healer }.astd_send_to_character(character).await
+}
+
+//Tells the client that dying damaged the equipment
+pub async fn send_durability_damage_death(character: &Character) -> Result<()> {
+    SMSG_DURABILITY_DAMAGE_DEATH {}.astd_send_to_character(character).await
+}
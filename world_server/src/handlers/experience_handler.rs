@@ -0,0 +1,45 @@
+use crate::character::character_experience::LevelUpGains;
+use crate::character::Character;
+use crate::packet::ServerMessageExt;
+use crate::prelude::*;
+use wow_world_messages::wrath::{Area, SMSG_LOG_XPGAIN_ExperienceAwardType, SMSG_EXPLORATION_EXPERIENCE, SMSG_LEVELUP_INFO, SMSG_LOG_XPGAIN};
+
+//Shows the experience of a kill in the combat log, rested experience included in the total
+pub async fn send_kill_experience(character: &Character, victim: Guid, total: u32, without_rested: u32) -> Result<()> {
+    SMSG_LOG_XPGAIN {
+        target: victim,
+        total_exp: total,
+        exp_type: SMSG_LOG_XPGAIN_ExperienceAwardType::Kill {
+            experience_without_rested: without_rested,
+            exp_group_bonus: 1.0,
+        },
+        exp_includes_recruit_a_friend_bonus: false,
+    }
+    .astd_send_to_character(character)
+    .await
+}
+
+pub async fn send_exploration_experience(character: &Character, area: Area, experience: u32) -> Result<()> {
+    SMSG_EXPLORATION_EXPERIENCE { area, experience }.astd_send_to_character(character).await
+}
+
+pub async fn send_levelup_info(character: &Character, gains: &LevelUpGains) -> Result<()> {
+    SMSG_LEVELUP_INFO {
+        new_level: gains.level,
+        health: gains.health,
+        mana: gains.mana,
+        rage: 0,
+        focus: 0,
+        energy: 0,
+        happiness: 0,
+        rune: 0,
+        runic_power: 0,
+        strength: gains.stats[0],
+        agility: gains.stats[1],
+        stamina: gains.stats[2],
+        intellect: gains.stats[3],
+        spirit: gains.stats[4],
+    }
+    .astd_send_to_character(character)
+    .await
+}
@@ -1,12 +1,34 @@
 use crate::character::*;
 use crate::packet::*;
 use crate::prelude::*;
-use wow_world_messages::wrath::FactionInitializer;
-use wow_world_messages::wrath::SMSG_INITIALIZE_FACTIONS;
+use wow_world_messages::wrath::{Faction, FactionFlag, FactionInitializer, FactionStanding, SMSG_INITIALIZE_FACTIONS, SMSG_SET_FACTION_STANDING};
 
 const NUM_FACTIONS: u32 = 128;
 
 pub async fn send_faction_list(character: &Character) -> Result<()> {
-    let factions = (0..NUM_FACTIONS).map(|_| FactionInitializer::default()).collect();
+    let mut factions: Vec<FactionInitializer> = (0..NUM_FACTIONS).map(|_| FactionInitializer::default()).collect();
+    for reputation in character.reputations.values() {
+        if let Some(faction) = factions.get_mut(reputation.reputation_index as usize) {
+            *faction = FactionInitializer {
+                flag: FactionFlag::empty().set_visible(),
+                standing: reputation.standing as u32,
+            };
+        }
+    }
     SMSG_INITIALIZE_FACTIONS { factions }.astd_send_to_character(character).await
 }
+
+pub async fn send_faction_standing(character: &Character, faction: u32, reputation_index: u32, standing: i32) -> Result<()> {
+    //The client only needs the faction to find its place in the reputation list
+    let faction = Faction::try_from(faction as u16).map_err(|_| anyhow!("Faction {} at index {} is not known", faction, reputation_index))?;
+    SMSG_SET_FACTION_STANDING {
+        refer_a_friend_bonus: 0.0,
+        any_rank_lowered: false,
+        faction_standings: vec![FactionStanding {
+            faction,
+            standing: standing as u32,
+        }],
+    }
+    .astd_send_to_character(character)
+    .await
+}
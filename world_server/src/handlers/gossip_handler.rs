@@ -0,0 +1,120 @@
+use crate::character::character_death::LifeState;
+use crate::character::Character;
+use crate::client_manager::ClientManager;
+use crate::data::{DataStorage, GossipOptionAction};
+use crate::packet::ServerMessageExt;
+use crate::prelude::*;
+use crate::world::guid_service::get_entry;
+use crate::world::prelude::unit_flags::{UNIT_NPC_FLAG_GOSSIP, UNIT_NPC_FLAG_SPIRIT_HEALER};
+use crate::world::World;
+use wow_world_messages::wrath::{
+    Gold, GossipItem, QuestItem, CMSG_GOSSIP_HELLO, CMSG_GOSSIP_SELECT_OPTION, SMSG_GOSSIP_COMPLETE, SMSG_GOSSIP_MESSAGE,
+};
+
+//Text the client shows for creatures without a gossip menu of their own
+const DEFAULT_GOSSIP_TEXT_ID: u32 = 0xFFFFFF;
+
+pub async fn handle_cmsg_gossip_hello(client_manager: &ClientManager, client_id: u64, world: &World, data: &CMSG_GOSSIP_HELLO) -> Result<()> {
+    let client = client_manager.get_authenticated_client(client_id).await?;
+    let character_lock = client.get_active_character().await?;
+    let character = character_lock.read().await;
+
+    let creature_lock = character
+        .get_npc(world, data.guid, UNIT_NPC_FLAG_GOSSIP | UNIT_NPC_FLAG_SPIRIT_HEALER)
+        .await?;
+    let (is_spirit_healer, menu_id) = {
+        let creature = creature_lock.read().await;
+        (creature.has_npc_flag(UNIT_NPC_FLAG_SPIRIT_HEALER), creature.get_gossip_menu_id())
+    };
+
+    //Ghosts don't get to chat, they get asked whether they want to come back to life
+    if is_spirit_healer && character.get_life_state() == LifeState::Ghost {
+        return super::send_spirit_healer_confirm(&character, data.guid).await;
+    }
+
+    let data_storage = &client_manager.data_storage;
+    let quests = super::quest_handler::get_quest_menu_items(&character, data_storage, get_entry(data.guid));
+    send_gossip_menu(&character, data_storage, data.guid, menu_id, quests).await
+}
+
+pub async fn handle_cmsg_gossip_select_option(
+    client_manager: &ClientManager,
+    client_id: u64,
+    world: &World,
+    data: &CMSG_GOSSIP_SELECT_OPTION,
+) -> Result<()> {
+    let client = client_manager.get_authenticated_client(client_id).await?;
+    let character_lock = client.get_active_character().await?;
+    let mut character = character_lock.write().await;
+
+    character.get_npc(world, data.guid, UNIT_NPC_FLAG_GOSSIP).await?;
+    let data_storage = &client_manager.data_storage;
+    let action = data_storage
+        .get_gossip_menu(data.menu_id)
+        .and_then(|menu| menu.options.iter().find(|option| option.id == data.gossip_list_id))
+        .map(|option| option.action)
+        .ok_or_else(|| anyhow!("Gossip menu {} has no option {}", data.menu_id, data.gossip_list_id))?;
+
+    match action {
+        GossipOptionAction::Menu(menu_id) => send_gossip_menu(&character, data_storage, data.guid, menu_id, vec![]).await,
+        GossipOptionAction::Vendor => {
+            send_gossip_complete(&character).await?;
+            super::send_vendor_inventory(&character, world, data.guid).await
+        }
+        GossipOptionAction::Banker => {
+            send_gossip_complete(&character).await?;
+            super::send_show_bank(&character, world, data.guid).await
+        }
+        GossipOptionAction::SpiritHealer => {
+            send_gossip_complete(&character).await?;
+            super::send_spirit_healer_confirm(&character, data.guid).await
+        }
+        GossipOptionAction::Trainer => {
+            send_gossip_complete(&character).await?;
+            super::send_trainer_list(&character, world, data.guid).await
+        }
+        GossipOptionAction::UnlearnTalents => {
+            send_gossip_complete(&character).await?;
+            character.get_trainer(world, data.guid).await?;
+            super::send_talent_wipe_confirm(&character, data.guid).await
+        }
+        GossipOptionAction::DualSpec => {
+            send_gossip_complete(&character).await?;
+            character.buy_dual_talent_spec(world, data.guid).await
+        }
+    }
+}
+
+async fn send_gossip_menu(character: &Character, data_storage: &DataStorage, guid: Guid, menu_id: u32, quests: Vec<QuestItem>) -> Result<()> {
+    let menu = data_storage.get_gossip_menu(menu_id);
+    let gossips = menu
+        .map(|menu| {
+            menu.options
+                .iter()
+                .map(|option| GossipItem {
+                    id: option.id,
+                    item_icon: option.icon,
+                    coded: false,
+                    money_required: Gold::new(0),
+                    message: option.text.clone(),
+                    accept_text: String::new(),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    SMSG_GOSSIP_MESSAGE {
+        guid,
+        menu_id,
+        title_text_id: menu.map_or(DEFAULT_GOSSIP_TEXT_ID, |menu| menu.text_id),
+        gossips,
+        quests,
+    }
+    .astd_send_to_character(character)
+    .await
+}
+
+//Closes the gossip window
+async fn send_gossip_complete(character: &Character) -> Result<()> {
+    SMSG_GOSSIP_COMPLETE {}.astd_send_to_character(character).await
+}
@@ -1,9 +1,261 @@
-use wow_world_messages::wrath::SMSG_RAID_INSTANCE_INFO;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
 
+use wow_world_messages::wrath::{
+    DungeonDifficulty, GroupListMember, GroupType, Map, PartyOperation, PartyResult, PlayerInviteStatus, RaidDifficulty, RaidInfo,
+    SMSG_GROUP_LIST_group_not_empty, CMSG_GROUP_INVITE, CMSG_GROUP_UNINVITE_GUID, CMSG_LOOT_METHOD, SMSG_GROUP_DECLINE, SMSG_GROUP_DESTROYED,
+    SMSG_GROUP_INVITE, SMSG_GROUP_LIST, SMSG_GROUP_UNINVITE, SMSG_PARTY_COMMAND_RESULT, SMSG_RAID_INSTANCE_INFO,
+};
+
+use crate::client::Client;
+use crate::group::{Group, GroupChange, GroupMember};
+use crate::world::prelude::GameObject;
+use crate::world::World;
 use crate::{client_manager::ClientManager, packet::ServerMessageExt, prelude::*};
 
-pub async fn handle_cmsg_request_raid_info(client_manager: &ClientManager, client_id: u64) -> Result<()> {
+pub async fn handle_cmsg_request_raid_info(client_manager: &ClientManager, client_id: u64, world: &World) -> Result<()> {
+    let client = client_manager.get_authenticated_client(client_id).await?;
+    let character_id = client.get_active_character().await?.read().await.get_guid().guid() as u32;
+
+    let unix_time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+    let mut raid_infos = vec![];
+    for save in world.get_realm_database().get_character_instances(character_id).await? {
+        if save.reset_time <= unix_time {
+            continue;
+        }
+
+        let map = Map::try_from(save.map as u32)?;
+        raid_infos.push(RaidInfo {
+            map,
+            difficulty: get_save_difficulty(world, map, save.difficulty)?,
+            instance_id: save.id as u64,
+            expired: false,
+            extended: false,
+            time_until_reset: (save.reset_time - unix_time) as u32,
+        });
+    }
+
+    SMSG_RAID_INSTANCE_INFO { raid_infos }.astd_send_to_client(client).await
+}
+
+//Dungeon and raid saves share the difficulty field, the client reads it as a dungeon difficulty for dungeon maps
+fn get_save_difficulty(world: &World, map: Map, difficulty: u8) -> Result<RaidDifficulty> {
+    if world.get_instance_manager().is_raid(map) {
+        Ok(RaidDifficulty::try_from(difficulty)?)
+    } else {
+        Ok(RaidDifficulty::try_from(DungeonDifficulty::try_from(difficulty)?.as_int())?)
+    }
+}
+
+pub async fn handle_cmsg_group_invite(client_manager: &ClientManager, client_id: u64, world: &World, data: &CMSG_GROUP_INVITE) -> Result<()> {
+    let client = client_manager.get_authenticated_client(client_id).await?;
+    let inviter = get_group_member(&client).await?;
+    let group_manager = world.get_group_manager();
+
+    let invitee_client = client_manager.find_client_from_active_character_name(&data.name).await?;
+    let Some(invitee_client) = invitee_client else {
+        return send_party_command_result(&client, PartyOperation::Invite, &data.name, PartyResult::BadPlayerNameS).await;
+    };
+    let invitee = get_group_member(&invitee_client).await?;
+    if invitee.guid == inviter.guid {
+        return send_party_command_result(&client, PartyOperation::Invite, &data.name, PartyResult::BadPlayerNameS).await;
+    }
+    if group_manager.get_group_of(invitee.guid).await.is_some() || group_manager.has_invite(invitee.guid).await {
+        return send_party_command_result(&client, PartyOperation::Invite, &invitee.name, PartyResult::AlreadyInGroupS).await;
+    }
+    if let Some(group) = group_manager.get_group_of(inviter.guid).await {
+        if group.leader != inviter.guid {
+            return send_party_command_result(&client, PartyOperation::Invite, &invitee.name, PartyResult::NotLeader).await;
+        }
+        if group.is_full() {
+            return send_party_command_result(&client, PartyOperation::Invite, &invitee.name, PartyResult::GroupFull).await;
+        }
+    }
+
+    SMSG_GROUP_INVITE {
+        status: PlayerInviteStatus::NotInGroup,
+        name: inviter.name.clone(),
+        unknown1: 0,
+        count: 0,
+        unknown2: 0,
+    }
+    .astd_send_to_client(invitee_client)
+    .await?;
+    group_manager.add_invite(invitee.guid, inviter).await;
+    send_party_command_result(&client, PartyOperation::Invite, &invitee.name, PartyResult::Success).await
+}
+
+pub async fn handle_cmsg_group_accept(client_manager: &ClientManager, client_id: u64, world: &World) -> Result<()> {
+    let client = client_manager.get_authenticated_client(client_id).await?;
+    let invitee = get_group_member(&client).await?;
+    let group_manager = world.get_group_manager();
+
+    let inviter = group_manager
+        .take_invite(invitee.guid)
+        .await
+        .ok_or_else(|| anyhow!("{} accepted a group invite they never got", invitee.name))?;
+    let group = group_manager.add_member(inviter, invitee).await?;
+    send_group_list(client_manager, world, &group).await
+}
+
+pub async fn handle_cmsg_group_decline(client_manager: &ClientManager, client_id: u64, world: &World) -> Result<()> {
+    let client = client_manager.get_authenticated_client(client_id).await?;
+    let invitee = get_group_member(&client).await?;
+
+    let Some(inviter) = world.get_group_manager().take_invite(invitee.guid).await else {
+        return Ok(());
+    };
+    if let Some(inviter_client) = client_manager.find_client_from_active_character_guid(&inviter.guid).await? {
+        SMSG_GROUP_DECLINE { name: invitee.name }.astd_send_to_client(inviter_client).await?;
+    }
+    Ok(())
+}
+
+pub async fn handle_cmsg_group_disband(client_manager: &ClientManager, client_id: u64, world: &World) -> Result<()> {
     let client = client_manager.get_authenticated_client(client_id).await?;
+    let member = get_group_member(&client).await?;
+
+    leave_group(client_manager, world, member.guid).await
+}
+
+pub async fn handle_cmsg_group_uninvite_guid(
+    client_manager: &ClientManager,
+    client_id: u64,
+    world: &World,
+    data: &CMSG_GROUP_UNINVITE_GUID,
+) -> Result<()> {
+    let client = client_manager.get_authenticated_client(client_id).await?;
+    let leader = get_group_member(&client).await?;
+
+    let group = world.get_group_manager().get_group_of(leader.guid).await;
+    let Some(group) = group.filter(|group| group.leader == leader.guid) else {
+        return send_party_command_result(&client, PartyOperation::Uninvite, "", PartyResult::NotLeader).await;
+    };
+    let Some(member) = group.members.iter().find(|member| member.guid == data.guid) else {
+        return send_party_command_result(&client, PartyOperation::Uninvite, "", PartyResult::TargetNotInGroupS).await;
+    };
+
+    if let Some(member_client) = client_manager.find_client_from_active_character_guid(&member.guid).await? {
+        SMSG_GROUP_UNINVITE {}.astd_send_to_client(member_client).await?;
+    }
+    leave_group(client_manager, world, member.guid).await
+}
+
+pub async fn handle_cmsg_loot_method(client_manager: &ClientManager, client_id: u64, world: &World, data: &CMSG_LOOT_METHOD) -> Result<()> {
+    let client = client_manager.get_authenticated_client(client_id).await?;
+    let leader = get_group_member(&client).await?;
+
+    let group = world
+        .get_group_manager()
+        .set_loot_method(leader.guid, data.loot_setting, data.loot_master, data.loot_threshold)
+        .await?;
+    send_group_list(client_manager, world, &group).await
+}
+
+//Also used when a character goes offline, the others are told who is left
+pub async fn leave_group(client_manager: &ClientManager, world: &World, member: Guid) -> Result<()> {
+    match world.get_group_manager().remove_member(member).await {
+        None => return Ok(()),
+        Some(GroupChange::Remaining(group)) => send_group_list(client_manager, world, &group).await?,
+        Some(GroupChange::Disbanded(remaining)) => {
+            for guid in remaining {
+                if let Some(client) = client_manager.find_client_from_active_character_guid(&guid).await? {
+                    SMSG_GROUP_DESTROYED {}.astd_send_to_client(client.clone()).await?;
+                    send_empty_group_list(world, client).await?;
+                }
+            }
+        }
+    }
+
+    if let Some(client) = client_manager.find_client_from_active_character_guid(&member).await? {
+        send_empty_group_list(world, client).await?;
+    }
+    Ok(())
+}
+
+async fn get_group_member(client: &Client) -> Result<GroupMember> {
+    let character_lock = client.get_active_character().await?;
+    let character = character_lock.read().await;
+    Ok(GroupMember {
+        guid: character.get_guid(),
+        name: character.name.clone(),
+    })
+}
+
+//Every member gets a list of everyone but themselves
+async fn send_group_list(client_manager: &ClientManager, world: &World, group: &Group) -> Result<()> {
+    let mut clients = Vec::new();
+    for member in &group.members {
+        clients.push(client_manager.find_client_from_active_character_guid(&member.guid).await?);
+    }
+
+    for (member, client) in group.members.iter().zip(&clients) {
+        let Some(client) = client else {
+            continue;
+        };
+        let members = group
+            .members
+            .iter()
+            .zip(&clients)
+            .filter(|(other, _)| other.guid != member.guid)
+            .map(|(other, other_client)| GroupListMember {
+                name: other.name.clone(),
+                guid: other.guid,
+                is_online: other_client.is_some(),
+                group_id: 0,
+                flags: 0,
+                lfg_roles: 0,
+            })
+            .collect();
+
+        SMSG_GROUP_LIST {
+            group_type: GroupType::Normal,
+            group_id: 0,
+            flags: 0,
+            roles: 0,
+            lfg: None,
+            group: group.guid,
+            counter: world.get_group_manager().next_list_counter(),
+            members,
+            leader: group.leader,
+            group_not_empty: Some(SMSG_GROUP_LIST_group_not_empty {
+                loot_setting: group.loot_method,
+                master_loot: group.master_looter,
+                loot_threshold: group.loot_threshold,
+                difficulty: DungeonDifficulty::default(),
+                raid_difficulty: RaidDifficulty::default(),
+                heroic: 0,
+            }),
+        }
+        .astd_send_to_client(client.clone())
+        .await?;
+    }
+    Ok(())
+}
+
+async fn send_empty_group_list(world: &World, client: Arc<Client>) -> Result<()> {
+    SMSG_GROUP_LIST {
+        group_type: GroupType::Normal,
+        group_id: 0,
+        flags: 0,
+        roles: 0,
+        lfg: None,
+        group: Guid::zero(),
+        counter: world.get_group_manager().next_list_counter(),
+        members: Vec::new(),
+        leader: Guid::zero(),
+        group_not_empty: None,
+    }
+    .astd_send_to_client(client)
+    .await
+}
 
-    SMSG_RAID_INSTANCE_INFO { raid_infos: vec![] }.astd_send_to_client(client).await
+async fn send_party_command_result(client: &Client, operation: PartyOperation, member: &str, result: PartyResult) -> Result<()> {
+    SMSG_PARTY_COMMAND_RESULT {
+        operation,
+        member: member.to_string(),
+        result,
+    }
+    .astd_send_to_client(client)
+    .await
 }
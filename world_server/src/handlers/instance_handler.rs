@@ -1,15 +1,80 @@
 use crate::character::Character;
+use crate::client_manager::ClientManager;
 use crate::packet::ServerMessageExt;
 use crate::prelude::*;
+use crate::world::prelude::GameObject;
+use crate::world::World;
 
-use wow_world_messages::wrath::{DungeonDifficulty, MSG_SET_DUNGEON_DIFFICULTY_Server};
+use wow_world_messages::wrath::{
+    MSG_SET_DUNGEON_DIFFICULTY_Client, MSG_SET_DUNGEON_DIFFICULTY_Server, MSG_SET_RAID_DIFFICULTY_Client, MSG_SET_RAID_DIFFICULTY_Server,
+};
 
-pub async fn send_dungeon_difficulty(character: &Character) -> Result<()> {
+pub async fn send_dungeon_difficulty(character: &Character, world: &World) -> Result<()> {
     MSG_SET_DUNGEON_DIFFICULTY_Server {
-        difficulty: DungeonDifficulty::Normal,
+        difficulty: character.dungeon_difficulty,
         unknown1: 1,
-        is_in_group: false,
+        is_in_group: is_in_group(character, world).await,
     }
     .astd_send_to_character(character)
     .await
 }
+
+pub async fn send_raid_difficulty(character: &Character, world: &World) -> Result<()> {
+    MSG_SET_RAID_DIFFICULTY_Server {
+        difficulty: character.raid_difficulty,
+        unknown1: 1,
+        is_in_group: is_in_group(character, world).await,
+    }
+    .astd_send_to_character(character)
+    .await
+}
+
+pub async fn handle_msg_set_dungeon_difficulty(
+    client_manager: &ClientManager,
+    client_id: u64,
+    world: &World,
+    packet: &MSG_SET_DUNGEON_DIFFICULTY_Client,
+) -> Result<()> {
+    let client = client_manager.get_authenticated_client(client_id).await?;
+    let character_lock = client.get_active_character().await?;
+    let mut character = character_lock.write().await;
+
+    //Sending back the difficulty the character already has tells the client the change was refused
+    if !can_change_difficulty(&character, world).await {
+        return send_dungeon_difficulty(&character, world).await;
+    }
+
+    character.dungeon_difficulty = packet.difficulty;
+    send_dungeon_difficulty(&character, world).await
+}
+
+pub async fn handle_msg_set_raid_difficulty(
+    client_manager: &ClientManager,
+    client_id: u64,
+    world: &World,
+    packet: &MSG_SET_RAID_DIFFICULTY_Client,
+) -> Result<()> {
+    let client = client_manager.get_authenticated_client(client_id).await?;
+    let character_lock = client.get_active_character().await?;
+    let mut character = character_lock.write().await;
+
+    if !can_change_difficulty(&character, world).await {
+        return send_raid_difficulty(&character, world).await;
+    }
+
+    character.raid_difficulty = packet.difficulty;
+    send_raid_difficulty(&character, world).await
+}
+
+async fn is_in_group(character: &Character, world: &World) -> bool {
+    world.get_group_manager().get_group_of(character.get_guid()).await.is_some()
+}
+
+//Only group leaders pick the difficulty, and never from inside an instance
+async fn can_change_difficulty(character: &Character, world: &World) -> bool {
+    if world.get_instance_manager().is_instance(character.map) {
+        return false;
+    }
+    let group = world.get_group_manager().get_group_of(character.get_guid()).await;
+    !group.is_some_and(|group| group.leader != character.get_guid())
+}
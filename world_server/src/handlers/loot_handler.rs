@@ -0,0 +1,227 @@
+use crate::character::Character;
+use crate::client_manager::ClientManager;
+use crate::data::DataStorage;
+use crate::group::{LootRoll, ROLL_TIMEOUT};
+use crate::loot::LootItem;
+use crate::packet::ServerMessageExt;
+use crate::prelude::*;
+use crate::world::prelude::{GameObject, MapManager};
+use crate::world::World;
+use wow_world_messages::wrath::{
+    LootMethodError, LootSlotType, Map, RollFlags, RollVote, SMSG_LOOT_RESPONSE_LootMethod, CMSG_AUTOSTORE_LOOT_ITEM, CMSG_LOOT,
+    CMSG_LOOT_MASTER_GIVE, CMSG_LOOT_RELEASE, CMSG_LOOT_ROLL, CMSG_OPEN_ITEM, SMSG_LOOT_ALL_PASSED, SMSG_LOOT_CLEAR_MONEY, SMSG_LOOT_MASTER_LIST,
+    SMSG_LOOT_MONEY_NOTIFY, SMSG_LOOT_RELEASE_RESPONSE, SMSG_LOOT_REMOVED, SMSG_LOOT_RESPONSE, SMSG_LOOT_ROLL, SMSG_LOOT_ROLL_WON,
+    SMSG_LOOT_START_ROLL,
+};
+
+pub async fn handle_cmsg_loot(client_manager: &ClientManager, client_id: u64, world: &World, data: &CMSG_LOOT) -> Result<()> {
+    let client = client_manager.get_authenticated_client(client_id).await?;
+    let character_lock = client.get_active_character().await?;
+    let mut character = character_lock.write().await;
+
+    character.open_corpse_loot(world, data.guid).await
+}
+
+pub async fn handle_cmsg_open_item(client_manager: &ClientManager, client_id: u64, world: &World, data: &CMSG_OPEN_ITEM) -> Result<()> {
+    let client = client_manager.get_authenticated_client(client_id).await?;
+    let character_lock = client.get_active_character().await?;
+    let mut character = character_lock.write().await;
+
+    character.open_container(world, (data.slot, data.bag_index)).await
+}
+
+pub async fn handle_cmsg_autostore_loot_item(
+    client_manager: &ClientManager,
+    client_id: u64,
+    world: &World,
+    data: &CMSG_AUTOSTORE_LOOT_ITEM,
+) -> Result<()> {
+    let client = client_manager.get_authenticated_client(client_id).await?;
+    let character_lock = client.get_active_character().await?;
+    let mut character = character_lock.write().await;
+
+    character.store_loot_item(world, data.item_slot).await
+}
+
+pub async fn handle_cmsg_loot_money(client_manager: &ClientManager, client_id: u64, world: &World) -> Result<()> {
+    let client = client_manager.get_authenticated_client(client_id).await?;
+    let character_lock = client.get_active_character().await?;
+    let mut character = character_lock.write().await;
+
+    character.take_loot_money(world).await
+}
+
+pub async fn handle_cmsg_loot_release(client_manager: &ClientManager, client_id: u64, world: &World, data: &CMSG_LOOT_RELEASE) -> Result<()> {
+    let client = client_manager.get_authenticated_client(client_id).await?;
+    let character_lock = client.get_active_character().await?;
+    let mut character = character_lock.write().await;
+
+    character.release_loot(world, data.guid).await
+}
+
+pub async fn handle_cmsg_loot_roll(client_manager: &ClientManager, client_id: u64, world: &World, data: &CMSG_LOOT_ROLL) -> Result<()> {
+    let client = client_manager.get_authenticated_client(client_id).await?;
+    let voter = client.get_active_character().await?.read().await.get_guid();
+
+    let roll = world.get_group_manager().vote(data.item, data.item_slot as u8, voter, data.vote).await?;
+    let Some(map) = roll.map.upgrade() else {
+        return Ok(());
+    };
+    //The numbers are rolled once everyone voted
+    SMSG_LOOT_ROLL {
+        creature: roll.source,
+        loot_slot: roll.slot as u32,
+        player: voter,
+        item: roll.item,
+        item_random_suffix: 0,
+        item_random_property_id: 0,
+        roll_number: 0,
+        vote: data.vote,
+        auto_pass: false,
+    }
+    .astd_send_to_characters_on_map(&roll.eligible, &map)
+    .await
+}
+
+pub async fn handle_cmsg_loot_master_give(client_manager: &ClientManager, client_id: u64, world: &World, data: &CMSG_LOOT_MASTER_GIVE) -> Result<()> {
+    let client = client_manager.get_authenticated_client(client_id).await?;
+    let character_lock = client.get_active_character().await?;
+    let mut character = character_lock.write().await;
+
+    character.give_master_loot(world, data.loot, data.slot_id, data.player).await
+}
+
+pub async fn send_loot_response(
+    character: &Character,
+    data_storage: &DataStorage,
+    guid: Guid,
+    money: u32,
+    items: &[(u8, LootItem, LootSlotType)],
+) -> Result<()> {
+    let items = items
+        .iter()
+        .map(|(slot, item, slot_type)| wow_world_messages::wrath::LootItem {
+            index: *slot,
+            item: item.item,
+            item_count: item.count,
+            display_id: data_storage.get_item_template(item.item).map_or(0, |template| template.displayid),
+            unknown: 0,
+            item_random_property_id: 0,
+            ty: *slot_type,
+        })
+        .collect();
+
+    SMSG_LOOT_RESPONSE {
+        guid,
+        loot_method: SMSG_LOOT_RESPONSE_LootMethod::Corpse { gold: money, items },
+    }
+    .astd_send_to_character(character)
+    .await
+}
+
+pub async fn send_loot_error(character: &Character, guid: Guid, error: LootMethodError) -> Result<()> {
+    SMSG_LOOT_RESPONSE {
+        guid,
+        loot_method: SMSG_LOOT_RESPONSE_LootMethod::ErrorX { loot_error: error },
+    }
+    .astd_send_to_character(character)
+    .await
+}
+
+pub async fn send_loot_removed(character: &Character, slot: u8) -> Result<()> {
+    SMSG_LOOT_REMOVED { slot }.astd_send_to_character(character).await
+}
+
+pub async fn send_loot_money(character: &Character, amount: u32, alone: bool) -> Result<()> {
+    SMSG_LOOT_MONEY_NOTIFY { amount, alone }.astd_send_to_character(character).await?;
+    SMSG_LOOT_CLEAR_MONEY {}.astd_send_to_character(character).await
+}
+
+pub async fn send_loot_release_response(character: &Character, guid: Guid) -> Result<()> {
+    SMSG_LOOT_RELEASE_RESPONSE { guid, unknown1: 1 }.astd_send_to_character(character).await
+}
+
+pub async fn send_loot_master_list(character: &Character, looters: &[Guid]) -> Result<()> {
+    SMSG_LOOT_MASTER_LIST { guids: looters.to_vec() }.astd_send_to_character(character).await
+}
+
+//The character that starts the roll is locked by the caller, so they get theirs directly
+pub async fn send_loot_start_roll(character: &Character, map: &MapManager, roll: &LootRoll) -> Result<()> {
+    let map_id = Map::try_from(map.get_map_id())?;
+    let message = |need_allowed: bool| {
+        let mut roll_vote_mask = RollFlags::PASS | RollFlags::GREED | RollFlags::DISENCHANT;
+        if need_allowed {
+            roll_vote_mask |= RollFlags::NEED;
+        }
+        SMSG_LOOT_START_ROLL {
+            creature: roll.source,
+            map: map_id,
+            loot_slot: roll.slot as u32,
+            item: roll.item,
+            item_random_suffix: 0,
+            item_random_property_id: 0,
+            count: roll.count,
+            countdown_time: (ROLL_TIMEOUT * 1000.0) as u32,
+            roll_vote_mask: RollFlags::new(roll_vote_mask),
+        }
+    };
+
+    let (need_allowed, greed_only): (Vec<Guid>, Vec<Guid>) = roll
+        .eligible
+        .iter()
+        .filter(|guid| **guid != character.get_guid())
+        .partition(|guid| roll.need_allowed.contains(guid));
+    message(true).astd_send_to_characters_on_map(&need_allowed, map).await?;
+    message(false).astd_send_to_characters_on_map(&greed_only, map).await?;
+    message(roll.need_allowed.contains(&character.get_guid()))
+        .astd_send_to_character(character)
+        .await
+}
+
+pub async fn send_loot_roll_results(
+    map: &MapManager,
+    roll: &LootRoll,
+    results: &[(Guid, RollVote, u8)],
+    winner: Option<(Guid, RollVote, u8)>,
+) -> Result<()> {
+    for &(player, vote, roll_number) in results {
+        SMSG_LOOT_ROLL {
+            creature: roll.source,
+            loot_slot: roll.slot as u32,
+            player,
+            item: roll.item,
+            item_random_suffix: 0,
+            item_random_property_id: 0,
+            roll_number,
+            vote,
+            auto_pass: false,
+        }
+        .astd_send_to_characters_on_map(&roll.eligible, map)
+        .await?;
+    }
+
+    if let Some((winning_player, vote, winning_roll)) = winner {
+        SMSG_LOOT_ROLL_WON {
+            looted_target: roll.source,
+            loot_slot: roll.slot as u32,
+            item: roll.item,
+            item_random_suffix: 0,
+            item_random_property_id: 0,
+            winning_player,
+            winning_roll,
+            vote,
+        }
+        .astd_send_to_characters_on_map(&roll.eligible, map)
+        .await
+    } else {
+        SMSG_LOOT_ALL_PASSED {
+            looted_target: roll.source,
+            loot_slot: roll.slot as u32,
+            item: roll.item,
+            item_random_property_id: 0,
+            item_random_suffix_id: 0,
+        }
+        .astd_send_to_characters_on_map(&roll.eligible, map)
+        .await
+    }
+}
@@ -14,29 +14,67 @@ pub use account_data_handler::handle_csmg_ready_for_account_data_times;
 pub use account_data_handler::handle_csmg_update_account_data;
 pub use account_data_handler::send_character_account_data_times;
 
+mod bank_handler;
+pub use bank_handler::handle_cmsg_autobank_item;
+pub use bank_handler::handle_cmsg_autostore_bank_item;
+pub use bank_handler::handle_cmsg_banker_activate;
+pub use bank_handler::handle_cmsg_buy_bank_slot;
+pub use bank_handler::send_show_bank;
+
 mod bars_buttons_handler;
 pub use bars_buttons_handler::handle_cmsg_set_action_button;
 pub use bars_buttons_handler::handle_csmg_set_actionbar_toggles;
 
 mod character_handler;
 pub use character_handler::handle_cmsg_autoequip_item;
+pub use character_handler::handle_cmsg_autostore_bag_item;
 pub use character_handler::handle_cmsg_char_create;
 pub use character_handler::handle_cmsg_char_delete;
 pub use character_handler::handle_cmsg_char_enum;
+pub use character_handler::handle_cmsg_destroyitem;
 pub use character_handler::handle_cmsg_player_login;
+pub use character_handler::handle_cmsg_split_item;
 pub use character_handler::handle_cmsg_standstate_change;
 pub use character_handler::handle_cmsg_swap_inv_item;
+pub use character_handler::handle_cmsg_swap_item;
 pub use character_handler::send_action_buttons;
 pub use character_handler::send_bind_update;
+pub use character_handler::send_inventory_change_failure;
 pub use character_handler::send_verify_world;
 
+mod combat_handler;
+pub use combat_handler::handle_cmsg_attackstop;
+pub use combat_handler::handle_cmsg_attackswing;
+pub use combat_handler::send_attack_start;
+pub use combat_handler::send_attack_stop;
+pub use combat_handler::send_attacker_state_update;
+pub use combat_handler::send_attackswing_cant_attack;
+pub use combat_handler::send_attackswing_dead_target;
+pub use combat_handler::send_attackswing_not_in_range;
+
+mod death_handler;
+pub use death_handler::handle_cmsg_reclaim_corpse;
+pub use death_handler::handle_cmsg_repop_request;
+pub use death_handler::handle_cmsg_spirit_healer_activate;
+pub use death_handler::handle_msg_corpse_query;
+pub use death_handler::send_corpse_reclaim_delay;
+pub use death_handler::send_durability_damage_death;
+pub use death_handler::send_spirit_healer_confirm;
+
 mod cinematics_handler;
 pub use cinematics_handler::handle_csmg_complete_cinematic;
 pub use cinematics_handler::handle_csmg_next_cinematic_camera;
 pub use cinematics_handler::send_trigger_cinematic;
 
 mod group_handler;
+pub use group_handler::handle_cmsg_group_accept;
+pub use group_handler::handle_cmsg_group_decline;
+pub use group_handler::handle_cmsg_group_disband;
+pub use group_handler::handle_cmsg_group_invite;
+pub use group_handler::handle_cmsg_group_uninvite_guid;
+pub use group_handler::handle_cmsg_loot_method;
 pub use group_handler::handle_cmsg_request_raid_info;
+pub use group_handler::leave_group;
 
 mod gm_handler;
 pub use gm_handler::handle_cmsg_gmticket_create;
@@ -44,7 +82,25 @@ pub use gm_handler::handle_cmsg_gmticket_getticket;
 pub use gm_handler::handle_cmsg_gmticket_system_status;
 
 mod instance_handler;
+pub use instance_handler::handle_msg_set_dungeon_difficulty;
+pub use instance_handler::handle_msg_set_raid_difficulty;
 pub use instance_handler::send_dungeon_difficulty;
+pub use instance_handler::send_raid_difficulty;
+
+mod spell_handler;
+pub use spell_handler::handle_cmsg_cancel_aura;
+pub use spell_handler::handle_cmsg_cancel_cast;
+pub use spell_handler::handle_cmsg_cast_spell;
+pub use spell_handler::send_aura_update_all;
+pub use spell_handler::send_aura_updates;
+pub use spell_handler::send_cast_failed;
+pub use spell_handler::send_learned_spell;
+pub use spell_handler::send_removed_spell;
+pub use spell_handler::send_resync_runes;
+pub use spell_handler::send_spell_failure;
+pub use spell_handler::send_spell_go;
+pub use spell_handler::send_spell_start;
+pub use spell_handler::send_superceded_spell;
 
 mod voice_chat_handler;
 pub use voice_chat_handler::send_voice_chat_status;
@@ -54,8 +110,77 @@ pub use tutorial_handler::handle_cmsg_tutorial_flag;
 pub use tutorial_handler::handle_cmsg_tutorial_reset;
 pub use tutorial_handler::send_tutorial_flags;
 
+mod experience_handler;
+pub use experience_handler::send_exploration_experience;
+pub use experience_handler::send_kill_experience;
+pub use experience_handler::send_levelup_info;
+
+mod loot_handler;
+pub use loot_handler::handle_cmsg_autostore_loot_item;
+pub use loot_handler::handle_cmsg_loot;
+pub use loot_handler::handle_cmsg_loot_master_give;
+pub use loot_handler::handle_cmsg_loot_money;
+pub use loot_handler::handle_cmsg_loot_release;
+pub use loot_handler::handle_cmsg_loot_roll;
+pub use loot_handler::handle_cmsg_open_item;
+pub use loot_handler::send_loot_error;
+pub use loot_handler::send_loot_master_list;
+pub use loot_handler::send_loot_money;
+pub use loot_handler::send_loot_release_response;
+pub use loot_handler::send_loot_removed;
+pub use loot_handler::send_loot_response;
+pub use loot_handler::send_loot_roll_results;
+pub use loot_handler::send_loot_start_roll;
+
 mod faction_handler;
 pub use faction_handler::send_faction_list;
+pub use faction_handler::send_faction_standing;
+
+mod gossip_handler;
+pub use gossip_handler::handle_cmsg_gossip_hello;
+pub use gossip_handler::handle_cmsg_gossip_select_option;
+
+mod vendor_handler;
+pub use vendor_handler::handle_cmsg_buy_item;
+pub use vendor_handler::handle_cmsg_buyback_item;
+pub use vendor_handler::handle_cmsg_list_inventory;
+pub use vendor_handler::handle_cmsg_repair_item;
+pub use vendor_handler::handle_cmsg_sell_item;
+pub use vendor_handler::send_buy_failed;
+pub use vendor_handler::send_buy_item;
+pub use vendor_handler::send_sell_failed;
+pub use vendor_handler::send_vendor_inventory;
+
+mod trainer_handler;
+pub use trainer_handler::handle_cmsg_trainer_buy_spell;
+pub use trainer_handler::handle_cmsg_trainer_list;
+pub use trainer_handler::send_trainer_buy_failed;
+pub use trainer_handler::send_trainer_buy_succeeded;
+pub use trainer_handler::send_trainer_list;
+
+mod talent_handler;
+pub use talent_handler::handle_cmsg_inspect;
+pub use talent_handler::handle_cmsg_learn_preview_talents;
+pub use talent_handler::handle_cmsg_learn_talent;
+pub use talent_handler::handle_msg_talent_wipe_confirm;
+pub use talent_handler::send_talent_wipe_confirm;
+pub use talent_handler::send_talents_info;
+
+mod quest_handler;
+pub use quest_handler::handle_cmsg_questgiver_accept_quest;
+pub use quest_handler::handle_cmsg_questgiver_choose_reward;
+pub use quest_handler::handle_cmsg_questgiver_complete_quest;
+pub use quest_handler::handle_cmsg_questgiver_hello;
+pub use quest_handler::handle_cmsg_questgiver_query_quest;
+pub use quest_handler::handle_cmsg_questgiver_request_reward;
+pub use quest_handler::handle_cmsg_questgiver_status_multiple_query;
+pub use quest_handler::handle_cmsg_questgiver_status_query;
+pub use quest_handler::handle_cmsg_questlog_remove_quest;
+pub use quest_handler::send_quest_giver_quest_complete;
+pub use quest_handler::send_quest_invalid;
+pub use quest_handler::send_quest_update_add_kill;
+pub use quest_handler::send_quest_update_complete;
+pub use quest_handler::send_questlog_full;
 
 mod world_handler;
 pub use world_handler::handle_cmsg_time_sync_resp;
@@ -78,8 +203,10 @@ mod queries_handler;
 pub use queries_handler::handle_cmsg_item_name_query;
 pub use queries_handler::handle_cmsg_item_query_single;
 pub use queries_handler::handle_cmsg_name_query;
+pub use queries_handler::handle_cmsg_npc_text_query;
 pub use queries_handler::handle_cmsg_played_time;
 pub use queries_handler::handle_cmsg_query_time;
+pub use queries_handler::handle_cmsg_quest_query;
 pub use queries_handler::handle_cmsg_world_state_ui_timer_update;
 
 pub mod movement_handler;
@@ -92,6 +219,7 @@ pub use movement_handler::handle_msg_world_teleport;
 pub use movement_handler::send_msg_move_teleport_ack;
 pub use movement_handler::send_smsg_force_move_root;
 pub use movement_handler::send_smsg_force_move_unroot;
+pub use movement_handler::send_smsg_force_run_speed_change;
 pub use movement_handler::send_smsg_new_world;
 pub use movement_handler::send_smsg_stand_state_update;
 pub use movement_handler::send_smsg_transfer_pending;
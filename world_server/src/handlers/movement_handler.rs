@@ -13,7 +13,7 @@ use wow_world_messages::wrath::{
     MSG_MOVE_SET_RUN_MODE, MSG_MOVE_SET_WALK_MODE, MSG_MOVE_START_BACKWARD, MSG_MOVE_START_FORWARD, MSG_MOVE_START_PITCH_DOWN,
     MSG_MOVE_START_PITCH_UP, MSG_MOVE_START_STRAFE_LEFT, MSG_MOVE_START_STRAFE_RIGHT, MSG_MOVE_START_SWIM, MSG_MOVE_START_TURN_LEFT,
     MSG_MOVE_START_TURN_RIGHT, MSG_MOVE_STOP, MSG_MOVE_STOP_PITCH, MSG_MOVE_STOP_STRAFE, MSG_MOVE_STOP_SWIM, MSG_MOVE_STOP_TURN,
-    SMSG_FORCE_MOVE_ROOT, SMSG_FORCE_MOVE_UNROOT, SMSG_NEW_WORLD, SMSG_STANDSTATE_UPDATE, SMSG_TRANSFER_PENDING,
+    SMSG_FORCE_MOVE_ROOT, SMSG_FORCE_MOVE_UNROOT, SMSG_FORCE_RUN_SPEED_CHANGE, SMSG_NEW_WORLD, SMSG_STANDSTATE_UPDATE, SMSG_TRANSFER_PENDING,
 };
 
 pub trait MovementMessage: Sync + ServerMessage + ClientMessage {
@@ -141,12 +141,12 @@ pub async fn handle_msg_move_worldport_ack(client_manager: &ClientManager, clien
 
     if let TeleportationState::Executing(TeleportationDistance::Far(destination)) = character.teleportation_state.clone() {
         let mut character = RwLockUpgradableReadGuard::upgrade(character).await;
-        let map = world.get_instance_manager().get_or_create_map(&(*character), destination.map).await?;
+        let map = world.get_instance_manager().get_or_create_map(&mut character, destination.map).await?;
 
         character.map = destination.map;
         character.set_position(&destination.into());
         character.reset_time_sync();
-        character.send_packets_before_add_to_map().await?;
+        character.send_packets_before_add_to_map(world).await?;
         map.push_object(Arc::downgrade(&character_lock)).await;
         character.send_packets_after_add_to_map(world.get_realm_database()).await?;
 
@@ -196,6 +196,17 @@ pub async fn send_smsg_force_move_unroot(character: &Character) -> Result<()> {
     .await
 }
 
+pub async fn send_smsg_force_run_speed_change(character: &Character, speed: f32) -> Result<()> {
+    SMSG_FORCE_RUN_SPEED_CHANGE {
+        guid: character.get_guid(),
+        move_event: 0,
+        unknown: 0,
+        speed,
+    }
+    .astd_send_to_character(character)
+    .await
+}
+
 pub async fn handle_cmsg_set_active_mover(client_manager: &ClientManager, client_id: u64, packet: &CMSG_SET_ACTIVE_MOVER) -> Result<()> {
     //Many other emulators only do some verification upon receiving this packet.
     //Maybe it doesn't serve any other purpose but to have the server check it's content
@@ -243,6 +254,9 @@ pub async fn handle_cmsg_areatrigger(client_manager: &ClientManager, client_id:
     } else if let AreaTriggerPurpose::RestedArea = &trigger_data.purpose {
         let mut character = character_lock.write().await;
         character.handle_enter_inn()?;
+    } else if let AreaTriggerPurpose::Quest(quest_id) = &trigger_data.purpose {
+        let mut character = character_lock.write().await;
+        character.explore_quest_area(&client_manager.data_storage, *quest_id).await?;
     }
     Ok(())
 }
@@ -2,25 +2,26 @@ use crate::client::Client;
 use crate::client_manager::ClientManager;
 use crate::packet::*;
 use crate::prelude::*;
-use crate::world::World;
+use crate::world::{get_next_reset_time, World};
 use crate::{character::Character, world::prelude::GameObject};
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use wow_world_messages::wrath::{
-    CMSG_ITEM_NAME_QUERY, CMSG_ITEM_QUERY_SINGLE, CMSG_NAME_QUERY, CMSG_PLAYED_TIME, SMSG_ITEM_QUERY_SINGLE_RESPONSE, SMSG_NAME_QUERY_RESPONSE,
-    SMSG_PLAYED_TIME, SMSG_QUERY_TIME_RESPONSE, SMSG_WORLD_STATE_UI_TIMER_UPDATE,
+    AllowedClass, AllowedRace, Area, BagFamily, Bonding, Faction, Gold, InventoryType, ItemClassAndSubClass, ItemDamageType, ItemFlag, ItemFlag2,
+    ItemQuality, ItemSocket, ItemSpells, ItemStat, ItemStatType, Language, Level32, Map, NpcTextUpdate, NpcTextUpdateEmote, PageTextMaterial,
+    QuestItemReward, QuestObjective, SMSG_ITEM_QUERY_SINGLE_RESPONSE_found, SheatheType, Skill, SpellSchool, SpellTriggerType, Vector2d,
+    CMSG_ITEM_NAME_QUERY, CMSG_ITEM_QUERY_SINGLE, CMSG_NAME_QUERY, CMSG_NPC_TEXT_QUERY, CMSG_PLAYED_TIME, CMSG_QUEST_QUERY,
+    SMSG_ITEM_NAME_QUERY_RESPONSE, SMSG_ITEM_QUERY_SINGLE_RESPONSE, SMSG_NAME_QUERY_RESPONSE, SMSG_NPC_TEXT_UPDATE, SMSG_PLAYED_TIME,
+    SMSG_QUERY_TIME_RESPONSE, SMSG_QUEST_QUERY_RESPONSE, SMSG_WORLD_STATE_UI_TIMER_UPDATE,
 };
+use wrath_realm_db::item_template::DBItemTemplate;
 
 pub async fn handle_cmsg_played_time(client_manager: &ClientManager, client_id: u64, packet: &CMSG_PLAYED_TIME) -> Result<()> {
     let client = client_manager.get_authenticated_client(client_id).await?;
     let character_lock = client.get_active_character().await?;
 
     let (total_played_time, level_played_time) = {
-        let unix_time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() as u32;
         let mut character = character_lock.write().await;
-        let delta_seconds = unix_time - character.last_playtime_calculation_timestamp;
-        character.seconds_played_total += delta_seconds;
-        character.seconds_played_at_level += delta_seconds;
-        character.last_playtime_calculation_timestamp = unix_time;
+        character.update_played_time();
         (character.seconds_played_total, character.seconds_played_at_level)
     };
 
@@ -35,10 +36,10 @@ pub async fn handle_cmsg_played_time(client_manager: &ClientManager, client_id:
 
 pub async fn handle_cmsg_query_time(client_manager: &ClientManager, client_id: u64) -> Result<()> {
     let client = client_manager.get_client(client_id).await?;
-    let unix_time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() as u32;
+    let unix_time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
     SMSG_QUERY_TIME_RESPONSE {
-        time: unix_time,
-        time_until_daily_quest_reset: 0,
+        time: unix_time as u32,
+        time_until_daily_quest_reset: (get_next_reset_time(unix_time, false) - unix_time) as u32,
     }
     .astd_send_to_client(client)
     .await
@@ -97,6 +98,114 @@ async fn send_name_query_response(receiver: &Client, target_character: &Characte
     .await
 }
 
+//Greeting of creatures that don't have one in npc_text
+const DEFAULT_NPC_TEXT: &str = "Greetings, $N.";
+
+pub async fn handle_cmsg_npc_text_query(client_manager: &ClientManager, client_id: u64, packet: &CMSG_NPC_TEXT_QUERY) -> Result<()> {
+    let client = client_manager.get_authenticated_client(client_id).await?;
+    let text = client_manager.data_storage.get_npc_text(packet.text_id).unwrap_or(DEFAULT_NPC_TEXT);
+
+    //The client picks one of the texts by probability, only the first one is used
+    let texts = std::array::from_fn(|index| {
+        let text = if index == 0 { text.to_string() } else { String::new() };
+        NpcTextUpdate {
+            probability: if index == 0 { 1.0 } else { 0.0 },
+            //Versions for male and female characters
+            texts: [text.clone(), text],
+            language: Language::Universal,
+            emotes: [NpcTextUpdateEmote { delay: 0, emote: 0 }; 3],
+        }
+    });
+
+    SMSG_NPC_TEXT_UPDATE {
+        text_id: packet.text_id,
+        texts,
+    }
+    .astd_send_to_client(client)
+    .await
+}
+
+//Everything the client needs to show a quest in the quest log
+pub async fn handle_cmsg_quest_query(client_manager: &ClientManager, client_id: u64, packet: &CMSG_QUEST_QUERY) -> Result<()> {
+    let client = client_manager.get_authenticated_client(client_id).await?;
+    let data_storage = &client_manager.data_storage;
+    let template = data_storage
+        .get_quest_template(packet.quest_id)
+        .ok_or_else(|| anyhow!("Quest {} not found for client {}", packet.quest_id, client_id))?;
+    let info = &template.info;
+
+    let item_reward = |rewards: &[wrath_realm_db::quest::DBQuestRewardItem], index: usize| {
+        rewards
+            .get(index)
+            .map_or(QuestItemReward { item: 0, amount: 0 }, |reward| QuestItemReward {
+                item: reward.item,
+                amount: reward.count as u32,
+            })
+    };
+    let objective = |index: usize| {
+        let kill = template.kill_objectives.get(index);
+        let item = template.item_objectives.get(index);
+        QuestObjective {
+            creature_id: kill.map_or(0, |kill| kill.target),
+            kill_count: kill.map_or(0, |kill| kill.count as u32),
+            required_item_id: item.map_or(0, |item| item.target),
+            required_item_count: item.map_or(0, |item| item.count as u32),
+        }
+    };
+    let mut reward_factions = [0; 5];
+    let mut reward_reputations = [0; 5];
+    for (index, reward) in template.reward_factions.iter().take(5).enumerate() {
+        reward_factions[index] = reward.faction;
+        reward_reputations[index] = reward.value;
+    }
+
+    SMSG_QUEST_QUERY_RESPONSE {
+        quest_id: info.id,
+        quest_method: 2,
+        quest_level: info.quest_level as i32,
+        minimum_quest_level: info.min_level as u32,
+        zone_or_sort: info.zone_or_sort as i32,
+        quest_type: 0,
+        suggested_players: info.suggested_players as u32,
+        reputation_objective_faction: Faction::None,
+        reputation_objective_value: 0,
+        required_opposite_faction: Faction::None,
+        required_opposite_reputation_value: 0,
+        next_quest_in_chain: 0,
+        experience_reward: info.reward_xp,
+        money_reward: Gold::new(info.reward_money),
+        max_level_money_reward: Gold::new(0),
+        reward_spell: 0,
+        casted_reward_spell: 0,
+        honor_reward: 0,
+        honor_reward_multiplier: 0.0,
+        source_item_id: 0,
+        quest_flags: info.flags,
+        title_reward: 0,
+        players_slain: 0,
+        bonus_talents: 0,
+        bonus_arena_points: 0,
+        unknown1: 0,
+        rewards: [0, 1, 2, 3].map(|index| item_reward(&template.reward_items, index)),
+        choice_rewards: [0, 1, 2, 3, 4, 5].map(|index| item_reward(&template.reward_choice_items, index)),
+        reward_factions,
+        reward_reputations,
+        reward_reputations_override: [0; 5],
+        point_map_id: 0,
+        position: Vector2d::default(),
+        point_opt: 0,
+        title: info.title.clone(),
+        objective_text: info.objectives.clone(),
+        details: info.details.clone(),
+        end_text: info.end_text.clone(),
+        completed_text: String::new(),
+        objectives: [0, 1, 2, 3].map(objective),
+        objective_texts: [String::new(), String::new(), String::new(), String::new()],
+    }
+    .astd_send_to_client(client)
+    .await
+}
+
 pub async fn handle_cmsg_item_query_single(
     client_manager: &ClientManager,
     client_id: u64,
@@ -104,19 +213,14 @@ pub async fn handle_cmsg_item_query_single(
     packet: &CMSG_ITEM_QUERY_SINGLE,
 ) -> Result<()> {
     let client = client_manager.get_client(client_id).await?;
-    //TODO: use DB to lookup
-    let item = wow_items::wrath::lookup_item(packet.item);
-    match item {
-        None => {
-            SMSG_ITEM_QUERY_SINGLE_RESPONSE {
-                item: packet.item | 0x80000000,
-                found: None,
-            }
-            .astd_send_to_client(client)
-            .await
-        }
-        Some(item) => wow_world_messages::wrath::item_to_query_response(item).astd_send_to_client(client).await,
+    let found = client_manager.data_storage.get_item_template(packet.item).map(build_item_query_response);
+    SMSG_ITEM_QUERY_SINGLE_RESPONSE {
+        //The high bit tells the client that the item doesn't exist
+        item: if found.is_some() { packet.item } else { packet.item | 0x80000000 },
+        found,
     }
+    .astd_send_to_client(client)
+    .await
 }
 
 pub async fn handle_cmsg_item_name_query(
@@ -125,15 +229,143 @@ pub async fn handle_cmsg_item_name_query(
     _world: &World,
     packet: &CMSG_ITEM_NAME_QUERY,
 ) -> Result<()> {
-    //TODO: use DB to lookup
-    let item = wow_items::wrath::lookup_item(packet.item);
     let client = client_manager.get_client(client_id).await?;
-    match item {
-        Some(item) => {
-            wow_world_messages::wrath::item_to_name_query_response(item)
-                .astd_send_to_client(client)
-                .await
+    let template = client_manager
+        .data_storage
+        .get_item_template(packet.item)
+        .ok_or_else(|| anyhow!("Item {} not found for client {}", packet.item, client_id))?;
+
+    SMSG_ITEM_NAME_QUERY_RESPONSE {
+        item: template.id,
+        item_name: template.name.clone(),
+        inventory_type: InventoryType::try_from(template.inventory_type).unwrap_or(InventoryType::NonEquip),
+    }
+    .astd_send_to_client(client)
+    .await
+}
+
+fn build_item_query_response(template: &DBItemTemplate) -> SMSG_ITEM_QUERY_SINGLE_RESPONSE_found {
+    let resistances = template.granted_resistances.clone().unwrap_or_default();
+    let readable_info = template.readable_info.as_ref();
+
+    //The client always expects a fixed amount of damages, spells and sockets
+    let damage = |index: usize| {
+        let damage = template.damage.get(index).cloned().unwrap_or_default();
+        ItemDamageType {
+            damage_minimum: damage.min,
+            damage_maximum: damage.max,
+            school: SpellSchool::try_from(damage.damage_type).unwrap_or(SpellSchool::Normal),
+        }
+    };
+    let spell = |index: usize| {
+        let spell = template.spell_procs.get(index).cloned().unwrap_or_default();
+        ItemSpells {
+            spell: spell.spell_id,
+            spell_trigger: SpellTriggerType::try_from(spell.trigger_type).unwrap_or(SpellTriggerType::OnUse),
+            spell_charges: spell.charges as i16 as i32,
+            spell_cooldown: spell.cooldown as i32,
+            spell_category: spell.category as u32,
+            spell_category_cooldown: spell.category_cooldown as i32,
+        }
+    };
+    let socket = |index: usize| {
+        let socket = template.sockets.get(index).cloned().unwrap_or_default();
+        ItemSocket {
+            color: socket.color as u32,
+            content: socket.content,
         }
-        None => Err(anyhow!("Item {} not found for client {}", packet.item, client_id)),
+    };
+
+    SMSG_ITEM_QUERY_SINGLE_RESPONSE_found {
+        class_and_sub_class: ItemClassAndSubClass::try_from(template.class as u64 | (template.subclass as u64) << 32)
+            .unwrap_or(ItemClassAndSubClass::Consumable),
+        sound_override_sub_class: -1,
+        name1: template.name.clone(),
+        name2: String::new(),
+        name3: String::new(),
+        name4: String::new(),
+        display_id: template.displayid,
+        quality: ItemQuality::try_from(template.quality).unwrap_or(ItemQuality::Poor),
+        flags: ItemFlag::new(template.flags),
+        flags2: ItemFlag2::new(template.flags2),
+        buy_price: Gold::new(template.buy_price),
+        sell_price: Gold::new(template.sell_price),
+        inventory_type: InventoryType::try_from(template.inventory_type).unwrap_or(InventoryType::NonEquip),
+        allowed_class: AllowedClass::new(template.allowed_classes_mask.unwrap_or(u32::MAX)),
+        allowed_race: AllowedRace::new(template.allowed_races_mask.unwrap_or(u32::MAX)),
+        item_level: template.item_level as u32,
+        required_level: Level32::new(template.required_level.unwrap_or(0) as u32),
+        required_skill: template
+            .required_skill
+            .as_ref()
+            .and_then(|skill| Skill::try_from(skill.skill_id).ok())
+            .unwrap_or(Skill::None),
+        required_skill_rank: template.required_skill.as_ref().map_or(0, |skill| skill.required_rank as u32),
+        required_spell: template.required_spell_id.unwrap_or(0),
+        required_honor_rank: template.required_honor_rank.unwrap_or(0),
+        required_city_rank: 0,
+        required_faction: template
+            .required_faction
+            .as_ref()
+            .and_then(|faction| Faction::try_from(faction.faction_id).ok())
+            .unwrap_or(Faction::None),
+        required_faction_rank: template.required_faction.as_ref().map_or(0, |faction| faction.required_rank as u32),
+        max_count: template.max_count as u32,
+        stackable: template.stackable as u32,
+        container_slots: template.container_slots as u32,
+        stats: template
+            .granted_stats
+            .iter()
+            .filter_map(|stat| {
+                Some(ItemStat {
+                    stat_type: ItemStatType::try_from(stat.stat_type).ok()?,
+                    value: stat.stat_value as i16 as i32,
+                })
+            })
+            .collect(),
+        scaling_stats_entry: template.scaling_stat_distribution as u32,
+        scaling_stats_flag: template.scaling_stat_value,
+        damages: [damage(0), damage(1)],
+        armor: template.granted_armor.unwrap_or(0) as i32,
+        holy_resistance: resistances.holy as i32,
+        fire_resistance: resistances.fire as i32,
+        nature_resistance: resistances.nature as i32,
+        frost_resistance: resistances.frost as i32,
+        shadow_resistance: resistances.shadow as i32,
+        arcane_resistance: resistances.arcane as i32,
+        delay: Duration::from_millis(template.delay.unwrap_or(0) as u64),
+        ammo_type: template.ammo_type.unwrap_or(0) as u32,
+        ranged_range_modification: template.ranged_mod_range,
+        spells: [spell(0), spell(1), spell(2), spell(3), spell(4)],
+        bonding: Bonding::try_from(template.bonding).unwrap_or(Bonding::NoBind),
+        description: template.description.clone(),
+        page_text: readable_info.map_or(0, |info| info.text_id),
+        language: readable_info
+            .and_then(|info| Language::try_from(info.language_id as u32).ok())
+            .unwrap_or(Language::Universal),
+        page_text_material: readable_info
+            .and_then(|info| PageTextMaterial::try_from(info.page_material).ok())
+            .unwrap_or(PageTextMaterial::None),
+        start_quest: template.start_quest_id.unwrap_or(0),
+        lock_id: template.lock_id.unwrap_or(0),
+        material: template.material as u32,
+        sheathe_type: SheatheType::try_from(template.sheath_style).unwrap_or(SheatheType::None),
+        random_property: template.random_property,
+        random_suffix: template.random_suffix,
+        block: template.block_value.unwrap_or(0),
+        item_set: template.item_set_id.unwrap_or(0),
+        max_durability: template.max_durability as u32,
+        area: template.usable_area.and_then(|area| Area::try_from(area).ok()),
+        map: template.usable_map.and_then(|map| Map::try_from(map as u32).ok()),
+        bag_family: BagFamily::new(template.bag_family_mask.unwrap_or(0) as u32),
+        totem_category: template.totem_category.unwrap_or(0) as u32,
+        sockets: [socket(0), socket(1), socket(2)],
+        socket_bonus: template.socket_bonus.unwrap_or(0),
+        gem_properties: template.gem_properties,
+        required_disenchant_skill: template.required_disenchant_skill.map_or(-1, |skill| skill as i32),
+        armor_damage_modifier: template.armor_damage_modifier,
+        duration: Duration::from_secs(template.duration as u64),
+        item_limit_category: template.item_limit_category as u32,
+        holiday_id: template.holiday_id,
     }
 }
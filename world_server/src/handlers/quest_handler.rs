@@ -0,0 +1,388 @@
+use crate::character::character_quests::QuestStatus;
+use crate::character::Character;
+use crate::client_manager::ClientManager;
+use crate::data::{DataStorage, QuestTemplate};
+use crate::packet::ServerMessageExt;
+use crate::prelude::*;
+use crate::world::guid_service::{get_entry, HighGuid};
+use crate::world::prelude::{GameObject, Unit};
+use crate::world::World;
+use wow_world_messages::wrath::{
+    Gold, QuestCompletable, QuestFailedReason, QuestGiverReward, QuestGiverStatus, QuestGiverStatusReport, QuestItem, QuestItemRequirement,
+    QuestItemReward, CMSG_QUESTGIVER_ACCEPT_QUEST, CMSG_QUESTGIVER_CHOOSE_REWARD, CMSG_QUESTGIVER_COMPLETE_QUEST, CMSG_QUESTGIVER_HELLO,
+    CMSG_QUESTGIVER_QUERY_QUEST, CMSG_QUESTGIVER_REQUEST_REWARD, CMSG_QUESTGIVER_STATUS_QUERY, CMSG_QUESTLOG_REMOVE_QUEST,
+    SMSG_QUESTGIVER_OFFER_REWARD, SMSG_QUESTGIVER_QUEST_COMPLETE, SMSG_QUESTGIVER_QUEST_DETAILS, SMSG_QUESTGIVER_QUEST_INVALID,
+    SMSG_QUESTGIVER_QUEST_LIST, SMSG_QUESTGIVER_REQUEST_ITEMS, SMSG_QUESTGIVER_STATUS, SMSG_QUESTGIVER_STATUS_MULTIPLE, SMSG_QUESTLOG_FULL,
+    SMSG_QUESTUPDATE_ADD_KILL, SMSG_QUESTUPDATE_COMPLETE,
+};
+
+//Icons in the quest list of a quest giver
+const QUEST_ICON_AVAILABLE: u32 = 2;
+const QUEST_ICON_IN_LOG: u32 = 4;
+
+//Quest givers are creatures close to the character, the entry in their guid tells which quests they have
+fn get_quest_giver_entry(character: &Character, guid: Guid) -> Result<u32> {
+    if HighGuid::from_guid(guid) != Some(HighGuid::Unit) || !character.is_in_range(guid) {
+        bail!("Character {} can't talk to quest giver {}", character.name, guid);
+    }
+    Ok(get_entry(guid))
+}
+
+//The quests a quest giver offers or takes back, as they show up in its quest list or gossip menu
+pub(super) fn get_quest_menu_items(character: &Character, data_storage: &DataStorage, entry: u32) -> Vec<QuestItem> {
+    character
+        .get_quest_giver_menu(data_storage, entry)
+        .into_iter()
+        .filter_map(|(quest_id, in_log)| {
+            let template = data_storage.get_quest_template(quest_id)?;
+            Some(QuestItem {
+                quest_id,
+                quest_icon: if in_log { QUEST_ICON_IN_LOG } else { QUEST_ICON_AVAILABLE },
+                level: template.get_level_for(character.get_level()),
+                flags: template.info.flags,
+                repeatable: false,
+                title: template.info.title.clone(),
+            })
+        })
+        .collect()
+}
+
+pub async fn handle_cmsg_questgiver_status_query(client_manager: &ClientManager, client_id: u64, data: &CMSG_QUESTGIVER_STATUS_QUERY) -> Result<()> {
+    let client = client_manager.get_authenticated_client(client_id).await?;
+    let character_lock = client.get_active_character().await?;
+    let character = character_lock.read().await;
+
+    let entry = get_quest_giver_entry(&character, data.guid)?;
+    SMSG_QUESTGIVER_STATUS {
+        guid: data.guid,
+        status: character.get_quest_giver_status(&client_manager.data_storage, entry),
+    }
+    .astd_send_to_character(&*character)
+    .await
+}
+
+//Updates the marks above the heads of every quest giver around the character
+pub async fn handle_cmsg_questgiver_status_multiple_query(client_manager: &ClientManager, client_id: u64) -> Result<()> {
+    let client = client_manager.get_authenticated_client(client_id).await?;
+    let character_lock = client.get_active_character().await?;
+    let character = character_lock.read().await;
+
+    let statuses = character
+        .get_in_range_guids()
+        .into_iter()
+        .filter(|guid| HighGuid::from_guid(*guid) == Some(HighGuid::Unit))
+        .map(|guid| QuestGiverStatusReport {
+            npc: guid,
+            dialog_status: character.get_quest_giver_status(&client_manager.data_storage, get_entry(guid)),
+        })
+        .filter(|report| report.dialog_status != QuestGiverStatus::None)
+        .collect();
+    SMSG_QUESTGIVER_STATUS_MULTIPLE { statuses }.astd_send_to_character(&*character).await
+}
+
+pub async fn handle_cmsg_questgiver_hello(client_manager: &ClientManager, client_id: u64, data: &CMSG_QUESTGIVER_HELLO) -> Result<()> {
+    let client = client_manager.get_authenticated_client(client_id).await?;
+    let character_lock = client.get_active_character().await?;
+    let character = character_lock.read().await;
+
+    let entry = get_quest_giver_entry(&character, data.guid)?;
+    let quest_items = get_quest_menu_items(&character, &client_manager.data_storage, entry);
+
+    SMSG_QUESTGIVER_QUEST_LIST {
+        npc: data.guid,
+        title: String::new(),
+        emote_delay: 0,
+        emote: 0,
+        quest_items,
+    }
+    .astd_send_to_character(&*character)
+    .await
+}
+
+//Picking a quest from the list shows what it's about, or how far along the character is with it
+pub async fn handle_cmsg_questgiver_query_quest(client_manager: &ClientManager, client_id: u64, data: &CMSG_QUESTGIVER_QUERY_QUEST) -> Result<()> {
+    let client = client_manager.get_authenticated_client(client_id).await?;
+    let character_lock = client.get_active_character().await?;
+    let character = character_lock.read().await;
+
+    let data_storage = &client_manager.data_storage;
+    let entry = get_quest_giver_entry(&character, data.guid)?;
+    let template = data_storage
+        .get_quest_template(data.quest_id)
+        .ok_or_else(|| anyhow!("Quest {} doesn't exist", data.quest_id))?;
+
+    if character.get_quest_status(data.quest_id).is_some() {
+        if !data_storage.get_creature_quest_enders(entry).contains(&data.quest_id) {
+            bail!("Quest {} can't be turned in at {}", data.quest_id, data.guid);
+        }
+        send_quest_progress(&character, data_storage, data.guid, template).await
+    } else {
+        if !data_storage.get_creature_quest_starters(entry).contains(&data.quest_id) {
+            bail!("Quest {} is not offered by {}", data.quest_id, data.guid);
+        }
+        send_quest_details(&character, data_storage, data.guid, template).await
+    }
+}
+
+pub async fn handle_cmsg_questgiver_accept_quest(client_manager: &ClientManager, client_id: u64, data: &CMSG_QUESTGIVER_ACCEPT_QUEST) -> Result<()> {
+    let client = client_manager.get_authenticated_client(client_id).await?;
+    let character_lock = client.get_active_character().await?;
+    let mut character = character_lock.write().await;
+
+    let entry = get_quest_giver_entry(&character, data.guid)?;
+    if !client_manager.data_storage.get_creature_quest_starters(entry).contains(&data.quest_id) {
+        bail!("Quest {} is not offered by {}", data.quest_id, data.guid);
+    }
+    character.accept_quest(&client_manager.data_storage, data.quest_id).await
+}
+
+pub async fn handle_cmsg_questgiver_complete_quest(
+    client_manager: &ClientManager,
+    client_id: u64,
+    data: &CMSG_QUESTGIVER_COMPLETE_QUEST,
+) -> Result<()> {
+    let client = client_manager.get_authenticated_client(client_id).await?;
+    let character_lock = client.get_active_character().await?;
+    let character = character_lock.read().await;
+
+    let data_storage = &client_manager.data_storage;
+    let entry = get_quest_giver_entry(&character, data.guid)?;
+    let template = data_storage
+        .get_quest_template(data.quest_id)
+        .ok_or_else(|| anyhow!("Quest {} doesn't exist", data.quest_id))?;
+    if character.get_quest_status(data.quest_id).is_none() || !data_storage.get_creature_quest_enders(entry).contains(&data.quest_id) {
+        bail!("Character {} can't turn in quest {} at {}", character.name, data.quest_id, data.guid);
+    }
+    send_quest_progress(&character, data_storage, data.guid, template).await
+}
+
+pub async fn handle_cmsg_questgiver_request_reward(
+    client_manager: &ClientManager,
+    client_id: u64,
+    data: &CMSG_QUESTGIVER_REQUEST_REWARD,
+) -> Result<()> {
+    let client = client_manager.get_authenticated_client(client_id).await?;
+    let character_lock = client.get_active_character().await?;
+    let character = character_lock.read().await;
+
+    let data_storage = &client_manager.data_storage;
+    let entry = get_quest_giver_entry(&character, data.guid)?;
+    let template = data_storage
+        .get_quest_template(data.quest_id)
+        .ok_or_else(|| anyhow!("Quest {} doesn't exist", data.quest_id))?;
+    if character.get_quest_status(data.quest_id) != Some(QuestStatus::Complete)
+        || !data_storage.get_creature_quest_enders(entry).contains(&data.quest_id)
+    {
+        bail!(
+            "Character {} can't be rewarded for quest {} at {}",
+            character.name,
+            data.quest_id,
+            data.guid
+        );
+    }
+    send_quest_offer_reward(&character, data_storage, data.guid, template).await
+}
+
+pub async fn handle_cmsg_questgiver_choose_reward(
+    client_manager: &ClientManager,
+    client_id: u64,
+    world: &World,
+    data: &CMSG_QUESTGIVER_CHOOSE_REWARD,
+) -> Result<()> {
+    let client = client_manager.get_authenticated_client(client_id).await?;
+    let character_lock = client.get_active_character().await?;
+    let mut character = character_lock.write().await;
+
+    let entry = get_quest_giver_entry(&character, data.guid)?;
+    if !client_manager.data_storage.get_creature_quest_enders(entry).contains(&data.quest_id) {
+        bail!("Quest {} can't be turned in at {}", data.quest_id, data.guid);
+    }
+    character.reward_quest(world, data.quest_id, data.reward).await
+}
+
+pub async fn handle_cmsg_questlog_remove_quest(client_manager: &ClientManager, client_id: u64, data: &CMSG_QUESTLOG_REMOVE_QUEST) -> Result<()> {
+    let client = client_manager.get_authenticated_client(client_id).await?;
+    let character_lock = client.get_active_character().await?;
+    let mut character = character_lock.write().await;
+
+    character.abandon_quest(data.slot)
+}
+
+fn build_reward_items(data_storage: &DataStorage, rewards: &[wrath_realm_db::quest::DBQuestRewardItem]) -> Vec<QuestGiverReward> {
+    rewards
+        .iter()
+        .map(|reward| QuestGiverReward {
+            item: reward.item,
+            item_count: reward.count as u32,
+            display_id: data_storage.get_item_template(reward.item).map_or(0, |template| template.display_id),
+        })
+        .collect()
+}
+
+//Five factions at most fit in the quest packets
+fn build_reward_factions(template: &QuestTemplate) -> ([u32; 5], [i32; 5]) {
+    let mut factions = [0; 5];
+    let mut values = [0; 5];
+    for (index, reward) in template.reward_factions.iter().take(5).enumerate() {
+        factions[index] = reward.faction;
+        values[index] = reward.value;
+    }
+    (factions, values)
+}
+
+async fn send_quest_details(character: &Character, data_storage: &DataStorage, quest_giver: Guid, template: &QuestTemplate) -> Result<()> {
+    let (reward_factions, reward_reputations) = build_reward_factions(template);
+    SMSG_QUESTGIVER_QUEST_DETAILS {
+        guid: quest_giver,
+        guid2: Guid::zero(),
+        quest_id: template.info.id,
+        title: template.info.title.clone(),
+        details: template.info.details.clone(),
+        objectives: template.info.objectives.clone(),
+        auto_finish: false,
+        quest_flags: template.info.flags,
+        suggested_players: template.info.suggested_players as u32,
+        is_finished: 0,
+        choice_item_rewards: build_reward_items(data_storage, &template.reward_choice_items),
+        item_rewards: build_reward_items(data_storage, &template.reward_items),
+        money_reward: Gold::new(template.info.reward_money),
+        experience_reward: template.info.reward_xp,
+        honor_reward: 0,
+        honor_reward_multiplier: 0.0,
+        reward_spell: 0,
+        casted_spell: 0,
+        title_reward: 0,
+        reward_talents: 0,
+        arena_point_reward: 0,
+        unknown2: 0,
+        reward_factions,
+        reward_reputations,
+        reward_reputations_override: [0; 5],
+        emotes: vec![],
+    }
+    .astd_send_to_character(character)
+    .await
+}
+
+//Quests with items to hand in show those first, everything else goes straight to the reward
+async fn send_quest_progress(character: &Character, data_storage: &DataStorage, quest_giver: Guid, template: &QuestTemplate) -> Result<()> {
+    let complete = character.get_quest_status(template.info.id) == Some(QuestStatus::Complete);
+    if complete && template.item_objectives.is_empty() {
+        return send_quest_offer_reward(character, data_storage, quest_giver, template).await;
+    }
+
+    SMSG_QUESTGIVER_REQUEST_ITEMS {
+        npc: quest_giver,
+        quest_id: template.info.id,
+        title: template.info.title.clone(),
+        request_items_text: template.info.request_items_text.clone(),
+        emote_delay: 0,
+        emote: 0,
+        auto_finish: 0,
+        flags: template.info.flags,
+        suggested_players: template.info.suggested_players as u32,
+        required_money: Gold::new(0),
+        required_items: template
+            .item_objectives
+            .iter()
+            .map(|objective| QuestItemRequirement {
+                item: objective.target,
+                item_count: objective.count as u32,
+                item_display_id: data_storage.get_item_template(objective.target).map_or(0, |item| item.display_id),
+            })
+            .collect(),
+        completable: if complete {
+            QuestCompletable::CompletableQuest
+        } else {
+            QuestCompletable::NotCompletable
+        },
+        flags2: 0,
+        flags3: 0,
+        flags4: 0,
+    }
+    .astd_send_to_character(character)
+    .await
+}
+
+async fn send_quest_offer_reward(character: &Character, data_storage: &DataStorage, quest_giver: Guid, template: &QuestTemplate) -> Result<()> {
+    let (reward_factions, reward_reputations) = build_reward_factions(template);
+    SMSG_QUESTGIVER_OFFER_REWARD {
+        npc: quest_giver,
+        quest_id: template.info.id,
+        title: template.info.title.clone(),
+        offer_reward_text: template.info.offer_reward_text.clone(),
+        auto_finish: false,
+        flags: template.info.flags,
+        suggested_players: template.info.suggested_players as u32,
+        emotes: vec![],
+        choice_item_rewards: build_reward_items(data_storage, &template.reward_choice_items),
+        item_rewards: build_reward_items(data_storage, &template.reward_items),
+        money_reward: Gold::new(template.info.reward_money),
+        experience_reward: template.info.reward_xp,
+        honor_reward: 0,
+        honor_reward_multiplier: 0.0,
+        unknown5: 0,
+        reward_spell: 0,
+        reward_spell_cast: 0,
+        title_reward: 0,
+        reward_talents: 0,
+        arena_point_reward: 0,
+        reward_reputation_mask: 0,
+        reward_factions,
+        reward_reputations,
+        reward_reputations_override: [0; 5],
+    }
+    .astd_send_to_character(character)
+    .await
+}
+
+pub async fn send_quest_giver_quest_complete(
+    character: &Character,
+    quest_id: u32,
+    experience: u32,
+    money: u32,
+    reward_items: &[(u32, u32)],
+) -> Result<()> {
+    SMSG_QUESTGIVER_QUEST_COMPLETE {
+        quest_id,
+        experience_reward: experience,
+        money_reward: Gold::new(money),
+        honor_reward: 0,
+        talents: 0,
+        arena_point_reward: 0,
+        item_rewards: reward_items.iter().map(|&(item, amount)| QuestItemReward { item, amount }).collect(),
+    }
+    .astd_send_to_character(character)
+    .await
+}
+
+pub async fn send_quest_invalid(character: &Character, reason: QuestFailedReason) -> Result<()> {
+    SMSG_QUESTGIVER_QUEST_INVALID { msg: reason }.astd_send_to_character(character).await
+}
+
+pub async fn send_questlog_full(character: &Character) -> Result<()> {
+    SMSG_QUESTLOG_FULL {}.astd_send_to_character(character).await
+}
+
+pub async fn send_quest_update_add_kill(
+    character: &Character,
+    quest_id: u32,
+    creature_entry: u32,
+    kill_count: u16,
+    required_kill_count: u16,
+    victim: Guid,
+) -> Result<()> {
+    SMSG_QUESTUPDATE_ADD_KILL {
+        quest_id,
+        create_id: creature_entry,
+        kill_count: kill_count as u32,
+        required_kill_count: required_kill_count as u32,
+        guid: victim,
+    }
+    .astd_send_to_character(character)
+    .await
+}
+
+pub async fn send_quest_update_complete(character: &Character, quest_id: u32) -> Result<()> {
+    SMSG_QUESTUPDATE_COMPLETE { quest_id }.astd_send_to_character(character).await
+}
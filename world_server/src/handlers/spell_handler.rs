@@ -0,0 +1,201 @@
+use crate::character::character_spells::SpellCast;
+use crate::character::Character;
+use crate::client_manager::ClientManager;
+use crate::packet::ServerMessageExt;
+use crate::prelude::*;
+use crate::world::prelude::GameObject;
+use crate::world::World;
+use std::time::{SystemTime, UNIX_EPOCH};
+use wow_world_messages::wrath::{
+    ResyncRune, SMSG_SPELL_GO_CastFlags, SMSG_SPELL_START_CastFlags, SpellCastResult, CMSG_CANCEL_CAST, CMSG_CAST_SPELL, SMSG_CAST_FAILED,
+    SMSG_LEARNED_SPELL, SMSG_REMOVED_SPELL, SMSG_RESYNC_RUNES, SMSG_SPELL_FAILED_OTHER, SMSG_SPELL_FAILURE, SMSG_SPELL_GO, SMSG_SPELL_START,
+    SMSG_SUPERCEDED_SPELL,
+};
+
+pub async fn handle_cmsg_cast_spell(client_manager: &ClientManager, client_id: u64, world: &World, data: &CMSG_CAST_SPELL) -> Result<()> {
+    let client = client_manager.get_authenticated_client(client_id).await?;
+    let character_lock = client.get_active_character().await?;
+    let mut character = character_lock.write().await;
+
+    character
+        .try_cast_spell(world, &client_manager.data_storage, data.spell, data.cast_count, data.targets.clone())
+        .await
+}
+
+pub async fn handle_cmsg_cancel_cast(client_manager: &ClientManager, client_id: u64, world: &World, data: &CMSG_CANCEL_CAST) -> Result<()> {
+    let client = client_manager.get_authenticated_client(client_id).await?;
+    let character_lock = client.get_active_character().await?;
+    let mut character = character_lock.write().await;
+
+    character.cancel_spell_cast(world, data.id).await
+}
+
+//Auras are removed on the next tick, together with everything else that changes auras
+pub async fn handle_cmsg_cancel_aura(client_manager: &ClientManager, client_id: u64, data: &CMSG_CANCEL_AURA) -> Result<()> {
+    let client = client_manager.get_authenticated_client(client_id).await?;
+    let character_lock = client.get_active_character().await?;
+    let mut character = character_lock.write().await;
+
+    if !character.auras.cancel(data.id) {
+        trace!("Character {} tried to cancel aura {}, which they can't", character.name, data.id);
+    }
+    Ok(())
+}
+
+//Only the caster gets to know why their cast was refused
+pub async fn send_cast_failed(character: &Character, cast_count: u8, spell_id: u32, result: SpellCastResult) -> Result<()> {
+    SMSG_CAST_FAILED {
+        cast_count,
+        id: spell_id,
+        result,
+        multiple_casts: false,
+    }
+    .astd_send_to_character(character)
+    .await
+}
+
+pub async fn send_spell_start(character: &Character, world: &World, cast: &SpellCast) -> Result<()> {
+    SMSG_SPELL_START {
+        cast_item: character.get_guid(),
+        caster: character.get_guid(),
+        cast_count: cast.cast_count,
+        spell: cast.spell.id,
+        flags: SMSG_SPELL_START_CastFlags::empty(),
+        timer: (cast.spell.cast_time * 1000.0) as u32,
+        targets: cast.targets.clone(),
+    }
+    .astd_send_to_all_in_range(character, true, world)
+    .await
+}
+
+pub async fn send_spell_go(character: &Character, world: &World, cast: &SpellCast, hits: Vec<Guid>) -> Result<()> {
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u32;
+    SMSG_SPELL_GO {
+        cast_item: character.get_guid(),
+        caster: character.get_guid(),
+        extra_casts: cast.cast_count,
+        spell: cast.spell.id,
+        flags: SMSG_SPELL_GO_CastFlags::empty(),
+        timestamp,
+        hits,
+        //TODO: fill misses once spells can miss
+        misses: vec![],
+        targets: cast.targets.clone(),
+    }
+    .astd_send_to_all_in_range(character, true, world)
+    .await
+}
+
+pub async fn send_spell_failure(character: &Character, world: &World, cast: &SpellCast, result: SpellCastResult) -> Result<()> {
+    SMSG_SPELL_FAILURE {
+        guid: character.get_guid(),
+        extra_casts: cast.cast_count,
+        spell: cast.spell.id,
+        result,
+    }
+    .astd_send_to_character(character)
+    .await?;
+
+    SMSG_SPELL_FAILED_OTHER {
+        caster: character.get_guid(),
+        extra_casts: cast.cast_count,
+        id: cast.spell.id,
+        result,
+    }
+    .astd_send_to_all_in_range(character, false, world)
+    .await
+}
+
+pub async fn send_learned_spell(character: &Character, spell_id: u32) -> Result<()> {
+    SMSG_LEARNED_SPELL { id: spell_id, unknown1: 0 }.astd_send_to_character(character).await
+}
+
+pub async fn send_removed_spell(character: &Character, spell_id: u32) -> Result<()> {
+    SMSG_REMOVED_SPELL { spell: spell_id }.astd_send_to_character(character).await
+}
+
+//Replaces a rank of a spell with another one, in the spellbook and on the action bars
+pub async fn send_superceded_spell(character: &Character, old_spell_id: u32, new_spell_id: u32) -> Result<()> {
+    SMSG_SUPERCEDED_SPELL { new_spell_id, old_spell_id }
+        .astd_send_to_character(character)
+        .await
+}
+
+//Runes are given as their type and how far along their cooldown they are
+pub async fn send_resync_runes(character: &Character, runes: &[(u8, u8)]) -> Result<()> {
+    SMSG_RESYNC_RUNES {
+        runes: runes
+            .iter()
+            .map(|&(current_rune, rune_cooldown)| ResyncRune { current_rune, rune_cooldown })
+            .collect(),
+    }
+    .astd_send_to_character(character)
+    .await
+}
+
+//An empty slot tells the client to remove whatever aura was in it
+fn build_aura_update(unit: Guid, slot: u8, aura: Option<&Aura>) -> AuraUpdate {
+    let Some(aura) = aura else {
+        return AuraUpdate {
+            visual_slot: slot,
+            spell: 0,
+            flags: AuraUpdate_AuraFlag::empty(),
+            level: Level::new(0),
+            aura_stack_count: 0,
+        };
+    };
+
+    let mut flags = AuraUpdate_AuraFlag::empty();
+    for effect in aura.effects.iter() {
+        flags = match effect.effect_index {
+            0 => flags.set_effect_1(),
+            1 => flags.set_effect_2(),
+            _ => flags.set_effect_3(),
+        };
+    }
+    if aura.caster != unit {
+        flags = flags.set_not_caster(AuraUpdate_AuraFlag_NotCaster { caster: aura.caster });
+    }
+    if let (Some(duration), Some(time_left)) = (aura.max_duration, aura.remaining_duration) {
+        flags = flags.set_duration(AuraUpdate_AuraFlag_Duration {
+            duration: (duration * 1000.0) as u32,
+            time_left: (time_left.max(0.0) * 1000.0) as u32,
+        });
+    }
+    if aura.is_negative() {
+        flags = flags.set_negative();
+    } else if aura.cancellable {
+        flags = flags.set_cancellable();
+    }
+
+    AuraUpdate {
+        visual_slot: slot,
+        spell: aura.spell_id,
+        flags,
+        level: Level::new(aura.caster_level),
+        //Auras with charges show those instead of stacks
+        aura_stack_count: if aura.charges > 0 { aura.charges } else { aura.stacks },
+    }
+}
+
+pub async fn send_aura_updates(unit: &dyn GameObject, auras: &AuraContainer, slots: &[u8], map: &MapManager) -> Result<()> {
+    for slot in slots.iter().copied() {
+        SMSG_AURA_UPDATE {
+            unit: unit.get_guid(),
+            aura_update: build_aura_update(unit.get_guid(), slot, auras.get_aura(slot)),
+        }
+        .astd_send_to_all_in_range_of(unit, true, map)
+        .await?;
+    }
+    Ok(())
+}
+
+//Everything a character needs to know about the auras on a unit that just came into view
+pub async fn send_aura_update_all(character: &Character, unit: Guid, auras: &AuraContainer) -> Result<()> {
+    SMSG_AURA_UPDATE_ALL {
+        unit,
+        aura_updates: auras.iter().map(|(slot, aura)| build_aura_update(unit, slot, Some(aura))).collect(),
+    }
+    .astd_send_to_character(character)
+    .await
+}
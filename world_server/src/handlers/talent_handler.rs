@@ -0,0 +1,110 @@
+use crate::character::Character;
+use crate::client_manager::ClientManager;
+use crate::packet::ServerMessageExt;
+use crate::prelude::*;
+use crate::world::World;
+use wow_world_messages::wrath::{
+    InspectTalent, InspectTalentGearMask, MSG_TALENT_WIPE_CONFIRM_Client, MSG_TALENT_WIPE_CONFIRM_Server, SMSG_TALENTS_INFO_TalentType,
+    TalentInfoSpec, CMSG_INSPECT, CMSG_LEARN_PREVIEW_TALENTS, CMSG_LEARN_TALENT, SMSG_INSPECT_TALENT, SMSG_TALENTS_INFO,
+};
+
+pub async fn handle_cmsg_learn_talent(client_manager: &ClientManager, client_id: u64, data: &CMSG_LEARN_TALENT) -> Result<()> {
+    let client = client_manager.get_authenticated_client(client_id).await?;
+    let character_lock = client.get_active_character().await?;
+    let mut character = character_lock.write().await;
+
+    character
+        .learn_talent(&client_manager.data_storage, data.talent, data.requested_rank as u8)
+        .await
+}
+
+pub async fn handle_cmsg_learn_preview_talents(client_manager: &ClientManager, client_id: u64, data: &CMSG_LEARN_PREVIEW_TALENTS) -> Result<()> {
+    let client = client_manager.get_authenticated_client(client_id).await?;
+    let character_lock = client.get_active_character().await?;
+    let mut character = character_lock.write().await;
+
+    let talents: Vec<(u32, u8)> = data.talents.iter().map(|talent| (talent.talent, talent.rank as u8)).collect();
+    character.learn_preview_talents(&client_manager.data_storage, &talents).await
+}
+
+//The client confirmed the cost of the talent reset
+pub async fn handle_msg_talent_wipe_confirm(
+    client_manager: &ClientManager,
+    client_id: u64,
+    world: &World,
+    data: &MSG_TALENT_WIPE_CONFIRM_Client,
+) -> Result<()> {
+    let client = client_manager.get_authenticated_client(client_id).await?;
+    let character_lock = client.get_active_character().await?;
+    let mut character = character_lock.write().await;
+
+    character.reset_talents(world, data.wiping_npc).await
+}
+
+pub async fn handle_cmsg_inspect(client_manager: &ClientManager, client_id: u64, world: &World, data: &CMSG_INSPECT) -> Result<()> {
+    let client = client_manager.get_authenticated_client(client_id).await?;
+    let character_lock = client.get_active_character().await?;
+    let character = character_lock.read().await;
+
+    let map = world
+        .get_instance_manager()
+        .try_get_map_for_character(&character)
+        .await
+        .ok_or_else(|| anyhow!("Character {} is not on a map", character.name))?;
+    let target_lock = map
+        .try_get_object(data.guid)
+        .await
+        .and_then(|weak| weak.upgrade())
+        .ok_or_else(|| anyhow!("Character {} inspected {} which isn't on the map", character.name, data.guid))?;
+    let target_object = target_lock.read().await;
+    let target = target_object
+        .as_character()
+        .ok_or_else(|| anyhow!("Character {} inspected {} which isn't a character", character.name, data.guid))?;
+
+    SMSG_INSPECT_TALENT {
+        player: target.get_guid(),
+        unspent_talent_points: target.get_free_talent_points(),
+        active_spec: target.get_active_talent_spec_index(),
+        specs: build_talent_specs(target),
+        glyphs: vec![],
+        talent_gear_mask: InspectTalentGearMask::default(),
+    }
+    .astd_send_to_character(&*character)
+    .await
+}
+
+//Glyphs aren't implemented, so every spec is just its talents
+fn build_talent_specs(character: &Character) -> Vec<TalentInfoSpec> {
+    character
+        .get_talent_specs()
+        .map(|talents| TalentInfoSpec {
+            talents: talents
+                .into_iter()
+                .map(|(talent, rank)| InspectTalent { talent, max_rank: rank })
+                .collect(),
+            glyphs: vec![],
+        })
+        .collect()
+}
+
+pub async fn send_talents_info(character: &Character) -> Result<()> {
+    SMSG_TALENTS_INFO {
+        talent_type: SMSG_TALENTS_INFO_TalentType::Player {
+            points_left: character.get_free_talent_points(),
+            active_spec: character.get_active_talent_spec_index(),
+            specs: build_talent_specs(character),
+        },
+    }
+    .astd_send_to_character(character)
+    .await
+}
+
+//Asks the client to confirm the cost of resetting its talents
+pub async fn send_talent_wipe_confirm(character: &Character, trainer: Guid) -> Result<()> {
+    MSG_TALENT_WIPE_CONFIRM_Server {
+        wiping_npc: trainer,
+        cost_in_copper: character.get_talent_reset_cost(),
+    }
+    .astd_send_to_character(character)
+    .await
+}
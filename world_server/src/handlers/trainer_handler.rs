@@ -0,0 +1,83 @@
+use crate::character::Character;
+use crate::client_manager::ClientManager;
+use crate::packet::ServerMessageExt;
+use crate::prelude::*;
+use crate::world::guid_service::get_entry;
+use crate::world::World;
+use wow_world_messages::wrath::{
+    Level, Skill, TrainerSpell, TrainingFailureReason, CMSG_TRAINER_BUY_SPELL, CMSG_TRAINER_LIST, SMSG_TRAINER_BUY_FAILED,
+    SMSG_TRAINER_BUY_SUCCEEDED, SMSG_TRAINER_LIST,
+};
+
+//Class trainers, as opposed to mount, tradeskill and pet trainers
+const TRAINER_TYPE_CLASS: u32 = 0;
+const TRAINER_GREETING: &str = "Hello! Ready for some training?";
+
+pub async fn handle_cmsg_trainer_list(client_manager: &ClientManager, client_id: u64, world: &World, data: &CMSG_TRAINER_LIST) -> Result<()> {
+    let client = client_manager.get_authenticated_client(client_id).await?;
+    let character_lock = client.get_active_character().await?;
+    let character = character_lock.read().await;
+
+    send_trainer_list(&character, world, data.guid).await
+}
+
+pub async fn handle_cmsg_trainer_buy_spell(
+    client_manager: &ClientManager,
+    client_id: u64,
+    world: &World,
+    data: &CMSG_TRAINER_BUY_SPELL,
+) -> Result<()> {
+    let client = client_manager.get_authenticated_client(client_id).await?;
+    let character_lock = client.get_active_character().await?;
+    let mut character = character_lock.write().await;
+
+    character.learn_trainer_spell(world, data.guid, data.id).await
+}
+
+pub async fn send_trainer_list(character: &Character, world: &World, trainer: Guid) -> Result<()> {
+    character.get_trainer(world, trainer).await?;
+
+    let spells = world
+        .get_data_storage()
+        .get_trainer_spells(get_entry(trainer))
+        .iter()
+        .map(|trainer_spell| TrainerSpell {
+            spell: trainer_spell.spell,
+            state: character.get_trainer_spell_state(trainer_spell),
+            spell_cost: trainer_spell.cost,
+            talent_point_cost: 0,
+            first_rank: 0,
+            required_level: Level::new(trainer_spell.required_level),
+            required_skill: Skill::try_from(trainer_spell.required_skill).unwrap_or(Skill::None),
+            required_skill_value: trainer_spell.required_skill_value as u32,
+            spell_chain_required: trainer_spell.required_spell,
+            spell_chain_previous: trainer_spell.required_spell,
+            unknown1: 0,
+        })
+        .collect();
+
+    SMSG_TRAINER_LIST {
+        guid: trainer,
+        trainer_type: TRAINER_TYPE_CLASS,
+        spells,
+        greeting: TRAINER_GREETING.to_string(),
+    }
+    .astd_send_to_character(character)
+    .await
+}
+
+pub async fn send_trainer_buy_succeeded(character: &Character, trainer: Guid, spell_id: u32) -> Result<()> {
+    SMSG_TRAINER_BUY_SUCCEEDED { guid: trainer, id: spell_id }
+        .astd_send_to_character(character)
+        .await
+}
+
+pub async fn send_trainer_buy_failed(character: &Character, trainer: Guid, spell_id: u32, error: TrainingFailureReason) -> Result<()> {
+    SMSG_TRAINER_BUY_FAILED {
+        guid: trainer,
+        id: spell_id,
+        error,
+    }
+    .astd_send_to_character(character)
+    .await
+}
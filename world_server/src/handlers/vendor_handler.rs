@@ -0,0 +1,102 @@
+use crate::character::Character;
+use crate::client_manager::ClientManager;
+use crate::packet::ServerMessageExt;
+use crate::prelude::*;
+use crate::world::guid_service::get_entry;
+use crate::world::prelude::unit_flags::UNIT_NPC_FLAG_VENDOR;
+use crate::world::World;
+use wow_world_messages::wrath::{
+    BuyResult, Gold, ListInventoryItem, SellItemResult, CMSG_BUYBACK_ITEM, CMSG_BUY_ITEM, CMSG_LIST_INVENTORY, CMSG_REPAIR_ITEM, CMSG_SELL_ITEM,
+    SMSG_BUY_FAILED, SMSG_BUY_ITEM, SMSG_LIST_INVENTORY, SMSG_SELL_ITEM,
+};
+
+//Shown in the vendor window for items the vendor never runs out of
+const UNLIMITED_VENDOR_ITEM_COUNT: u32 = 0xFFFFFFFF;
+
+pub async fn handle_cmsg_list_inventory(client_manager: &ClientManager, client_id: u64, world: &World, data: &CMSG_LIST_INVENTORY) -> Result<()> {
+    let client = client_manager.get_authenticated_client(client_id).await?;
+    let character_lock = client.get_active_character().await?;
+    let character = character_lock.read().await;
+
+    send_vendor_inventory(&character, world, data.guid).await
+}
+
+pub async fn handle_cmsg_buy_item(client_manager: &ClientManager, client_id: u64, world: &World, data: &CMSG_BUY_ITEM) -> Result<()> {
+    let client = client_manager.get_authenticated_client(client_id).await?;
+    let character_lock = client.get_active_character().await?;
+    let mut character = character_lock.write().await;
+
+    character
+        .buy_item_from_vendor(world, data.guid, data.item, data.vendor_slot, data.amount)
+        .await
+}
+
+pub async fn handle_cmsg_sell_item(client_manager: &ClientManager, client_id: u64, world: &World, data: &CMSG_SELL_ITEM) -> Result<()> {
+    let client = client_manager.get_authenticated_client(client_id).await?;
+    let character_lock = client.get_active_character().await?;
+    let mut character = character_lock.write().await;
+
+    character.sell_item_to_vendor(world, data.vendor, data.item, data.amount).await
+}
+
+pub async fn handle_cmsg_buyback_item(client_manager: &ClientManager, client_id: u64, world: &World, data: &CMSG_BUYBACK_ITEM) -> Result<()> {
+    let client = client_manager.get_authenticated_client(client_id).await?;
+    let character_lock = client.get_active_character().await?;
+    let mut character = character_lock.write().await;
+
+    character.buy_back_item(world, data.guid, data.slot).await
+}
+
+pub async fn handle_cmsg_repair_item(client_manager: &ClientManager, client_id: u64, world: &World, data: &CMSG_REPAIR_ITEM) -> Result<()> {
+    let client = client_manager.get_authenticated_client(client_id).await?;
+    let character_lock = client.get_active_character().await?;
+    let mut character = character_lock.write().await;
+
+    character.repair_items(world, data.npc, data.item, data.from_guild_bank).await
+}
+
+pub async fn send_vendor_inventory(character: &Character, world: &World, vendor: Guid) -> Result<()> {
+    let data_storage = world.get_data_storage();
+    let creature_lock = character.get_npc(world, vendor, UNIT_NPC_FLAG_VENDOR).await?;
+    let creature = creature_lock.read().await;
+
+    let items = data_storage
+        .get_vendor_items(get_entry(vendor))
+        .iter()
+        .enumerate()
+        .filter_map(|(index, vendor_item)| {
+            let template = data_storage.get_item_template(vendor_item.item)?;
+            Some(ListInventoryItem {
+                vendor_slot: index as u32 + 1,
+                item: vendor_item.item,
+                item_display_id: template.displayid,
+                max_items: creature.get_vendor_item_count(vendor_item).unwrap_or(UNLIMITED_VENDOR_ITEM_COUNT),
+                price: Gold::new(template.buy_price),
+                max_durability: template.max_durability as u32,
+                buy_count: template.buy_count as u32,
+                extended_cost: 0,
+            })
+        })
+        .collect();
+
+    SMSG_LIST_INVENTORY { vendor, items }.astd_send_to_character(character).await
+}
+
+pub async fn send_buy_item(character: &Character, vendor: Guid, vendor_slot: u32, remaining: Option<u32>, amount: u32) -> Result<()> {
+    SMSG_BUY_ITEM {
+        guid: vendor,
+        vendor_slot,
+        amount_for_sale: remaining.unwrap_or(UNLIMITED_VENDOR_ITEM_COUNT),
+        amount_bought: amount,
+    }
+    .astd_send_to_character(character)
+    .await
+}
+
+pub async fn send_buy_failed(character: &Character, vendor: Guid, item: u32, result: BuyResult) -> Result<()> {
+    SMSG_BUY_FAILED { guid: vendor, item, result }.astd_send_to_character(character).await
+}
+
+pub async fn send_sell_failed(character: &Character, vendor: Guid, item: Guid, result: SellItemResult) -> Result<()> {
+    SMSG_SELL_ITEM { guid: vendor, item, result }.astd_send_to_character(character).await
+}
@@ -18,7 +18,7 @@ pub async fn handle_cmsg_zoneupdate(client_manager: &ClientManager, client_id: u
     let character_lock = client.get_active_character().await?;
 
     let mut character = character_lock.write().await;
-    (*character).zone_update(packet.area).await
+    (*character).zone_update(&client_manager.data_storage, packet.area).await
 }
 
 pub async fn send_initial_world_states(character: &Character) -> Result<()> {
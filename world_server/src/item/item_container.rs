@@ -3,6 +3,7 @@ use std::ops::{Index, IndexMut};
 use wow_world_messages::wrath::UpdateItem;
 
 use super::Item;
+use crate::prelude::*;
 
 //generic, index based item container, could represent a bag, bank, loot,
 //everything that doesn't require more logic to item placement
@@ -13,3 +14,55 @@ where
 {
     fn get_items_update_state(&self) -> Vec<UpdateItem>;
 }
+
+//A fixed amount of item slots, numbered from 0. Used for the contents of bags and the bank
+#[derive(Default)]
+pub struct BagContents {
+    items: Vec<Option<Item>>,
+}
+
+impl BagContents {
+    pub fn new(num_slots: u8) -> Self {
+        Self {
+            items: (0..num_slots).map(|_| None).collect(),
+        }
+    }
+
+    pub fn num_slots(&self) -> u8 {
+        self.items.len() as u8
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.iter().all(Option::is_none)
+    }
+
+    pub fn get_item(&self, slot: u8) -> Option<&Item> {
+        self.items.get(slot as usize).and_then(Option::as_ref)
+    }
+
+    pub fn get_item_mut(&mut self, slot: u8) -> Option<&mut Item> {
+        self.items.get_mut(slot as usize).and_then(Option::as_mut)
+    }
+
+    //Puts the item in the slot, and returns the item that was there before
+    pub fn replace_item(&mut self, slot: u8, item: Option<Item>) -> Result<Option<Item>> {
+        let num_slots = self.num_slots();
+        let cell = self
+            .items
+            .get_mut(slot as usize)
+            .ok_or_else(|| anyhow!("Slot {} is out of range, container only has {} slots", slot, num_slots))?;
+        Ok(std::mem::replace(cell, item))
+    }
+
+    pub fn find_free_slot(&self) -> Option<u8> {
+        self.items.iter().position(Option::is_none).map(|slot| slot as u8)
+    }
+
+    pub fn get_all_items(&self) -> Vec<(u8, &Item)> {
+        self.items
+            .iter()
+            .enumerate()
+            .filter_map(|(slot, item)| item.as_ref().map(|item| (slot as u8, item)))
+            .collect()
+    }
+}
@@ -1,23 +1,83 @@
-use wow_world_messages::{wrath::UpdateItemBuilder, Guid};
-use wrath_realm_db::item_instance::DBItemInstance;
+use wow_world_messages::{
+    wrath::{InventoryType, UpdateItemBuilder},
+    Guid,
+};
+use wrath_realm_db::{item_instance::DBItemInstance, item_template::DBItemTemplate};
 
+use super::item_container::BagContents;
 use super::Item;
+use crate::prelude::*;
+use crate::world::guid_service::{get_low_guid, item_guid, player_guid};
+
+//Enchantments are stored as space separated ids, one per enchantment slot
+pub fn parse_enchantments(text: &str) -> Vec<u32> {
+    text.split_whitespace().filter_map(|x| x.parse().ok()).collect()
+}
+
+fn parse_charges(text: &str) -> Vec<i32> {
+    text.split_whitespace().filter_map(|x| x.parse().ok()).collect()
+}
+
+fn join_values<T: ToString>(values: &[T]) -> String {
+    values.iter().map(|x| x.to_string()).collect::<Vec<String>>().join(" ")
+}
+
+impl Item {
+    pub fn from_database(value: &DBItemInstance, template: &DBItemTemplate) -> Self {
+        let owner = player_guid(value.owner_guid);
+        let container = if value.container_guid == 0 {
+            owner
+        } else {
+            item_guid(value.container_guid)
+        };
+        let creator = if value.creator_guid == 0 {
+            Guid::zero()
+        } else {
+            player_guid(value.creator_guid)
+        };
 
-impl From<&DBItemInstance> for Item {
-    fn from(value: &DBItemInstance) -> Self {
-        //TODO: this object guid is not really correct, but guid format is not implemented yet
-        //TODO: other attributes here need to be read from DB as well
         Item {
             update_state: UpdateItemBuilder::new()
-                .set_object_guid(((value.character_id as u64) << (32 + value.slot_id as u64)).into())
-                .set_object_entry(value.item.try_into().unwrap())
+                .set_object_guid(item_guid(value.guid))
+                .set_object_entry(value.entry as i32)
                 .set_object_scale_x(1.0)
-                .set_item_owner(Guid::new(value.character_id as u64))
-                .set_item_contained(Guid::new(value.character_id as u64))
-                .set_item_stack_count(1)
-                .set_item_durability(100)
-                .set_item_maxdurability(100)
+                .set_item_owner(owner)
+                .set_item_contained(container)
+                .set_item_creator(creator)
+                .set_item_stack_count(value.stack_count as i32)
+                .set_item_durability(value.durability as i32)
+                .set_item_maxdurability(template.max_durability as i32)
+                .set_item_flags(value.flags as i32)
                 .finalize(),
+            inventory_type: InventoryType::try_from(template.inventory_type).unwrap_or(InventoryType::NonEquip),
+            enchantments: parse_enchantments(&value.enchantments),
+            charges: parse_charges(&value.charges),
+            contents: (template.container_slots > 0).then(|| BagContents::new(template.container_slots)),
         }
     }
+
+    pub fn to_database_entry(&self, slot: u8) -> Result<DBItemInstance> {
+        let guid = self
+            .update_state
+            .object_guid()
+            .ok_or_else(|| anyhow!("Item in slot {} has no guid", slot))?;
+        let entry = self.update_state.object_entry().ok_or_else(|| anyhow!("Item {} has no entry", guid))?;
+        let owner = self.update_state.item_owner().unwrap_or(Guid::zero());
+        let container = self.update_state.item_contained().unwrap_or(Guid::zero());
+
+        Ok(DBItemInstance {
+            guid: get_low_guid(guid),
+            entry: entry as u32,
+            owner_guid: get_low_guid(owner),
+            //Items held directly by the character have the character as their container
+            container_guid: if container == owner { 0 } else { get_low_guid(container) },
+            slot,
+            stack_count: self.update_state.item_stack_count().unwrap_or(1) as u32,
+            durability: self.update_state.item_durability().unwrap_or(0) as u32,
+            enchantments: join_values(&self.enchantments),
+            flags: self.update_state.item_flags().unwrap_or(0) as u32,
+            creator_guid: get_low_guid(self.update_state.item_creator().unwrap_or(Guid::zero())),
+            charges: join_values(&self.charges),
+        })
+    }
 }
@@ -1,26 +1,154 @@
 pub mod item_container;
 mod item_database;
 
-use wow_world_messages::wrath::UpdateItem;
+use item_container::BagContents;
+pub use item_database::parse_enchantments;
+use wow_world_messages::wrath::{
+    InventoryType, MovementBlock, MovementBlock_UpdateFlag, Object, ObjectType, Object_UpdateType, UpdateContainer, UpdateItem, UpdateItemBuilder,
+    UpdateMask,
+};
+use wrath_realm_db::item_template::DBItemTemplate;
 
 use crate::character::character_inventory::InventoryStorable;
+use crate::prelude::*;
 
-#[derive(Default)]
 pub struct Item {
     pub update_state: UpdateItem,
+    //Copied from the item template, so we don't need a template lookup for every inventory operation
+    pub inventory_type: InventoryType,
+    //TODO: enchantments and spell charges are only persisted, not applied to the update fields yet
+    pub enchantments: Vec<u32>,
+    pub charges: Vec<i32>,
+    //Only bags have contents, the items inside of them are owned by the bag
+    pub contents: Option<BagContents>,
+}
+
+impl Item {
+    //A brand new item, as it comes out of its template
+    pub fn new(guid: Guid, owner: Guid, template: &DBItemTemplate, stack_count: u32) -> Self {
+        Self {
+            update_state: UpdateItemBuilder::new()
+                .set_object_guid(guid)
+                .set_object_entry(template.id as i32)
+                .set_object_scale_x(1.0)
+                .set_item_owner(owner)
+                .set_item_contained(owner)
+                .set_item_stack_count(stack_count as i32)
+                .set_item_durability(template.max_durability as i32)
+                .set_item_maxdurability(template.max_durability as i32)
+                .finalize(),
+            inventory_type: InventoryType::try_from(template.inventory_type).unwrap_or(InventoryType::NonEquip),
+            enchantments: vec![],
+            charges: vec![],
+            contents: (template.container_slots > 0).then(|| BagContents::new(template.container_slots)),
+        }
+    }
+
+    pub fn get_guid(&self) -> Guid {
+        self.update_state.object_guid().unwrap()
+    }
+
+    pub fn get_entry(&self) -> u32 {
+        self.update_state.object_entry().unwrap() as u32
+    }
+
+    pub fn get_stack_count(&self) -> u32 {
+        self.update_state.item_stack_count().unwrap_or(1) as u32
+    }
+
+    pub fn set_stack_count(&mut self, stack_count: u32) {
+        self.update_state.set_item_stack_count(stack_count as i32);
+    }
+
+    pub fn get_durability(&self) -> u32 {
+        self.update_state.item_durability().unwrap_or(0).max(0) as u32
+    }
+
+    pub fn get_max_durability(&self) -> u32 {
+        self.update_state.item_maxdurability().unwrap_or(0).max(0) as u32
+    }
+
+    pub fn set_durability(&mut self, durability: u32) {
+        let durability = durability.min(self.get_max_durability());
+        self.update_state.set_item_durability(durability as i32);
+    }
+
+    //Items without durability never break
+    pub fn is_broken(&self) -> bool {
+        self.get_max_durability() > 0 && self.get_durability() == 0
+    }
+
+    pub fn is_bag(&self) -> bool {
+        self.contents.is_some()
+    }
+
+    pub fn get_object_type(&self) -> ObjectType {
+        if self.is_bag() {
+            ObjectType::Container
+        } else {
+            ObjectType::Item
+        }
+    }
+
+    pub fn get_update_mask(&self) -> UpdateMask {
+        match self.build_container_update_state() {
+            Some(container) => UpdateMask::Container(container),
+            None => UpdateMask::Item(self.update_state.clone()),
+        }
+    }
+
+    pub fn build_create_update(&self) -> Object {
+        Object {
+            update_type: Object_UpdateType::CreateObject {
+                guid3: self.get_guid(),
+                mask2: self.get_update_mask(),
+                movement2: MovementBlock {
+                    update_flag: MovementBlock_UpdateFlag::empty(),
+                },
+                object_type: self.get_object_type(),
+            },
+        }
+    }
+
+    pub fn build_values_update(&self) -> Object {
+        Object {
+            update_type: Object_UpdateType::Values {
+                guid1: self.get_guid(),
+                mask1: self.get_update_mask(),
+            },
+        }
+    }
+
+    //Bags are sent to the client as containers, which have the item fields plus the bag slots
+    fn build_container_update_state(&self) -> Option<UpdateContainer> {
+        let contents = self.contents.as_ref()?;
+        let item = &self.update_state;
+        let mut builder = UpdateContainer::builder()
+            .set_object_guid(self.get_guid())
+            .set_object_entry(item.object_entry().unwrap_or(0))
+            .set_object_scale_x(1.0)
+            .set_item_owner(item.item_owner().unwrap_or(Guid::zero()))
+            .set_item_contained(item.item_contained().unwrap_or(Guid::zero()))
+            .set_item_creator(item.item_creator().unwrap_or(Guid::zero()))
+            .set_item_stack_count(item.item_stack_count().unwrap_or(1))
+            .set_item_durability(item.item_durability().unwrap_or(0))
+            .set_item_maxdurability(item.item_maxdurability().unwrap_or(0))
+            .set_item_flags(item.item_flags().unwrap_or(0))
+            .set_container_num_slots(contents.num_slots() as i32);
+        for (slot, bag_item) in contents.get_all_items() {
+            builder = builder.set_container_slot(slot, bag_item.get_guid());
+        }
+        Some(builder.finalize())
+    }
 }
 
-impl Item {}
 impl std::fmt::Display for Item {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "Item")
     }
 }
 impl InventoryStorable for Item {
-    fn get_inventory_type(&self) -> wow_world_base::wrath::InventoryType {
-        //TODO: this might need to change when templates are loaded from DB
-        wow_items::wrath::lookup_item(self.update_state.object_entry().unwrap().try_into().unwrap())
-            .unwrap()
-            .inventory_type()
+    fn get_inventory_type(&self) -> InventoryType {
+        self.inventory_type
     }
 }
@@ -0,0 +1,179 @@
+use crate::prelude::*;
+use rand::Rng;
+use std::collections::BTreeMap;
+use wrath_realm_db::loot::DBLootTemplate;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LootItem {
+    pub item: u32,
+    pub count: u32,
+    pub quest_required: bool,
+    pub looted: bool,
+    //Only this character may take the item, after winning it or when it's their turn
+    pub owner: Option<Guid>,
+    //Rolled for by the group, nobody can take it until the roll is over
+    pub roll_pending: bool,
+    //Handed out by the master looter
+    pub master_loot: bool,
+}
+
+impl LootItem {
+    pub fn can_be_taken_by(&self, looter: Guid) -> bool {
+        !self.looted && !self.roll_pending && !self.master_loot && self.owner.map_or(true, |owner| owner == looter)
+    }
+}
+
+//What a corpse or container holds, items keep their slot after being taken, the client refers to them by it
+#[derive(Debug, Default)]
+pub struct Loot {
+    pub items: Vec<LootItem>,
+    pub money: u32,
+    //Who may open it, the money is split between them. Nobody claimed it if empty, then anyone may.
+    pub looters: Vec<Guid>,
+    pub master_looter: Option<Guid>,
+}
+
+impl Loot {
+    pub fn generate(template: &[DBLootTemplate], min_money: u32, max_money: u32) -> Self {
+        let mut rng = rand::thread_rng();
+        let money = if max_money > min_money {
+            rng.gen_range(min_money..=max_money)
+        } else {
+            min_money
+        };
+        Self {
+            items: roll_loot(template, &mut rng),
+            money,
+            ..Default::default()
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.money == 0 && self.items.iter().all(|item| item.looted)
+    }
+
+    pub fn get_remaining_item(&self, slot: u8) -> Option<&LootItem> {
+        self.items.get(slot as usize).filter(|item| !item.looted)
+    }
+
+    pub fn get_remaining_items(&self) -> Vec<(u8, LootItem)> {
+        self.items
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| !item.looted)
+            .map(|(slot, item)| (slot as u8, item.clone()))
+            .collect()
+    }
+
+    pub fn can_be_opened_by(&self, looter: Guid) -> bool {
+        self.looters.is_empty() || self.looters.contains(&looter)
+    }
+
+    pub fn mark_looted(&mut self, slot: u8) {
+        if let Some(item) = self.items.get_mut(slot as usize) {
+            item.looted = true;
+        }
+    }
+
+    //Ends a roll or master loot, None leaves the item to anyone who may loot
+    pub fn assign_item(&mut self, slot: u8, owner: Option<Guid>) {
+        if let Some(item) = self.items.get_mut(slot as usize) {
+            item.roll_pending = false;
+            item.master_loot = false;
+            item.owner = owner;
+        }
+    }
+}
+
+//Entries outside of a group drop on their own chance, every group drops at most one of its entries
+pub fn roll_loot(template: &[DBLootTemplate], rng: &mut impl Rng) -> Vec<LootItem> {
+    let mut dropped = Vec::new();
+    let mut groups: BTreeMap<u8, Vec<&DBLootTemplate>> = BTreeMap::new();
+    for entry in template {
+        if entry.group_id == 0 {
+            if rng.gen_range(0.0..100.0) < entry.chance {
+                dropped.push(entry);
+            }
+        } else {
+            groups.entry(entry.group_id).or_default().push(entry);
+        }
+    }
+    for entries in groups.values() {
+        dropped.extend(pick_from_group(entries, rng.gen_range(0.0..100.0)));
+    }
+
+    dropped
+        .into_iter()
+        .map(|entry| LootItem {
+            item: entry.item,
+            count: rng.gen_range(entry.min_count..=entry.max_count.max(entry.min_count)) as u32,
+            quest_required: entry.quest_required != 0,
+            looted: false,
+            owner: None,
+            roll_pending: false,
+            master_loot: false,
+        })
+        .collect()
+}
+
+//Roll goes from 0 to 100. Entries without a chance share whatever the others leave equally.
+fn pick_from_group<'a>(entries: &[&'a DBLootTemplate], roll: f32) -> Option<&'a DBLootTemplate> {
+    let mut remaining = roll;
+    for entry in entries.iter().filter(|entry| entry.chance > 0.0) {
+        if remaining < entry.chance {
+            return Some(entry);
+        }
+        remaining -= entry.chance;
+    }
+
+    let equal_chanced: Vec<&DBLootTemplate> = entries.iter().copied().filter(|entry| entry.chance <= 0.0).collect();
+    let explicit_total = roll - remaining;
+    if equal_chanced.is_empty() || explicit_total >= 100.0 {
+        return None;
+    }
+    let index = (remaining / (100.0 - explicit_total) * equal_chanced.len() as f32) as usize;
+    equal_chanced.get(index.min(equal_chanced.len() - 1)).copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(item: u32, chance: f32, group_id: u8) -> DBLootTemplate {
+        DBLootTemplate {
+            entry: 1,
+            item,
+            chance,
+            quest_required: 0,
+            group_id,
+            min_count: 1,
+            max_count: 1,
+        }
+    }
+
+    #[test]
+    fn group_picks_one() {
+        let entries = [entry(1, 30.0, 1), entry(2, 20.0, 1), entry(3, 0.0, 1), entry(4, 0.0, 1)];
+        let group: Vec<&DBLootTemplate> = entries.iter().collect();
+        assert_eq!(pick_from_group(&group, 10.0).map(|e| e.item), Some(1));
+        assert_eq!(pick_from_group(&group, 40.0).map(|e| e.item), Some(2));
+        //The last 50 are split between the entries without a chance
+        assert_eq!(pick_from_group(&group, 60.0).map(|e| e.item), Some(3));
+        assert_eq!(pick_from_group(&group, 80.0).map(|e| e.item), Some(4));
+        //Without those, the group can come up empty
+        assert_eq!(pick_from_group(&group[..2], 60.0).map(|e| e.item), None);
+    }
+
+    #[test]
+    fn ungrouped_entries_roll_on_their_own() {
+        let template = [
+            entry(1, 100.0, 0),
+            entry(2, 0.0, 0),
+            entry(3, 100.0, 0),
+            entry(4, 100.0, 1),
+            entry(5, 100.0, 1),
+        ];
+        let items: Vec<u32> = roll_loot(&template, &mut rand::thread_rng()).iter().map(|item| item.item).collect();
+        assert_eq!(items, vec![1, 3, 4]);
+    }
+}
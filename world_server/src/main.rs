@@ -20,13 +20,19 @@ mod auth;
 mod character;
 mod client;
 mod client_manager;
+mod combat;
 mod console_input;
 mod constants;
+mod corpse;
+mod creature;
 mod data;
+mod group;
 pub mod handlers;
 mod item;
+mod loot;
 mod packet;
 mod packet_handler;
+mod spell;
 mod world;
 
 pub mod prelude {
@@ -72,7 +78,8 @@ async fn main() -> Result<()> {
 
     smol::spawn(auth::auth_server_heartbeats()).detach();
 
-    let world = std::sync::Arc::new(world::World::new(realm_database_ref));
+    let autosave_interval = Duration::from_secs(std::env::var("AUTOSAVE_INTERVAL_SECONDS")?.parse()?);
+    let world = std::sync::Arc::new(world::World::new(realm_database_ref, data_storage.clone(), autosave_interval).await?);
 
     let (sender, receiver) = std::sync::mpsc::channel::<PacketToHandle>();
     let realm_packet_handler = PacketHandler::new(receiver, world.clone());
@@ -129,6 +136,7 @@ async fn main() -> Result<()> {
         previous_loop_total = std::time::Instant::now().duration_since(before).as_secs_f32();
     }
 
+    client_manager.save_all_characters(&world).await;
     info!("World server shut down");
     Ok(())
 }
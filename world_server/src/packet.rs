@@ -1,5 +1,5 @@
 use super::client::Client;
-use crate::{character::*, prelude::*, world::game_object::GameObject, world::World};
+use crate::{character::*, prelude::*, world::game_object::GameObject, world::prelude::MapManager, world::World};
 use smol::prelude::*;
 use std::{borrow::Borrow, pin::Pin};
 use wow_world_messages::wrath::ServerMessage;
@@ -45,6 +45,62 @@ pub trait ServerMessageExt: ServerMessage {
         })
     }
 
+    //Like astd_send_to_all_in_range, but also works for objects that aren't characters themselves
+    fn astd_send_to_all_in_range_of<'life0, 'life1, 'life2, 'async_trait>(
+        &'life0 self,
+        object: &'life1 dyn GameObject,
+        include_self: bool,
+        map: &'life2 MapManager,
+    ) -> Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send + 'async_trait>>
+    where
+        'life0: 'async_trait,
+        'life1: 'async_trait,
+        'life2: 'async_trait,
+        Self: Sync + 'async_trait,
+    {
+        Box::pin(async move {
+            for guid in object.get_in_range_guids() {
+                let Some(object_lock) = map.try_get_object(guid).await.and_then(|weak| weak.upgrade()) else {
+                    continue;
+                };
+                let read_obj = object_lock.read().await;
+                if let Some(in_range_character) = read_obj.as_character() {
+                    self.astd_send_to_character(in_range_character).await?;
+                }
+            }
+            if let Some(character) = object.as_character().filter(|_| include_self) {
+                self.astd_send_to_character(character).await?;
+            }
+            Ok(())
+        })
+    }
+
+    //For characters that aren't in range of each other, like members of a group. The caller mustn't hold the lock of any of them.
+    fn astd_send_to_characters_on_map<'life0, 'life1, 'life2, 'async_trait>(
+        &'life0 self,
+        guids: &'life1 [Guid],
+        map: &'life2 MapManager,
+    ) -> Pin<Box<dyn Future<Output = anyhow::Result<()>> + Send + 'async_trait>>
+    where
+        'life0: 'async_trait,
+        'life1: 'async_trait,
+        'life2: 'async_trait,
+        Self: Sync + 'async_trait,
+    {
+        Box::pin(async move {
+            for guid in guids {
+                let Some(object_lock) = map.try_get_object(*guid).await.and_then(|weak| weak.upgrade()) else {
+                    continue;
+                };
+                let read_obj = object_lock.read().await;
+                if let Some(character) = read_obj.as_character() {
+                    self.astd_send_to_character(character).await?;
+                }
+            }
+            Ok(())
+        })
+    }
+
     fn astd_send_to_character<'life0, 'life1, 'async_trait>(
         &'life0 self,
         character: impl Borrow<Character> + 'life1 + Send,
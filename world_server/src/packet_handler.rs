@@ -127,16 +127,98 @@ impl PacketHandler {
             ClientOpcodeMessage::CMSG_GMTICKET_SYSTEMSTATUS => handle_cmsg_gmticket_system_status(client_manager, packet.client_id).await,
             ClientOpcodeMessage::CMSG_NEXT_CINEMATIC_CAMERA => handle_csmg_next_cinematic_camera(client_manager, packet.client_id).await,
             ClientOpcodeMessage::CMSG_COMPLETE_CINEMATIC => handle_csmg_complete_cinematic(client_manager, packet.client_id).await,
-            ClientOpcodeMessage::CMSG_REQUEST_RAID_INFO => handle_cmsg_request_raid_info(client_manager, packet.client_id).await,
+            ClientOpcodeMessage::CMSG_REQUEST_RAID_INFO => handle_cmsg_request_raid_info(client_manager, packet.client_id, world).await,
+            ClientOpcodeMessage::CMSG_GROUP_INVITE(data) => handle_cmsg_group_invite(client_manager, packet.client_id, world, data).await,
+            ClientOpcodeMessage::CMSG_GROUP_ACCEPT(_) => handle_cmsg_group_accept(client_manager, packet.client_id, world).await,
+            ClientOpcodeMessage::CMSG_GROUP_DECLINE => handle_cmsg_group_decline(client_manager, packet.client_id, world).await,
+            ClientOpcodeMessage::CMSG_GROUP_DISBAND => handle_cmsg_group_disband(client_manager, packet.client_id, world).await,
+            ClientOpcodeMessage::CMSG_GROUP_UNINVITE_GUID(data) => {
+                handle_cmsg_group_uninvite_guid(client_manager, packet.client_id, world, data).await
+            }
+            ClientOpcodeMessage::CMSG_LOOT_METHOD(data) => handle_cmsg_loot_method(client_manager, packet.client_id, world, data).await,
+            ClientOpcodeMessage::MSG_SET_DUNGEON_DIFFICULTY(data) => {
+                handle_msg_set_dungeon_difficulty(client_manager, packet.client_id, world, data).await
+            }
+            ClientOpcodeMessage::MSG_SET_RAID_DIFFICULTY(data) => handle_msg_set_raid_difficulty(client_manager, packet.client_id, world, data).await,
             ClientOpcodeMessage::CMSG_CONTACT_LIST(data) => handle_cmsg_contact_list(client_manager, packet.client_id, data).await,
             ClientOpcodeMessage::CMSG_CALENDAR_GET_NUM_PENDING => handle_cmsg_calendar_get_num_pending(client_manager, packet.client_id).await,
             ClientOpcodeMessage::CMSG_SET_ACTIONBAR_TOGGLES(data) => handle_csmg_set_actionbar_toggles(client_manager, packet.client_id, data).await,
             ClientOpcodeMessage::CMSG_ITEM_QUERY_SINGLE(data) => handle_cmsg_item_query_single(client_manager, packet.client_id, world, data).await,
             ClientOpcodeMessage::CMSG_ITEM_NAME_QUERY(data) => handle_cmsg_item_name_query(client_manager, packet.client_id, world, data).await,
             ClientOpcodeMessage::CMSG_SWAP_INV_ITEM(data) => handle_cmsg_swap_inv_item(client_manager, world, packet.client_id, data).await,
+            ClientOpcodeMessage::CMSG_SWAP_ITEM(data) => handle_cmsg_swap_item(client_manager, world, packet.client_id, data).await,
             ClientOpcodeMessage::CMSG_AUTOEQUIP_ITEM(data) => handle_cmsg_autoequip_item(client_manager, world, packet.client_id, data).await,
+            ClientOpcodeMessage::CMSG_SPLIT_ITEM(data) => handle_cmsg_split_item(client_manager, world, packet.client_id, data).await,
+            ClientOpcodeMessage::CMSG_DESTROYITEM(data) => handle_cmsg_destroyitem(client_manager, packet.client_id, data).await,
+            ClientOpcodeMessage::CMSG_AUTOSTORE_BAG_ITEM(data) => handle_cmsg_autostore_bag_item(client_manager, packet.client_id, data).await,
+            ClientOpcodeMessage::CMSG_BANKER_ACTIVATE(data) => handle_cmsg_banker_activate(client_manager, packet.client_id, world, data).await,
+            ClientOpcodeMessage::CMSG_BUY_BANK_SLOT(data) => handle_cmsg_buy_bank_slot(client_manager, packet.client_id, data).await,
+            ClientOpcodeMessage::CMSG_AUTOBANK_ITEM(data) => handle_cmsg_autobank_item(client_manager, packet.client_id, world, data).await,
+            ClientOpcodeMessage::CMSG_AUTOSTORE_BANK_ITEM(data) => {
+                handle_cmsg_autostore_bank_item(client_manager, packet.client_id, world, data).await
+            }
             ClientOpcodeMessage::CMSG_MESSAGECHAT(data) => handle_cmsg_messagechat(client_manager, world, packet.client_id, data).await,
             ClientOpcodeMessage::CMSG_SET_ACTION_BUTTON(data) => handle_cmsg_set_action_button(client_manager, packet.client_id, data).await,
+            ClientOpcodeMessage::CMSG_CAST_SPELL(data) => handle_cmsg_cast_spell(client_manager, packet.client_id, world, data).await,
+            ClientOpcodeMessage::CMSG_CANCEL_CAST(data) => handle_cmsg_cancel_cast(client_manager, packet.client_id, world, data).await,
+            ClientOpcodeMessage::CMSG_ATTACKSWING(data) => handle_cmsg_attackswing(client_manager, packet.client_id, world, data).await,
+            ClientOpcodeMessage::CMSG_ATTACKSTOP => handle_cmsg_attackstop(client_manager, packet.client_id, world).await,
+            ClientOpcodeMessage::CMSG_REPOP_REQUEST(_) => handle_cmsg_repop_request(client_manager, packet.client_id, world).await,
+            ClientOpcodeMessage::CMSG_RECLAIM_CORPSE(_) => handle_cmsg_reclaim_corpse(client_manager, packet.client_id, world).await,
+            ClientOpcodeMessage::CMSG_SPIRIT_HEALER_ACTIVATE(data) => {
+                handle_cmsg_spirit_healer_activate(client_manager, packet.client_id, world, data).await
+            }
+            ClientOpcodeMessage::MSG_CORPSE_QUERY => handle_msg_corpse_query(client_manager, packet.client_id).await,
+            ClientOpcodeMessage::CMSG_LOOT(data) => handle_cmsg_loot(client_manager, packet.client_id, world, data).await,
+            ClientOpcodeMessage::CMSG_OPEN_ITEM(data) => handle_cmsg_open_item(client_manager, packet.client_id, world, data).await,
+            ClientOpcodeMessage::CMSG_AUTOSTORE_LOOT_ITEM(data) => {
+                handle_cmsg_autostore_loot_item(client_manager, packet.client_id, world, data).await
+            }
+            ClientOpcodeMessage::CMSG_LOOT_MONEY => handle_cmsg_loot_money(client_manager, packet.client_id, world).await,
+            ClientOpcodeMessage::CMSG_LOOT_RELEASE(data) => handle_cmsg_loot_release(client_manager, packet.client_id, world, data).await,
+            ClientOpcodeMessage::CMSG_LOOT_ROLL(data) => handle_cmsg_loot_roll(client_manager, packet.client_id, world, data).await,
+            ClientOpcodeMessage::CMSG_LOOT_MASTER_GIVE(data) => handle_cmsg_loot_master_give(client_manager, packet.client_id, world, data).await,
+            ClientOpcodeMessage::CMSG_CANCEL_AURA(data) => handle_cmsg_cancel_aura(client_manager, packet.client_id, data).await,
+            ClientOpcodeMessage::CMSG_QUESTGIVER_STATUS_QUERY(data) => {
+                handle_cmsg_questgiver_status_query(client_manager, packet.client_id, data).await
+            }
+            ClientOpcodeMessage::CMSG_QUESTGIVER_STATUS_MULTIPLE_QUERY => {
+                handle_cmsg_questgiver_status_multiple_query(client_manager, packet.client_id).await
+            }
+            ClientOpcodeMessage::CMSG_QUESTGIVER_HELLO(data) => handle_cmsg_questgiver_hello(client_manager, packet.client_id, data).await,
+            ClientOpcodeMessage::CMSG_QUESTGIVER_QUERY_QUEST(data) => {
+                handle_cmsg_questgiver_query_quest(client_manager, packet.client_id, data).await
+            }
+            ClientOpcodeMessage::CMSG_QUESTGIVER_ACCEPT_QUEST(data) => {
+                handle_cmsg_questgiver_accept_quest(client_manager, packet.client_id, data).await
+            }
+            ClientOpcodeMessage::CMSG_QUESTGIVER_COMPLETE_QUEST(data) => {
+                handle_cmsg_questgiver_complete_quest(client_manager, packet.client_id, data).await
+            }
+            ClientOpcodeMessage::CMSG_QUESTGIVER_REQUEST_REWARD(data) => {
+                handle_cmsg_questgiver_request_reward(client_manager, packet.client_id, data).await
+            }
+            ClientOpcodeMessage::CMSG_QUESTGIVER_CHOOSE_REWARD(data) => {
+                handle_cmsg_questgiver_choose_reward(client_manager, packet.client_id, world, data).await
+            }
+            ClientOpcodeMessage::CMSG_QUESTLOG_REMOVE_QUEST(data) => handle_cmsg_questlog_remove_quest(client_manager, packet.client_id, data).await,
+            ClientOpcodeMessage::CMSG_QUEST_QUERY(data) => handle_cmsg_quest_query(client_manager, packet.client_id, data).await,
+            ClientOpcodeMessage::CMSG_GOSSIP_HELLO(data) => handle_cmsg_gossip_hello(client_manager, packet.client_id, world, data).await,
+            ClientOpcodeMessage::CMSG_GOSSIP_SELECT_OPTION(data) => {
+                handle_cmsg_gossip_select_option(client_manager, packet.client_id, world, data).await
+            }
+            ClientOpcodeMessage::CMSG_NPC_TEXT_QUERY(data) => handle_cmsg_npc_text_query(client_manager, packet.client_id, data).await,
+            ClientOpcodeMessage::CMSG_LIST_INVENTORY(data) => handle_cmsg_list_inventory(client_manager, packet.client_id, world, data).await,
+            ClientOpcodeMessage::CMSG_BUY_ITEM(data) => handle_cmsg_buy_item(client_manager, packet.client_id, world, data).await,
+            ClientOpcodeMessage::CMSG_SELL_ITEM(data) => handle_cmsg_sell_item(client_manager, packet.client_id, world, data).await,
+            ClientOpcodeMessage::CMSG_BUYBACK_ITEM(data) => handle_cmsg_buyback_item(client_manager, packet.client_id, world, data).await,
+            ClientOpcodeMessage::CMSG_REPAIR_ITEM(data) => handle_cmsg_repair_item(client_manager, packet.client_id, world, data).await,
+            ClientOpcodeMessage::CMSG_TRAINER_LIST(data) => handle_cmsg_trainer_list(client_manager, packet.client_id, world, data).await,
+            ClientOpcodeMessage::CMSG_TRAINER_BUY_SPELL(data) => handle_cmsg_trainer_buy_spell(client_manager, packet.client_id, world, data).await,
+            ClientOpcodeMessage::CMSG_LEARN_TALENT(data) => handle_cmsg_learn_talent(client_manager, packet.client_id, data).await,
+            ClientOpcodeMessage::CMSG_LEARN_PREVIEW_TALENTS(data) => handle_cmsg_learn_preview_talents(client_manager, packet.client_id, data).await,
+            ClientOpcodeMessage::MSG_TALENT_WIPE_CONFIRM(data) => handle_msg_talent_wipe_confirm(client_manager, packet.client_id, world, data).await,
+            ClientOpcodeMessage::CMSG_INSPECT(data) => handle_cmsg_inspect(client_manager, packet.client_id, world, data).await,
             _ => bail!("Unhandled opcode"),
         }
     }
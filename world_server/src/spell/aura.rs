@@ -0,0 +1,322 @@
+use std::collections::BTreeMap;
+
+use wow_world_messages::wrath::Power;
+
+use crate::data::{SpellEffectInfo, SpellInfo};
+use crate::prelude::*;
+use crate::world::prelude::spell::*;
+use crate::world::prelude::Unit;
+
+//The client has this many visible aura slots per unit
+pub const MAX_AURA_SLOTS: u8 = 255;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuraEffect {
+    pub effect_index: u8,
+    pub aura_type: u32,
+    pub amount: i32,
+    pub misc_value: i32,
+    pub period: f32, //in seconds, 0 for effects that don't tick
+    period_timer: f32,
+}
+
+impl AuraEffect {
+    pub fn new(effect: &SpellEffectInfo, amount: i32) -> Self {
+        let period = effect.aura_period as f32 / 1000.0;
+        Self {
+            effect_index: effect.index,
+            aura_type: effect.aura,
+            amount,
+            misc_value: effect.misc_value,
+            period,
+            period_timer: period,
+        }
+    }
+
+    pub fn is_negative(&self) -> bool {
+        match self.aura_type {
+            SPELL_AURA_PERIODIC_DAMAGE | SPELL_AURA_MOD_STUN | SPELL_AURA_MOD_ROOT | SPELL_AURA_MOD_DECREASE_SPEED => true,
+            _ => self.amount < 0,
+        }
+    }
+}
+
+//All aura effects of a single cast of a spell on a single unit
+#[derive(Debug, Clone)]
+pub struct Aura {
+    pub spell_id: u32,
+    pub caster: Guid,
+    pub caster_level: u8,
+    pub effects: Vec<AuraEffect>,
+    pub max_duration: Option<f32>, //None means it lasts until cancelled
+    pub remaining_duration: Option<f32>,
+    pub stacks: u8,
+    pub max_stacks: u8,
+    pub charges: u8,
+    pub cancellable: bool,
+}
+
+impl Aura {
+    pub fn new(spell: &SpellInfo, caster: Guid, caster_level: u8, effects: Vec<AuraEffect>) -> Self {
+        Self {
+            spell_id: spell.id,
+            caster,
+            caster_level,
+            effects,
+            max_duration: spell.duration,
+            remaining_duration: spell.duration,
+            stacks: 1,
+            max_stacks: spell.max_stacks.clamp(1, u8::MAX as u32) as u8,
+            charges: spell.proc_charges.min(u8::MAX as u32) as u8,
+            cancellable: spell.attributes & SPELL_ATTRIBUTE_CANT_CANCEL == 0,
+        }
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.effects.iter().any(AuraEffect::is_negative)
+    }
+
+    //Players can only get rid of their own buffs
+    pub fn can_be_cancelled(&self) -> bool {
+        self.cancellable && !self.is_negative()
+    }
+}
+
+//A periodic effect that went off and still needs to be applied to the unit holding the aura
+#[derive(Debug, Clone, PartialEq)]
+pub struct PeriodicAuraTick {
+    pub spell_id: u32,
+    pub aura_type: u32,
+    pub amount: i32,
+    pub misc_value: i32,
+}
+
+//The auras on a unit, by the visible slot they take up on the client
+#[derive(Default)]
+pub struct AuraContainer {
+    auras: BTreeMap<u8, Aura>,
+    changed_slots: Vec<u8>,
+}
+
+impl AuraContainer {
+    pub fn get_aura(&self, slot: u8) -> Option<&Aura> {
+        self.auras.get(&slot)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.auras.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (u8, &Aura)> {
+        self.auras.iter().map(|(slot, aura)| (*slot, aura))
+    }
+
+    fn mark_changed(&mut self, slot: u8) {
+        if !self.changed_slots.contains(&slot) {
+            self.changed_slots.push(slot);
+        }
+    }
+
+    //Slots that were added, refreshed or removed since the last time this was called
+    pub fn take_changed_slots(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.changed_slots)
+    }
+
+    //Applying the same spell from the same caster again refreshes the aura and adds a stack
+    pub fn apply(&mut self, aura: Aura) -> Result<u8> {
+        let existing_slot = self
+            .auras
+            .iter()
+            .find(|(_, existing)| existing.spell_id == aura.spell_id && existing.caster == aura.caster)
+            .map(|(slot, _)| *slot);
+
+        if let Some(slot) = existing_slot {
+            let existing = self.auras.get_mut(&slot).unwrap();
+            existing.stacks = existing.stacks.saturating_add(1).min(existing.max_stacks);
+            existing.remaining_duration = aura.max_duration;
+            existing.charges = aura.charges;
+            for effect in aura.effects {
+                match existing.effects.iter_mut().find(|e| e.effect_index == effect.effect_index) {
+                    Some(existing_effect) => existing_effect.amount = effect.amount,
+                    None => existing.effects.push(effect),
+                }
+            }
+            self.mark_changed(slot);
+            return Ok(slot);
+        }
+
+        let slot = (0..MAX_AURA_SLOTS)
+            .find(|slot| !self.auras.contains_key(slot))
+            .ok_or_else(|| anyhow!("No free aura slot for spell {}", aura.spell_id))?;
+        self.auras.insert(slot, aura);
+        self.mark_changed(slot);
+        Ok(slot)
+    }
+
+    pub fn remove(&mut self, slot: u8) -> Option<Aura> {
+        let removed = self.auras.remove(&slot);
+        if removed.is_some() {
+            self.mark_changed(slot);
+        }
+        removed
+    }
+
+    pub fn clear(&mut self) {
+        let slots: Vec<u8> = self.auras.keys().copied().collect();
+        for slot in slots {
+            self.remove(slot);
+        }
+    }
+
+    fn remove_matching(&mut self, predicate: impl Fn(&Aura) -> bool) -> bool {
+        let slots: Vec<u8> = self.iter().filter(|(_, aura)| predicate(aura)).map(|(slot, _)| slot).collect();
+        for slot in slots.iter() {
+            self.remove(*slot);
+        }
+        !slots.is_empty()
+    }
+
+    //Returns whether anything was cancelled
+    pub fn cancel(&mut self, spell_id: u32) -> bool {
+        self.remove_matching(|aura| aura.spell_id == spell_id && aura.can_be_cancelled())
+    }
+
+    //Unlike cancelling, this also takes away debuffs and auras that can't be cancelled
+    pub fn remove_spell(&mut self, spell_id: u32) -> bool {
+        self.remove_matching(|aura| aura.spell_id == spell_id)
+    }
+
+    pub fn has_spell(&self, spell_id: u32) -> bool {
+        self.auras.values().any(|aura| aura.spell_id == spell_id)
+    }
+
+    pub fn has_aura_type(&self, aura_type: u32) -> bool {
+        self.auras
+            .values()
+            .flat_map(|aura| aura.effects.iter())
+            .any(|effect| effect.aura_type == aura_type)
+    }
+
+    //Sum of the amounts of all effects of this type, with every stack counted
+    pub fn get_total_modifier(&self, aura_type: u32, filter: impl Fn(&AuraEffect) -> bool) -> i32 {
+        self.auras
+            .values()
+            .flat_map(|aura| aura.effects.iter().map(move |effect| (aura.stacks, effect)))
+            .filter(|(_, effect)| effect.aura_type == aura_type && filter(effect))
+            .map(|(stacks, effect)| effect.amount * stacks as i32)
+            .sum()
+    }
+
+    //Counts down durations and periodic timers, expired auras are removed
+    pub fn tick(&mut self, delta_time: f32) -> Vec<PeriodicAuraTick> {
+        let mut ticks = vec![];
+        let mut expired_slots = vec![];
+        for (slot, aura) in self.auras.iter_mut() {
+            for effect in aura.effects.iter_mut().filter(|effect| effect.period > 0.0) {
+                effect.period_timer -= delta_time;
+                while effect.period_timer <= 0.0 {
+                    effect.period_timer += effect.period;
+                    ticks.push(PeriodicAuraTick {
+                        spell_id: aura.spell_id,
+                        aura_type: effect.aura_type,
+                        amount: effect.amount * aura.stacks as i32,
+                        misc_value: effect.misc_value,
+                    });
+                }
+            }
+
+            if let Some(remaining) = aura.remaining_duration.as_mut() {
+                *remaining -= delta_time;
+                if *remaining <= 0.0 {
+                    expired_slots.push(*slot);
+                }
+            }
+        }
+
+        for slot in expired_slots {
+            self.remove(slot);
+        }
+        ticks
+    }
+}
+
+//Periodic effects only ever change the unit holding the aura
+pub fn apply_periodic_aura_tick(unit: &mut dyn Unit, tick: &PeriodicAuraTick) {
+    let amount = tick.amount.max(0) as u32;
+    match tick.aura_type {
+        SPELL_AURA_PERIODIC_DAMAGE => unit.set_health(unit.get_health().saturating_sub(amount)),
+        SPELL_AURA_PERIODIC_HEAL if unit.is_alive() => unit.set_health(unit.get_health().saturating_add(amount)),
+        SPELL_AURA_PERIODIC_ENERGIZE => {
+            if let Ok(power) = Power::try_from(tick.misc_value as u8) {
+                unit.set_power(power, unit.get_power(power) + tick.amount);
+            }
+        }
+        _ => trace!("Periodic aura type {} of spell {} is not implemented", tick.aura_type, tick.spell_id),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn aura(spell_id: u32, caster: u64, effects: Vec<AuraEffect>, duration: Option<f32>) -> Aura {
+        let spell = SpellInfo {
+            id: spell_id,
+            duration,
+            max_stacks: 3,
+            ..Default::default()
+        };
+        Aura::new(&spell, Guid::new(caster), 1, effects)
+    }
+
+    fn effect(aura_type: u32, amount: i32, period_ms: u32) -> AuraEffect {
+        let effect = SpellEffectInfo {
+            aura: aura_type,
+            aura_period: period_ms,
+            ..Default::default()
+        };
+        AuraEffect::new(&effect, amount)
+    }
+
+    #[test]
+    fn reapplying_adds_stacks_up_to_the_max() {
+        let mut auras = AuraContainer::default();
+        for _ in 0..5 {
+            auras.apply(aura(1, 1, vec![effect(SPELL_AURA_MOD_STAT, 10, 0)], Some(10.0))).unwrap();
+        }
+        //Another caster gets its own aura
+        auras.apply(aura(1, 2, vec![effect(SPELL_AURA_MOD_STAT, 10, 0)], Some(10.0))).unwrap();
+
+        assert_eq!(auras.get_aura(0).unwrap().stacks, 3);
+        assert_eq!(auras.get_aura(1).unwrap().stacks, 1);
+        assert_eq!(auras.get_total_modifier(SPELL_AURA_MOD_STAT, |_| true), 40);
+        assert_eq!(auras.take_changed_slots(), vec![0, 1]);
+        assert!(auras.take_changed_slots().is_empty());
+    }
+
+    #[test]
+    fn periodic_effects_tick_until_the_aura_expires() {
+        let mut auras = AuraContainer::default();
+        auras
+            .apply(aura(1, 1, vec![effect(SPELL_AURA_PERIODIC_DAMAGE, 5, 1000)], Some(3.0)))
+            .unwrap();
+        auras.take_changed_slots();
+
+        let ticks: usize = (0..4).map(|_| auras.tick(1.0).len()).sum();
+        assert_eq!(ticks, 3);
+        assert!(auras.get_aura(0).is_none());
+        assert_eq!(auras.take_changed_slots(), vec![0]);
+    }
+
+    #[test]
+    fn only_positive_auras_can_be_cancelled() {
+        let mut auras = AuraContainer::default();
+        auras.apply(aura(1, 1, vec![effect(SPELL_AURA_MOD_ROOT, 0, 0)], None)).unwrap();
+        auras.apply(aura(2, 1, vec![effect(SPELL_AURA_MOD_INCREASE_SPEED, 30, 0)], None)).unwrap();
+
+        assert!(!auras.cancel(1));
+        assert!(auras.cancel(2));
+        assert!(auras.has_aura_type(SPELL_AURA_MOD_ROOT));
+        assert!(!auras.has_aura_type(SPELL_AURA_MOD_INCREASE_SPEED));
+    }
+}
@@ -0,0 +1,355 @@
+use rand::Rng;
+use wow_world_messages::wrath::Power;
+
+use super::AuraEffect;
+use crate::data::{PositionAndOrientation, SpellEffectInfo, SpellInfo, WorldZoneLocation};
+use crate::prelude::*;
+use crate::world::prelude::spell::*;
+
+//Everything an effect handler gets to know about the cast it's part of
+pub struct SpellEffectContext<'a> {
+    pub spell: &'a SpellInfo,
+    pub effect: &'a SpellEffectInfo,
+    pub caster_level: u32,
+    pub caster_position: PositionAndOrientation,
+    pub caster_home: Option<WorldZoneLocation>,
+    pub target: Guid,
+}
+
+//Effect handlers only decide what should happen, the caster applies the outcome to the world.
+//This keeps the handlers free of locks and packets.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SpellEffectOutcome {
+    Damage { target: Guid, amount: u32 },
+    Heal { target: Guid, amount: u32 },
+    Teleport { target: Guid, destination: WorldZoneLocation },
+    ApplyAura { target: Guid, effect: AuraEffect },
+    Energize { target: Guid, power: Power, amount: i32 },
+    Summon { entry: u32, position: PositionAndOrientation },
+    CreateItem { item: u32, count: u32 },
+    LearnSpell { spell: u32 },
+    ActivateTalentSpec { spec: u8 },
+}
+
+pub type SpellEffectHandler = fn(&SpellEffectContext) -> Result<SpellEffectOutcome>;
+
+//New effects are added by writing a handler below and registering its effect id here
+pub fn get_effect_handler(effect: u32) -> Option<SpellEffectHandler> {
+    match effect {
+        SPELL_EFFECT_SCHOOL_DAMAGE => Some(effect_school_damage),
+        SPELL_EFFECT_TELEPORT_UNITS => Some(effect_teleport_units),
+        SPELL_EFFECT_APPLY_AURA => Some(effect_apply_aura),
+        SPELL_EFFECT_HEAL => Some(effect_heal),
+        SPELL_EFFECT_CREATE_ITEM => Some(effect_create_item),
+        SPELL_EFFECT_SUMMON => Some(effect_summon),
+        SPELL_EFFECT_ENERGIZE => Some(effect_energize),
+        SPELL_EFFECT_LEARN_SPELL => Some(effect_learn_spell),
+        SPELL_EFFECT_ACTIVATE_TALENT_SPEC => Some(effect_activate_talent_spec),
+        _ => None,
+    }
+}
+
+//The DBC stores one less than the lowest value, the die roll is added on top of that
+pub fn calculate_effect_amount(context: &SpellEffectContext) -> i32 {
+    let effect = context.effect;
+    let roll = if effect.die_sides > 0 {
+        rand::thread_rng().gen_range(1..=effect.die_sides)
+    } else {
+        0
+    };
+
+    let mut level = context.caster_level;
+    if context.spell.max_level > 0 {
+        level = level.min(context.spell.max_level);
+    }
+    let level_bonus = effect.real_points_per_level * level.saturating_sub(context.spell.base_level) as f32;
+    effect.base_points + roll + level_bonus as i32
+}
+
+fn effect_school_damage(context: &SpellEffectContext) -> Result<SpellEffectOutcome> {
+    Ok(SpellEffectOutcome::Damage {
+        target: context.target,
+        amount: calculate_effect_amount(context).max(0) as u32,
+    })
+}
+
+fn effect_heal(context: &SpellEffectContext) -> Result<SpellEffectOutcome> {
+    Ok(SpellEffectOutcome::Heal {
+        target: context.target,
+        amount: calculate_effect_amount(context).max(0) as u32,
+    })
+}
+
+fn effect_teleport_units(context: &SpellEffectContext) -> Result<SpellEffectOutcome> {
+    let effect = context.effect;
+    if effect.implicit_target_a != SPELL_TARGET_DEST_HOME && effect.implicit_target_b != SPELL_TARGET_DEST_HOME {
+        bail!("Spell {} teleports to a destination that isn't supported", context.spell.id);
+    }
+
+    let destination = context
+        .caster_home
+        .clone()
+        .ok_or_else(|| anyhow!("Spell {} teleports home, but the caster has no bind location", context.spell.id))?;
+    Ok(SpellEffectOutcome::Teleport {
+        target: context.target,
+        destination,
+    })
+}
+
+fn effect_apply_aura(context: &SpellEffectContext) -> Result<SpellEffectOutcome> {
+    Ok(SpellEffectOutcome::ApplyAura {
+        target: context.target,
+        effect: AuraEffect::new(context.effect, calculate_effect_amount(context)),
+    })
+}
+
+fn effect_energize(context: &SpellEffectContext) -> Result<SpellEffectOutcome> {
+    let power = Power::try_from(context.effect.misc_value as u8)
+        .map_err(|_| anyhow!("Spell {} energizes invalid power type {}", context.spell.id, context.effect.misc_value))?;
+    Ok(SpellEffectOutcome::Energize {
+        target: context.target,
+        power,
+        amount: calculate_effect_amount(context),
+    })
+}
+
+fn effect_summon(context: &SpellEffectContext) -> Result<SpellEffectOutcome> {
+    if context.effect.misc_value <= 0 {
+        bail!("Spell {} summons invalid creature entry {}", context.spell.id, context.effect.misc_value);
+    }
+
+    Ok(SpellEffectOutcome::Summon {
+        entry: context.effect.misc_value as u32,
+        position: context.caster_position.clone(),
+    })
+}
+
+fn effect_create_item(context: &SpellEffectContext) -> Result<SpellEffectOutcome> {
+    if context.effect.item_type == 0 {
+        bail!("Spell {} creates an item without an item id", context.spell.id);
+    }
+
+    Ok(SpellEffectOutcome::CreateItem {
+        item: context.effect.item_type,
+        count: calculate_effect_amount(context).max(1) as u32,
+    })
+}
+
+fn effect_learn_spell(context: &SpellEffectContext) -> Result<SpellEffectOutcome> {
+    if context.effect.trigger_spell == 0 {
+        bail!("Spell {} teaches a spell without a spell id", context.spell.id);
+    }
+
+    Ok(SpellEffectOutcome::LearnSpell {
+        spell: context.effect.trigger_spell,
+    })
+}
+
+//The amount is the spec to switch to, counting from 1
+fn effect_activate_talent_spec(context: &SpellEffectContext) -> Result<SpellEffectOutcome> {
+    let amount = calculate_effect_amount(context);
+    if amount < 1 {
+        bail!("Spell {} activates invalid talent spec {}", context.spell.id, amount);
+    }
+
+    Ok(SpellEffectOutcome::ActivateTalentSpec { spec: (amount - 1) as u8 })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CASTER: u64 = 1;
+    const TARGET: u64 = 2;
+
+    //die_sides of 1 always rolls 1, which keeps the amounts predictable
+    fn effect(effect: u32, base_points: i32) -> SpellEffectInfo {
+        SpellEffectInfo {
+            effect,
+            base_points,
+            die_sides: 1,
+            ..Default::default()
+        }
+    }
+
+    fn spell(effect: SpellEffectInfo) -> SpellInfo {
+        SpellInfo {
+            id: 100,
+            effects: vec![effect],
+            ..Default::default()
+        }
+    }
+
+    fn run(spell: &SpellInfo) -> Result<SpellEffectOutcome> {
+        let effect = &spell.effects[0];
+        let context = SpellEffectContext {
+            spell,
+            effect,
+            caster_level: 10,
+            caster_position: PositionAndOrientation::default(),
+            caster_home: None,
+            target: Guid::new(TARGET),
+        };
+        let handler = get_effect_handler(effect.effect).expect("effect should have a handler");
+        handler(&context)
+    }
+
+    #[test]
+    fn unknown_effects_have_no_handler() {
+        assert!(get_effect_handler(0).is_none());
+        assert!(get_effect_handler(u32::MAX).is_none());
+    }
+
+    #[test]
+    fn amount_scales_with_level() {
+        let mut scaling_effect = effect(SPELL_EFFECT_SCHOOL_DAMAGE, 9);
+        scaling_effect.real_points_per_level = 2.0;
+        let mut spell = spell(scaling_effect);
+        spell.base_level = 5;
+        spell.max_level = 8;
+
+        //Levels 5 to 8 count, the caster being level 10 doesn't add anything beyond that
+        let outcome = run(&spell).unwrap();
+        assert_eq!(
+            outcome,
+            SpellEffectOutcome::Damage {
+                target: Guid::new(TARGET),
+                amount: 16
+            }
+        );
+    }
+
+    #[test]
+    fn school_damage() {
+        let outcome = run(&spell(effect(SPELL_EFFECT_SCHOOL_DAMAGE, 19))).unwrap();
+        assert_eq!(
+            outcome,
+            SpellEffectOutcome::Damage {
+                target: Guid::new(TARGET),
+                amount: 20
+            }
+        );
+    }
+
+    #[test]
+    fn heal() {
+        let outcome = run(&spell(effect(SPELL_EFFECT_HEAL, 49))).unwrap();
+        assert_eq!(
+            outcome,
+            SpellEffectOutcome::Heal {
+                target: Guid::new(TARGET),
+                amount: 50
+            }
+        );
+    }
+
+    #[test]
+    fn teleport_units_goes_home() {
+        let mut teleport = effect(SPELL_EFFECT_TELEPORT_UNITS, 0);
+        teleport.implicit_target_b = SPELL_TARGET_DEST_HOME;
+        let spell = spell(teleport);
+        let home = WorldZoneLocation {
+            orientation: 1.5,
+            ..Default::default()
+        };
+        let context = SpellEffectContext {
+            spell: &spell,
+            effect: &spell.effects[0],
+            caster_level: 1,
+            caster_position: PositionAndOrientation::default(),
+            caster_home: Some(home.clone()),
+            target: Guid::new(CASTER),
+        };
+
+        let outcome = effect_teleport_units(&context).unwrap();
+        assert_eq!(
+            outcome,
+            SpellEffectOutcome::Teleport {
+                target: Guid::new(CASTER),
+                destination: home
+            }
+        );
+    }
+
+    #[test]
+    fn teleport_units_needs_a_destination() {
+        let mut teleport = effect(SPELL_EFFECT_TELEPORT_UNITS, 0);
+        teleport.implicit_target_b = SPELL_TARGET_DEST_HOME;
+        assert!(run(&spell(teleport)).is_err());
+        assert!(run(&spell(effect(SPELL_EFFECT_TELEPORT_UNITS, 0))).is_err());
+    }
+
+    #[test]
+    fn apply_aura() {
+        let mut aura = effect(SPELL_EFFECT_APPLY_AURA, 4);
+        aura.aura = SPELL_AURA_PERIODIC_HEAL;
+        aura.aura_period = 2000;
+
+        let SpellEffectOutcome::ApplyAura { target, effect } = run(&spell(aura)).unwrap() else {
+            panic!("apply aura should result in an aura");
+        };
+        assert_eq!(target, Guid::new(TARGET));
+        assert_eq!(effect.aura_type, SPELL_AURA_PERIODIC_HEAL);
+        assert_eq!(effect.amount, 5);
+        assert_eq!(effect.period, 2.0);
+    }
+
+    #[test]
+    fn energize() {
+        let mut energize = effect(SPELL_EFFECT_ENERGIZE, 99);
+        energize.misc_value = Power::Rage.as_int() as i32;
+
+        let outcome = run(&spell(energize)).unwrap();
+        assert_eq!(
+            outcome,
+            SpellEffectOutcome::Energize {
+                target: Guid::new(TARGET),
+                power: Power::Rage,
+                amount: 100
+            }
+        );
+    }
+
+    #[test]
+    fn summon() {
+        let mut summon = effect(SPELL_EFFECT_SUMMON, 0);
+        summon.misc_value = 416;
+
+        let outcome = run(&spell(summon)).unwrap();
+        assert_eq!(
+            outcome,
+            SpellEffectOutcome::Summon {
+                entry: 416,
+                position: PositionAndOrientation::default()
+            }
+        );
+        assert!(run(&spell(effect(SPELL_EFFECT_SUMMON, 0))).is_err());
+    }
+
+    #[test]
+    fn create_item() {
+        let mut create_item = effect(SPELL_EFFECT_CREATE_ITEM, 1);
+        create_item.item_type = 5512;
+
+        let outcome = run(&spell(create_item)).unwrap();
+        assert_eq!(outcome, SpellEffectOutcome::CreateItem { item: 5512, count: 2 });
+        assert!(run(&spell(effect(SPELL_EFFECT_CREATE_ITEM, 1))).is_err());
+    }
+
+    #[test]
+    fn learn_spell() {
+        let mut learn = effect(SPELL_EFFECT_LEARN_SPELL, 0);
+        learn.trigger_spell = 133;
+
+        let outcome = run(&spell(learn)).unwrap();
+        assert_eq!(outcome, SpellEffectOutcome::LearnSpell { spell: 133 });
+        assert!(run(&spell(effect(SPELL_EFFECT_LEARN_SPELL, 0))).is_err());
+    }
+
+    #[test]
+    fn activate_talent_spec() {
+        let outcome = run(&spell(effect(SPELL_EFFECT_ACTIVATE_TALENT_SPEC, 1))).unwrap();
+        assert_eq!(outcome, SpellEffectOutcome::ActivateTalentSpec { spec: 1 });
+        assert!(run(&spell(effect(SPELL_EFFECT_ACTIVATE_TALENT_SPEC, -1))).is_err());
+    }
+}
@@ -0,0 +1,4 @@
+mod aura;
+mod effects;
+pub use aura::*;
+pub use effects::*;
@@ -1,7 +1,7 @@
 use std::sync::Weak;
 
 use smol::lock::RwLock;
-use wow_world_messages::wrath::{MovementInfo, ObjectType, UpdateMask};
+use wow_world_messages::wrath::{MovementInfo, ObjectType, Power, UpdateMask};
 use wow_world_messages::Guid;
 
 use super::map_manager::MapManager;
@@ -9,6 +9,7 @@ use super::prelude::ReceiveUpdates;
 use crate::character::Character;
 use crate::data::PositionAndOrientation;
 use crate::prelude::*;
+use crate::spell::AuraContainer;
 
 #[async_trait::async_trait]
 pub trait GameObject: Send + Sync {
@@ -26,6 +27,8 @@ pub trait GameObject: Send + Sync {
     fn clear_recently_removed_range_guids(&mut self);
 
     fn as_character(&self) -> Option<&Character>;
+    fn as_unit(&self) -> Option<&dyn Unit>;
+    fn as_unit_mut(&mut self) -> Option<&mut dyn Unit>;
     fn as_update_receiver(&self) -> Option<&dyn ReceiveUpdates>;
     fn as_update_receiver_mut(&mut self) -> Option<&mut dyn ReceiveUpdates>;
 
@@ -33,3 +36,23 @@ pub trait GameObject: Send + Sync {
     fn get_type(&self) -> ObjectType;
     async fn on_pushed_to_map(&mut self, map_manager: &MapManager) -> Result<()>;
 }
+
+//Things that players and creatures have in common
+pub trait Unit {
+    fn get_level(&self) -> u32;
+    fn get_armor(&self) -> u32;
+    fn get_faction_template(&self) -> u32;
+    fn get_health(&self) -> u32;
+    fn get_max_health(&self) -> u32;
+    //Clamped between 0 and the max health
+    fn set_health(&mut self, health: u32);
+    fn get_power(&self, power: Power) -> i32;
+    //Clamped between 0 and the max power
+    fn set_power(&mut self, power: Power, value: i32);
+    fn get_auras(&self) -> &AuraContainer;
+    fn get_auras_mut(&mut self) -> &mut AuraContainer;
+
+    fn is_alive(&self) -> bool {
+        self.get_health() > 0
+    }
+}
@@ -0,0 +1,122 @@
+use crate::prelude::*;
+use std::sync::atomic::{AtomicU32, Ordering};
+use wrath_realm_db::RealmDatabase;
+
+//The upper 16 bits of a guid tell the client what kind of object it belongs to
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HighGuid {
+    Player = 0x0000,
+    Item = 0x4000,
+    MapObjectTransport = 0x1FC0,
+    Group = 0x1F50,
+    DynamicObject = 0xF100,
+    Corpse = 0xF101,
+    GameObject = 0xF110,
+    Transport = 0xF120,
+    Unit = 0xF130,
+    Pet = 0xF140,
+    Vehicle = 0xF150,
+}
+
+//World objects (units, gameobjects, etc) carry their entry in the guid and only have 24 bits of counter left
+const LOW_GUID_WITH_ENTRY_MASK: u32 = 0x00FF_FFFF;
+
+impl HighGuid {
+    fn has_entry(self) -> bool {
+        matches!(
+            self,
+            HighGuid::GameObject | HighGuid::Transport | HighGuid::Unit | HighGuid::Pet | HighGuid::Vehicle
+        )
+    }
+
+    pub fn from_guid(guid: Guid) -> Option<Self> {
+        let high = (guid.guid() >> 48) as u16;
+        [
+            HighGuid::Player,
+            HighGuid::Item,
+            HighGuid::MapObjectTransport,
+            HighGuid::Group,
+            HighGuid::DynamicObject,
+            HighGuid::Corpse,
+            HighGuid::GameObject,
+            HighGuid::Transport,
+            HighGuid::Unit,
+            HighGuid::Pet,
+            HighGuid::Vehicle,
+        ]
+        .into_iter()
+        .find(|&high_guid| high_guid as u16 == high)
+    }
+
+    pub fn new_guid(self, entry: u32, low_guid: u32) -> Guid {
+        let high = (self as u64) << 48;
+        if self.has_entry() {
+            Guid::new(high | ((entry as u64) << 24) | (low_guid & LOW_GUID_WITH_ENTRY_MASK) as u64)
+        } else {
+            Guid::new(high | low_guid as u64)
+        }
+    }
+}
+
+//Players are the only objects without a counter here, their guid is the characters row id
+pub fn player_guid(character_id: u32) -> Guid {
+    HighGuid::Player.new_guid(0, character_id)
+}
+
+pub fn item_guid(low_guid: u32) -> Guid {
+    HighGuid::Item.new_guid(0, low_guid)
+}
+
+//The part of the guid that is stored in the database
+pub fn get_low_guid(guid: Guid) -> u32 {
+    match HighGuid::from_guid(guid) {
+        Some(high_guid) if high_guid.has_entry() => guid.guid() as u32 & LOW_GUID_WITH_ENTRY_MASK,
+        _ => guid.guid() as u32,
+    }
+}
+
+//The creature or gameobject entry of world objects, 0 for everything else
+pub fn get_entry(guid: Guid) -> u32 {
+    match HighGuid::from_guid(guid) {
+        Some(high_guid) if high_guid.has_entry() => (guid.guid() >> 24) as u32 & LOW_GUID_WITH_ENTRY_MASK,
+        _ => 0,
+    }
+}
+
+pub struct GuidService {
+    next_item_guid: AtomicU32,
+    next_unit_guid: AtomicU32,
+    next_gameobject_guid: AtomicU32,
+    next_corpse_guid: AtomicU32,
+}
+
+impl GuidService {
+    pub async fn new(realm_db: &RealmDatabase) -> Result<Self> {
+        Ok(Self {
+            next_item_guid: AtomicU32::new(realm_db.get_highest_item_guid().await? + 1),
+            next_unit_guid: AtomicU32::new(realm_db.get_highest_creature_guid().await? + 1),
+            next_gameobject_guid: AtomicU32::new(1),
+            next_corpse_guid: AtomicU32::new(1),
+        })
+    }
+
+    //Item guids are unique across the whole realm, since they are persisted
+    pub fn generate_item_guid(&self) -> Guid {
+        item_guid(self.next_item_guid.fetch_add(1, Ordering::Relaxed))
+    }
+
+    //Every spawned unit gets its own guid, so the same spawn can exist in multiple instances
+    pub fn generate_unit_guid(&self, entry: u32) -> Guid {
+        HighGuid::Unit.new_guid(entry, self.next_unit_guid.fetch_add(1, Ordering::Relaxed))
+    }
+
+    #[allow(dead_code)]
+    pub fn generate_gameobject_guid(&self, entry: u32) -> Guid {
+        HighGuid::GameObject.new_guid(entry, self.next_gameobject_guid.fetch_add(1, Ordering::Relaxed))
+    }
+
+    pub fn generate_corpse_guid(&self) -> Guid {
+        HighGuid::Corpse.new_guid(0, self.next_corpse_guid.fetch_add(1, Ordering::Relaxed))
+    }
+}
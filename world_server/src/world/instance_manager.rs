@@ -1,57 +1,118 @@
 use crate::character::Character;
 use crate::client::Client;
+use crate::data::DataStorage;
+use crate::group::GroupManager;
 use crate::prelude::*;
-use smol::lock::{RwLock, RwLockUpgradableReadGuard};
+use smol::lock::{Mutex, RwLock, RwLockUpgradableReadGuard};
 use std::collections::HashMap;
+use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::Arc;
-use wow_world_messages::wrath::Map;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use wow_dbc::Indexable;
+use wow_world_messages::wrath::{DungeonDifficulty, Map};
+use wrath_realm_db::instance::DBInstance;
+use wrath_realm_db::RealmDatabase;
 
-use super::map_manager::MapManager;
+use super::guid_service::GuidService;
+use super::map_manager::{InstanceProperties, MapManager};
 use super::prelude::GameObject;
 
+//How long an instance without players stays alive, so players can get back in after a disconnect
+const EMPTY_INSTANCE_GRACE_PERIOD: Duration = Duration::from_secs(5 * 60);
+
+//Values of the instance_type column in Map.dbc
+const MAP_INSTANCE_TYPE_NONE: i32 = 0;
+const MAP_INSTANCE_TYPE_RAID: i32 = 2;
+
+//Saves reset daily (heroic dungeons) or weekly (raids) at this hour, UTC
+const INSTANCE_RESET_HOUR: u64 = 4;
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+//How often the database is checked for instances that have reset
+const INSTANCE_RESET_CHECK_INTERVAL: f32 = 60.0;
+
 pub type InstanceID = u32;
 pub type MapID = u32;
 
-#[derive(Default)]
 pub struct InstanceManager {
     //Multiple instances are things like raids and dungeons which can spawn many times for
     //different groups
     multiple_instances: RwLock<HashMap<InstanceID, Arc<MapManager>>>,
     world_maps: RwLock<HashMap<MapID, Arc<MapManager>>>,
+    next_instance_id: AtomicU32,
+    reset_check_cooldown: Mutex<f32>,
+    realm_db: Arc<RealmDatabase>,
+    data_storage: Arc<DataStorage>,
+    guid_service: Arc<GuidService>,
+    group_manager: Arc<GroupManager>,
 }
 
 impl InstanceManager {
-    pub fn new() -> Self {
-        Self {
+    pub async fn new(
+        realm_db: Arc<RealmDatabase>,
+        data_storage: Arc<DataStorage>,
+        guid_service: Arc<GuidService>,
+        group_manager: Arc<GroupManager>,
+    ) -> Result<Self> {
+        //Instance id 0 means "not in an instance", so start counting after the highest one in use
+        let highest_instance_id = realm_db
+            .get_highest_character_instance_id()
+            .await?
+            .max(realm_db.get_highest_instance_id().await?);
+
+        Ok(Self {
             multiple_instances: RwLock::new(HashMap::default()),
             world_maps: RwLock::new(HashMap::default()),
-        }
+            next_instance_id: AtomicU32::new(highest_instance_id + 1),
+            reset_check_cooldown: Mutex::new(0.0),
+            realm_db,
+            data_storage,
+            guid_service,
+            group_manager,
+        })
     }
 
     pub async fn tick(&self, delta_time: f32) -> Result<()> {
         self.tick_maps::<MapID>(&self.world_maps, delta_time).await?;
         self.tick_maps::<InstanceID>(&self.multiple_instances, delta_time).await?;
-        self.cleanup_maps::<MapID>(&self.world_maps).await?;
-        self.cleanup_maps::<InstanceID>(&self.multiple_instances).await?;
+        self.cleanup_maps::<MapID>(&self.world_maps, Duration::ZERO).await?;
+        self.cleanup_maps::<InstanceID>(&self.multiple_instances, EMPTY_INSTANCE_GRACE_PERIOD)
+            .await?;
+        self.tick_instance_resets(delta_time).await?;
 
         Ok(())
     }
 
+    async fn tick_instance_resets(&self, delta_time: f32) -> Result<()> {
+        let mut cooldown = self.reset_check_cooldown.lock().await;
+        *cooldown -= delta_time;
+        if *cooldown > 0.0 {
+            return Ok(());
+        }
+        *cooldown = INSTANCE_RESET_CHECK_INTERVAL;
+
+        //Running instances that reset are no longer joined, see MapManager::can_be_joined
+        let num_reset = self.realm_db.delete_expired_instances(get_unix_time()).await?;
+        if num_reset > 0 {
+            info!("Reset {} instance saves", num_reset);
+        }
+        Ok(())
+    }
+
     async fn tick_maps<T: PartialEq + Clone>(&self, list: &RwLock<HashMap<T, Arc<MapManager>>>, delta_time: f32) -> Result<()> {
         let maps = list.read().await;
         for map in maps.values() {
-            map.tick(delta_time).await?;
+            map.tick(delta_time, &self.data_storage).await?;
         }
         Ok(())
     }
 
-    async fn cleanup_maps<T: PartialEq + Clone>(&self, list: &RwLock<HashMap<T, Arc<MapManager>>>) -> Result<()> {
+    async fn cleanup_maps<T: PartialEq + Clone>(&self, list: &RwLock<HashMap<T, Arc<MapManager>>>, grace_period: Duration) -> Result<()> {
         let mut to_cleanup = Vec::new();
 
         let maps = list.upgradable_read().await;
         {
             for (id, map) in maps.iter() {
-                if map.should_shutdown().await {
+                if map.should_shutdown(grace_period).await {
                     map.shutdown().await?;
                     to_cleanup.push(id.clone());
                 }
@@ -66,27 +127,118 @@ impl InstanceManager {
         Ok(())
     }
 
-    fn is_instance(&self, _map_id: Map) -> bool {
-        //TODO: implement based on DBC storage
-        false
+    fn get_instance_type(&self, map: Map) -> i32 {
+        self.data_storage
+            .get_dbc_chr_map()
+            .ok()
+            .and_then(|dbc_map| dbc_map.get(map.as_int()))
+            .map_or(MAP_INSTANCE_TYPE_NONE, |map_info| map_info.instance_type)
+    }
+
+    pub fn is_instance(&self, map: Map) -> bool {
+        //Dungeons, raids, battlegrounds and arenas all have a non-zero instance type
+        self.get_instance_type(map) != MAP_INSTANCE_TYPE_NONE
+    }
+
+    pub fn is_raid(&self, map: Map) -> bool {
+        self.get_instance_type(map) == MAP_INSTANCE_TYPE_RAID
     }
 
-    pub async fn get_or_create_map(&self, object: &impl GameObject, map: Map) -> Result<Arc<MapManager>> {
-        let map = if !self.is_instance(map) {
-            Ok(self
-                .world_maps
-                .write()
-                .await
-                .entry(map.as_int())
-                .or_insert_with(|| Arc::new(MapManager::new(map.as_int())))
-                .clone())
-        } else if let Some(character) = object.as_character() {
-            Ok(self.get_or_create_map_for_instance(map, character.instance_id).await)
+    //Also binds the character to the instance it ends up in, if the map is instanced
+    pub async fn get_or_create_map(&self, character: &mut Character, map: Map) -> Result<Arc<MapManager>> {
+        if !self.is_instance(map) {
+            character.instance_id = 0;
+            let mut world_maps = self.world_maps.write().await;
+            if let Some(map_manager) = world_maps.get(&map.as_int()) {
+                return Ok(map_manager.clone());
+            }
+
+            let map_manager = self.create_map(map, 0, None).await?;
+            world_maps.insert(map.as_int(), map_manager.clone());
+            Ok(map_manager)
         } else {
-            Err(anyhow!("Not a valid map"))
-        };
+            let now = get_unix_time();
+            let character_id = character.get_guid().guid() as u32;
+            let is_raid = self.is_raid(map);
+            let difficulty = if is_raid {
+                character.raid_difficulty.as_int()
+            } else {
+                character.dungeon_difficulty.as_int()
+            };
+
+            //Raids and heroic dungeons lock the character to their instance until the next reset
+            let saves_progress = is_raid || character.dungeon_difficulty != DungeonDifficulty::Normal;
+            let saved_instance_id = if saves_progress {
+                self.realm_db
+                    .get_character_instances(character_id)
+                    .await?
+                    .into_iter()
+                    .find(|save| save.map as u32 == map.as_int() && save.difficulty == difficulty && save.reset_time > now)
+                    .map(|save| save.id)
+            } else {
+                None
+            };
+
+            //Characters in a group follow the group into the instance it entered before
+            let group_instance_id = self
+                .group_manager
+                .get_group_instance(character.get_guid(), map.as_int(), difficulty)
+                .await;
+            let mut multiple_instances = self.multiple_instances.write().await;
+            let wanted_instance_id = saved_instance_id.or(group_instance_id).unwrap_or(character.instance_id);
+            if let Some(map_manager) = multiple_instances.get(&wanted_instance_id) {
+                if map_manager.get_map_id() == map.as_int() && map_manager.can_be_joined(difficulty, now) {
+                    if saves_progress {
+                        self.realm_db.bind_character_to_instance(character_id, wanted_instance_id).await?;
+                    }
+                    self.group_manager
+                        .bind_group_instance(character.get_guid(), map.as_int(), difficulty, wanted_instance_id)
+                        .await;
+                    character.instance_id = wanted_instance_id;
+                    return Ok(map_manager.clone());
+                }
+            }
+
+            let reset_time = saves_progress.then(|| get_next_reset_time(now, is_raid));
+            let instance_id = match saved_instance_id {
+                Some(id) => id,
+                None => {
+                    let id = self.next_instance_id.fetch_add(1, Ordering::Relaxed);
+                    if let Some(reset_time) = reset_time {
+                        self.realm_db
+                            .create_instance(&DBInstance {
+                                id,
+                                map: map.as_int() as u16,
+                                difficulty,
+                                reset_time,
+                            })
+                            .await?;
+                    }
+                    id
+                }
+            };
+            if saves_progress {
+                self.realm_db.bind_character_to_instance(character_id, instance_id).await?;
+            }
 
-        map
+            info!("Creating instance {} of map {} for character {}", instance_id, map, character.name);
+            let map_manager = self
+                .create_map(map, instance_id, Some(InstanceProperties { difficulty, reset_time }))
+                .await?;
+            multiple_instances.insert(instance_id, map_manager.clone());
+            self.group_manager
+                .bind_group_instance(character.get_guid(), map.as_int(), difficulty, instance_id)
+                .await;
+            character.instance_id = instance_id;
+            Ok(map_manager)
+        }
+    }
+
+    async fn create_map(&self, map: Map, instance_id: InstanceID, instance_properties: Option<InstanceProperties>) -> Result<Arc<MapManager>> {
+        let map_manager = Arc::new(MapManager::new(map.as_int(), instance_properties));
+        map_manager.spawn_creatures(&self.realm_db, &self.guid_service).await?;
+        map_manager.spawn_corpses(&self.realm_db, &self.guid_service, instance_id).await?;
+        Ok(map_manager)
     }
 
     pub async fn try_get_map_for_instance(&self, instance_id: InstanceID) -> Option<Arc<MapManager>> {
@@ -94,20 +246,16 @@ impl InstanceManager {
     }
 
     pub async fn try_get_map_for_character(&self, character: &Character) -> Option<Arc<MapManager>> {
-        if !self.is_instance(character.map) {
-            self.world_maps.read().await.get(&character.map.as_int()).cloned()
-        } else {
-            self.multiple_instances.read().await.get(&character.instance_id).cloned()
-        }
+        self.try_get_map(character.map, character.instance_id).await
     }
 
-    async fn get_or_create_map_for_instance(&self, map: Map, instance_id: InstanceID) -> Arc<MapManager> {
-        self.multiple_instances
-            .write()
-            .await
-            .entry(instance_id)
-            .or_insert(Arc::new(MapManager::new(map.as_int())))
-            .clone()
+    //The instance id is ignored for maps that aren't instanced
+    pub async fn try_get_map(&self, map: Map, instance_id: InstanceID) -> Option<Arc<MapManager>> {
+        if !self.is_instance(map) {
+            self.world_maps.read().await.get(&map.as_int()).cloned()
+        } else {
+            self.multiple_instances.read().await.get(&instance_id).cloned()
+        }
     }
 
     pub async fn handle_client_disconnected(&self, client: &Client) -> Result<()> {
@@ -122,3 +270,20 @@ impl InstanceManager {
         Ok(())
     }
 }
+
+fn get_unix_time() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs()
+}
+
+pub fn get_next_reset_time(now: u64, weekly: bool) -> u64 {
+    let mut reset_time = now - now % SECONDS_PER_DAY + INSTANCE_RESET_HOUR * 60 * 60;
+    if reset_time <= now {
+        reset_time += SECONDS_PER_DAY;
+    }
+
+    //The unix epoch was on a thursday, weekly resets happen on tuesdays
+    while weekly && (reset_time / SECONDS_PER_DAY + 4) % 7 != 2 {
+        reset_time += SECONDS_PER_DAY;
+    }
+    reset_time
+}
@@ -1,15 +1,21 @@
 use super::{
     instance_manager::MapID,
-    prelude::{build_create_update_block_for_player, build_out_of_range_update_block_for_player, build_values_update_block},
+    prelude::{build_create_update_block_for_player, build_out_of_range_update_block_for_player, build_values_update_block, has_any_dirty_fields},
 };
 use std::collections::HashMap;
-use std::sync::Weak;
+use std::sync::{Arc, Weak};
+use std::time::Duration;
 
+use super::guid_service::{player_guid, GuidService};
 use super::prelude::GameObject;
+use crate::corpse::Corpse;
+use crate::creature::Creature;
+use crate::data::{DataStorage, PositionAndOrientation};
 use crate::prelude::*;
 use rstar::{PointDistance, RTree, RTreeObject, AABB};
 use smol::lock::{Mutex, RwLock};
-use wow_world_messages::wrath::UpdateMask;
+use wow_world_messages::wrath::Vector3d;
+use wrath_realm_db::RealmDatabase;
 
 const VISIBILITY_RANGE: f32 = 5000.0f32;
 
@@ -33,16 +39,32 @@ impl PointDistance for RStarTreeItem {
         self.envelope().distance_2(point)
     }
 }
+//Only set for instanced maps
+pub struct InstanceProperties {
+    pub difficulty: u8,
+    //Unix timestamp, None if this instance doesn't save progress
+    pub reset_time: Option<u64>,
+}
+
 pub struct MapManager {
     id: MapID,
     objects_on_map: RwLock<HashMap<Guid, Weak<RwLock<dyn GameObject>>>>,
     query_tree: RwLock<RTree<RStarTreeItem>>,
     add_queue: Mutex<Vec<Weak<RwLock<dyn GameObject>>>>,
     remove_queue: Mutex<Vec<Guid>>,
+    //The map owns its creatures, objects_on_map only holds weak references
+    creatures: RwLock<HashMap<Guid, Arc<RwLock<Creature>>>>,
+    //Corpses of released characters, by the guid of the character
+    corpses: RwLock<HashMap<Guid, Arc<RwLock<Corpse>>>>,
+    //Characters that need to know the auras of a unit that came into view, as (character, unit).
+    //These go out after the create blocks, or the client ignores them.
+    pending_aura_update_alls: Mutex<Vec<(Guid, Guid)>>,
+    time_without_players: Mutex<Duration>,
+    instance_properties: Option<InstanceProperties>,
 }
 
 impl MapManager {
-    pub fn new(id: MapID) -> Self {
+    pub fn new(id: MapID, instance_properties: Option<InstanceProperties>) -> Self {
         info!("spawned new map with id {}", id);
         Self {
             id,
@@ -50,6 +72,87 @@ impl MapManager {
             query_tree: RwLock::new(RTree::new()),
             add_queue: Mutex::new(Vec::new()),
             remove_queue: Mutex::new(Vec::new()),
+            creatures: RwLock::new(HashMap::new()),
+            corpses: RwLock::new(HashMap::new()),
+            pending_aura_update_alls: Mutex::new(Vec::new()),
+            time_without_players: Mutex::new(Duration::ZERO),
+            instance_properties,
+        }
+    }
+
+    pub async fn spawn_creatures(&self, realm_database: &RealmDatabase, guid_service: &GuidService) -> Result<()> {
+        let spawns = realm_database.get_creature_spawns_on_map(self.id as u16).await?;
+        let mut templates = HashMap::new();
+        let mut creatures = self.creatures.write().await;
+        for spawn in spawns.iter() {
+            if !templates.contains_key(&spawn.entry) {
+                templates.insert(spawn.entry, realm_database.get_creature_template(spawn.entry).await?);
+            }
+            let position = PositionAndOrientation {
+                position: Vector3d {
+                    x: spawn.position_x,
+                    y: spawn.position_y,
+                    z: spawn.position_z,
+                },
+                orientation: spawn.orientation,
+            };
+            let creature = Creature::new(guid_service.generate_unit_guid(spawn.entry), &position, &templates[&spawn.entry]);
+            let guid = creature.get_guid();
+            let creature = Arc::new(RwLock::new(creature));
+            self.push_object(Arc::downgrade(&creature)).await;
+            creatures.insert(guid, creature);
+        }
+
+        info!("Spawned {} creatures on map {}", creatures.len(), self.id);
+        Ok(())
+    }
+
+    //Creatures that are spawned at runtime, they are not saved to the database
+    pub async fn summon_creature(
+        &self,
+        realm_database: &RealmDatabase,
+        guid_service: &GuidService,
+        entry: u32,
+        position: &PositionAndOrientation,
+    ) -> Result<Guid> {
+        let template = realm_database.get_creature_template(entry).await?;
+        let creature = Creature::new(guid_service.generate_unit_guid(entry), position, &template);
+        let guid = creature.get_guid();
+        let creature = Arc::new(RwLock::new(creature));
+        self.push_object(Arc::downgrade(&creature)).await;
+        self.creatures.write().await.insert(guid, creature);
+        Ok(guid)
+    }
+
+    //Corpses stay behind while their owners are offline, so they come back with the map
+    pub async fn spawn_corpses(&self, realm_database: &RealmDatabase, guid_service: &GuidService, instance_id: u32) -> Result<()> {
+        for corpse in realm_database.get_corpses_on_map(self.id as u16, instance_id).await? {
+            let position = PositionAndOrientation {
+                position: Vector3d {
+                    x: corpse.position_x,
+                    y: corpse.position_y,
+                    z: corpse.position_z,
+                },
+                orientation: corpse.orientation,
+            };
+            self.spawn_corpse(guid_service, player_guid(corpse.character_id), corpse.display_id as i32, &position)
+                .await;
+        }
+        Ok(())
+    }
+
+    //A character only has one corpse, an older one is removed
+    pub async fn spawn_corpse(&self, guid_service: &GuidService, owner: Guid, display_id: i32, position: &PositionAndOrientation) {
+        self.remove_corpse(owner).await;
+        let corpse = Arc::new(RwLock::new(Corpse::new(guid_service.generate_corpse_guid(), owner, display_id, position)));
+        self.push_object(Arc::downgrade(&corpse)).await;
+        self.corpses.write().await.insert(owner, corpse);
+    }
+
+    pub async fn remove_corpse(&self, owner: Guid) {
+        if let Some(corpse) = self.corpses.write().await.remove(&owner) {
+            let guid = corpse.read().await.get_guid();
+            self.remove_object_by_guid(guid).await;
         }
     }
 
@@ -58,8 +161,37 @@ impl MapManager {
         Ok(())
     }
 
-    pub async fn should_shutdown(&self) -> bool {
-        self.objects_on_map.read().await.is_empty() && self.add_queue.lock().await.is_empty()
+    pub fn get_map_id(&self) -> MapID {
+        self.id
+    }
+
+    pub fn can_be_joined(&self, difficulty: u8, current_time: u64) -> bool {
+        self.instance_properties.as_ref().map_or(true, |props| {
+            props.difficulty == difficulty && props.reset_time.map_or(true, |t| t > current_time)
+        })
+    }
+
+    pub async fn should_shutdown(&self, grace_period: Duration) -> bool {
+        !self.has_players().await && *self.time_without_players.lock().await >= grace_period
+    }
+
+    async fn has_players(&self) -> bool {
+        //Creatures don't keep a map alive, only players do
+        let creatures = self.creatures.read().await;
+        if self.objects_on_map.read().await.keys().any(|guid| !creatures.contains_key(guid)) {
+            return true;
+        }
+
+        for object_lock in self.add_queue.lock().await.iter().filter_map(|weak| weak.upgrade()) {
+            if object_lock.read().await.as_character().is_some() {
+                return true;
+            }
+        }
+        false
+    }
+
+    pub async fn try_get_creature(&self, guid: Guid) -> Option<Arc<RwLock<Creature>>> {
+        self.creatures.read().await.get(&guid).cloned()
     }
 
     pub async fn try_get_object(&self, guid: Guid) -> Option<Weak<RwLock<dyn GameObject>>> {
@@ -67,10 +199,20 @@ impl MapManager {
         map_objects.get(&guid).cloned()
     }
 
-    pub async fn tick(&self, _delta_time: f32) -> Result<()> {
+    pub async fn tick(&self, delta_time: f32, data_storage: &DataStorage) -> Result<()> {
         self.rebuild_object_querying_tree().await?;
-        let any_removed = self.process_remove_queue().await?;
-        let any_added = self.process_add_queue().await?;
+        self.process_remove_queue().await?;
+        self.process_add_queue().await?;
+
+        if self.has_players().await {
+            *self.time_without_players.lock().await = Duration::ZERO;
+        } else {
+            *self.time_without_players.lock().await += Duration::from_secs_f32(delta_time);
+        }
+
+        for creature_lock in self.creatures.read().await.values() {
+            creature_lock.write().await.tick(delta_time, self, data_storage).await?;
+        }
 
         let map_objects = self.objects_on_map.read().await;
         for weak_locked_map_object in (*map_objects).values() {
@@ -78,51 +220,66 @@ impl MapManager {
 
             if let Some(locked_map_object) = weak_locked_map_object.upgrade() {
                 let mut map_object = locked_map_object.write().await;
-                let wants_to_receive_update_blocks = map_object.as_update_receiver().is_some();
                 assert_eq!(
                     map_object.as_update_receiver().is_some(),
                     map_object.as_update_receiver_mut().is_some(),
                     "Implementing one without the other, this will cause problems on an upwrap later"
                 );
 
-                if wants_to_receive_update_blocks {
-                    let has_any_update_bit = if let UpdateMask::Player(update_mask) = map_object.get_update_mask() {
-                        update_mask.has_any_dirty_fields()
-                    } else {
-                        bail!("any other type not supported");
-                    };
-
-                    let has_something_recently_removed = map_object.get_recently_removed_range_guids().is_empty();
+                //Changed values are sent to everyone around, also for objects that don't receive updates themselves
+                if has_any_dirty_fields(&map_object.get_update_mask())? {
+                    let values_update = build_values_update_block(&*map_object)?;
+                    if let Some(update_receiver) = map_object.as_update_receiver_mut() {
+                        update_receiver.push_object_update(values_update.clone());
+                    }
 
-                    if has_any_update_bit || any_removed || any_added || has_something_recently_removed {
-                        if let Some(out_of_range_update) = build_out_of_range_update_block_for_player(&*map_object) {
-                            map_object.clear_recently_removed_range_guids();
-                            map_object.as_update_receiver_mut().unwrap().push_object_update(out_of_range_update);
+                    let in_range_guids = map_object.get_in_range_guids();
+                    for in_range_object_lock in in_range_guids
+                        .iter()
+                        .filter_map(|guid| map_objects.get(guid))
+                        .filter_map(|weak| weak.upgrade())
+                    {
+                        let mut in_range_object = in_range_object_lock.write().await;
+                        if let Some(update_receiver) = in_range_object.as_update_receiver_mut() {
+                            update_receiver.push_object_update(values_update.clone());
                         }
+                    }
+                    map_object.clear_update_mask_header();
+                }
 
-                        if has_any_update_bit {
-                            let values_update = build_values_update_block(&*map_object)?;
-                            map_object.as_update_receiver_mut().unwrap().push_object_update(values_update.clone());
-
-                            let in_range_guids = map_object.get_in_range_guids();
-                            for in_range_object_lock in in_range_guids
-                                .iter()
-                                .filter_map(|guid| map_objects.get(guid))
-                                .filter_map(|weak| weak.upgrade())
-                            {
-                                let mut in_range_object = in_range_object_lock.write().await;
-                                if let Some(update_receiver) = in_range_object.as_update_receiver_mut() {
-                                    update_receiver.push_object_update(values_update.clone());
-                                }
-                            }
-                            map_object.clear_update_mask_header();
-                        }
+                if map_object.as_update_receiver().is_some() {
+                    if let Some(out_of_range_update) = build_out_of_range_update_block_for_player(&*map_object) {
+                        map_object.clear_recently_removed_range_guids();
+                        map_object.as_update_receiver_mut().unwrap().push_object_update(out_of_range_update);
                     }
 
                     map_object.as_update_receiver_mut().unwrap().process_pending_updates().await?;
                 }
             }
         }
+        drop(map_objects);
+
+        self.send_pending_aura_update_alls().await
+    }
+
+    async fn send_pending_aura_update_alls(&self) -> Result<()> {
+        let pending = std::mem::take(&mut *self.pending_aura_update_alls.lock().await);
+        let map_objects = self.objects_on_map.read().await;
+        for (character_guid, unit_guid) in pending {
+            let character_lock = map_objects.get(&character_guid).and_then(|weak| weak.upgrade());
+            let unit_lock = map_objects.get(&unit_guid).and_then(|weak| weak.upgrade());
+            let (Some(character_lock), Some(unit_lock)) = (character_lock, unit_lock) else {
+                continue;
+            };
+
+            let character_object = character_lock.read().await;
+            let unit_object = unit_lock.read().await;
+            if let (Some(character), Some(unit)) = (character_object.as_character(), unit_object.as_unit()) {
+                if !unit.get_auras().is_empty() {
+                    handlers::send_aura_update_all(character, unit_guid, unit.get_auras()).await?;
+                }
+            }
+        }
         Ok(())
     }
 
@@ -239,6 +396,10 @@ impl MapManager {
                         if wants_updates {
                             let create_block = build_create_update_block_for_player(&*write_obj, &*object_lock.read().await)?;
                             write_obj.as_update_receiver_mut().unwrap().push_object_update(create_block);
+                            self.pending_aura_update_alls
+                                .lock()
+                                .await
+                                .push((guid, object_lock.read().await.get_guid()));
                         }
                     }
                     let mut object = object_lock.write().await;
@@ -247,6 +408,7 @@ impl MapManager {
                     if wants_updates {
                         let create_block = build_create_update_block_for_player(&*object, &*lock_from_guid.read().await)?;
                         object.as_update_receiver_mut().unwrap().push_object_update(create_block);
+                        self.pending_aura_update_alls.lock().await.push((object.get_guid(), guid));
                     }
                 } else {
                     error!("Map manager had a GUID in its spacial querying tree that wasn't known in the objects on the map");
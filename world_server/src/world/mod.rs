@@ -1,13 +1,20 @@
+use crate::data::DataStorage;
+use crate::group::GroupManager;
 use crate::prelude::*;
+use guid_service::GuidService;
 use instance_manager::InstanceManager;
 use std::sync::Arc;
+use std::time::Duration;
 use wrath_realm_db::RealmDatabase;
 
 pub mod game_object;
+pub mod guid_service;
 mod instance_manager;
 mod map_manager;
 mod update_builder;
 
+pub use instance_manager::get_next_reset_time;
+
 pub mod prelude {
     pub use super::super::constants::*;
     pub use super::game_object::*;
@@ -19,14 +26,27 @@ pub mod prelude {
 pub struct World {
     instance_manager: Arc<InstanceManager>,
     realm_db: Arc<RealmDatabase>,
+    autosave_interval: Duration,
+    guid_service: Arc<GuidService>,
+    data_storage: Arc<DataStorage>,
+    group_manager: Arc<GroupManager>,
 }
 
 impl World {
-    pub fn new(realm_db: Arc<RealmDatabase>) -> Self {
-        Self {
-            instance_manager: Arc::new(InstanceManager::new()),
+    pub async fn new(realm_db: Arc<RealmDatabase>, data_storage: Arc<DataStorage>, autosave_interval: Duration) -> Result<Self> {
+        let guid_service = Arc::new(GuidService::new(&realm_db).await?);
+        let group_manager = Arc::new(GroupManager::new());
+
+        Ok(Self {
+            instance_manager: Arc::new(
+                InstanceManager::new(realm_db.clone(), data_storage.clone(), guid_service.clone(), group_manager.clone()).await?,
+            ),
             realm_db,
-        }
+            autosave_interval,
+            guid_service,
+            data_storage,
+            group_manager,
+        })
     }
 
     pub fn get_instance_manager(&self) -> Arc<InstanceManager> {
@@ -37,8 +57,25 @@ impl World {
         self.realm_db.clone()
     }
 
+    pub fn get_autosave_interval(&self) -> Duration {
+        self.autosave_interval
+    }
+
+    pub fn get_guid_service(&self) -> Arc<GuidService> {
+        self.guid_service.clone()
+    }
+
+    pub fn get_data_storage(&self) -> Arc<DataStorage> {
+        self.data_storage.clone()
+    }
+
+    pub fn get_group_manager(&self) -> Arc<GroupManager> {
+        self.group_manager.clone()
+    }
+
     pub async fn tick(&self, delta_time: f32) -> Result<()> {
         self.instance_manager.tick(delta_time).await?;
+        self.group_manager.tick(delta_time).await?;
         Ok(())
     }
 }
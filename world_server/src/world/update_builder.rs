@@ -10,9 +10,9 @@ pub trait ReceiveUpdates {
 }
 
 pub fn build_create_update_block_for_player(player: &dyn GameObject, object: &dyn GameObject) -> Result<wow_world_messages::wrath::Object> {
-    use wow_world_messages::wrath::{MovementBlock, MovementBlock_UpdateFlag, Object, Object_UpdateType};
-
-    assert!(object.as_character().is_some(), "Only characters currently supported");
+    use wow_world_messages::wrath::{
+        MovementBlock, MovementBlock_UpdateFlag, MovementBlock_UpdateFlag_HasPosition, Object, ObjectType, Object_UpdateType,
+    };
 
     let object_guid = object.get_guid();
     let player_guid = player.get_guid();
@@ -20,21 +20,28 @@ pub fn build_create_update_block_for_player(player: &dyn GameObject, object: &dy
 
     let movement_info = object.get_movement_info();
 
-    let mut update_flag = MovementBlock_UpdateFlag::empty()
-        .set_living(
-            movement_info.to_movement_block_update_flag_living(
-                0.0, /* backwards_flight_speed */
-                4.5, /* backwards_running_speed */
-                0.0, /* backwards_swimming_speed */
-                0.0, /* flight_speed */
-                0.0, /* pitch_rate */
-                7.0, /* running_speed */
-                0.0, /* swimming_speed */
-                std::f32::consts::PI, /* turn_rate */
-                1.0, /* walking_speed */
-                None, /* spline_enabled */
-                )
-            )
+    //Corpses don't move, so they only tell the client where they are
+    let update_flag = if object.get_type() == ObjectType::Corpse {
+        MovementBlock_UpdateFlag::empty().set_has_position(MovementBlock_UpdateFlag_HasPosition {
+            position: movement_info.position,
+            orientation: movement_info.orientation,
+        })
+    } else {
+        MovementBlock_UpdateFlag::empty().set_living(movement_info.to_movement_block_update_flag_living(
+            0.0,                  /* backwards_flight_speed */
+            4.5,                  /* backwards_running_speed */
+            0.0,                  /* backwards_swimming_speed */
+            0.0,                  /* flight_speed */
+            0.0,                  /* pitch_rate */
+            7.0,                  /* running_speed */
+            0.0,                  /* swimming_speed */
+            std::f32::consts::PI, /* turn_rate */
+            1.0,                  /* walking_speed */
+            None,                 /* spline_enabled */
+        ))
+    };
+
+    let mut update_flag = update_flag
         .set_high_guid(wow_world_messages::wrath::MovementBlock_UpdateFlag_HighGuid {
             unknown0: if creating_self { 0x2F } else { 0x08 },
         })
@@ -50,7 +57,9 @@ pub fn build_create_update_block_for_player(player: &dyn GameObject, object: &dy
     let mut all_dirty_update_mask = object.get_update_mask();
     match all_dirty_update_mask {
         wow_world_messages::wrath::UpdateMask::Player(ref mut inner) => inner.mark_fully_dirty(),
-        _ => unimplemented!(),
+        wow_world_messages::wrath::UpdateMask::Unit(ref mut inner) => inner.mark_fully_dirty(),
+        wow_world_messages::wrath::UpdateMask::Corpse(ref mut inner) => inner.mark_fully_dirty(),
+        _ => bail!("Creating objects of type {:?} is not supported", object.get_type()),
     }
 
     let update_type = if creating_self {
@@ -72,6 +81,17 @@ pub fn build_create_update_block_for_player(player: &dyn GameObject, object: &dy
     Ok(Object { update_type })
 }
 
+pub fn has_any_dirty_fields(update_mask: &wow_world_messages::wrath::UpdateMask) -> Result<bool> {
+    use wow_world_messages::wrath::UpdateMask;
+
+    Ok(match update_mask {
+        UpdateMask::Player(inner) => inner.has_any_dirty_fields(),
+        UpdateMask::Unit(inner) => inner.has_any_dirty_fields(),
+        UpdateMask::Corpse(inner) => inner.has_any_dirty_fields(),
+        _ => bail!("any other type not supported"),
+    })
+}
+
 pub fn build_out_of_range_update_block_for_player(player: &dyn GameObject) -> Option<wow_world_messages::wrath::Object> {
     use wow_world_messages::wrath::{Object, Object_UpdateType};
 